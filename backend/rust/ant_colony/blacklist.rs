@@ -0,0 +1,145 @@
+use anyhow::Result;
+use log::{info, warn};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlacklistEntry {
+    pub token_address: String,
+    pub reason: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+// On-disk layout; files written before the whitelist existed hold just the entry list
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoredLists {
+    entries: Vec<BlacklistEntry>,
+    #[serde(default)]
+    whitelist: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Lists {
+    entries: HashMap<String, BlacklistEntry>,
+    whitelist: HashSet<String>,
+    whitelist_only: bool, // Only whitelisted tokens may be traded
+}
+
+// Tokens the colony must never re-enter, persisted as JSON so entries survive restarts.
+// Clones share the same lists, so the sniping core can check the colony's blacklist directly.
+#[derive(Debug, Clone, Default)]
+pub struct TokenBlacklist {
+    lists: Arc<Mutex<Lists>>,
+    path: Option<PathBuf>,
+}
+
+impl TokenBlacklist {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let stored = if path.exists() {
+            let contents = std::fs::read_to_string(&path)?;
+            match serde_json::from_str::<serde_json::Value>(&contents)? {
+                serde_json::Value::Array(_) => StoredLists {
+                    entries: serde_json::from_str(&contents)?,
+                    whitelist: Vec::new(),
+                },
+                value => serde_json::from_value(value)?,
+            }
+        } else {
+            StoredLists::default()
+        };
+
+        info!("Loaded {} blacklisted and {} whitelisted tokens from {:?}",
+              stored.entries.len(), stored.whitelist.len(), path);
+        Ok(Self {
+            lists: Arc::new(Mutex::new(Lists {
+                entries: stored.entries.into_iter()
+                    .map(|e| (e.token_address.clone(), e))
+                    .collect(),
+                whitelist: stored.whitelist.into_iter().collect(),
+                whitelist_only: false,
+            })),
+            path: Some(path),
+        })
+    }
+
+    // Returns true if the token was newly added
+    pub fn add(&self, token_address: &str, reason: &str) -> Result<bool> {
+        let mut lists = self.lists.lock().unwrap();
+        if lists.entries.contains_key(token_address) {
+            return Ok(false);
+        }
+
+        lists.entries.insert(token_address.to_string(), BlacklistEntry {
+            token_address: token_address.to_string(),
+            reason: reason.to_string(),
+            timestamp: Utc::now(),
+        });
+        warn!("Blacklisted token {}: {}", token_address, reason);
+
+        self.save(&lists)?;
+        Ok(true)
+    }
+
+    pub fn contains(&self, token_address: &str) -> bool {
+        self.lists.lock().unwrap().entries.contains_key(token_address)
+    }
+
+    pub fn get(&self, token_address: &str) -> Option<BlacklistEntry> {
+        self.lists.lock().unwrap().entries.get(token_address).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lists.lock().unwrap().entries.len()
+    }
+
+    // Returns true if the token was newly whitelisted
+    pub fn whitelist(&self, token_address: &str) -> Result<bool> {
+        let mut lists = self.lists.lock().unwrap();
+        if !lists.whitelist.insert(token_address.to_string()) {
+            return Ok(false);
+        }
+        info!("Whitelisted token {}", token_address);
+
+        self.save(&lists)?;
+        Ok(true)
+    }
+
+    pub fn is_whitelisted(&self, token_address: &str) -> bool {
+        self.lists.lock().unwrap().whitelist.contains(token_address)
+    }
+
+    pub fn set_whitelist_only(&self, whitelist_only: bool) {
+        self.lists.lock().unwrap().whitelist_only = whitelist_only;
+    }
+
+    // Whether a new position may be opened in the token. A blacklisted token never is,
+    // even if it is also whitelisted.
+    pub fn is_allowed(&self, token_address: &str) -> bool {
+        let lists = self.lists.lock().unwrap();
+        if lists.entries.contains_key(token_address) {
+            return false;
+        }
+        !lists.whitelist_only || lists.whitelist.contains(token_address)
+    }
+
+    fn save(&self, lists: &Lists) -> Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let stored = StoredLists {
+            entries: lists.entries.values().cloned().collect(),
+            whitelist: lists.whitelist.iter().cloned().collect(),
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&stored)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::warn;
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+
+// Enough refreshes to cover a blockhash's ~150-block lifetime at the default interval
+const RECENT_BLOCKHASHES: usize = 64;
+
+// Where the cache fetches blockhashes from
+#[async_trait]
+pub trait BlockhashSource: Send + Sync {
+    // The latest blockhash and the last block height a transaction built on it can land in
+    async fn latest_blockhash(&self) -> Result<(Hash, u64)>;
+}
+
+pub struct RpcBlockhashSource {
+    rpc_client: RpcClient,
+}
+
+impl RpcBlockhashSource {
+    pub fn new(rpc_url: String) -> Self {
+        Self { rpc_client: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()) }
+    }
+}
+
+#[async_trait]
+impl BlockhashSource for RpcBlockhashSource {
+    async fn latest_blockhash(&self) -> Result<(Hash, u64)> {
+        Ok(self.rpc_client.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed()).await?)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachedBlockhash {
+    pub blockhash: Hash,
+    pub last_valid_block_height: u64,
+    pub fetched_at: Instant,
+}
+
+impl CachedBlockhash {
+    // Whether a transaction built on this blockhash can no longer land at `current_block_height`
+    pub fn is_expired(&self, current_block_height: u64) -> bool {
+        current_block_height > self.last_valid_block_height
+    }
+}
+
+// Keeps a recent blockhash on hand so building a transaction doesn't wait on a
+// getLatestBlockhash round trip. `start` refreshes it in the background every
+// `refresh_interval`.
+pub struct BlockhashCache {
+    source: Arc<dyn BlockhashSource>,
+    refresh_interval: Duration,
+    max_age: Duration, // Past this the background refresh is assumed stuck and callers fetch themselves
+    recent: RwLock<VecDeque<CachedBlockhash>>, // Newest last, so transactions built on older ones can be checked
+}
+
+impl BlockhashCache {
+    pub fn new(config: &Config, source: Arc<dyn BlockhashSource>) -> Self {
+        let refresh_interval = Duration::from_millis(
+            config.get_int("ant_colony.transaction_handler.blockhash_cache.refresh_interval_ms").unwrap_or(2000) as u64
+        );
+        let max_age = Duration::from_millis(
+            config.get_int("ant_colony.transaction_handler.blockhash_cache.max_age_ms").unwrap_or(10_000) as u64
+        );
+
+        Self {
+            source,
+            refresh_interval,
+            max_age,
+            recent: RwLock::new(VecDeque::new()),
+        }
+    }
+
+    pub fn refresh_interval(&self) -> Duration {
+        self.refresh_interval
+    }
+
+    pub async fn refresh(&self) -> Result<CachedBlockhash> {
+        let (blockhash, last_valid_block_height) = self.source.latest_blockhash().await?;
+        let cached = CachedBlockhash { blockhash, last_valid_block_height, fetched_at: Instant::now() };
+        let mut recent = self.recent.write().unwrap();
+        if recent.len() == RECENT_BLOCKHASHES {
+            recent.pop_front();
+        }
+        recent.push_back(cached);
+        Ok(cached)
+    }
+
+    // Refreshes right away and then every interval. A failed refresh keeps the previous
+    // blockhash, which stays usable until `is_expired`.
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(self.refresh_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.refresh().await {
+                    warn!("Blockhash refresh failed, keeping the cached one: {}", e);
+                }
+            }
+        })
+    }
+
+    pub fn cached(&self) -> Option<CachedBlockhash> {
+        self.recent.read().unwrap().back().copied()
+    }
+
+    // The cache entry `blockhash` was handed out as, while it is among the recent ones
+    pub fn lookup(&self, blockhash: &Hash) -> Option<CachedBlockhash> {
+        self.recent.read().unwrap().iter().rev().find(|cached| cached.blockhash == *blockhash).copied()
+    }
+
+    // The cached blockhash, or a freshly fetched one when the cache is empty or too old
+    pub async fn blockhash(&self) -> Result<Hash> {
+        match self.cached() {
+            Some(cached) if cached.fetched_at.elapsed() <= self.max_age => Ok(cached.blockhash),
+            _ => Ok(self.refresh().await?.blockhash),
+        }
+    }
+
+    // Whether a transaction built on the cached blockhash can no longer land at
+    // `current_block_height`. An empty cache counts as expired.
+    pub fn is_expired(&self, current_block_height: u64) -> bool {
+        self.cached().map_or(true, |cached| cached.is_expired(current_block_height))
+    }
+}
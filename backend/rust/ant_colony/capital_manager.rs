@@ -1,7 +1,8 @@
 use anyhow::Result;
 use config::Config;
 use log::{info, error, warn};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use crate::ant_colony::ColonyState;
 use serde::{Serialize, Deserialize};
@@ -22,6 +23,36 @@ pub enum AllocationStatus {
     Available,
 }
 
+// Every capital manager's available and starting capital, so colony health can read how
+// much of the budget is left without locking each manager
+#[derive(Debug, Default)]
+pub struct CapitalBudget {
+    managers: Mutex<HashMap<String, (f64, f64)>>, // id -> (available, initial)
+}
+
+impl CapitalBudget {
+    pub fn report(&self, manager_id: &str, available: f64, initial: f64) {
+        self.managers.lock().unwrap().insert(manager_id.to_string(), (available, initial));
+    }
+
+    // A retired manager's capital no longer counts either way
+    pub fn remove(&self, manager_id: &str) {
+        self.managers.lock().unwrap().remove(manager_id);
+    }
+
+    // Fraction of the managers' starting capital still available; None before any report
+    pub fn remaining(&self) -> Option<f64> {
+        let managers = self.managers.lock().unwrap();
+        let (available, initial) = managers.values()
+            .fold((0.0, 0.0), |(available, initial), (a, i)| (available + a, initial + i));
+        if initial > 0.0 {
+            Some((available / initial).clamp(0.0, 1.0))
+        } else {
+            None
+        }
+    }
+}
+
 pub struct CapitalManager {
     id: String,
     state: Arc<RwLock<ColonyState>>,
@@ -31,6 +62,8 @@ pub struct CapitalManager {
     min_active_workers: usize,
     allocations: Vec<CapitalAllocation>,
     available_capital: f64,
+    initial_capital: f64,
+    budget: Arc<CapitalBudget>, // The colony's, kept current with `available_capital`
 }
 
 impl CapitalManager {
@@ -39,8 +72,9 @@ impl CapitalManager {
         let max_active_workers = config.get_int("ant_colony.capital_manager.max_active_workers")? as usize;
         let min_active_workers = config.get_int("ant_colony.capital_manager.min_active_workers")? as usize;
         let initial_capital = config.get_float("ant_colony.capital_manager.initial_capital")? as f64;
+        let budget = state.read().await.capital_budget.clone();
 
-        Ok(Self {
+        let manager = Self {
             id: uuid::Uuid::new_v4().to_string(),
             state,
             is_active: false,
@@ -49,7 +83,15 @@ impl CapitalManager {
             min_active_workers,
             allocations: Vec::new(),
             available_capital: initial_capital,
-        })
+            initial_capital,
+            budget,
+        };
+        manager.report_budget();
+        Ok(manager)
+    }
+
+    fn report_budget(&self) {
+        self.budget.report(&self.id, self.available_capital, self.initial_capital);
     }
 
     pub async fn start_monitoring(&mut self) -> Result<()> {
@@ -92,6 +134,7 @@ impl CapitalManager {
             if matches!(self.allocations[i].status, AllocationStatus::Sold) {
                 // Return capital to available pool
                 self.available_capital += self.allocations[i].amount;
+                self.report_budget();
                 
                 // Remove the allocation
                 self.allocations.remove(i);
@@ -133,6 +176,7 @@ impl CapitalManager {
 
         // Update available capital
         self.available_capital -= self.worker_ant_budget;
+        self.report_budget();
 
         // Add allocation
         self.allocations.push(allocation);
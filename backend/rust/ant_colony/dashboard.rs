@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use crate::common::{AlertSeverity, ColonyAlert};
+
+// What the dashboard shows for one worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerSnapshot {
+    pub id: String,
+    pub is_active: bool,
+    pub balance: f64,
+    pub total_trades: u64,
+    pub success_rate: f64,
+    pub profit_loss: f64,
+    pub last_active: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TierState {
+    Pending,
+    Hit,
+}
+
+// A rung of the sell ladder and whether an open position has reached it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfitTierStatus {
+    pub multiplier: f64,
+    pub percentage: f64,
+    pub status: TierState,
+    pub timestamp: Option<DateTime<Utc>>, // When it was hit
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRecord {
+    pub alert_type: String, // Component that raised it, e.g. "rug_detector"
+    pub severity: AlertSeverity,
+    pub details: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl From<&ColonyAlert> for AlertRecord {
+    fn from(alert: &ColonyAlert) -> Self {
+        Self {
+            alert_type: alert.source.clone(),
+            severity: alert.severity,
+            details: alert.message.clone(),
+            timestamp: alert.timestamp,
+        }
+    }
+}
+
+// Realized profit and gas of one filled sell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformancePoint {
+    pub timestamp: DateTime<Utc>,
+    pub profit: f64,
+    pub gas_fees: f64,
+}
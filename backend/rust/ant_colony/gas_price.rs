@@ -0,0 +1,111 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use crate::ant_colony::transaction_handler::TransactionHandler;
+
+// Solana charges this per signature on top of any priority fee
+const BASE_FEE_LAMPORTS: f64 = 5000.0;
+
+// Where the profit manager samples what priority fees are currently paying
+#[async_trait]
+pub trait GasPriceSource: Send + Sync {
+    // Priority fee in micro-lamports per compute unit
+    async fn priority_fee(&self) -> Result<f64>;
+}
+
+// Reads the fee the shared TransactionHandler would bid, so sells are budgeted from the same
+// samples (and the same min_priority_fee floor) they are sent with
+pub struct HandlerGasPriceSource {
+    transaction_handler: Arc<RwLock<TransactionHandler>>,
+}
+
+impl HandlerGasPriceSource {
+    pub fn new(transaction_handler: Arc<RwLock<TransactionHandler>>) -> Self {
+        Self { transaction_handler }
+    }
+}
+
+#[async_trait]
+impl GasPriceSource for HandlerGasPriceSource {
+    async fn priority_fee(&self) -> Result<f64> {
+        Ok(self.transaction_handler.read().await.network_priority_fee().await? as f64)
+    }
+}
+
+// Rolling window of priority fee samples, from which sells are priced
+#[derive(Debug)]
+pub struct GasPriceHistory {
+    window: usize,      // Most recent samples kept
+    percentile: f64,    // Of the window's samples; higher lands faster but pays more
+    safety_buffer: f64, // Multiplier on top, for fees rising between sample and send
+    compute_units: f64, // Budget of a typical sell
+    samples: VecDeque<f64>,
+}
+
+impl Default for GasPriceHistory {
+    fn default() -> Self {
+        Self {
+            window: 100,
+            percentile: 0.75,
+            safety_buffer: 1.2,
+            compute_units: 200_000.0,
+            samples: VecDeque::new(),
+        }
+    }
+}
+
+impl GasPriceHistory {
+    pub fn new(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            window: config.get_int("ant_colony.profit_manager.gas_price_window")
+                .map(|window| window.max(1) as usize)
+                .unwrap_or(defaults.window),
+            percentile: config.get_float("ant_colony.profit_manager.gas.percentile")
+                .map(|percentile| percentile.clamp(0.0, 1.0))
+                .unwrap_or(defaults.percentile),
+            safety_buffer: config.get_float("ant_colony.profit_manager.gas.safety_buffer")
+                .map(|buffer| buffer.max(1.0))
+                .unwrap_or(defaults.safety_buffer),
+            compute_units: config.get_int("ant_colony.profit_manager.gas.compute_units")
+                .map(|units| units.max(0) as f64)
+                .unwrap_or(defaults.compute_units),
+            ..defaults
+        }
+    }
+
+    pub fn record(&mut self, priority_fee: f64) {
+        self.samples.push_back(priority_fee.max(0.0));
+        while self.samples.len() > self.window {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    // Priority fee to bid, in micro-lamports per compute unit; 0 until anything is sampled
+    pub fn optimal_price(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+
+        let mut fees: Vec<f64> = self.samples.iter().copied().collect();
+        fees.sort_by(|a, b| a.total_cmp(b));
+        let rank = (self.percentile * fees.len() as f64).ceil() as usize;
+        fees[rank.saturating_sub(1).min(fees.len() - 1)] * self.safety_buffer
+    }
+
+    // Cost in SOL of one sell paying `priority_fee`
+    pub fn cost(&self, priority_fee: f64) -> f64 {
+        (BASE_FEE_LAMPORTS + priority_fee * self.compute_units / 1_000_000.0) / 1_000_000_000.0
+    }
+}
@@ -0,0 +1,167 @@
+use chrono::{DateTime, Duration, Utc};
+use config::Config;
+use serde::{Serialize, Deserialize};
+
+// Ordered so the overall verdict is simply the worst contributing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthVerdict {
+    Healthy,
+    Degraded,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReason {
+    pub signal: String, // rpc, loop, errors, drawdown, trading, wallets, strategies or budget
+    pub verdict: HealthVerdict,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthSummary {
+    pub verdict: HealthVerdict,
+    pub reasons: Vec<HealthReason>, // Only the signals that aren't healthy
+    pub checked_at: DateTime<Utc>,
+}
+
+// Point-in-time inputs; signals a caller doesn't track are left as None and not judged
+#[derive(Debug, Clone, Default)]
+pub struct HealthSignals {
+    pub rpc_providers: Option<(usize, usize)>, // (healthy, configured)
+    pub last_loop_tick: Option<DateTime<Utc>>,
+    pub error_rate: f64,       // Fraction of recent submissions that failed
+    pub drawdown: f64,         // Fraction of capital lost from the session's PnL peak
+    pub trading_halted: bool,  // Colony stopped trading, e.g. by the queen's risk limit
+    pub paused_wallets: usize,
+    pub disabled_strategies: usize,
+    pub budget_remaining: Option<f64>, // Fraction of capital still available to deploy
+}
+
+// Folds every subsystem signal into one verdict for operators. Each signal is judged
+// against a degraded and a critical threshold; the worst one wins.
+#[derive(Debug, Clone)]
+pub struct ColonyHealth {
+    loop_stale_after: Duration,
+    loop_dead_after: Duration,
+    error_rate_degraded: f64,
+    error_rate_critical: f64,
+    drawdown_degraded: f64,
+    drawdown_critical: f64,
+    budget_degraded: f64,
+    budget_critical: f64,
+}
+
+impl Default for ColonyHealth {
+    fn default() -> Self {
+        Self {
+            loop_stale_after: Duration::seconds(30),
+            loop_dead_after: Duration::seconds(120),
+            error_rate_degraded: 0.2,
+            error_rate_critical: 0.5,
+            drawdown_degraded: 0.1,
+            drawdown_critical: 0.25,
+            budget_degraded: 0.25,
+            budget_critical: 0.05,
+        }
+    }
+}
+
+impl ColonyHealth {
+    pub fn new(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            loop_stale_after: config.get_int("ant_colony.health.loop_stale_secs")
+                .map(Duration::seconds)
+                .unwrap_or(defaults.loop_stale_after),
+            loop_dead_after: config.get_int("ant_colony.health.loop_dead_secs")
+                .map(Duration::seconds)
+                .unwrap_or(defaults.loop_dead_after),
+            error_rate_degraded: config.get_float("ant_colony.health.error_rate_degraded")
+                .unwrap_or(defaults.error_rate_degraded),
+            error_rate_critical: config.get_float("ant_colony.health.error_rate_critical")
+                .unwrap_or(defaults.error_rate_critical),
+            drawdown_degraded: config.get_float("ant_colony.health.drawdown_degraded")
+                .unwrap_or(defaults.drawdown_degraded),
+            drawdown_critical: config.get_float("ant_colony.health.drawdown_critical")
+                .unwrap_or(defaults.drawdown_critical),
+            budget_degraded: config.get_float("ant_colony.health.budget_degraded")
+                .unwrap_or(defaults.budget_degraded),
+            budget_critical: config.get_float("ant_colony.health.budget_critical")
+                .unwrap_or(defaults.budget_critical),
+        }
+    }
+
+    pub fn evaluate(&self, signals: &HealthSignals) -> HealthSummary {
+        let now = Utc::now();
+        let mut reasons = Vec::new();
+        let mut flag = |signal: &str, verdict: HealthVerdict, detail: String| {
+            reasons.push(HealthReason { signal: signal.to_string(), verdict, detail });
+        };
+
+        if let Some((healthy, configured)) = signals.rpc_providers {
+            if configured > 0 && healthy == 0 {
+                flag("rpc", HealthVerdict::Critical, format!("none of {} RPC providers are healthy", configured));
+            } else if healthy < configured {
+                flag("rpc", HealthVerdict::Degraded, format!("{} of {} RPC providers are healthy", healthy, configured));
+            }
+        }
+
+        if let Some(last_tick) = signals.last_loop_tick {
+            let age = now - last_tick;
+            if age > self.loop_dead_after {
+                flag("loop", HealthVerdict::Critical, format!("main loop last ran {}s ago", age.num_seconds()));
+            } else if age > self.loop_stale_after {
+                flag("loop", HealthVerdict::Degraded, format!("main loop last ran {}s ago", age.num_seconds()));
+            }
+        }
+
+        if let Some(verdict) = Self::above(signals.error_rate, self.error_rate_degraded, self.error_rate_critical) {
+            flag("errors", verdict, format!("{:.0}% of recent submissions failed", signals.error_rate * 100.0));
+        }
+
+        if let Some(verdict) = Self::above(signals.drawdown, self.drawdown_degraded, self.drawdown_critical) {
+            flag("drawdown", verdict, format!("{:.1}% drawdown from the session peak", signals.drawdown * 100.0));
+        }
+
+        if signals.trading_halted {
+            flag("trading", HealthVerdict::Critical, "trading is halted".to_string());
+        }
+        if signals.paused_wallets > 0 {
+            flag("wallets", HealthVerdict::Degraded, format!("{} wallet(s) paused after failures", signals.paused_wallets));
+        }
+        if signals.disabled_strategies > 0 {
+            flag("strategies", HealthVerdict::Degraded,
+                 format!("{} strateg(ies) disabled by their PnL breaker", signals.disabled_strategies));
+        }
+
+        if let Some(remaining) = signals.budget_remaining {
+            let verdict = if remaining <= self.budget_critical {
+                Some(HealthVerdict::Critical)
+            } else if remaining <= self.budget_degraded {
+                Some(HealthVerdict::Degraded)
+            } else {
+                None
+            };
+            if let Some(verdict) = verdict {
+                flag("budget", verdict, format!("{:.0}% of the budget remains", remaining * 100.0));
+            }
+        }
+
+        HealthSummary {
+            verdict: reasons.iter().map(|r| r.verdict).max().unwrap_or(HealthVerdict::Healthy),
+            reasons,
+            checked_at: now,
+        }
+    }
+
+    fn above(value: f64, degraded: f64, critical: f64) -> Option<HealthVerdict> {
+        if value >= critical {
+            Some(HealthVerdict::Critical)
+        } else if value >= degraded {
+            Some(HealthVerdict::Degraded)
+        } else {
+            None
+        }
+    }
+}
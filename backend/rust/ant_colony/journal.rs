@@ -0,0 +1,188 @@
+use anyhow::Result;
+use config::Config;
+use log::warn;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use crate::ant_colony::session_report::SessionReport;
+
+// Everything the bot saw or did for a trade, in enough detail to replay it afterwards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum JournalEvent {
+    Opened {
+        token_address: String,
+        entry_price: f64,
+        position_size: f64,
+    },
+    PriceUpdate {
+        price: f64,
+        unrealized_profits: f64,
+    },
+    Decision {
+        action: String,
+        reason: String,
+    },
+    TransactionSent {
+        signature: String,
+        amount: f64,
+    },
+    TransactionFailed {
+        error: String,
+    },
+    PositionReconciled {
+        tracked_size: f64,
+        on_chain_size: f64,
+    },
+    SessionSummary {
+        report: SessionReport,
+    },
+    PoolMigrated {
+        from_pool: String,
+        to_pool: String,
+        dex: String,
+    },
+}
+
+impl JournalEvent {
+    pub fn describe(&self) -> String {
+        match self {
+            JournalEvent::Opened { token_address, entry_price, position_size } => {
+                format!("Opened {} tokens of {} at {}", position_size, token_address, entry_price)
+            }
+            JournalEvent::PriceUpdate { price, unrealized_profits } => {
+                format!("Price {} (unrealized {} SOL)", price, unrealized_profits)
+            }
+            JournalEvent::Decision { action, reason } => {
+                format!("Decided to {}: {}", action, reason)
+            }
+            JournalEvent::TransactionSent { signature, amount } => {
+                format!("Sent transaction {} for {} tokens", signature, amount)
+            }
+            JournalEvent::TransactionFailed { error } => {
+                format!("Transaction failed: {}", error)
+            }
+            JournalEvent::PositionReconciled { tracked_size, on_chain_size } => {
+                format!("Position corrected from {} to {} tokens on-chain", tracked_size, on_chain_size)
+            }
+            JournalEvent::SessionSummary { report } => {
+                format!("Session ended: {} trades, {:.1}% win rate, net {} SOL after {} SOL fees",
+                        report.trades_taken, report.win_rate * 100.0, report.net_pnl, report.fees_spent)
+            }
+            JournalEvent::PoolMigrated { from_pool, to_pool, dex } => {
+                format!("Pool migrated from {} to {} on {}", from_pool, to_pool, dex)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub trade_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub event: JournalEvent,
+}
+
+// Append-only JSON lines file; a journal without a path records nothing
+#[derive(Debug, Default, Clone)]
+pub struct TradeJournal {
+    path: Option<PathBuf>,
+}
+
+impl TradeJournal {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path: Some(path) }
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if !config.get_bool("ant_colony.journal.enabled").unwrap_or(false) {
+            return Ok(Self::default());
+        }
+        Ok(Self::new(PathBuf::from(config.get_string("ant_colony.journal.path")?)))
+    }
+
+    // Journaling must never interrupt trading, so write failures are only logged
+    pub fn record(&self, trade_id: &str, event: JournalEvent) {
+        if let Err(e) = self.append(trade_id, event) {
+            warn!("Failed to journal event for trade {}: {}", trade_id, e);
+        }
+    }
+
+    fn append(&self, trade_id: &str, event: JournalEvent) -> Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let entry = JournalEntry {
+            trade_id: trade_id.to_string(),
+            timestamp: Utc::now(),
+            event,
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+
+    // Entries for one trade in the order they were recorded
+    pub fn replay(&self, trade_id: &str) -> Result<Vec<JournalEntry>> {
+        let path = match &self.path {
+            Some(path) if path.exists() => path,
+            _ => return Ok(Vec::new()),
+        };
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(std::fs::File::open(path)?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<JournalEntry>(&line) {
+                Ok(entry) if entry.trade_id == trade_id => entries.push(entry),
+                Ok(_) => {}
+                Err(e) => warn!("Skipping unreadable journal line: {}", e),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    // Signatures of every transaction the bot journaled as sent, across all trades
+    pub fn sent_signatures(&self) -> Result<HashSet<String>> {
+        let path = match &self.path {
+            Some(path) if path.exists() => path,
+            _ => return Ok(HashSet::new()),
+        };
+
+        let mut signatures = HashSet::new();
+        for line in BufReader::new(std::fs::File::open(path)?).lines() {
+            let line = line?;
+            if let Ok(JournalEntry { event: JournalEvent::TransactionSent { signature, .. }, .. }) =
+                serde_json::from_str::<JournalEntry>(&line) {
+                signatures.insert(signature);
+            }
+        }
+
+        Ok(signatures)
+    }
+
+    pub fn format_timeline(trade_id: &str, entries: &[JournalEntry]) -> String {
+        let mut timeline = format!("Replay of trade {} ({} events)\n", trade_id, entries.len());
+        for (step, entry) in entries.iter().enumerate() {
+            timeline.push_str(&format!(
+                "{:>3}. {}  {}\n",
+                step + 1,
+                entry.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+                entry.event.describe()
+            ));
+        }
+        timeline
+    }
+}
@@ -3,20 +3,81 @@ mod queen;
 mod princess;
 mod worker;
 mod sentry;
+mod monitor_budget;
+mod capital_manager;
+mod profit_manager;
+mod rug_detector;
+mod transaction_handler;
+mod blockhash_cache;
+mod blacklist;
+mod reconciliation;
+mod journal;
+mod wallet_lock;
+mod pending_confirmations;
+mod session_report;
+mod pool_migration;
+mod price_history;
+mod gas_price;
+mod wallet_health;
+mod strategy_breaker;
+mod health;
+mod token_stats;
+mod wallet_guard;
+mod risk_governor;
+mod portfolio;
+mod wallet_pool;
+mod scaling;
+mod dashboard;
 
 use anyhow::Result;
 use config::Config;
-use log::{info, error};
+use log::{info, warn, error};
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
 use std::sync::Arc;
+use std::path::{Path, PathBuf};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use async_trait::async_trait;
+use crate::common::{AlertForwarder, Message, MessageKind, Metrics, MessageQueue, OverflowPolicy, SwapExecutor};
+use crate::api::WebSocketServer;
+use crate::backend::DashboardWebSocket;
+use crate::sniping_core::ColonyServices;
+use crate::logging::ErrorReporter;
+use crate::config::RpcConfig;
+use crate::rpc::RpcClientManager;
 
 // Re-export types for external use
 pub use drone::Drone;
 pub use queen::Queen;
 pub use princess::Princess;
-pub use worker::Worker;
-pub use sentry::Sentry;
+pub use worker::{Worker, ProfitDistribution};
+pub use sentry::{Sentry, AlertSeverity};
+pub use capital_manager::{CapitalManager, CapitalBudget};
+pub use profit_manager::{ProfitManager, ProfitTier, TradeProfit, ExitSimulation, SimulatedSell};
+pub use rug_detector::{RugDetector, RugAlert, RugAlertType, RugAlertSeverity};
+pub use transaction_handler::{TransactionHandler, TransactionBundle, TransactionResult, PaperTrade, SimulationResult};
+pub use blockhash_cache::{BlockhashCache, BlockhashSource, RpcBlockhashSource, CachedBlockhash};
+pub use blacklist::{TokenBlacklist, BlacklistEntry};
+pub use reconciliation::{BalanceSource, RpcBalanceSource, PositionDrift, PositionReconciler};
+pub use journal::{TradeJournal, JournalEntry, JournalEvent};
+pub use wallet_lock::{WalletLock, LockOwner};
+pub use pending_confirmations::{PendingConfirmations, PendingSlot};
+pub use session_report::{SessionStats, SessionReport, SESSION_JOURNAL_ID};
+pub use wallet_health::{WalletHealthMonitor, WalletPaused};
+pub use strategy_breaker::{StrategyBreakers, StrategyDisabled};
+pub use token_stats::{TokenStats, TokenPerformance, TokenStatsSnapshot, kelly_fraction};
+pub use wallet_guard::{CompromiseGuard, WalletCompromised, WalletActivitySource, RpcWalletActivity, ObservedTransaction};
+pub use risk_governor::{RiskGovernor, TradingFrozen};
+pub use portfolio::{Portfolio, PortfolioSummary, ClosedTrade, TradeStats};
+pub use wallet_pool::WalletPool;
+pub use scaling::ColonyScaler;
+pub use dashboard::{WorkerSnapshot, TierState, ProfitTierStatus, AlertRecord, PerformancePoint};
+pub use monitor_budget::{MonitorBudget, MonitorPriority, MonitorAdmission};
+pub use health::{ColonyHealth, HealthSignals, HealthSummary, HealthReason, HealthVerdict};
+pub use price_history::{Candle, CandleSource, GeckoTerminalCandles, VolatilityTracker};
+pub use gas_price::{GasPriceSource, HandlerGasPriceSource, GasPriceHistory};
+pub use pool_migration::{PoolLocator, DexScreenerPoolLocator, PoolInfo, PoolMigration, PoolMigrationDetector};
 
 // Shared state for the Ant Colony
 #[derive(Default)]
@@ -25,6 +86,101 @@ pub struct ColonyState {
     pub total_capital: f64,
     pub active_trades: u32,
     pub risk_level: f64, // 0.0 to 1.0
+    pub total_profit: f64, // Net SOL realized by profit-taking sells
+    pub blacklist: TokenBlacklist,
+    pub pending_confirmations: Arc<PendingConfirmations>,
+    pub session: Arc<SessionStats>,
+    pub portfolio: Arc<Portfolio>, // Open positions and closed-trade PnL across all princesses
+    pub wallet_health: Arc<WalletHealthMonitor>,
+    pub strategy_breakers: Arc<StrategyBreakers>,
+    pub risk_governor: Arc<RiskGovernor>, // Colony-wide daily loss and trade limits
+    pub token_stats: TokenStats,
+    pub compromise_guard: Arc<CompromiseGuard>,
+    pub monitor_budget: Arc<MonitorBudget>,
+    pub capital_budget: Arc<CapitalBudget>, // Every princess's capital manager reports into it
+    pub rpc_manager: Option<Arc<RpcClientManager>>, // Shared with the sniping core's radar; None without rpc.toml
+    pub last_loop_tick: Option<DateTime<Utc>>, // Last pass of the queen's monitoring loop
+    pub paper_trading: bool, // Fills are simulated; nothing reaches the chain
+    pub metrics: Metrics, // Exported from the dashboard server's `/metrics`
+    pub total_trades: u64,
+    pub successful_trades: u64,
+    pub total_gas_spent: f64, // SOL
+    pub active_workers: Vec<WorkerSnapshot>, // One per princess, refreshed as it trades
+    pub profit_tiers: Vec<ProfitTierStatus>,
+    pub alerts: VecDeque<AlertRecord>,                 // Most recent last, capped at MAX_DASHBOARD_HISTORY
+    pub performance_history: VecDeque<PerformancePoint>, // Most recent last, capped at MAX_DASHBOARD_HISTORY
+}
+
+// Dashboard history kept in memory; older entries are dropped first
+pub const MAX_DASHBOARD_HISTORY: usize = 500;
+
+impl ColonyState {
+    pub fn record_alert(&mut self, alert: AlertRecord) {
+        self.alerts.push_back(alert);
+        if self.alerts.len() > MAX_DASHBOARD_HISTORY {
+            self.alerts.pop_front();
+        }
+    }
+
+    // Replaces the snapshot with the same id, or adds it for a new worker
+    pub fn update_worker(&mut self, snapshot: WorkerSnapshot) {
+        match self.active_workers.iter_mut().find(|worker| worker.id == snapshot.id) {
+            Some(worker) => *worker = snapshot,
+            None => self.active_workers.push(snapshot),
+        }
+    }
+
+    pub fn remove_worker(&mut self, id: &str) {
+        self.active_workers.retain(|worker| worker.id != id);
+    }
+
+    // Lists the sell ladder as pending; rungs already listed keep their state
+    pub fn track_profit_tiers(&mut self, tiers: &[ProfitTier]) {
+        for tier in tiers {
+            if !self.profit_tiers.iter().any(|status| status.multiplier == tier.multiplier) {
+                self.profit_tiers.push(ProfitTierStatus {
+                    multiplier: tier.multiplier,
+                    percentage: tier.percentage,
+                    status: TierState::Pending,
+                    timestamp: None,
+                });
+            }
+        }
+    }
+
+    pub fn record_tier_hit(&mut self, multiplier: f64) {
+        if let Some(status) = self.profit_tiers.iter_mut().find(|status| status.multiplier == multiplier) {
+            status.status = TierState::Hit;
+            status.timestamp = Some(Utc::now());
+        }
+    }
+
+    // Books a filled sell for the dashboard's trade metrics and performance chart
+    pub fn record_fill(&mut self, profit: f64, gas_fees: f64) {
+        self.total_trades += 1;
+        if profit > 0.0 {
+            self.successful_trades += 1;
+        }
+        self.total_gas_spent += gas_fees;
+        self.performance_history.push_back(PerformancePoint { timestamp: Utc::now(), profit, gas_fees });
+        if self.performance_history.len() > MAX_DASHBOARD_HISTORY {
+            self.performance_history.pop_front();
+        }
+    }
+
+    pub fn health_signals(&self) -> HealthSignals {
+        HealthSignals {
+            rpc_providers: self.rpc_manager.as_ref().map(|rpc_manager| rpc_manager.provider_health()),
+            last_loop_tick: self.last_loop_tick,
+            error_rate: self.wallet_health.overall_failure_rate(),
+            drawdown: self.session.drawdown(self.total_capital),
+            trading_halted: !self.is_active,
+            paused_wallets: self.wallet_health.paused_count() + self.compromise_guard.halted_count(),
+            disabled_strategies: self.strategy_breakers.disabled_count(),
+            budget_remaining: self.capital_budget.remaining(),
+            ..HealthSignals::default()
+        }
+    }
 }
 
 #[async_trait]
@@ -42,12 +198,79 @@ pub struct AntColony {
     workers: Vec<Arc<RwLock<Worker>>>,
     sentries: Vec<Arc<RwLock<Sentry>>>,
     state: Arc<RwLock<ColonyState>>,
+    transaction_handler: Arc<RwLock<TransactionHandler>>, // Shared by every princess
+    message_queue: MessageQueue, // Carries alerts and updates between the colony and the sniping core
+    swap_executor: Option<Arc<SwapExecutor>>, // Signs with the trading wallet; None leaves swaps dry runs
+    blockhash_cache: Option<Arc<BlockhashCache>>, // Shared by the handler and the swap executor
+    blockhash_refresh: Option<JoinHandle<()>>, // The cache's background refresh, started by `init`
+    dashboard_feed: Arc<DashboardWebSocket>, // Colony snapshots and trade executions for dashboard clients
+    dashboard_interval: std::time::Duration,
+    alert_recorder: Option<JoinHandle<()>>, // Copies colony alerts into the dashboard's history
+    alert_forwarder: Option<JoinHandle<()>>, // Sends High and Critical alerts to Telegram and Discord
+    queen_monitor: Option<JoinHandle<()>>, // The queen's monitoring loop, which publishes colony health
+    reconciler: Option<Arc<PositionReconciler>>, // Checks every princess's positions against on-chain balances
+    reconciliation: Option<JoinHandle<()>>, // The reconciler's loop, started with coordination
+    journal: TradeJournal,
+    session_report_enabled: bool,
+    health: ColonyHealth,
+    scaler: ColonyScaler,
+    error_tracker: Option<Arc<dyn ErrorReporter>>, // Sentry, when a DSN is configured
+    config: Config, // Kept to build princesses spawned after startup
 }
 
 impl AntColony {
     pub async fn new(config: &Config) -> Result<Self> {
-        let state = Arc::new(RwLock::new(ColonyState::default()));
+        let data_dir = config.get_string("general.data_dir")?;
+        let blacklist = TokenBlacklist::load(PathBuf::from(&data_dir).join("blacklist.json"))?;
+        blacklist.set_whitelist_only(config.get_bool("ant_colony.blacklist.whitelist_only").unwrap_or(false));
+        let token_stats = TokenStats::load(PathBuf::from(&data_dir).join("token_stats.json"), config)?;
+        let max_pending = config.get_int("ant_colony.max_pending_confirmations")? as usize;
+        let compromise_guard = Arc::new(CompromiseGuard::new(config));
+        let state = Arc::new(RwLock::new(ColonyState {
+            blacklist,
+            pending_confirmations: Arc::new(PendingConfirmations::new(max_pending)),
+            wallet_health: Arc::new(WalletHealthMonitor::new(config)),
+            strategy_breakers: Arc::new(StrategyBreakers::new(config)),
+            risk_governor: Arc::new(RiskGovernor::new(config)),
+            token_stats,
+            compromise_guard: compromise_guard.clone(),
+            monitor_budget: Arc::new(MonitorBudget::new(config)),
+            paper_trading: config.get_bool("general.paper_trading").unwrap_or(false),
+            ..ColonyState::default()
+        }));
         let queen = Arc::new(RwLock::new(Queen::new(config, state.clone()).await?));
+        let blockhash_cache = if config.get_bool("ant_colony.transaction_handler.blockhash_cache.enabled").unwrap_or(true) {
+            let source = RpcBlockhashSource::new(config.get_string("ant_colony.transaction_handler.helius_rpc_url")?);
+            Some(Arc::new(BlockhashCache::new(config, Arc::new(source))))
+        } else {
+            None
+        };
+        let mut transaction_handler = TransactionHandler::new(config).await?;
+        transaction_handler.set_compromise_guard(compromise_guard);
+        transaction_handler.set_session(state.read().await.session.clone());
+        if let Some(cache) = &blockhash_cache {
+            transaction_handler.set_blockhash_cache(cache.clone());
+        }
+        let transaction_handler = Arc::new(RwLock::new(transaction_handler));
+        let swap_executor = SwapExecutor::from_config(config, transaction_handler.clone())?.map(|mut executor| {
+            if let Some(cache) = &blockhash_cache {
+                executor.set_blockhash_cache(cache.clone());
+            }
+            Arc::new(executor)
+        });
+        // One set of RPC pools for the colony's health and the sniping core's radar
+        match config.clone().try_deserialize::<RpcConfig>() {
+            Ok(rpc_config) => {
+                let mut rpc_manager = RpcClientManager::new(&rpc_config).await?;
+                let mut state = state.write().await;
+                rpc_manager.set_metrics(state.metrics.clone());
+                state.rpc_manager = Some(Arc::new(rpc_manager));
+            }
+            Err(e) => warn!("No RPC providers configured, colony health won't judge RPC: {}", e),
+        }
+        let session_report_enabled = config.get_bool("ant_colony.session_report.enabled").unwrap_or(true);
+        let message_queue = MessageQueue::new(config.get_int("general.message_queue_capacity").unwrap_or(1024) as usize);
+        state.read().await.risk_governor.set_message_queue(message_queue.clone());
         
         Ok(Self {
             queen,
@@ -56,12 +279,43 @@ impl AntColony {
             workers: Vec::new(),
             sentries: Vec::new(),
             state,
+            transaction_handler,
+            message_queue,
+            swap_executor,
+            blockhash_cache,
+            blockhash_refresh: None,
+            dashboard_feed: Arc::new(DashboardWebSocket::new(state.clone())),
+            dashboard_interval: std::time::Duration::from_millis(
+                config.get_int("api.dashboard_interval_ms").unwrap_or(1000) as u64
+            ),
+            alert_recorder: None,
+            alert_forwarder: None,
+            queen_monitor: None,
+            reconciler: PositionReconciler::from_config(config)?.map(Arc::new),
+            reconciliation: None,
+            journal: TradeJournal::from_config(config)?,
+            session_report_enabled,
+            health: ColonyHealth::new(config),
+            scaler: ColonyScaler::new(config),
+            error_tracker: None,
+            config: config.clone(),
         })
     }
 
     pub async fn init(&mut self, config: &Config) -> Result<()> {
         info!("Initializing Ant Colony System...");
 
+        if let Some(cache) = &self.blockhash_cache {
+            self.blockhash_refresh = Some(cache.clone().start());
+        }
+        self.dashboard_feed.forward_trade_executions(&self.message_queue).await;
+        self.alert_recorder = Some(self.record_alerts().await);
+        let alert_forwarder = AlertForwarder::new(config)?;
+        if alert_forwarder.is_enabled() {
+            self.alert_forwarder = Some(tokio::spawn(alert_forwarder.run(self.message_queue.clone())));
+        }
+        self.dashboard_feed.clone().start_broadcast_loop(self.dashboard_interval);
+
         // Initialize components
         self.init_drones(config).await?;
         self.init_princesses(config).await?;
@@ -86,13 +340,125 @@ impl AntColony {
 
     async fn init_princesses(&mut self, config: &Config) -> Result<()> {
         let princess_count = config.get_int("ant_colony.princess_count")? as usize;
-        for _ in 0..princess_count {
-            let princess = Arc::new(RwLock::new(Princess::new(config, self.state.clone()).await?));
-            self.princesses.push(princess);
+        for index in 0..princess_count {
+            let princess = self.build_princess(config, index).await?;
+            self.princesses.push(Arc::new(RwLock::new(princess)));
+        }
+        Ok(())
+    }
+
+    async fn build_princess(&self, config: &Config, index: usize) -> Result<Princess> {
+        let capital_manager = Arc::new(RwLock::new(CapitalManager::new(config, self.state.clone()).await?));
+        let mut profit_manager = ProfitManager::new(config, self.state.clone()).await?;
+        if let Some(swap_executor) = &self.swap_executor {
+            profit_manager.set_swap_executor(swap_executor.clone());
+        }
+        // Tier decisions net out the fee the shared handler will actually bid on the sell
+        if config.get_bool("ant_colony.profit_manager.gas.enabled").unwrap_or(false) {
+            profit_manager.set_gas_price_source(Arc::new(HandlerGasPriceSource::new(self.transaction_handler.clone())));
+        }
+        let profit_manager = Arc::new(RwLock::new(profit_manager));
+        let mut rug_detector = RugDetector::new(config, self.state.clone()).await?;
+        rug_detector.set_message_queue(self.message_queue.clone());
+        if let Some(error_tracker) = &self.error_tracker {
+            rug_detector.set_error_tracker(error_tracker.clone());
+        }
+        let rug_detector = Arc::new(RwLock::new(rug_detector));
+        let wallet_pool = WalletPool::from_config(config, index)?;
+        if let Some(reconciler) = &self.reconciler {
+            let wallets = wallet_pool.as_ref().map(WalletPool::addresses).unwrap_or_default();
+            reconciler.register(profit_manager.clone(), wallets);
+        }
+        let mut princess = Princess::new(
+            config, self.state.clone(), capital_manager, profit_manager, rug_detector, self.transaction_handler.clone(),
+        ).await?;
+        if let Some(wallet_pool) = wallet_pool {
+            princess.set_wallet_pool(Arc::new(wallet_pool));
+        }
+        if let Some(swap_executor) = &self.swap_executor {
+            princess.set_swap_executor(swap_executor.clone());
+        }
+        Ok(princess)
+    }
+
+    // A spawned princess takes its capital allocation from the colony straight away
+    async fn spawn_princess(&mut self) -> Result<()> {
+        let mut princess = self.build_princess(&self.config, self.princesses.len()).await?;
+        princess.init().await?;
+        info!("Spawned Princess {}", princess.get_id());
+        self.princesses.push(Arc::new(RwLock::new(princess)));
+        Ok(())
+    }
+
+    // The newest idle princess goes first and hands its capital back to the colony. Princesses
+    // with open trades are left alone, since their capital is still out in those positions.
+    // Returns false when every princess has open trades.
+    async fn despawn_princess(&mut self) -> Result<bool> {
+        let mut idle = None;
+        for (index, princess) in self.princesses.iter().enumerate().rev() {
+            if princess.read().await.open_position_count().await == 0 {
+                idle = Some(index);
+                break;
+            }
+        }
+        let Some(index) = idle else {
+            return Ok(false);
+        };
+
+        let princess = self.princesses.remove(index);
+        let princess = princess.read().await;
+        princess.shutdown().await?;
+        let released = princess.release_capital().await;
+        if let Some(reconciler) = &self.reconciler {
+            reconciler.unregister(&princess.profit_manager());
+        }
+        self.state.write().await.remove_worker(princess.get_id());
+        info!("Despawned Princess {}, returned {} to the colony", princess.get_id(), released);
+        Ok(true)
+    }
+
+    async fn scale_princesses(&mut self, target: usize) -> Result<()> {
+        while self.princesses.len() < target {
+            self.spawn_princess().await?;
+        }
+        while self.princesses.len() > target {
+            if !self.despawn_princess().await? {
+                warn!("Every princess has open trades, holding the colony at {} princesses instead of {}",
+                      self.princesses.len(), target);
+                break;
+            }
         }
         Ok(())
     }
 
+    // Bull markets grow the colony, bear markets shrink it
+    pub async fn adjust_for_market_conditions(&mut self, bull_market: bool) -> Result<usize> {
+        let current = self.princesses.len();
+        let target = self.scaler.target(current, bull_market);
+        info!("Scaling colony for {} market: {} -> {} princesses",
+              if bull_market { "bull" } else { "bear" }, current, target);
+        self.scale_princesses(target).await?;
+        Ok(self.princesses.len())
+    }
+
+    pub fn get_princess_count(&self) -> usize {
+        self.princesses.len()
+    }
+
+    pub async fn update_performance_metrics(&mut self, success_rate: f64) -> Result<()> {
+        self.scaler.record_success_rate(success_rate)?;
+        self.state.read().await.metrics.set_success_rate(success_rate);
+        Ok(())
+    }
+
+    pub async fn should_scale_up(&self) -> bool {
+        self.scaler.should_scale_up()
+    }
+
+    pub async fn should_scale_down(&self) -> bool {
+        self.scaler.should_scale_down()
+    }
+
     async fn init_workers(&mut self, config: &Config) -> Result<()> {
         let worker_count = config.get_int("ant_colony.worker_count")? as usize;
         for _ in 0..worker_count {
@@ -111,13 +477,12 @@ impl AntColony {
         Ok(())
     }
 
-    async fn start_coordination(&self) -> Result<()> {
-        let mut state = self.state.write().await;
-        state.is_active = true;
+    async fn start_coordination(&mut self) -> Result<()> {
+        self.state.write().await.is_active = true;
 
         // Start all components
-        let queen = self.queen.read().await;
-        queen.run().await?;
+        self.queen_monitor = Some(tokio::spawn(Queen::run(self.queen.clone())));
+        self.reconciliation = self.reconciler.clone().map(PositionReconciler::start);
 
         for drone in &self.drones {
             let drone = drone.read().await;
@@ -143,12 +508,18 @@ impl AntColony {
     }
 
     pub async fn shutdown(&self) -> Result<()> {
+        // Stopped first: a pass holds the queen while it waits on the state
+        if let Some(queen_monitor) = &self.queen_monitor {
+            queen_monitor.abort();
+        }
+        if let Some(reconciliation) = &self.reconciliation {
+            reconciliation.abort();
+        }
         let mut state = self.state.write().await;
         state.is_active = false;
 
         // Shutdown all components
-        let queen = self.queen.read().await;
-        queen.shutdown().await?;
+        self.queen.write().await.shutdown().await?;
 
         for drone in &self.drones {
             let drone = drone.read().await;
@@ -170,18 +541,121 @@ impl AntColony {
             sentry.shutdown().await?;
         }
 
+        if let Some(blockhash_refresh) = &self.blockhash_refresh {
+            blockhash_refresh.abort();
+        }
+        self.dashboard_feed.shutdown();
+        if let Some(alert_recorder) = &self.alert_recorder {
+            alert_recorder.abort();
+        }
+        if let Some(alert_forwarder) = &self.alert_forwarder {
+            alert_forwarder.abort();
+        }
+
+        if self.session_report_enabled {
+            let report = state.session.report();
+            info!("{}", report);
+            self.journal.record(SESSION_JOURNAL_ID, JournalEvent::SessionSummary { report });
+        }
+
         Ok(())
     }
+
+    // The queen publishes the colony's health to it on every monitoring pass
+    pub async fn set_dashboard(&self, dashboard: WebSocketServer) {
+        self.queen.write().await.set_dashboard(dashboard);
+    }
+
+    // Reports from the shared handler, every rug detector built after this, and the sniping
+    // core's coin scanner through `services`
+    pub async fn set_error_tracker(&mut self, error_tracker: Arc<dyn ErrorReporter>) {
+        self.transaction_handler.write().await.set_error_tracker(error_tracker.clone());
+        self.error_tracker = Some(error_tracker);
+    }
+
+    async fn record_alerts(&self) -> JoinHandle<()> {
+        let mut alerts = self.message_queue.subscribe_filtered(
+            "colony_alert_history".to_string(),
+            MessageKind::COLONY_ALERT,
+            self.message_queue.default_capacity(),
+            OverflowPolicy::DropOldest,
+        ).await;
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            while let Some(message) = alerts.recv().await {
+                if let Message::ColonyAlert(alert) = &message {
+                    state.write().await.record_alert(AlertRecord::from(alert));
+                }
+            }
+        })
+    }
+
+    pub fn dashboard_feed(&self) -> Arc<DashboardWebSocket> {
+        self.dashboard_feed.clone()
+    }
+
+    pub async fn session_report(&self) -> SessionReport {
+        self.state.read().await.session.report()
+    }
+
+    pub async fn health(&self) -> HealthSummary {
+        self.health.evaluate(&self.state.read().await.health_signals())
+    }
+
+    // Manual blacklisting, e.g. from the operator; persisted like the rug detector's entries
+    pub async fn blacklist_token(&self, token_address: &str) -> Result<bool> {
+        self.state.read().await.blacklist.add(token_address, "Blacklisted manually")
+    }
+
+    pub async fn is_token_blacklisted(&self, token_address: &str) -> bool {
+        self.state.read().await.blacklist.contains(token_address)
+    }
+
+    // Shared handle for components outside the colony state, such as the sniping core
+    pub async fn blacklist(&self) -> TokenBlacklist {
+        self.state.read().await.blacklist.clone()
+    }
+
+    // Everything the sniping core trades against alongside the colony
+    pub async fn services(&self) -> ColonyServices {
+        let state = self.state.read().await;
+        ColonyServices {
+            blacklist: Some(state.blacklist.clone()),
+            risk_governor: Some(state.risk_governor.clone()),
+            message_queue: Some(self.message_queue.clone()),
+            swap_executor: self.swap_executor.clone(),
+            portfolio: Some(state.portfolio.clone()),
+            metrics: Some(state.metrics.clone()),
+            rpc_manager: state.rpc_manager.clone(),
+            error_tracker: self.error_tracker.clone(),
+        }
+    }
+
+    pub async fn portfolio_summary(&self) -> PortfolioSummary {
+        self.state.read().await.portfolio.summary()
+    }
+
+    pub async fn export_portfolio_csv(&self, path: &Path) -> Result<()> {
+        self.state.read().await.portfolio.export_csv(path)
+    }
 }
 
 // Global instance for the Ant Colony
 static mut ANT_COLONY: Option<Arc<RwLock<AntColony>>> = None;
 
-pub async fn init(config: &Config) -> Result<()> {
+// `dashboard`, when given, serves the colony's health from /status; `error_tracker`, when
+// given, receives the colony's and the sniping core's failures
+pub async fn init(config: &Config, dashboard: Option<WebSocketServer>, error_tracker: Option<Arc<dyn ErrorReporter>>) -> Result<()> {
     unsafe {
         if ANT_COLONY.is_none() {
-            let colony = Arc::new(RwLock::new(AntColony::new(config).await?));
-            ANT_COLONY = Some(colony);
+            let mut colony = AntColony::new(config).await?;
+            if let Some(dashboard) = dashboard {
+                colony.set_dashboard(dashboard).await;
+            }
+            if let Some(error_tracker) = error_tracker {
+                colony.set_error_tracker(error_tracker).await;
+            }
+            ANT_COLONY = Some(Arc::new(RwLock::new(colony)));
         }
         
         if let Some(colony) = &ANT_COLONY {
@@ -192,6 +666,26 @@ pub async fn init(config: &Config) -> Result<()> {
     Ok(())
 }
 
+// The running colony's shared services; empty before `init`
+pub async fn services() -> ColonyServices {
+    unsafe {
+        match &ANT_COLONY {
+            Some(colony) => colony.read().await.services().await,
+            None => ColonyServices::default(),
+        }
+    }
+}
+
+// The running colony's dashboard feed; None before `init`
+pub async fn dashboard_feed() -> Option<Arc<DashboardWebSocket>> {
+    unsafe {
+        match &ANT_COLONY {
+            Some(colony) => Some(colony.read().await.dashboard_feed()),
+            None => None,
+        }
+    }
+}
+
 pub async fn shutdown() -> Result<()> {
     unsafe {
         if let Some(colony) = &ANT_COLONY {
@@ -0,0 +1,124 @@
+use config::Config;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+// Why a token is watched. Held positions outrank every candidate; candidates rank by risk.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MonitorPriority {
+    Candidate { risk: f64 },
+    Held,
+}
+
+impl MonitorPriority {
+    fn rank(&self) -> (u8, f64) {
+        match self {
+            MonitorPriority::Candidate { risk } => (0, *risk),
+            MonitorPriority::Held => (1, 0.0),
+        }
+    }
+
+    fn outranks(&self, other: &MonitorPriority) -> bool {
+        self.rank() > other.rank()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MonitorAdmission {
+    Admitted { evicted: Option<String> }, // Lower priority token dropped to make room
+    AlreadyMonitored,                     // Another sentry already watches the token
+    Rejected,
+}
+
+#[derive(Debug, Clone)]
+struct MonitorSlot {
+    owner: String,
+    priority: MonitorPriority,
+}
+
+// Colony-wide cap on monitored tokens, shared by every sentry so adding sentries doesn't
+// multiply RPC load. Each token is watched by one sentry at most; once the cap is reached a
+// new token only gets in by displacing a lower priority one.
+#[derive(Debug, Default)]
+pub struct MonitorBudget {
+    max_monitors: Option<usize>, // None leaves the count uncapped
+    slots: Mutex<HashMap<String, MonitorSlot>>,
+}
+
+impl MonitorBudget {
+    pub fn new(config: &Config) -> Self {
+        let max_monitors = if config.get_bool("ant_colony.monitor_budget.enabled").unwrap_or(false) {
+            Some(config.get_int("ant_colony.monitor_budget.max_monitors").unwrap_or(50) as usize)
+        } else {
+            None
+        };
+        Self {
+            max_monitors,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn acquire(&self, token_address: &str, owner: &str, priority: MonitorPriority) -> MonitorAdmission {
+        let mut slots = self.slots.lock().unwrap();
+
+        if let Some(slot) = slots.get_mut(token_address) {
+            // A position opened on a watched candidate keeps its slot at the higher priority
+            if priority.outranks(&slot.priority) {
+                slot.priority = priority;
+            }
+            return if slot.owner == owner {
+                MonitorAdmission::Admitted { evicted: None }
+            } else {
+                MonitorAdmission::AlreadyMonitored
+            };
+        }
+
+        let mut evicted = None;
+        if let Some(max_monitors) = self.max_monitors {
+            if slots.len() >= max_monitors {
+                let weakest = slots.iter()
+                    .filter(|(_, slot)| priority.outranks(&slot.priority))
+                    .min_by(|(_, a), (_, b)| {
+                        a.priority.rank().partial_cmp(&b.priority.rank()).unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .map(|(token, _)| token.clone());
+
+                match weakest {
+                    Some(token) => {
+                        info!("Monitor budget full ({}): dropping {} for higher priority {}",
+                              max_monitors, token, token_address);
+                        slots.remove(&token);
+                        evicted = Some(token);
+                    }
+                    None => {
+                        warn!("Monitor budget full ({}): not monitoring {}", max_monitors, token_address);
+                        return MonitorAdmission::Rejected;
+                    }
+                }
+            }
+        }
+
+        slots.insert(token_address.to_string(), MonitorSlot { owner: owner.to_string(), priority });
+        MonitorAdmission::Admitted { evicted }
+    }
+
+    pub fn release(&self, token_address: &str, owner: &str) {
+        let mut slots = self.slots.lock().unwrap();
+        if slots.get(token_address).map_or(false, |slot| slot.owner == owner) {
+            slots.remove(token_address);
+        }
+    }
+
+    // False once the token was evicted or handed to another sentry
+    pub fn holds(&self, token_address: &str, owner: &str) -> bool {
+        self.slots.lock().unwrap().get(token_address).map_or(false, |slot| slot.owner == owner)
+    }
+
+    pub fn priority(&self, token_address: &str) -> Option<MonitorPriority> {
+        self.slots.lock().unwrap().get(token_address).map(|slot| slot.priority)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.lock().unwrap().len()
+    }
+}
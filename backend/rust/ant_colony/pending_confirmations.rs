@@ -0,0 +1,61 @@
+use log::info;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+// Caps how many buys may be submitted but not yet confirmed across the colony. While a
+// congested network holds confirmations back the colony's real exposure is unknown, so
+// further submissions wait for a slot instead of piling on.
+#[derive(Debug)]
+pub struct PendingConfirmations {
+    semaphore: Arc<Semaphore>,
+    max_pending: usize,
+}
+
+// Held from submission until the transaction confirms or fails; dropping it frees the slot
+#[derive(Debug)]
+pub struct PendingSlot {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl PendingConfirmations {
+    pub fn new(max_pending: usize) -> Self {
+        let max_pending = max_pending.clamp(1, Semaphore::MAX_PERMITS);
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_pending)),
+            max_pending,
+        }
+    }
+
+    // Waits until fewer than `max_pending` submissions are awaiting confirmation
+    pub async fn reserve(&self) -> PendingSlot {
+        if self.semaphore.available_permits() == 0 {
+            info!("{} submissions awaiting confirmation, deferring new submission", self.max_pending);
+        }
+        let permit = self.semaphore.clone()
+            .acquire_owned()
+            .await
+            .expect("pending confirmation semaphore is never closed");
+        PendingSlot { _permit: permit }
+    }
+
+    pub fn try_reserve(&self) -> Option<PendingSlot> {
+        self.semaphore.clone()
+            .try_acquire_owned()
+            .ok()
+            .map(|permit| PendingSlot { _permit: permit })
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.max_pending - self.semaphore.available_permits()
+    }
+
+    pub fn max_pending(&self) -> usize {
+        self.max_pending
+    }
+}
+
+impl Default for PendingConfirmations {
+    fn default() -> Self {
+        Self::new(Semaphore::MAX_PERMITS)
+    }
+}
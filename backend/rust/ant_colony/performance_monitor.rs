@@ -5,7 +5,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc, Duration};
-use crate::ant_colony::ColonyState;
+use crate::ant_colony::{ColonyState, ColonyScaler};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
@@ -36,20 +36,15 @@ pub struct PerformanceMonitor {
     metrics_history: Vec<PerformanceMetrics>,
     last_scaling_check: DateTime<Utc>,
     check_interval: i32, // minutes
-    success_rate_threshold_low: f64,
-    success_rate_threshold_high: f64,
+    scaler: ColonyScaler, // Same success-rate policy the colony scales princesses with
     min_workers: u32,
-    max_workers: u32,
     metrics_window: i32, // hours
 }
 
 impl PerformanceMonitor {
     pub async fn new(config: &Config, state: Arc<RwLock<ColonyState>>) -> Result<Self> {
         let check_interval = config.get_int("ant_colony.performance_monitor.check_interval")? as i32;
-        let success_rate_threshold_low = config.get_float("ant_colony.performance_monitor.success_rate_threshold_low")? as f64;
-        let success_rate_threshold_high = config.get_float("ant_colony.performance_monitor.success_rate_threshold_high")? as f64;
         let min_workers = config.get_int("ant_colony.performance_monitor.min_workers")? as u32;
-        let max_workers = config.get_int("ant_colony.performance_monitor.max_workers")? as u32;
         let metrics_window = config.get_int("ant_colony.performance_monitor.metrics_window")? as i32;
 
         Ok(Self {
@@ -59,10 +54,8 @@ impl PerformanceMonitor {
             metrics_history: Vec::new(),
             last_scaling_check: Utc::now(),
             check_interval,
-            success_rate_threshold_low,
-            success_rate_threshold_high,
+            scaler: ColonyScaler::new(config),
             min_workers,
-            max_workers,
             metrics_window,
         })
     }
@@ -96,6 +89,7 @@ impl PerformanceMonitor {
             self.cleanup_old_metrics().await?;
             
             // Make scaling decision
+            self.scaler.record_success_rate(metrics.success_rate)?;
             if let Some(decision) = self.make_scaling_decision(&metrics).await? {
                 self.apply_scaling_decision(decision).await?;
             }
@@ -132,6 +126,10 @@ impl PerformanceMonitor {
             0.0
         };
 
+        state.metrics.set_success_rate(success_rate);
+        state.metrics.set_active_trades(state.active_trades);
+        state.metrics.set_total_profit(state.total_profit);
+
         Ok(PerformanceMetrics {
             success_rate,
             avg_execution_time_ms: avg_execution_time,
@@ -147,18 +145,16 @@ impl PerformanceMonitor {
 
     async fn make_scaling_decision(&self, metrics: &PerformanceMetrics) -> Result<Option<ScalingDecision>> {
         let state = self.state.read().await;
-        let current_workers = state.active_workers.len() as u32;
+        let current_workers = state.active_workers.iter().filter(|worker| worker.is_active).count() as u32;
         let mut target_workers = current_workers;
         let mut reason = String::new();
 
         // Check success rate
-        if metrics.success_rate < self.success_rate_threshold_low {
-            // Scale down
-            target_workers = (current_workers as f64 * 0.7).max(self.min_workers as f64) as u32;
+        if self.scaler.should_scale_down() {
+            target_workers = self.scaler.target(current_workers as usize, false) as u32;
             reason = format!("Low success rate: {:.2}%", metrics.success_rate * 100.0);
-        } else if metrics.success_rate > self.success_rate_threshold_high {
-            // Scale up
-            target_workers = (current_workers as f64 * 1.2).min(self.max_workers as f64) as u32;
+        } else if self.scaler.should_scale_up() {
+            target_workers = self.scaler.target(current_workers as usize, true) as u32;
             reason = format!("High success rate: {:.2}%", metrics.success_rate * 100.0);
         }
 
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolInfo {
+    pub pool_address: String,
+    pub dex: String,
+    pub liquidity: f64, // USD
+}
+
+// Where the colony looks up every pool currently trading a mint
+#[async_trait]
+pub trait PoolLocator: Send + Sync {
+    async fn pools_for(&self, token_address: &str) -> Result<Vec<PoolInfo>>;
+}
+
+pub struct DexScreenerPoolLocator {
+    client: Client,
+    base_url: String,
+}
+
+impl DexScreenerPoolLocator {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DexScreenerPairs {
+    #[serde(default)]
+    pairs: Option<Vec<DexScreenerPair>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DexScreenerPair {
+    pair_address: String,
+    dex_id: String,
+    #[serde(default)]
+    liquidity: Option<DexScreenerLiquidity>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DexScreenerLiquidity {
+    #[serde(default)]
+    usd: f64,
+}
+
+#[async_trait]
+impl PoolLocator for DexScreenerPoolLocator {
+    async fn pools_for(&self, token_address: &str) -> Result<Vec<PoolInfo>> {
+        let response: DexScreenerPairs = self.client
+            .get(format!("{}/{}", self.base_url.trim_end_matches('/'), token_address))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(response.pairs.unwrap_or_default().into_iter()
+            .map(|pair| PoolInfo {
+                pool_address: pair.pair_address,
+                dex: pair.dex_id,
+                liquidity: pair.liquidity.map_or(0.0, |l| l.usd),
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoolMigration {
+    pub token_address: String,
+    pub from_pool: String,
+    pub to_pool: String,
+    pub to_dex: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+// A mint has migrated once a different pool holds most of its liquidity, e.g. when a
+// pump.fun bonding curve graduates to Raydium and the curve is drained
+pub struct PoolMigrationDetector {
+    enabled: bool,
+    min_liquidity_share: f64, // Fraction of the mint's total liquidity the new pool must hold
+}
+
+impl PoolMigrationDetector {
+    pub fn new(config: &Config) -> Result<Self> {
+        Ok(Self {
+            enabled: config.get_bool("ant_colony.profit_manager.pool_migration.enabled").unwrap_or(false),
+            min_liquidity_share: config.get_float("ant_colony.profit_manager.pool_migration.min_liquidity_share")
+                .unwrap_or(0.8),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn detect(&self, token_address: &str, current_pool: &str, pools: &[PoolInfo]) -> Option<PoolMigration> {
+        if !self.enabled {
+            return None;
+        }
+
+        let total_liquidity: f64 = pools.iter().map(|p| p.liquidity.max(0.0)).sum();
+        if total_liquidity <= 0.0 {
+            return None;
+        }
+
+        let dominant = pools.iter()
+            .max_by(|a, b| a.liquidity.partial_cmp(&b.liquidity).unwrap_or(std::cmp::Ordering::Equal))?;
+        if dominant.pool_address == current_pool || dominant.liquidity / total_liquidity < self.min_liquidity_share {
+            return None;
+        }
+
+        Some(PoolMigration {
+            token_address: token_address.to_string(),
+            from_pool: current_pool.to_string(),
+            to_pool: dominant.pool_address.clone(),
+            to_dex: dominant.dex.clone(),
+            detected_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Mutex;
+use crate::ant_colony::TradeProfit;
+use crate::sniping_core::{TradeExecution, TradeStatus};
+
+// A position that has been fully sold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClosedTrade {
+    pub trade_id: String,
+    pub token_address: String,
+    pub entry_time: DateTime<Utc>,
+    pub exit_time: DateTime<Utc>,
+    pub entry_price: f64,
+    pub exit_price: f64,   // Price at the last sell
+    pub amount: f64,       // Tokens held when the position opened
+    pub realized_pnl: f64, // SOL, net of fees
+    pub fees: f64,         // SOL
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortfolioSummary {
+    pub open_positions: usize,
+    pub closed_trades: usize,
+    pub total_realized: f64,   // Closed trades plus partial sells of open ones
+    pub total_unrealized: f64,
+    pub win_rate: f64,         // Of closed trades
+    pub fees_paid: f64,
+}
+
+// Closed-trade outcomes, the inputs to Kelly sizing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TradeStats {
+    pub trades: usize,
+    pub win_rate: f64,
+    pub avg_win: f64,  // SOL, mean realized PnL of winners
+    pub avg_loss: f64, // SOL, mean size of losses as a positive number
+}
+
+#[derive(Debug)]
+struct OpenPosition {
+    trade: TradeProfit,
+    amount: f64, // Size at open; `trade.position_size` shrinks as tiers sell
+}
+
+#[derive(Debug, Default)]
+struct Positions {
+    open: HashMap<String, OpenPosition>,
+    closed: Vec<ClosedTrade>,
+}
+
+// Open positions and closed-trade PnL across every princess, shared through `ColonyState`
+#[derive(Debug, Default)]
+pub struct Portfolio {
+    positions: Mutex<Positions>,
+}
+
+impl Portfolio {
+    // Latest state of a position as the profit manager tracks it; the first call opens it
+    pub fn update_position(&self, trade: &TradeProfit) {
+        let mut positions = self.positions.lock().unwrap();
+        positions.open.entry(trade.trade_id.clone())
+            .and_modify(|position| position.trade = trade.clone())
+            .or_insert_with(|| OpenPosition { trade: trade.clone(), amount: trade.position_size });
+    }
+
+    // Opens a position from a filled buy; unfilled executions are ignored
+    pub fn record_execution(&self, trade_id: &str, execution: &TradeExecution) {
+        if !matches!(execution.status, TradeStatus::Completed) {
+            return;
+        }
+        self.update_position(&TradeProfit {
+            trade_id: trade_id.to_string(),
+            token_address: execution.token_address.clone(),
+            entry_price: execution.price,
+            entry_time: execution.timestamp,
+            current_price: execution.price,
+            position_size: execution.amount,
+            gas_fees: execution.total_costs,
+            realized_profits: 0.0,
+            unrealized_profits: 0.0,
+            profit_tiers_hit: Vec::new(),
+            pool_address: String::new(),
+        });
+    }
+
+    // Moves a position to the closed trades, taking its final PnL and fees from `trade`.
+    // Errors if the trade isn't open, so a repeated close can't book it twice.
+    pub fn close_position(&self, trade: &TradeProfit) -> Result<()> {
+        let mut positions = self.positions.lock().unwrap();
+        let amount = positions.open.remove(&trade.trade_id)
+            .ok_or_else(|| anyhow::anyhow!("Position {} is not open", trade.trade_id))?
+            .amount;
+        positions.closed.push(ClosedTrade {
+            trade_id: trade.trade_id.clone(),
+            token_address: trade.token_address.clone(),
+            entry_time: trade.entry_time,
+            exit_time: Utc::now(),
+            entry_price: trade.entry_price,
+            exit_price: trade.current_price,
+            amount,
+            realized_pnl: trade.realized_profits,
+            fees: trade.gas_fees,
+        });
+        Ok(())
+    }
+
+    pub fn closed_trades(&self) -> Vec<ClosedTrade> {
+        self.positions.lock().unwrap().closed.clone()
+    }
+
+    pub fn summary(&self) -> PortfolioSummary {
+        let positions = self.positions.lock().unwrap();
+        let open = positions.open.values().map(|position| &position.trade);
+        let closed = &positions.closed;
+        let wins = closed.iter().filter(|trade| trade.realized_pnl > 0.0).count();
+
+        PortfolioSummary {
+            open_positions: positions.open.len(),
+            closed_trades: closed.len(),
+            total_realized: closed.iter().map(|trade| trade.realized_pnl).sum::<f64>()
+                + open.clone().map(|trade| trade.realized_profits).sum::<f64>(),
+            total_unrealized: open.clone().map(|trade| trade.unrealized_profits).sum(),
+            win_rate: if closed.is_empty() { 0.0 } else { wins as f64 / closed.len() as f64 },
+            fees_paid: closed.iter().map(|trade| trade.fees).sum::<f64>()
+                + open.map(|trade| trade.gas_fees).sum::<f64>(),
+        }
+    }
+
+    pub fn trade_stats(&self) -> TradeStats {
+        let positions = self.positions.lock().unwrap();
+        let closed = &positions.closed;
+        let (wins, losses): (Vec<f64>, Vec<f64>) = closed.iter()
+            .map(|trade| trade.realized_pnl)
+            .partition(|pnl| *pnl > 0.0);
+        let mean = |values: &[f64]| if values.is_empty() { 0.0 } else { values.iter().sum::<f64>() / values.len() as f64 };
+
+        TradeStats {
+            trades: closed.len(),
+            win_rate: if closed.is_empty() { 0.0 } else { wins.len() as f64 / closed.len() as f64 },
+            avg_win: mean(&wins),
+            avg_loss: -mean(&losses),
+        }
+    }
+
+    // One row per closed trade, oldest exit first
+    pub fn export_csv(&self, path: &Path) -> Result<()> {
+        let mut csv = String::from("trade_id,token_address,entry_time,exit_time,entry_price,exit_price,amount,realized_pnl,fees\n");
+        for trade in self.closed_trades() {
+            writeln!(csv, "{},{},{},{},{},{},{},{},{}",
+                     trade.trade_id, trade.token_address, trade.entry_time.to_rfc3339(), trade.exit_time.to_rfc3339(),
+                     trade.entry_price, trade.exit_price, trade.amount, trade.realized_pnl, trade.fees)?;
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, csv).with_context(|| format!("Failed to write portfolio CSV {}", path.display()))
+    }
+}
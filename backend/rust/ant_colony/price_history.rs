@@ -0,0 +1,173 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub timestamp: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
+// Where a freshly opened position gets its recent price history from
+#[async_trait]
+pub trait CandleSource: Send + Sync {
+    // Oldest first, at most `limit` candles
+    async fn recent_candles(&self, token_address: &str, pool_address: &str, limit: usize) -> Result<Vec<Candle>>;
+}
+
+pub struct GeckoTerminalCandles {
+    client: Client,
+    base_url: String,
+    timeframe: String, // minute, hour or day
+}
+
+impl GeckoTerminalCandles {
+    pub fn new(base_url: String, timeframe: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+            timeframe,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OhlcvResponse {
+    data: OhlcvData,
+}
+
+#[derive(Debug, Deserialize)]
+struct OhlcvData {
+    attributes: OhlcvAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+struct OhlcvAttributes {
+    ohlcv_list: Vec<[f64; 6]>, // [unix time, open, high, low, close, volume], newest first
+}
+
+#[async_trait]
+impl CandleSource for GeckoTerminalCandles {
+    async fn recent_candles(&self, _token_address: &str, pool_address: &str, limit: usize) -> Result<Vec<Candle>> {
+        let response: OhlcvResponse = self.client
+            .get(format!("{}/pools/{}/ohlcv/{}", self.base_url.trim_end_matches('/'), pool_address, self.timeframe))
+            .query(&[("limit", limit.to_string())])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let mut candles: Vec<Candle> = response.data.attributes.ohlcv_list.into_iter()
+            .filter_map(|[time, open, high, low, close, _]| {
+                DateTime::from_timestamp(time as i64, 0).map(|timestamp| Candle { timestamp, open, high, low, close })
+            })
+            .collect();
+        candles.sort_by_key(|candle| candle.timestamp);
+        Ok(candles)
+    }
+}
+
+// Rolling prices per token for volatility and trend. Seeded from candles when a position
+// opens so exit logic isn't blind until enough live ticks arrive.
+#[derive(Debug, Clone)]
+pub struct VolatilityTracker {
+    window: usize,
+    min_samples: usize, // Fewer prices than this and no reading is given
+    scale: f64,         // Return stdev that maps to a normalized volatility of 1.0
+    prices: HashMap<String, VecDeque<f64>>,
+}
+
+impl Default for VolatilityTracker {
+    fn default() -> Self {
+        Self {
+            window: 60,
+            min_samples: 3,
+            scale: 0.1,
+            prices: HashMap::new(),
+        }
+    }
+}
+
+impl VolatilityTracker {
+    pub fn new(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            window: config.get_int("ant_colony.profit_manager.backfill.window")
+                .map(|window| window.max(2) as usize)
+                .unwrap_or(defaults.window),
+            min_samples: config.get_int("ant_colony.profit_manager.backfill.min_samples")
+                .map(|samples| samples.max(2) as usize)
+                .unwrap_or(defaults.min_samples),
+            scale: config.get_float("ant_colony.profit_manager.backfill.volatility_scale")
+                .unwrap_or(defaults.scale),
+            prices: HashMap::new(),
+        }
+    }
+
+    // Backfilled closes go in ahead of any live ticks already recorded
+    pub fn seed(&mut self, token_address: &str, candles: &[Candle]) {
+        let history = self.prices.entry(token_address.to_string()).or_default();
+        for candle in candles.iter().rev() {
+            history.push_front(candle.close);
+        }
+        while history.len() > self.window {
+            history.pop_front();
+        }
+    }
+
+    pub fn record(&mut self, token_address: &str, price: f64) {
+        let history = self.prices.entry(token_address.to_string()).or_default();
+        history.push_back(price);
+        while history.len() > self.window {
+            history.pop_front();
+        }
+    }
+
+    pub fn history_len(&self, token_address: &str) -> usize {
+        self.prices.get(token_address).map_or(0, |history| history.len())
+    }
+
+    // Standard deviation of period returns, normalized to 0-1
+    pub fn volatility(&self, token_address: &str) -> Option<f64> {
+        let returns = self.returns(token_address)?;
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / returns.len() as f64;
+        Some((variance.sqrt() / self.scale).clamp(0.0, 1.0))
+    }
+
+    // Change across the window, e.g. 0.2 for a 20% rise
+    pub fn trend(&self, token_address: &str) -> Option<f64> {
+        let history = self.prices.get(token_address).filter(|history| history.len() >= self.min_samples)?;
+        let first = *history.front()?;
+        let last = *history.back()?;
+        if first <= 0.0 {
+            return None;
+        }
+        Some(last / first - 1.0)
+    }
+
+    // Drops history for tokens no longer held
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.prices.retain(|token_address, _| keep(token_address));
+    }
+
+    fn returns(&self, token_address: &str) -> Option<Vec<f64>> {
+        let history = self.prices.get(token_address).filter(|history| history.len() >= self.min_samples)?;
+        let returns: Vec<f64> = history.iter().zip(history.iter().skip(1))
+            .filter(|(previous, _)| **previous > 0.0)
+            .map(|(previous, current)| current / previous - 1.0)
+            .collect();
+        if returns.is_empty() {
+            return None;
+        }
+        Some(returns)
+    }
+}
@@ -4,15 +4,20 @@ use log::{info, error, warn};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::ant_colony::{
-    ColonyState, 
+    ColonyState, WorkerSnapshot,
     capital_manager::CapitalManager,
     profit_manager::{ProfitManager, TradeProfit},
     rug_detector::RugDetector,
     transaction_handler::TransactionHandler,
+    journal::TradeJournal,
+    wallet_guard::{WalletActivitySource, WalletCompromised},
+    wallet_pool::WalletPool,
 };
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
-use solana_sdk::transaction::Transaction;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::{Keypair, Signer}};
+use std::str::FromStr;
+use crate::common::{validate_amount, SwapExecutor};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
@@ -38,6 +43,8 @@ pub struct PrincessState {
     pub total_profit: f64,
     pub success_rate: f64,
     pub last_trade_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub total_trades: u64, // Closed trades, won or lost
 }
 
 pub struct Princess {
@@ -54,10 +61,14 @@ pub struct Princess {
     min_position_size: f64,
     active_trades: Vec<Trade>,
     princess_state: Arc<RwLock<PrincessState>>,
-    max_trades: u32,
+    max_open_positions: usize,
+    kelly_multiplier: f64, // Fraction of the full Kelly stake actually taken
+    strategy: String, // Name its realized PnL is tracked under by the strategy breakers
     min_success_rate: f64,
     capital_allocation: f64,
     trade_timeout: u64,
+    wallet_pool: Option<Arc<WalletPool>>, // Rotating signers; without one the princess keeps a single wallet
+    swap_executor: Option<Arc<SwapExecutor>>, // Without one, trades are dry runs and nothing is sent
 }
 
 impl Princess {
@@ -72,7 +83,13 @@ impl Princess {
         let max_position_size = config.get_float("ant_colony.princess.max_position_size")? as f64;
         let min_position_size = config.get_float("ant_colony.princess.min_position_size")? as f64;
         let initial_balance = config.get_float("ant_colony.princess.initial_balance")? as f64;
-        let max_trades = config.get_int("ant_colony.princess.max_trades")? as u32;
+        // Hard ceiling on open positions, enforced no matter how much capital is free;
+        // older configs only set `max_trades`
+        let max_open_positions = config.get_int("ant_colony.princess.max_open_positions")
+            .or_else(|_| config.get_int("ant_colony.princess.max_trades"))? as usize;
+        let kelly_multiplier = config.get_float("ant_colony.token_stats.kelly_fraction").unwrap_or(0.5);
+        let strategy = config.get_string("ant_colony.princess.strategy")
+            .unwrap_or_else(|_| "default".to_string());
         let min_success_rate = config.get_float("ant_colony.princess.min_success_rate")? as f64;
         let capital_allocation = config.get_float("ant_colony.princess.capital_allocation")? as f64;
         let trade_timeout = config.get_int("ant_colony.princess.trade_timeout")? as u64;
@@ -84,6 +101,7 @@ impl Princess {
             total_profit: 0.0,
             success_rate: 1.0,
             last_trade_time: None,
+            total_trades: 0,
         }));
 
         Ok(Self {
@@ -94,28 +112,58 @@ impl Princess {
             rug_detector,
             transaction_handler,
             is_active: false,
-            wallet_address,
+            wallet_address: String::new(), // Set during wallet initialization
             balance: initial_balance,
             max_position_size,
             min_position_size,
             active_trades: Vec::new(),
             princess_state,
-            max_trades,
+            max_open_positions,
+            kelly_multiplier,
+            strategy,
             min_success_rate,
             capital_allocation,
             trade_timeout,
+            wallet_pool: None,
+            swap_executor: None,
         })
     }
 
+    pub fn set_wallet_pool(&mut self, wallet_pool: Arc<WalletPool>) {
+        self.wallet_pool = Some(wallet_pool);
+    }
+
+    pub fn set_swap_executor(&mut self, swap_executor: Arc<SwapExecutor>) {
+        self.swap_executor = Some(swap_executor);
+    }
+
     pub async fn init(&mut self) -> Result<()> {
         // Initialize wallet and allocate capital
         self.initialize_wallet().await?;
         self.allocate_capital().await?;
         self.is_active = true;
+        self.publish_snapshot().await;
         info!("Princess {} initialized with capital: {}", self.id, self.capital_allocation);
         Ok(())
     }
 
+    // Refreshes this princess's row on the dashboard
+    async fn publish_snapshot(&self) {
+        let snapshot = {
+            let princess_state = self.princess_state.read().await;
+            WorkerSnapshot {
+                id: self.id.clone(),
+                is_active: self.is_active,
+                balance: princess_state.allocated_capital,
+                total_trades: princess_state.total_trades,
+                success_rate: princess_state.success_rate,
+                profit_loss: princess_state.total_profit,
+                last_active: princess_state.last_trade_time,
+            }
+        };
+        self.state.write().await.update_worker(snapshot);
+    }
+
     async fn initialize_wallet(&mut self) -> Result<()> {
         // TODO: Implement wallet initialization
         // This would involve:
@@ -148,21 +196,135 @@ impl Princess {
     }
 
     pub async fn execute_trade(&self, token_address: String, amount: f64) -> Result<()> {
+        validate_amount(amount)?;
+        let amount = self.size_position(&token_address, amount).await;
+        if amount <= 0.0 {
+            info!("Princess {} skipped {}: its recorded history shows no edge", self.id, token_address);
+            return Err(anyhow::anyhow!("Token {} has no edge under Kelly sizing", token_address));
+        }
+
+        // Never re-enter a token the colony has blacklisted, nor one off the whitelist in
+        // whitelist-only mode
+        let blacklist = self.state.read().await.blacklist.clone();
+        if let Some(entry) = blacklist.get(&token_address) {
+            warn!("Princess {} rejected trade for blacklisted token {}: {}",
+                  self.id, token_address, entry.reason);
+            return Err(anyhow::anyhow!("Token {} is blacklisted: {}", token_address, entry.reason));
+        }
+        if !blacklist.is_allowed(&token_address) {
+            warn!("Princess {} rejected trade for {}: not whitelisted", self.id, token_address);
+            return Err(anyhow::anyhow!("Token {} is not whitelisted", token_address));
+        }
+
+        // The position ceiling applies regardless of how well funded the princess is. The
+        // slot is taken up front so concurrent entries can't all pass the check, and given
+        // back if this one doesn't end up open.
+        self.reserve_position(&token_address).await?;
+        match self.enter_position(&token_address, amount).await {
+            Ok(true) => Ok(()),
+            Ok(false) => {
+                self.release_position(&token_address).await;
+                Ok(())
+            }
+            Err(e) => {
+                self.release_position(&token_address).await;
+                Err(e)
+            }
+        }
+    }
+
+    // Counts and records the position under one lock
+    async fn reserve_position(&self, token_address: &str) -> Result<()> {
         let mut princess_state = self.princess_state.write().await;
+        let open_positions = princess_state.active_trades.len();
+        if open_positions >= self.max_open_positions {
+            warn!("Princess {} rejected trade for {}: at position ceiling ({}/{})",
+                  self.id, token_address, open_positions, self.max_open_positions);
+            return Err(anyhow::anyhow!(
+                "Princess {} is at its position ceiling of {} open positions",
+                self.id, self.max_open_positions
+            ));
+        }
+        princess_state.active_trades.push(token_address.to_string());
+        Ok(())
+    }
 
+    async fn release_position(&self, token_address: &str) {
+        let mut princess_state = self.princess_state.write().await;
+        if let Some(pos) = princess_state.active_trades.iter().rposition(|x| x == token_address) {
+            princess_state.active_trades.remove(pos);
+        }
+    }
+
+    // The rest of an entry once its slot is reserved; false when it was skipped without
+    // trading
+    async fn enter_position(&self, token_address: &str, amount: f64) -> Result<bool> {
         // Validate trade
         if !self.can_execute_trade(amount).await? {
             warn!("Princess {} cannot execute trade: insufficient capital", self.id);
-            return Ok(());
+            return Ok(false);
+        }
+
+        // A strategy whose recent results tripped its breaker takes no new entries
+        let (wallet_health, strategy_breakers, compromise_guard, session, risk_governor) = {
+            let state = self.state.read().await;
+            (state.wallet_health.clone(), state.strategy_breakers.clone(),
+             state.compromise_guard.clone(), state.session.clone(), state.risk_governor.clone())
+        };
+        if let Err(disabled) = strategy_breakers.check(&self.strategy) {
+            warn!("Princess {} rejected trade for {}: {}", self.id, token_address, disabled);
+            return Err(disabled.into());
+        }
+
+        // With a wallet pool the trade is signed by whichever wallet is currently in rotation,
+        // and that wallet needs a token account for the mint before it can receive tokens
+        let signer = self.wallet_pool.as_ref().map(|wallet_pool| wallet_pool.current());
+        let mut setup = Vec::new();
+        let wallet_address = match (&signer, &self.wallet_pool) {
+            (Some(signer), Some(wallet_pool)) => {
+                let mint = Pubkey::from_str(token_address)
+                    .map_err(|e| anyhow::anyhow!("Invalid token mint {}: {}", token_address, e))?;
+                let (_, create_token_account) = wallet_pool.token_account_setup(&signer.pubkey(), &mint);
+                setup.extend(create_token_account);
+                signer.pubkey().to_string()
+            }
+            _ => self.princess_state.read().await.wallet_address.clone(),
+        };
+
+        // Don't keep burning fees from a wallet whose recent transactions mostly fail
+        if let Err(compromised) = compromise_guard.check(&wallet_address) {
+            error!("Princess {} rejected trade for {}: {}", self.id, token_address, compromised);
+            return Err(compromised.into());
         }
+        if let Err(paused) = wallet_health.check(&wallet_address) {
+            warn!("Princess {} rejected trade for {}: {}", self.id, token_address, paused);
+            return Err(paused.into());
+        }
+
+        // Wait for a slot if too many submissions are still unconfirmed; the slot is
+        // held until this trade confirms or fails
+        let pending_confirmations = self.state.read().await.pending_confirmations.clone();
+        let _pending_slot = pending_confirmations.reserve().await;
 
         // Execute trade
-        match self._execute_trade(&token_address, amount).await {
-            Ok(_) => {
-                princess_state.active_trades.push(token_address);
-                princess_state.last_trade_time = Some(Utc::now());
+        let result = self._execute_trade(token_address, amount, signer.as_deref(), &setup).await;
+        if wallet_health.record(&wallet_address, result.is_ok()) {
+            session.record_alert();
+        }
+
+        match result {
+            Ok(landed) => {
+                risk_governor.record_trade().await;
+                // Only a transaction that landed created the token account or used up the wallet's turn
+                if let (Some(_), Some(signer), Some(wallet_pool)) = (&landed, &signer, &self.wallet_pool) {
+                    if !setup.is_empty() {
+                        wallet_pool.mark_token_account(&signer.pubkey(), &Pubkey::from_str(token_address)?);
+                    }
+                    wallet_pool.record_trade();
+                }
+                self.princess_state.write().await.last_trade_time = Some(Utc::now());
                 info!("Princess {} executed trade for {}", self.id, amount);
-                Ok(())
+                Ok(true)
             }
             Err(e) => {
                 error!("Princess {} trade execution failed: {}", self.id, e);
@@ -171,7 +333,48 @@ impl Princess {
         }
     }
 
+    // Tokens with enough recorded history stake their Kelly fraction of the princess's
+    // allocated capital, bounded by the position limits; anything else keeps the requested
+    // amount. Zero when the history shows no edge, meaning the trade should be skipped.
+    pub async fn size_position(&self, token_key: &str, requested: f64) -> f64 {
+        let kelly = self.state.read().await.token_stats.kelly_fraction(token_key);
+        match kelly {
+            Some(kelly) if kelly <= 0.0 => 0.0,
+            Some(kelly) => {
+                let capital = self.princess_state.read().await.allocated_capital;
+                (capital * kelly * self.kelly_multiplier).clamp(self.min_position_size, self.max_position_size)
+            }
+            None => requested,
+        }
+    }
+
+    // Halts this princess's wallet if it signed anything the journal can't account for
+    pub async fn audit_wallet(
+        &self,
+        source: &dyn WalletActivitySource,
+        journal: &TradeJournal,
+    ) -> Result<Option<WalletCompromised>> {
+        let wallet_address = self.princess_state.read().await.wallet_address.clone();
+        let (compromise_guard, session) = {
+            let state = self.state.read().await;
+            (state.compromise_guard.clone(), state.session.clone())
+        };
+
+        let alert = compromise_guard.scan(&wallet_address, source, &journal.sent_signatures()?).await?;
+        if alert.is_some() {
+            session.record_alert();
+        }
+        Ok(alert)
+    }
+
     async fn can_execute_trade(&self, amount: f64) -> Result<bool> {
+        // Once the colony's daily limits are used up nothing opens until the next UTC day
+        let risk_governor = self.state.read().await.risk_governor.clone();
+        if let Err(frozen) = risk_governor.check() {
+            warn!("Princess {} cannot execute trade: {}", self.id, frozen);
+            return Err(frozen.into());
+        }
+
         let princess_state = self.princess_state.read().await;
         
         // Check if we have enough capital
@@ -179,11 +382,6 @@ impl Princess {
             return Ok(false);
         }
 
-        // Check if we've reached max trades
-        if princess_state.active_trades.len() >= self.max_trades as usize {
-            return Ok(false);
-        }
-
         // Check success rate
         if princess_state.success_rate < self.min_success_rate {
             return Ok(false);
@@ -192,14 +390,24 @@ impl Princess {
         Ok(true)
     }
 
-    async fn _execute_trade(&self, token_address: &str, amount: f64) -> Result<()> {
-        // TODO: Implement actual trade execution
-        // This would involve:
-        // 1. Creating the transaction
-        // 2. Signing the transaction
-        // 3. Sending the transaction
-        // 4. Waiting for confirmation
-        Ok(())
+    // Buys through the swap executor, signed by `signer` when the princess has a wallet pool,
+    // with `setup` instructions (e.g. creating the signer's token account) before the swap.
+    // The signature once it confirms, or None for a dry run without an executor.
+    async fn _execute_trade(
+        &self,
+        token_address: &str,
+        amount: f64,
+        signer: Option<&Keypair>,
+        setup: &[Instruction],
+    ) -> Result<Option<String>> {
+        let Some(swap_executor) = &self.swap_executor else {
+            return Ok(None);
+        };
+        let transaction = match signer {
+            Some(signer) => swap_executor.build_buy_as(signer, setup, token_address, amount).await?,
+            None => swap_executor.build_buy(token_address, amount).await?,
+        };
+        Ok(Some(swap_executor.submit(transaction).await?))
     }
 
     pub async fn update_trade_status(&self, token_address: &str, success: bool, profit: f64) -> Result<()> {
@@ -212,7 +420,22 @@ impl Princess {
 
         // Update profit and success rate
         princess_state.total_profit += profit;
-        princess_state.success_rate = self.calculate_success_rate(success).await?;
+        princess_state.success_rate = Self::calculate_success_rate(&princess_state, success);
+        princess_state.total_trades += 1;
+        princess_state.last_trade_time = Some(Utc::now());
+        drop(princess_state);
+
+        let mut state = self.state.write().await;
+        if state.strategy_breakers.record_pnl(&self.strategy, profit) {
+            state.session.record_alert();
+        }
+        state.token_stats.record(token_address, profit);
+        let stats_snapshot = state.token_stats.snapshot();
+        drop(state);
+        if let Some(stats_snapshot) = stats_snapshot {
+            stats_snapshot.save().await?;
+        }
+        self.publish_snapshot().await;
 
         info!(
             "Princess {} trade update - Token: {}, Success: {}, Profit: {}",
@@ -221,14 +444,13 @@ impl Princess {
         Ok(())
     }
 
-    async fn calculate_success_rate(&self, new_trade_success: bool) -> Result<f64> {
-        let princess_state = self.princess_state.read().await;
+    // Takes the state the caller already holds; locking it again here would deadlock
+    fn calculate_success_rate(princess_state: &PrincessState, new_trade_success: bool) -> f64 {
         let current_rate = princess_state.success_rate;
-        let total_trades = princess_state.active_trades.len() as f64 + 1.0;
+        let total_trades = princess_state.total_trades as f64 + 1.0;
 
         // Weighted average calculation
-        let new_rate = (current_rate * (total_trades - 1.0) + (new_trade_success as u32 as f64)) / total_trades;
-        Ok(new_rate)
+        (current_rate * (total_trades - 1.0) + (new_trade_success as u32 as f64)) / total_trades
     }
 
     pub async fn run(&self) -> Result<()> {
@@ -294,15 +516,43 @@ impl Princess {
         Ok(())
     }
 
+    // Hands the princess's allocation back to the colony, e.g. when it is despawned
+    pub async fn release_capital(&self) -> f64 {
+        // Read before the colony lock: the manager's own pass locks itself, then the colony
+        let capital_manager_id = self.capital_manager.read().await.get_id().to_string();
+        let mut colony_state = self.state.write().await;
+        colony_state.capital_budget.remove(&capital_manager_id);
+        let mut princess_state = self.princess_state.write().await;
+        let released = std::mem::take(&mut princess_state.allocated_capital);
+        colony_state.total_capital += released;
+        released
+    }
+
     // Getters
     pub fn get_id(&self) -> &str {
         &self.id
     }
 
+    pub fn profit_manager(&self) -> Arc<RwLock<ProfitManager>> {
+        self.profit_manager.clone()
+    }
+
     pub fn get_balance(&self) -> f64 {
         self.balance
     }
 
+    pub async fn open_position_count(&self) -> usize {
+        self.princess_state.read().await.active_trades.len()
+    }
+
+    pub fn max_open_positions(&self) -> usize {
+        self.max_open_positions
+    }
+
+    pub fn strategy(&self) -> &str {
+        &self.strategy
+    }
+
     pub fn get_active_trades(&self) -> &[Trade] {
         &self.active_trades
     }
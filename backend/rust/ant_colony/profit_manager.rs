@@ -1,20 +1,99 @@
 use anyhow::Result;
-use config::Config;
+use config::{Config, ConfigError};
 use log::{info, error, warn};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::ant_colony::ColonyState;
+use crate::common::{TradeWebhook, TradeConfirmation, TradeOutcome, SwapExecutor};
+use crate::common::{Message, MessageQueue, TradeSignal, TradeAction, TradeExecutionEvent, TradeEventStatus};
+use crate::ant_colony::journal::{TradeJournal, JournalEvent};
+use crate::ant_colony::pool_migration::{PoolLocator, DexScreenerPoolLocator, PoolMigrationDetector, PoolMigration};
+use crate::ant_colony::price_history::{CandleSource, GeckoTerminalCandles, VolatilityTracker};
+use crate::ant_colony::gas_price::{GasPriceSource, GasPriceHistory};
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use solana_sdk::transaction::Transaction;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProfitTier {
     pub multiplier: f64,
     pub percentage: f64,
+    #[serde(default = "default_gas_buffer")]
     pub gas_buffer: f64,
+    #[serde(default)]
     pub volatility_adjustment: f64,
 }
 
+fn default_gas_buffer() -> f64 {
+    1.0
+}
+
+impl ProfitTier {
+    // A ladder must climb and must not sell more than the whole position
+    pub fn validate_ladder(tiers: &[ProfitTier]) -> Result<()> {
+        if let Some(tier) = tiers.iter().find(|t| t.percentage <= 0.0 || t.multiplier <= 0.0) {
+            return Err(anyhow::anyhow!("Profit tier needs a positive multiplier and percentage: {:?}", tier));
+        }
+        if let Some(pair) = tiers.windows(2).find(|pair| pair[1].multiplier <= pair[0].multiplier) {
+            return Err(anyhow::anyhow!(
+                "Profit tier multipliers must be strictly increasing, got {} after {}",
+                pair[1].multiplier, pair[0].multiplier
+            ));
+        }
+
+        let total: f64 = tiers.iter().map(|t| t.percentage).sum();
+        if total > 1.0 + 1e-6 {
+            return Err(anyhow::anyhow!("Profit tier percentages must sum to at most 1.0, got {}", total));
+        }
+        Ok(())
+    }
+}
+
+// Makes the whole ladder more aggressive once volatility passes a threshold: tiers trigger
+// closer to entry and sell a larger fraction, so gains are banked before they round-trip.
+// Separate from each tier's `volatility_adjustment`, which only nudges its trigger.
+#[derive(Debug, Clone)]
+struct ProfitAcceleration {
+    enabled: bool,
+    volatility_threshold: f64, // Below this the ladder is left untouched
+    sensitivity: f64,          // Extra acceleration per unit of volatility above the threshold
+    max_acceleration: f64,     // Upper bound on the acceleration factor
+}
+
+impl ProfitAcceleration {
+    fn from_config(config: &Config) -> Result<Self> {
+        let enabled = config.get_bool("ant_colony.profit_manager.profit_acceleration.enabled").unwrap_or(false);
+        if !enabled {
+            return Ok(Self { enabled, volatility_threshold: 1.0, sensitivity: 0.0, max_acceleration: 1.0 });
+        }
+
+        Ok(Self {
+            enabled,
+            volatility_threshold: config.get_float("ant_colony.profit_manager.profit_acceleration.volatility_threshold")?,
+            sensitivity: config.get_float("ant_colony.profit_manager.profit_acceleration.sensitivity")?,
+            max_acceleration: config.get_float("ant_colony.profit_manager.profit_acceleration.max_acceleration")?.max(1.0),
+        })
+    }
+
+    fn factor(&self, volatility: f64) -> f64 {
+        if !self.enabled || volatility <= self.volatility_threshold {
+            return 1.0;
+        }
+        (1.0 + (volatility - self.volatility_threshold) * self.sensitivity).min(self.max_acceleration)
+    }
+
+    // Pulls the trigger towards entry and scales the sell fraction by the same factor
+    fn apply(&self, tier: &ProfitTier, volatility: f64) -> ProfitTier {
+        let factor = self.factor(volatility);
+        ProfitTier {
+            multiplier: 1.0 + (tier.multiplier - 1.0) / factor,
+            percentage: (tier.percentage * factor).min(1.0),
+            ..tier.clone()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeProfit {
     pub trade_id: String,
@@ -27,6 +106,44 @@ pub struct TradeProfit {
     pub realized_profits: f64,
     pub unrealized_profits: f64,
     pub profit_tiers_hit: Vec<f64>,
+    #[serde(default)]
+    pub pool_address: String, // Pool the position is monitored and exited through
+}
+
+// One sell the ladder would make in a dry run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedSell {
+    pub action: String,                  // take_profit, or full_exit when the profit cap fires
+    pub tier_multiplier: Option<f64>,    // Ladder tier that fired; None for the profit cap
+    pub trigger_multiplier: Option<f64>, // Price multiple the tier actually triggers at
+    pub sell_amount: f64,
+    pub net_profit: f64,
+    pub estimated_gas: f64,
+}
+
+// What the exit ladder would do if the position's price moved straight to `price`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitSimulation {
+    pub price: f64,
+    pub multiplier: f64,
+    pub sells: Vec<SimulatedSell>,
+    pub realized_profit: f64, // Net of gas across the simulated sells
+    pub remaining_position: f64,
+    pub unrealized_profit: f64, // On what's left, at the scenario price
+}
+
+// Outcome of judging one (already accelerated) tier against a position
+enum TierDecision {
+    NotReached,
+    BelowMinimum { min_profit_multiplier: f64 },
+    Hold { net_profit: f64 },
+    Sell {
+        sell_amount: f64,
+        potential_profit: f64,
+        estimated_gas: f64,
+        total_costs: f64,
+        net_profit: f64,
+    },
 }
 
 pub struct ProfitManager {
@@ -34,17 +151,121 @@ pub struct ProfitManager {
     state: Arc<RwLock<ColonyState>>,
     is_active: bool,
     profit_tiers: Vec<ProfitTier>,
+    profit_acceleration: ProfitAcceleration,
+    volatility_tracker: VolatilityTracker,
+    default_volatility: f64, // Assumed until a token has enough prices for a reading
+    candle_source: Option<Arc<dyn CandleSource>>, // Backfills history when a position opens
+    backfill_candles: usize,
     active_trades: Vec<TradeProfit>,
     min_profit_threshold: f64,
-    gas_price_history: Vec<(DateTime<Utc>, f64)>,
+    max_unrealized_profit: Option<f64>, // SOL; force a full exit above this regardless of tiers
+    max_exit_slippage: f64, // Exits outside the ladder accept quotes this far below the current price
+    gas_price_history: GasPriceHistory,
+    gas_price_source: Option<Arc<dyn GasPriceSource>>, // Without one sells only budget the base fee
+    pool_migration: PoolMigrationDetector,
+    pool_locator: Option<Arc<dyn PoolLocator>>,
+    swap_executor: Option<Arc<SwapExecutor>>, // Signs sells and sends them through the shared TransactionHandler; without one they are dry runs
+    message_queue: Option<MessageQueue>, // Receives a Sell signal for every filled sell, and TradeExecution events for ladder sells
+    webhook: TradeWebhook,
+    journal: TradeJournal,
 }
 
 impl ProfitManager {
     pub async fn new(config: &Config, state: Arc<RwLock<ColonyState>>) -> Result<Self> {
         let min_profit_threshold = config.get_float("ant_colony.profit_manager.min_profit_threshold")? as f64;
+        let max_unrealized_profit = config.get_float("ant_colony.profit_manager.max_unrealized_profit").ok();
+
+        // Follows positions to their new pool when a mint migrates, e.g. on pump.fun graduation.
+        // The locator also finds a new position's pool, which the backfill reads candles from.
+        let pool_migration = PoolMigrationDetector::new(config)?;
+        let backfill_enabled = config.get_bool("ant_colony.profit_manager.backfill.enabled").unwrap_or(false);
+        let pool_locator: Option<Arc<dyn PoolLocator>> = if pool_migration.is_enabled() || backfill_enabled {
+            let base_url = config.get_string("ant_colony.profit_manager.pool_migration.dex_screener_url")
+                .unwrap_or_else(|_| "https://api.dexscreener.com/latest/dex/tokens".to_string());
+            Some(Arc::new(DexScreenerPoolLocator::new(base_url)))
+        } else {
+            None
+        };
+
+        // Seeds volatility and trend from recent candles instead of starting blind
+        let candle_source: Option<Arc<dyn CandleSource>> =
+            if backfill_enabled {
+                let base_url = config.get_string("ant_colony.profit_manager.backfill.gecko_terminal_url")
+                    .unwrap_or_else(|_| "https://api.geckoterminal.com/api/v2/networks/solana".to_string());
+                let timeframe = config.get_string("ant_colony.profit_manager.backfill.timeframe")
+                    .unwrap_or_else(|_| "minute".to_string());
+                Some(Arc::new(GeckoTerminalCandles::new(base_url, timeframe)))
+            } else {
+                None
+            };
+        let backfill_candles = config.get_int("ant_colony.profit_manager.backfill.candles").unwrap_or(60) as usize;
+
+        let profit_tiers = Self::load_profit_tiers(config)?;
+        state.write().await.track_profit_tiers(&profit_tiers);
+
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            state,
+            is_active: false,
+            profit_tiers,
+            profit_acceleration: ProfitAcceleration::from_config(config)?,
+            volatility_tracker: VolatilityTracker::new(config),
+            default_volatility: config.get_float("ant_colony.profit_manager.default_volatility")
+                .unwrap_or(0.1)
+                .clamp(0.0, 1.0),
+            candle_source,
+            backfill_candles,
+            active_trades: Vec::new(),
+            min_profit_threshold,
+            max_unrealized_profit,
+            max_exit_slippage: config.get_float("ant_colony.profit_manager.max_exit_slippage")
+                .unwrap_or(0.15)
+                .clamp(0.0, 1.0),
+            gas_price_history: GasPriceHistory::new(config),
+            gas_price_source: None,
+            pool_migration,
+            pool_locator,
+            swap_executor: None,
+            message_queue: None,
+            webhook: TradeWebhook::new(config)?,
+            journal: TradeJournal::from_config(config)?,
+        })
+    }
 
-        // Initialize profit tiers
-        let profit_tiers = vec![
+    // Reads the [[ant_colony.profit_manager.tiers]] array, or else the older flat tier_1_*,
+    // tier_2_* ... keys in [ant_colony.profit_tiers], falling back to the built-in ladder
+    // when neither is configured
+    fn load_profit_tiers(config: &Config) -> Result<Vec<ProfitTier>> {
+        let tiers = match config.get::<Vec<ProfitTier>>("ant_colony.profit_manager.tiers") {
+            Ok(tiers) => tiers,
+            Err(ConfigError::NotFound(_)) => Self::load_flat_profit_tiers(config)?,
+            Err(e) => return Err(e.into()),
+        };
+        let tiers = if tiers.is_empty() { Self::default_profit_tiers() } else { tiers };
+        ProfitTier::validate_ladder(&tiers)?;
+        Ok(tiers)
+    }
+
+    fn load_flat_profit_tiers(config: &Config) -> Result<Vec<ProfitTier>> {
+        let mut tiers = Vec::new();
+        for n in 1.. {
+            let prefix = format!("ant_colony.profit_tiers.tier_{}", n);
+            let multiplier = match config.get_float(&format!("{}_multiplier", prefix)) {
+                Ok(multiplier) => multiplier,
+                Err(_) => break,
+            };
+            tiers.push(ProfitTier {
+                multiplier,
+                percentage: config.get_float(&format!("{}_percentage", prefix))?,
+                gas_buffer: config.get_float(&format!("{}_gas_buffer", prefix)).unwrap_or(1.0),
+                volatility_adjustment: config.get_float(&format!("{}_volatility_adjustment", prefix)).unwrap_or(0.0),
+            });
+        }
+        Ok(tiers)
+    }
+
+    fn default_profit_tiers() -> Vec<ProfitTier> {
+        vec![
             ProfitTier {
                 multiplier: 1.2,  // Sell 40% at 1.2x for quick profits
                 percentage: 0.4,
@@ -69,17 +290,7 @@ impl ProfitManager {
                 gas_buffer: 1.5,
                 volatility_adjustment: 0.2,
             },
-        ];
-
-        Ok(Self {
-            id: uuid::Uuid::new_v4().to_string(),
-            state,
-            is_active: false,
-            profit_tiers,
-            active_trades: Vec::new(),
-            min_profit_threshold,
-            gas_price_history: Vec::new(),
-        })
+        ]
     }
 
     pub async fn start_monitoring(&mut self) -> Result<()> {
@@ -97,16 +308,21 @@ impl ProfitManager {
     }
 
     async fn monitor_and_manage(&mut self) -> Result<()> {
-        let state = self.state.read().await;
-        
         // Skip if colony is not active
-        if !state.is_active {
+        if !self.state.read().await.is_active {
             return Ok(());
         }
 
         // Update gas price history
         self.update_gas_price_history().await?;
 
+        // Exits have to go through the pool that actually holds the liquidity
+        if self.pool_locator.is_some() && self.pool_migration.is_enabled() {
+            if let Err(e) = self.check_pool_migrations().await {
+                error!("Profit Manager {} pool migration check error: {}", self.id, e);
+            }
+        }
+
         // Check profit tiers for all active trades
         self.check_profit_tiers().await?;
 
@@ -116,59 +332,82 @@ impl ProfitManager {
         Ok(())
     }
 
-    async fn update_gas_price_history(&mut self) -> Result<()> {
-        // Placeholder for gas price fetching
-        // This would involve:
-        // 1. Fetching current gas price
-        // 2. Adding to history
-        // 3. Maintaining a rolling window of prices
+    // A failed sample only leaves the window a little older; it never stops the loop
+    pub async fn update_gas_price_history(&mut self) -> Result<()> {
+        let gas_price_source = match &self.gas_price_source {
+            Some(gas_price_source) => gas_price_source.clone(),
+            None => return Ok(()),
+        };
+
+        match gas_price_source.priority_fee().await {
+            Ok(priority_fee) => self.gas_price_history.record(priority_fee),
+            Err(e) => warn!("Profit Manager {} failed to sample priority fees: {}", self.id, e),
+        }
         Ok(())
     }
 
-    async fn check_profit_tiers(&mut self) -> Result<()> {
-        for trade in &mut self.active_trades {
+    pub async fn check_profit_tiers(&mut self) -> Result<()> {
+        // Lock in exceptional gains before they can round-trip, regardless of the ladder.
+        // A failed exit is retried next pass; it doesn't hold up the other trades, and the
+        // trade isn't sold by tier in the meantime.
+        let mut failed_exits = Vec::new();
+        if let Some(max_unrealized_profit) = self.max_unrealized_profit {
+            let capped_trades: Vec<(String, f64)> = self.active_trades.iter()
+                .filter(|t| t.position_size > 0.0 && t.unrealized_profits >= max_unrealized_profit)
+                .map(|t| (t.trade_id.clone(), t.unrealized_profits))
+                .collect();
+
+            for (trade_id, unrealized_profits) in capped_trades {
+                self.journal.record(&trade_id, JournalEvent::Decision {
+                    action: "full_exit".to_string(),
+                    reason: format!("unrealized profit {} SOL reached cap {} SOL", unrealized_profits, max_unrealized_profit),
+                });
+                if self.execute_full_exit(&trade_id).await.is_err() {
+                    failed_exits.push(trade_id);
+                }
+            }
+        }
+
+        // Tiers are walked on a copy so sells can go through `self`; the copy is written
+        // back once the trade's ladder has been checked
+        let profit_tiers = self.profit_tiers.clone();
+        for index in 0..self.active_trades.len() {
+            let mut trade = self.active_trades[index].clone();
+            // Dust left by a ladder sell was already closed and confirmed
+            if trade.position_size <= 1e-9 || failed_exits.contains(&trade.trade_id) {
+                continue;
+            }
+
             // Calculate current profit multiplier
             let current_multiplier = trade.current_price / trade.entry_price;
 
             // Calculate dynamic position size based on volatility
-            let volatility = self.calculate_volatility(trade).await?;
-            let position_adjustment = 1.0 - (volatility * 0.5); // Reduce position size as volatility increases
+            let volatility = self.calculate_volatility(&trade).await?;
 
             // Calculate total costs including gas fees
-            let total_costs = trade.gas_fees + self.estimate_gas_cost().await?;
+            let gas_cost = self.estimate_gas_cost().await?;
+            let total_costs = trade.gas_fees + gas_cost;
             let min_profit_multiplier = 1.0 + (total_costs / (trade.position_size * trade.entry_price));
 
             // Check each profit tier
-            for tier in &self.profit_tiers {
+            for base_tier in &profit_tiers {
                 // Skip if tier already hit
-                if trade.profit_tiers_hit.contains(&tier.multiplier) {
+                if trade.profit_tiers_hit.contains(&base_tier.multiplier) {
                     continue;
                 }
 
-                // Calculate adjusted multiplier based on volatility and costs
-                let adjusted_multiplier = tier.multiplier * (1.0 - volatility * tier.volatility_adjustment);
+                // Under high volatility the tier triggers earlier and sells more
+                let tier = &self.profit_acceleration.apply(base_tier, volatility);
 
-                // Ensure we never sell below minimum profit threshold
-                if adjusted_multiplier < min_profit_multiplier {
-                    warn!("Skipping tier {}x for trade {} - below minimum profit threshold {}x", 
-                          tier.multiplier, trade.trade_id, min_profit_multiplier);
-                    continue;
-                }
-
-                // Check if we've hit this tier
-                if current_multiplier >= adjusted_multiplier {
-                    // Calculate potential profit with position adjustment
-                    let sell_amount = trade.position_size * tier.percentage * position_adjustment;
-                    let potential_profit = sell_amount * (trade.current_price - trade.entry_price);
-                    let estimated_gas = self.estimate_gas_cost().await? * tier.gas_buffer;
-                    let total_costs = estimated_gas + trade.gas_fees;
-
-                    // Calculate net profit after all costs
-                    let net_profit = potential_profit - total_costs;
-                    let net_profit_percentage = (net_profit / (sell_amount * trade.entry_price)) * 100.0;
+                match self.evaluate_tier(&trade, tier, volatility, gas_cost, min_profit_multiplier) {
+                    TierDecision::NotReached => {}
+                    TierDecision::BelowMinimum { min_profit_multiplier } => {
+                        warn!("Skipping tier {}x for trade {} - below minimum profit threshold {}x", 
+                              tier.multiplier, trade.trade_id, min_profit_multiplier);
+                    }
+                    TierDecision::Sell { sell_amount, potential_profit, estimated_gas, total_costs, net_profit } => {
+                        let net_profit_percentage = (net_profit / (sell_amount * trade.entry_price)) * 100.0;
 
-                    // Only sell if we have a net profit
-                    if net_profit > 0.0 && net_profit > self.min_profit_threshold {
                         // Log detailed profit analysis
                         info!("Profit analysis for trade {} at {}x:", trade.trade_id, tier.multiplier);
                         info!("  Sell amount: {} tokens", sell_amount);
@@ -177,12 +416,26 @@ impl ProfitManager {
                         info!("  Total costs: {} ETH", total_costs);
                         info!("  Net profit: {} ETH ({}%)", net_profit, net_profit_percentage);
 
-                        // Execute partial sell
-                        self.execute_partial_sell(trade, tier).await?;
+                        self.journal.record(&trade.trade_id, JournalEvent::Decision {
+                            action: "take_profit".to_string(),
+                            reason: format!("{}x reached tier {}x, net profit {} after costs {}",
+                                            current_multiplier, tier.multiplier, net_profit, total_costs),
+                        });
+
+                        // A failed sell leaves the tier unhit, so the next pass retries it
+                        // Build sell transaction with minimum profit guarantee
+                        let min_price = trade.entry_price * (1.0 + (trade.gas_fees / (sell_amount * trade.entry_price)));
+                        let label = format!("at {}x", tier.multiplier);
+                        if self.execute_partial_sell(&trade, sell_amount, min_price, net_profit, &label).await.is_err() {
+                            break;
+                        }
                         
                         // Mark tier as hit
-                        trade.profit_tiers_hit.push(tier.multiplier);
+                        trade.profit_tiers_hit.push(base_tier.multiplier);
+                        self.state.write().await.record_tier_hit(base_tier.multiplier);
                         
+                        self.record_sell(&trade, sell_amount, net_profit, estimated_gas).await;
+
                         // Update trade metrics
                         trade.realized_profits += net_profit;
                         trade.position_size -= sell_amount;
@@ -191,89 +444,488 @@ impl ProfitManager {
                         // Log successful profit taking
                         info!("Profit Manager {} took profit for trade {} at {}x: {} ETH ({}%)", 
                               self.id, trade.trade_id, tier.multiplier, net_profit, net_profit_percentage);
-                    } else {
+                    }
+                    TierDecision::Hold { net_profit } => {
+                        self.journal.record(&trade.trade_id, JournalEvent::Decision {
+                            action: "hold".to_string(),
+                            reason: format!("tier {}x reached but net profit {} is below {}",
+                                            tier.multiplier, net_profit, self.min_profit_threshold),
+                        });
                         warn!("Skipping sell for trade {} at {}x - insufficient profit (Net: {} ETH, Required: {} ETH)", 
                               trade.trade_id, tier.multiplier, net_profit, self.min_profit_threshold);
                     }
                 }
             }
+
+            self.sync_portfolio(&trade).await;
+            self.active_trades[index] = trade;
         }
 
         Ok(())
     }
 
+    // Keeps the colony portfolio in step; a position sold down to nothing is closed there
+    // and confirmed as exited, whichever path sold the last of it
+    async fn sync_portfolio(&self, trade: &TradeProfit) {
+        let portfolio = self.state.read().await.portfolio.clone();
+        if trade.position_size <= 1e-9 {
+            if let Err(e) = portfolio.close_position(trade) {
+                warn!("Profit Manager {} could not close {} in the portfolio: {}", self.id, trade.trade_id, e);
+            }
+            self.confirm_exit(trade);
+        } else {
+            portfolio.update_position(trade);
+        }
+    }
+
+    fn confirm_exit(&self, trade: &TradeProfit) {
+        if !self.webhook.is_enabled() {
+            return;
+        }
+        match TradeConfirmation::new(TradeOutcome::Exited, &trade.trade_id, &trade.token_address, trade) {
+            Ok(confirmation) => self.webhook.notify(confirmation),
+            Err(e) => warn!("Profit Manager {} could not build confirmation for {}: {}", self.id, trade.trade_id, e),
+        }
+    }
+
+    // Books a filled sell: session and colony profit, the daily risk limits, plus a Sell
+    // signal for subscribers
+    async fn record_sell(&self, trade: &TradeProfit, sell_amount: f64, net_profit: f64, gas: f64) {
+        let risk_governor = {
+            let mut state = self.state.write().await;
+            state.session.record_realized(net_profit, gas);
+            state.total_profit += net_profit;
+            state.record_fill(net_profit, gas);
+            state.risk_governor.clone()
+        };
+        risk_governor.record_realized(net_profit).await;
+
+        if let Some(message_queue) = &self.message_queue {
+            message_queue.publish(Message::TradeSignal(TradeSignal {
+                token_address: trade.token_address.clone(),
+                action: TradeAction::Sell,
+                price: trade.current_price,
+                amount: sell_amount,
+                timestamp: Utc::now(),
+                confidence: 1.0,
+            })).await;
+        }
+    }
+
+    // Judges one tier against a position at its current price. Shared by the live ladder
+    // and the dry run so both always agree on the math.
+    fn evaluate_tier(
+        &self,
+        trade: &TradeProfit,
+        tier: &ProfitTier,
+        volatility: f64,
+        gas_cost: f64,
+        min_profit_multiplier: f64,
+    ) -> TierDecision {
+        // Calculate adjusted multiplier based on volatility and costs
+        let adjusted_multiplier = tier.multiplier * (1.0 - volatility * tier.volatility_adjustment);
+
+        // Ensure we never sell below minimum profit threshold
+        if adjusted_multiplier < min_profit_multiplier {
+            return TierDecision::BelowMinimum { min_profit_multiplier };
+        }
+
+        if trade.current_price / trade.entry_price < adjusted_multiplier {
+            return TierDecision::NotReached;
+        }
+
+        // Calculate potential profit with position adjustment
+        let position_adjustment = 1.0 - (volatility * 0.5); // Reduce position size as volatility increases
+        let sell_amount = trade.position_size * tier.percentage * position_adjustment;
+        let potential_profit = sell_amount * (trade.current_price - trade.entry_price);
+        let estimated_gas = gas_cost * tier.gas_buffer;
+        let total_costs = estimated_gas + trade.gas_fees;
+
+        // Only sell if we have a net profit
+        let net_profit = potential_profit - total_costs;
+        if net_profit > 0.0 && net_profit > self.min_profit_threshold {
+            TierDecision::Sell { sell_amount, potential_profit, estimated_gas, total_costs, net_profit }
+        } else {
+            TierDecision::Hold { net_profit }
+        }
+    }
+
+    // Dry run of the exit ladder for the open position in `token_address`: for each
+    // hypothetical price, what the profit cap and each tier would sell and the resulting
+    // PnL. Scenarios are independent and nothing is executed, journaled or recorded.
+    pub async fn simulate_exit_ladder(&self, token_address: &str, price_scenarios: &[f64]) -> Result<Vec<ExitSimulation>> {
+        let position = self.active_trades.iter()
+            .find(|t| t.token_address == token_address && t.position_size > 0.0)
+            .ok_or_else(|| anyhow::anyhow!("No open position in {}", token_address))?;
+        let volatility = self.calculate_volatility(position).await?;
+        let gas_cost = self.estimate_gas_cost().await?;
+
+        let mut simulations = Vec::new();
+        for &price in price_scenarios {
+            let mut trade = position.clone();
+            trade.current_price = price;
+            trade.unrealized_profits = (price - trade.entry_price) * trade.position_size;
+            let mut sells = Vec::new();
+
+            if self.max_unrealized_profit.map_or(false, |cap| trade.unrealized_profits >= cap) {
+                sells.push(SimulatedSell {
+                    action: "full_exit".to_string(),
+                    tier_multiplier: None,
+                    trigger_multiplier: None,
+                    sell_amount: trade.position_size,
+                    net_profit: trade.unrealized_profits - gas_cost,
+                    estimated_gas: gas_cost,
+                });
+                trade.position_size = 0.0;
+            } else {
+                let total_costs = trade.gas_fees + gas_cost;
+                let min_profit_multiplier = 1.0 + (total_costs / (trade.position_size * trade.entry_price));
+
+                for base_tier in &self.profit_tiers {
+                    if trade.profit_tiers_hit.contains(&base_tier.multiplier) {
+                        continue;
+                    }
+
+                    let tier = &self.profit_acceleration.apply(base_tier, volatility);
+                    if let TierDecision::Sell { sell_amount, estimated_gas, net_profit, .. } =
+                        self.evaluate_tier(&trade, tier, volatility, gas_cost, min_profit_multiplier) {
+                        sells.push(SimulatedSell {
+                            action: "take_profit".to_string(),
+                            tier_multiplier: Some(base_tier.multiplier),
+                            trigger_multiplier: Some(tier.multiplier * (1.0 - volatility * tier.volatility_adjustment)),
+                            sell_amount,
+                            net_profit,
+                            estimated_gas,
+                        });
+                        trade.position_size -= sell_amount;
+                        trade.gas_fees += estimated_gas;
+                    }
+                }
+            }
+
+            simulations.push(ExitSimulation {
+                price,
+                multiplier: price / trade.entry_price,
+                realized_profit: sells.iter().map(|sell| sell.net_profit).sum(),
+                remaining_position: trade.position_size,
+                unrealized_profit: (price - trade.entry_price) * trade.position_size,
+                sells,
+            });
+        }
+
+        Ok(simulations)
+    }
+
+    // Tracked size per mint, summed over its trades
+    pub fn tracked_positions(&self) -> HashMap<String, f64> {
+        let mut tracked: HashMap<String, f64> = HashMap::new();
+        for trade in &self.active_trades {
+            *tracked.entry(trade.token_address.clone()).or_insert(0.0) += trade.position_size;
+        }
+        tracked
+    }
+
+    pub fn newest_entry(&self, token_address: &str) -> Option<DateTime<Utc>> {
+        self.active_trades.iter()
+            .filter(|t| t.token_address == token_address)
+            .map(|t| t.entry_time)
+            .max()
+    }
+
+    // `tracked_size` and `on_chain_size` are the colony's totals for the mint, so every
+    // manager holding it scales its trades by the same ratio
+    pub fn apply_position_correction(&mut self, token_address: &str, tracked_size: f64, on_chain_size: f64) {
+        let mut trades: Vec<&mut TradeProfit> = self.active_trades.iter_mut()
+            .filter(|t| t.token_address == token_address)
+            .collect();
+
+        if tracked_size > 0.0 {
+            // Spread the correction across the token's trades in proportion to their size
+            let ratio = on_chain_size / tracked_size;
+            for trade in trades {
+                let previous_size = trade.position_size;
+                trade.position_size *= ratio;
+                trade.unrealized_profits = (trade.current_price - trade.entry_price) * trade.position_size;
+                self.journal.record(&trade.trade_id, JournalEvent::PositionReconciled {
+                    tracked_size: previous_size,
+                    on_chain_size: trade.position_size,
+                });
+            }
+        } else if let Some(trade) = trades.iter_mut().max_by_key(|t| t.entry_time) {
+            // Nothing tracked but the wallet holds tokens: a late fill on the newest trade
+            trade.position_size = on_chain_size;
+            trade.unrealized_profits = (trade.current_price - trade.entry_price) * trade.position_size;
+            self.journal.record(&trade.trade_id, JournalEvent::PositionReconciled {
+                tracked_size: 0.0,
+                on_chain_size,
+            });
+        }
+    }
+
+    pub fn set_pool_locator(&mut self, pool_locator: Arc<dyn PoolLocator>) {
+        self.pool_locator = Some(pool_locator);
+    }
+
+    pub fn set_candle_source(&mut self, candle_source: Arc<dyn CandleSource>) {
+        self.candle_source = Some(candle_source);
+    }
+
+    pub fn set_swap_executor(&mut self, swap_executor: Arc<SwapExecutor>) {
+        self.swap_executor = Some(swap_executor);
+    }
+
+    pub fn set_gas_price_source(&mut self, gas_price_source: Arc<dyn GasPriceSource>) {
+        self.gas_price_source = Some(gas_price_source);
+    }
+
+    pub fn set_message_queue(&mut self, message_queue: MessageQueue) {
+        self.message_queue = Some(message_queue);
+    }
+
+    // False when sells are only dry runs
+    pub fn has_swap_executor(&self) -> bool {
+        self.swap_executor.is_some()
+    }
+
+    // Re-points open positions whose liquidity has moved to a different pool for the same
+    // mint. Only the pool changes; size, entry and tiers already hit carry over untouched.
+    pub async fn check_pool_migrations(&mut self) -> Result<Vec<PoolMigration>> {
+        let pool_locator = match &self.pool_locator {
+            Some(locator) => locator.clone(),
+            None => return Ok(Vec::new()),
+        };
+
+        let mut watched: Vec<(String, String)> = self.active_trades.iter()
+            .filter(|t| t.position_size > 0.0)
+            .map(|t| (t.token_address.clone(), t.pool_address.clone()))
+            .collect();
+        watched.sort();
+        watched.dedup();
+
+        let mut migrations = Vec::new();
+        for (token_address, current_pool) in watched {
+            let pools = match pool_locator.pools_for(&token_address).await {
+                Ok(pools) => pools,
+                Err(e) => {
+                    warn!("Profit Manager {} could not look up pools for {}: {}", self.id, token_address, e);
+                    continue;
+                }
+            };
+
+            if let Some(migration) = self.pool_migration.detect(&token_address, &current_pool, &pools) {
+                self.apply_pool_migration(&migration);
+                migrations.push(migration);
+            }
+        }
+
+        Ok(migrations)
+    }
+
+    fn apply_pool_migration(&mut self, migration: &PoolMigration) {
+        for trade in self.active_trades.iter_mut()
+            .filter(|t| t.token_address == migration.token_address && t.pool_address == migration.from_pool) {
+            trade.pool_address = migration.to_pool.clone();
+            self.journal.record(&trade.trade_id, JournalEvent::PoolMigrated {
+                from_pool: migration.from_pool.clone(),
+                to_pool: migration.to_pool.clone(),
+                dex: migration.to_dex.clone(),
+            });
+        }
+        info!("Profit Manager {} moved {} from pool {} to {} on {}",
+              self.id, migration.token_address, migration.from_pool, migration.to_pool, migration.to_dex);
+    }
+
     async fn calculate_volatility(&self, trade: &TradeProfit) -> Result<f64> {
-        // Placeholder for volatility calculation
-        // This would involve:
-        // 1. Fetching price history
-        // 2. Calculating standard deviation
-        // 3. Normalizing to 0-1 range
-        Ok(0.1) // Example value
+        // Normalized stdev of the token's recorded price returns
+        if let Some(volatility) = self.volatility_tracker.volatility(&trade.token_address) {
+            return Ok(volatility);
+        }
+        Ok(self.default_volatility)
+    }
+
+    // SOL a sell is expected to pay at the current optimal priority fee
+    pub async fn estimate_gas_cost(&self) -> Result<f64> {
+        let gas_price = self.get_optimal_gas_price().await?;
+        Ok(self.gas_price_history.cost(gas_price))
     }
 
-    async fn estimate_gas_cost(&self) -> Result<f64> {
-        // Placeholder for gas cost estimation
-        // This would involve:
-        // 1. Using gas price history
-        // 2. Estimating transaction size
-        // 3. Calculating total cost
-        Ok(0.01) // Example value
+    async fn publish_execution(&self, event: TradeExecutionEvent) {
+        if let Some(message_queue) = &self.message_queue {
+            message_queue.publish(Message::TradeExecution(event)).await;
+        }
     }
 
-    async fn execute_partial_sell(&mut self, trade: &TradeProfit, tier: &ProfitTier) -> Result<()> {
+    // Sells `sell_amount` of the trade no lower than `min_price`; `label` says why in the logs
+    async fn execute_partial_sell(&self, trade: &TradeProfit, sell_amount: f64, min_price: f64, net_profit: f64, label: &str) -> Result<()> {
         // Calculate optimal gas price based on current market conditions
         let gas_price = self.get_optimal_gas_price().await?;
-        
-        // Build sell transaction with minimum profit guarantee
-        let sell_amount = trade.position_size * tier.percentage;
-        let min_price = trade.entry_price * (1.0 + (trade.gas_fees / (sell_amount * trade.entry_price)));
-        
+
         // Create sell transaction with minimum price guarantee
         let transaction = self.build_sell_transaction(
             trade.token_address.clone(),
+            trade.pool_address.clone(),
             sell_amount,
             min_price,
             gas_price
         ).await?;
 
         // Execute transaction with enhanced monitoring
+        self.publish_execution(TradeExecutionEvent::new(
+            &trade.token_address, TradeAction::Sell, sell_amount, TradeEventStatus::Submitted,
+        )).await;
         match self.send_transaction(transaction).await {
             Ok(hash) => {
-                info!("Successfully executed sell for trade {} at {}x: {}", 
-                      trade.trade_id, tier.multiplier, hash);
+                self.journal.record(&trade.trade_id, JournalEvent::TransactionSent {
+                    signature: hash.clone(),
+                    amount: sell_amount,
+                });
+                // Without a swap executor the hash is a placeholder and the sell a dry run
+                let event = match self.swap_executor {
+                    Some(_) => TradeExecutionEvent {
+                        signature: Some(hash.clone()),
+                        ..TradeExecutionEvent::new(&trade.token_address, TradeAction::Sell, sell_amount, TradeEventStatus::Confirmed)
+                    },
+                    None => TradeExecutionEvent::new(&trade.token_address, TradeAction::Sell, sell_amount, TradeEventStatus::Simulated),
+                };
+                self.publish_execution(TradeExecutionEvent { realized_pnl: Some(net_profit), ..event }).await;
+                info!("Successfully executed sell for trade {} {}: {}",
+                      trade.trade_id, label, hash);
                 Ok(())
             }
             Err(e) => {
-                error!("Failed to execute sell for trade {} at {}x: {}", 
-                       trade.trade_id, tier.multiplier, e);
+                self.journal.record(&trade.trade_id, JournalEvent::TransactionFailed { error: e.to_string() });
+                self.publish_execution(TradeExecutionEvent {
+                    error: Some(e.to_string()),
+                    ..TradeExecutionEvent::new(&trade.token_address, TradeAction::Sell, sell_amount, TradeEventStatus::Failed)
+                }).await;
+                error!("Failed to execute sell for trade {} {}: {}",
+                       trade.trade_id, label, e);
                 Err(e)
             }
         }
     }
 
-    async fn get_optimal_gas_price(&self) -> Result<f64> {
-        // TODO: Implement optimal gas price calculation
-        // This would involve:
-        // 1. Analyzing recent gas price history
-        // 2. Predicting optimal gas price
-        // 3. Adding safety buffer
-        Ok(0.0) // Replace with actual implementation
+    // Every failure is logged and journaled here, whether the sell couldn't be built or sent
+    async fn execute_full_exit(&mut self, trade_id: &str) -> Result<()> {
+        let result = self.try_full_exit(trade_id).await;
+        if let Err(e) = &result {
+            self.journal.record(trade_id, JournalEvent::TransactionFailed { error: e.to_string() });
+            error!("Failed to force-exit trade {}: {}", trade_id, e);
+        }
+        result
+    }
+
+    async fn try_full_exit(&mut self, trade_id: &str) -> Result<()> {
+        let trade = match self.get_trade_profits(trade_id).await {
+            Some(trade) => trade,
+            None => return Ok(()),
+        };
+
+        let gas_price = self.get_optimal_gas_price().await?;
+        let estimated_gas = self.estimate_gas_cost().await?;
+        let transaction = self.build_sell_transaction(
+            trade.token_address.clone(),
+            trade.pool_address.clone(),
+            trade.position_size,
+            self.exit_min_price(&trade),
+            gas_price
+        ).await?;
+
+        let hash = self.send_transaction(transaction).await?;
+        self.journal.record(trade_id, JournalEvent::TransactionSent {
+            signature: hash.clone(),
+            amount: trade.position_size,
+        });
+        let net_profit = trade.unrealized_profits - estimated_gas;
+        self.record_sell(&trade, trade.position_size, net_profit, estimated_gas).await;
+        self.state.read().await.session.record_trade_closed(trade.realized_profits + net_profit);
+        if let Some(index) = self.active_trades.iter().position(|t| t.trade_id == trade_id) {
+            let mut trade = self.active_trades[index].clone();
+            trade.realized_profits += net_profit;
+            trade.unrealized_profits = 0.0;
+            trade.position_size = 0.0;
+            trade.gas_fees += estimated_gas;
+            self.sync_portfolio(&trade).await;
+            self.active_trades[index] = trade;
+        }
+        info!("Profit Manager {} force-exited trade {} above profit cap: {} SOL ({})",
+              self.id, trade_id, net_profit, hash);
+        Ok(())
+    }
+
+    // Sells `fraction` of the open position in `token_address` outside the ladder, e.g. to
+    // follow a copied wallet out. Returns false when there is no such position.
+    pub async fn sell_token_fraction(&mut self, token_address: &str, fraction: f64, reason: &str) -> Result<bool> {
+        let Some(index) = self.active_trades.iter()
+            .position(|t| t.token_address == token_address && t.position_size > 0.0) else {
+            return Ok(false);
+        };
+        let trade_id = self.active_trades[index].trade_id.clone();
+        let fraction = fraction.clamp(0.0, 1.0);
+        if fraction <= 0.0 {
+            return Ok(true);
+        }
+
+        self.journal.record(&trade_id, JournalEvent::Decision {
+            action: if fraction >= 1.0 { "full_exit" } else { "partial_exit" }.to_string(),
+            reason: format!("selling {:.0}%: {}", fraction * 100.0, reason),
+        });
+        if fraction >= 1.0 {
+            // Already logged and journaled; the caller still learns the position wasn't sold
+            return self.execute_full_exit(&trade_id).await.map(|_| true);
+        }
+
+        let mut trade = self.active_trades[index].clone();
+        let sell_amount = trade.position_size * fraction;
+        let estimated_gas = self.estimate_gas_cost().await?;
+        let net_profit = trade.unrealized_profits * fraction - estimated_gas;
+        let label = format!("({:.0}%, {})", fraction * 100.0, reason);
+        self.execute_partial_sell(&trade, sell_amount, self.exit_min_price(&trade), net_profit, &label).await?;
+        self.record_sell(&trade, sell_amount, net_profit, estimated_gas).await;
+
+        trade.realized_profits += net_profit;
+        trade.unrealized_profits *= 1.0 - fraction;
+        trade.position_size -= sell_amount;
+        trade.gas_fees += estimated_gas;
+        self.sync_portfolio(&trade).await;
+        self.active_trades[index] = trade;
+        Ok(true)
+    }
+
+    // Lowest price an exit outside the ladder accepts. It follows the latest quote rather than
+    // the entry, so a position under water can still be closed.
+    fn exit_min_price(&self, trade: &TradeProfit) -> f64 {
+        trade.current_price * (1.0 - self.max_exit_slippage)
+    }
+
+    // Priority fee in micro-lamports per compute unit, from the rolling sample window
+    pub async fn get_optimal_gas_price(&self) -> Result<f64> {
+        Ok(self.gas_price_history.optimal_price())
     }
 
     async fn build_sell_transaction(
         &self,
         token_address: String,
+        pool_address: String,
         amount: f64,
         min_price: f64,
         gas_price: f64
     ) -> Result<Transaction> {
-        // TODO: Implement sell transaction building
-        // This would involve:
-        // 1. Creating sell instruction with minimum price
-        // 2. Setting up transaction with optimal gas
-        // 3. Adding necessary signatures
-        // 4. Setting appropriate fees
-        Ok(Transaction::default())
+        // Jupiter routes across pools, so the tracked pool only matters for monitoring;
+        // priority fees are set by the TransactionHandler
+        match &self.swap_executor {
+            Some(swap_executor) => swap_executor.build_sell(&token_address, amount, min_price).await,
+            None => Ok(Transaction::default()),
+        }
+    }
+
+    // Sells go out through the swap executor, which submits them via the shared
+    // TransactionHandler; without one they are dry runs
+    async fn send_transaction(&self, transaction: Transaction) -> Result<String> {
+        match &self.swap_executor {
+            Some(swap_executor) => swap_executor.submit(transaction).await,
+            None => Ok("transaction_hash".to_string()),
+        }
     }
 
     async fn cleanup_completed_trades(&mut self) -> Result<()> {
@@ -284,24 +936,103 @@ impl ProfitManager {
             now - trade.entry_time < max_age
         });
 
+        let active_trades = &self.active_trades;
+        self.volatility_tracker.retain(|token| active_trades.iter().any(|t| t.token_address == token));
+
         Ok(())
     }
 
-    pub async fn add_trade(&mut self, trade: TradeProfit) -> Result<()> {
+    pub async fn add_trade(&mut self, mut trade: TradeProfit) -> Result<()> {
+        if trade.pool_address.is_empty() {
+            trade.pool_address = self.locate_pool(&trade.token_address).await;
+        }
+        self.journal.record(&trade.trade_id, JournalEvent::Opened {
+            token_address: trade.token_address.clone(),
+            entry_price: trade.entry_price,
+            position_size: trade.position_size,
+        });
+        {
+            let state = self.state.read().await;
+            state.session.record_trade_opened();
+            state.portfolio.update_position(&trade);
+        }
+        self.backfill_price_history(&trade).await;
         self.active_trades.push(trade);
         info!("Profit Manager {} added new trade", self.id);
         Ok(())
     }
 
+    // The mint's deepest pool, or empty when it can't be looked up; with migration checks
+    // enabled, a position left without one is re-pointed on the next check
+    async fn locate_pool(&self, token_address: &str) -> String {
+        let Some(pool_locator) = &self.pool_locator else {
+            return String::new();
+        };
+        match pool_locator.pools_for(token_address).await {
+            Ok(pools) => pools.into_iter()
+                .max_by(|a, b| a.liquidity.partial_cmp(&b.liquidity).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|pool| pool.pool_address)
+                .unwrap_or_default(),
+            Err(e) => {
+                warn!("Profit Manager {} could not look up the pool for {}: {}", self.id, token_address, e);
+                String::new()
+            }
+        }
+    }
+
+    // A failed backfill only costs the head start; live ticks still fill the history
+    async fn backfill_price_history(&mut self, trade: &TradeProfit) {
+        let candle_source = match &self.candle_source {
+            Some(source) => source.clone(),
+            None => return,
+        };
+        if self.volatility_tracker.history_len(&trade.token_address) > 0 {
+            return;
+        }
+        if trade.pool_address.is_empty() {
+            warn!("Profit Manager {} has no pool for {}; skipping the backfill", self.id, trade.token_address);
+            return;
+        }
+
+        match candle_source.recent_candles(&trade.token_address, &trade.pool_address, self.backfill_candles).await {
+            Ok(candles) => {
+                self.volatility_tracker.seed(&trade.token_address, &candles);
+                info!("Profit Manager {} backfilled {} candles for {}", self.id, candles.len(), trade.token_address);
+            }
+            Err(e) => warn!("Profit Manager {} could not backfill price history for {}: {}",
+                            self.id, trade.token_address, e),
+        }
+    }
+
+    pub fn profit_tiers(&self) -> &[ProfitTier] {
+        &self.profit_tiers
+    }
+
+    pub fn volatility_tracker(&self) -> &VolatilityTracker {
+        &self.volatility_tracker
+    }
+
     pub async fn update_trade_price(&mut self, trade_id: &str, current_price: f64) -> Result<()> {
         if let Some(trade) = self.active_trades.iter_mut()
             .find(|t| t.trade_id == trade_id) {
+            self.volatility_tracker.record(&trade.token_address, current_price);
             trade.current_price = current_price;
             trade.unrealized_profits = (current_price - trade.entry_price) * trade.position_size;
+            self.journal.record(trade_id, JournalEvent::PriceUpdate {
+                price: current_price,
+                unrealized_profits: trade.unrealized_profits,
+            });
+            self.state.read().await.portfolio.update_position(trade);
         }
         Ok(())
     }
 
+    // Stops tracking a trade without selling it, e.g. once it was closed elsewhere
+    pub fn remove_trade(&mut self, trade_id: &str) -> Option<TradeProfit> {
+        let index = self.active_trades.iter().position(|t| t.trade_id == trade_id)?;
+        Some(self.active_trades.remove(index))
+    }
+
     pub async fn get_trade_profits(&self, trade_id: &str) -> Option<TradeProfit> {
         self.active_trades.iter()
             .find(|t| t.trade_id == trade_id)
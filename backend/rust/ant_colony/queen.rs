@@ -3,7 +3,8 @@ use config::Config;
 use log::{info, error, warn};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use crate::ant_colony::ColonyState;
+use crate::ant_colony::{ColonyState, ColonyHealth};
+use crate::api::WebSocketServer;
 
 pub struct Queen {
     id: String,
@@ -13,6 +14,8 @@ pub struct Queen {
     reserve_capital: f64,
     reinvestment_threshold: f64,
     risk_threshold: f64,
+    health: ColonyHealth,
+    dashboard: Option<WebSocketServer>, // Its /status is refreshed on every monitoring pass
 }
 
 impl Queen {
@@ -29,36 +32,58 @@ impl Queen {
             reserve_capital: initial_capital * 0.2, // 20% reserve
             reinvestment_threshold,
             risk_threshold,
+            health: ColonyHealth::new(config),
+            dashboard: None,
         })
     }
 
-    pub async fn start_monitoring(&mut self) -> Result<()> {
-        self.is_active = true;
-        info!("Queen {} started monitoring", self.id);
+    pub fn set_dashboard(&mut self, dashboard: WebSocketServer) {
+        self.dashboard = Some(dashboard);
+    }
 
-        while self.is_active {
-            if let Err(e) = self.monitor_and_manage().await {
-                error!("Queen {} monitoring error: {}", self.id, e);
+    // Monitors every 5 seconds until `shutdown`. The queen is only locked for one pass at a
+    // time, so shutdown and the colony's getters get in between passes.
+    pub async fn run(queen: Arc<RwLock<Self>>) {
+        let id = {
+            let mut queen = queen.write().await;
+            queen.is_active = true;
+            queen.id.clone()
+        };
+        info!("Queen {} started monitoring", id);
+
+        loop {
+            {
+                let mut queen = queen.write().await;
+                if !queen.is_active {
+                    break;
+                }
+                if let Err(e) = queen.monitor_and_manage().await {
+                    error!("Queen {} monitoring error: {}", id, e);
+                }
             }
             tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
         }
-
-        Ok(())
     }
 
     async fn monitor_and_manage(&mut self) -> Result<()> {
-        let mut state = self.state.write().await;
-        
-        // Update colony state
-        state.total_capital = self.total_capital;
-        state.risk_level = self.calculate_risk_level();
-
-        // Check if we need to stop trading
-        if state.risk_level > self.risk_threshold {
-            warn!("Risk level {} exceeds threshold {}, stopping trades", 
-                  state.risk_level, self.risk_threshold);
-            state.is_active = false;
-            return Ok(());
+        {
+            let mut state = self.state.write().await;
+
+            // Update colony state
+            state.last_loop_tick = Some(chrono::Utc::now());
+            state.total_capital = self.total_capital;
+            state.risk_level = self.calculate_risk_level(state.active_trades);
+            if let Some(dashboard) = &self.dashboard {
+                dashboard.publish_health(self.health.evaluate(&state.health_signals())).await;
+            }
+
+            // Check if we need to stop trading
+            if state.risk_level > self.risk_threshold {
+                warn!("Risk level {} exceeds threshold {}, stopping trades",
+                      state.risk_level, self.risk_threshold);
+                state.is_active = false;
+                return Ok(());
+            }
         }
 
         // Manage capital distribution
@@ -67,18 +92,17 @@ impl Queen {
         Ok(())
     }
 
-    fn calculate_risk_level(&self) -> f64 {
+    // From the open trade count in the state guard the caller already holds
+    fn calculate_risk_level(&self, active_trades: u32) -> f64 {
         // Calculate risk level based on various factors
-        let active_trades_risk = (self.state.read().await.active_trades as f64) / 100.0;
+        let active_trades_risk = active_trades as f64 / 100.0;
         let capital_utilization = 1.0 - (self.reserve_capital / self.total_capital);
-        
+
         // Weighted average of risk factors
-        (active_trades_risk * 0.4 + capital_utilization * 0.6)
+        active_trades_risk * 0.4 + capital_utilization * 0.6
     }
 
     async fn manage_capital_distribution(&mut self) -> Result<()> {
-        let state = self.state.read().await;
-        
         // Check if we need to replenish reserve
         if self.reserve_capital < self.total_capital * 0.2 {
             self.replenish_reserve().await?;
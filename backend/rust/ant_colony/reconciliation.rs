@@ -0,0 +1,221 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::{error, warn};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_account_decoder::UiAccountData;
+use solana_sdk::pubkey::Pubkey;
+use crate::ant_colony::profit_manager::ProfitManager;
+
+// Where the colony reads what its wallets actually hold
+#[async_trait]
+pub trait BalanceSource: Send + Sync {
+    // UI token amount (decimals applied) held by `wallet` for a mint
+    async fn token_balance(&self, wallet: &Pubkey, token_address: &str) -> Result<f64>;
+}
+
+pub struct RpcBalanceSource {
+    rpc_client: RpcClient,
+}
+
+impl RpcBalanceSource {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            rpc_client: RpcClient::new(rpc_url),
+        }
+    }
+}
+
+#[async_trait]
+impl BalanceSource for RpcBalanceSource {
+    async fn token_balance(&self, wallet: &Pubkey, token_address: &str) -> Result<f64> {
+        let mint = Pubkey::from_str(token_address)?;
+        let accounts = self.rpc_client
+            .get_token_accounts_by_owner(wallet, TokenAccountsFilter::Mint(mint))
+            .await?;
+
+        // A wallet can hold the same mint across several token accounts
+        let balance = accounts.iter()
+            .filter_map(|keyed| match &keyed.account.data {
+                UiAccountData::Json(parsed) => {
+                    parsed.parsed["info"]["tokenAmount"]["uiAmount"].as_f64()
+                }
+                _ => None,
+            })
+            .sum();
+
+        Ok(balance)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionDrift {
+    pub token_address: String,
+    pub tracked_size: f64,
+    pub on_chain_size: f64,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl PositionDrift {
+    pub fn difference(&self) -> f64 {
+        self.on_chain_size - self.tracked_size
+    }
+}
+
+// A princess's profit manager and the pool wallets its trades are signed by; empty when
+// the princess trades from the configured wallet
+struct ReconciledPrincess {
+    profit_manager: Arc<RwLock<ProfitManager>>,
+    wallets: Vec<Pubkey>,
+}
+
+// Compares what every princess tracks with what the colony's wallets hold, e.g. after a
+// manual sell or a fill that confirmed after we gave up. Positions are totalled per mint
+// across princesses and balances across wallets, so a mint two princesses hold isn't
+// counted twice and a buy signed by a pool wallet isn't read as missing.
+pub struct PositionReconciler {
+    balance_source: Arc<dyn BalanceSource>,
+    trading_wallet: Option<Pubkey>, // Signs for princesses without a wallet pool
+    interval: std::time::Duration,
+    drift_tolerance: f64, // Fraction of the tracked size ignored as rounding noise
+    princesses: Mutex<Vec<ReconciledPrincess>>,
+}
+
+impl PositionReconciler {
+    // Off unless enabled in [ant_colony.profit_manager.reconciliation]
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if !config.get_bool("ant_colony.profit_manager.reconciliation.enabled").unwrap_or(false) {
+            return Ok(None);
+        }
+        let rpc_url = config.get_string("ant_colony.profit_manager.reconciliation.rpc_url")?;
+        let wallet_address = config.get_string("ant_colony.profit_manager.reconciliation.wallet_address")
+            .unwrap_or_default();
+        let trading_wallet = if wallet_address.is_empty() {
+            None
+        } else {
+            Some(Pubkey::from_str(&wallet_address)?)
+        };
+        Ok(Some(Self::new(config, Arc::new(RpcBalanceSource::new(rpc_url)), trading_wallet)))
+    }
+
+    pub fn new(config: &Config, balance_source: Arc<dyn BalanceSource>, trading_wallet: Option<Pubkey>) -> Self {
+        Self {
+            balance_source,
+            trading_wallet,
+            interval: std::time::Duration::from_secs(
+                config.get_int("ant_colony.profit_manager.reconciliation.interval_secs").unwrap_or(30) as u64
+            ),
+            drift_tolerance: config.get_float("ant_colony.profit_manager.reconciliation.drift_tolerance")
+                .unwrap_or(0.01),
+            princesses: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn register(&self, profit_manager: Arc<RwLock<ProfitManager>>, wallets: Vec<Pubkey>) {
+        self.princesses.lock().unwrap().push(ReconciledPrincess { profit_manager, wallets });
+    }
+
+    // A despawned princess's positions and wallets no longer count
+    pub fn unregister(&self, profit_manager: &Arc<RwLock<ProfitManager>>) {
+        self.princesses.lock().unwrap().retain(|p| !Arc::ptr_eq(&p.profit_manager, profit_manager));
+    }
+
+    pub async fn reconcile(&self) -> Result<Vec<PositionDrift>> {
+        let (profit_managers, wallets) = {
+            let princesses = self.princesses.lock().unwrap();
+            let profit_managers: Vec<Arc<RwLock<ProfitManager>>> = princesses.iter()
+                .map(|p| p.profit_manager.clone())
+                .collect();
+            let mut wallets: Vec<Pubkey> = Vec::new();
+            for princess in princesses.iter() {
+                let signers = if princess.wallets.is_empty() {
+                    self.trading_wallet.as_slice()
+                } else {
+                    princess.wallets.as_slice()
+                };
+                for wallet in signers {
+                    if !wallets.contains(wallet) {
+                        wallets.push(*wallet);
+                    }
+                }
+            }
+            (profit_managers, wallets)
+        };
+
+        let mut tracked: HashMap<String, f64> = HashMap::new();
+        for profit_manager in &profit_managers {
+            for (token_address, size) in profit_manager.read().await.tracked_positions() {
+                *tracked.entry(token_address).or_insert(0.0) += size;
+            }
+        }
+
+        let mut drifts = Vec::new();
+        'mints: for (token_address, tracked_size) in tracked {
+            let mut on_chain_size = 0.0;
+            for wallet in &wallets {
+                match self.balance_source.token_balance(wallet, &token_address).await {
+                    Ok(balance) => on_chain_size += balance,
+                    Err(e) => {
+                        // A partial total would read as drift
+                        warn!("Reconciler could not read {}'s balance of {}: {}", wallet, token_address, e);
+                        continue 'mints;
+                    }
+                }
+            }
+
+            let tolerance = tracked_size.abs() * self.drift_tolerance;
+            if (on_chain_size - tracked_size).abs() <= tolerance.max(f64::EPSILON) {
+                continue;
+            }
+
+            warn!("Reconciler found position drift on {}: tracked {} vs on-chain {}",
+                  token_address, tracked_size, on_chain_size);
+            if tracked_size > 0.0 {
+                for profit_manager in &profit_managers {
+                    profit_manager.write().await.apply_position_correction(&token_address, tracked_size, on_chain_size);
+                }
+            } else {
+                // Nothing tracked but the wallets hold tokens: a late fill on the colony's newest trade
+                let mut newest: Option<(DateTime<Utc>, &Arc<RwLock<ProfitManager>>)> = None;
+                for profit_manager in &profit_managers {
+                    if let Some(entry) = profit_manager.read().await.newest_entry(&token_address) {
+                        if newest.map_or(true, |(latest, _)| entry > latest) {
+                            newest = Some((entry, profit_manager));
+                        }
+                    }
+                }
+                if let Some((_, profit_manager)) = newest {
+                    profit_manager.write().await.apply_position_correction(&token_address, tracked_size, on_chain_size);
+                }
+            }
+            drifts.push(PositionDrift {
+                token_address,
+                tracked_size,
+                on_chain_size,
+                detected_at: Utc::now(),
+            });
+        }
+
+        Ok(drifts)
+    }
+
+    // Runs a pass every interval until the handle is aborted
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.reconcile().await {
+                    error!("Reconciliation error: {}", e);
+                }
+                tokio::time::sleep(self.interval).await;
+            }
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use config::Config;
+use log::{error, info};
+use std::sync::Mutex;
+use thiserror::Error;
+use crate::common::{AlertSeverity, ColonyAlert, Message, MessageQueue, RiskUpdate};
+
+#[derive(Debug, Clone, Error)]
+#[error("Trading is frozen for {day}: daily loss {daily_loss} SOL (limit {max_daily_loss}), {daily_trades} trades (limit {max_daily_trades})")]
+pub struct TradingFrozen {
+    pub day: NaiveDate,
+    pub daily_loss: f64,
+    pub max_daily_loss: f64,
+    pub daily_trades: u32,
+    pub max_daily_trades: u32,
+}
+
+#[derive(Debug)]
+struct DailyRisk {
+    day: NaiveDate,
+    realized_pnl: f64, // SOL, net of every close today
+    trades: u32,
+}
+
+impl DailyRisk {
+    fn new(day: NaiveDate) -> Self {
+        Self { day, realized_pnl: 0.0, trades: 0 }
+    }
+
+    fn loss(&self) -> f64 {
+        (-self.realized_pnl).max(0.0)
+    }
+}
+
+// Colony-wide daily limits from `max_daily_loss_sol` and `max_daily_trades`: once today's net
+// realized loss or trade count reaches its limit, no new trade opens until the next UTC day.
+// Unlike the strategy breakers this stops every strategy at once.
+pub struct RiskGovernor {
+    enabled: bool,
+    max_daily_loss: f64, // SOL, the unit trades are booked in
+    max_daily_trades: u32,
+    today: Mutex<DailyRisk>,
+    message_queue: Mutex<Option<MessageQueue>>, // Receives a RiskUpdate after every change, and an alert on freezing
+}
+
+impl Default for RiskGovernor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_daily_loss: 1.0,
+            max_daily_trades: 50,
+            today: Mutex::new(DailyRisk::new(Utc::now().date_naive())),
+            message_queue: Mutex::new(None),
+        }
+    }
+}
+
+impl RiskGovernor {
+    pub fn new(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: config.get_bool("ant_colony.risk_governor.enabled").unwrap_or(false),
+            max_daily_loss: config.get_float("ant_colony.risk_governor.max_daily_loss_sol").unwrap_or(defaults.max_daily_loss),
+            max_daily_trades: config.get_int("general.max_daily_trades")
+                .map(|trades| trades.max(0) as u32)
+                .unwrap_or(defaults.max_daily_trades),
+            ..defaults
+        }
+    }
+
+    pub fn set_message_queue(&self, message_queue: MessageQueue) {
+        *self.message_queue.lock().unwrap() = Some(message_queue);
+    }
+
+    // Called before any new position opens; Err while today's limits are used up
+    pub fn check(&self) -> Result<(), TradingFrozen> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let today = self.today_at(Utc::now());
+        match self.frozen(&today) {
+            Some(frozen) => Err(frozen),
+            None => Ok(()),
+        }
+    }
+
+    pub fn should_freeze_trading(&self) -> bool {
+        self.check().is_err()
+    }
+
+    pub async fn record_trade(&self) {
+        let (update, frozen) = {
+            let mut today = self.today_at(Utc::now());
+            let was_frozen = self.frozen(&today).is_some();
+            today.trades += 1;
+            self.after_change(&today, was_frozen)
+        };
+        self.publish(update, frozen).await;
+    }
+
+    // Realized PnL of a sell in SOL, net of gas; losses are negative
+    pub async fn record_realized(&self, pnl: f64) {
+        let (update, frozen) = {
+            let mut today = self.today_at(Utc::now());
+            let was_frozen = self.frozen(&today).is_some();
+            today.realized_pnl += pnl;
+            self.after_change(&today, was_frozen)
+        };
+        self.publish(update, frozen).await;
+    }
+
+    pub fn daily_loss(&self) -> f64 {
+        self.today_at(Utc::now()).loss()
+    }
+
+    pub fn daily_trades(&self) -> u32 {
+        self.today_at(Utc::now()).trades
+    }
+
+    // The counters start over at UTC midnight, which is also what lifts a freeze
+    fn today_at(&self, now: DateTime<Utc>) -> std::sync::MutexGuard<'_, DailyRisk> {
+        let mut today = self.today.lock().unwrap();
+        let day = now.date_naive();
+        if today.day != day {
+            if self.frozen(&today).is_some() {
+                info!("New UTC day {}: lifting the daily risk freeze", day);
+            }
+            *today = DailyRisk::new(day);
+        }
+        today
+    }
+
+    fn frozen(&self, today: &DailyRisk) -> Option<TradingFrozen> {
+        if today.loss() < self.max_daily_loss && today.trades < self.max_daily_trades {
+            return None;
+        }
+        Some(TradingFrozen {
+            day: today.day,
+            daily_loss: today.loss(),
+            max_daily_loss: self.max_daily_loss,
+            daily_trades: today.trades,
+            max_daily_trades: self.max_daily_trades,
+        })
+    }
+
+    // The update to publish, plus the freeze if this change is what caused it
+    fn after_change(&self, today: &DailyRisk, was_frozen: bool) -> (RiskUpdate, Option<TradingFrozen>) {
+        let frozen = if self.enabled && !was_frozen { self.frozen(today) } else { None };
+        if let Some(frozen) = &frozen {
+            error!("ALERT: {}", frozen);
+        }
+        let update = RiskUpdate {
+            position_size: 0.0, // Exposure is tracked by the capital manager, not here
+            daily_loss: today.loss(),
+            daily_trades: today.trades,
+            timestamp: Utc::now(),
+        };
+        (update, frozen)
+    }
+
+    async fn publish(&self, update: RiskUpdate, frozen: Option<TradingFrozen>) {
+        let message_queue = self.message_queue.lock().unwrap().clone();
+        if let Some(message_queue) = message_queue {
+            message_queue.publish(Message::RiskUpdate(update)).await;
+            if let Some(frozen) = frozen {
+                message_queue.publish(Message::ColonyAlert(ColonyAlert {
+                    source: "risk_governor".to_string(),
+                    token_address: None,
+                    severity: AlertSeverity::Critical,
+                    message: frozen.to_string(),
+                    timestamp: Utc::now(),
+                })).await;
+            }
+        }
+    }
+}
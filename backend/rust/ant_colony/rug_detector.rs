@@ -4,6 +4,8 @@ use log::{info, error, warn};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::ant_colony::ColonyState;
+use crate::common::{AlertSeverity, ColonyAlert, LiquidityAlert, Message, MessageQueue};
+use crate::logging::ErrorReporter;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 
@@ -53,9 +55,13 @@ pub struct RugDetector {
     price_drop_threshold: f64,
     volume_drop_threshold: f64,
     liquidity_drop_threshold: f64,
+    liquidity_alert_threshold: f64, // Drops from here on are published as LiquidityAlerts
     holder_drop_threshold: f64,
     contract_risk_threshold: f64,
     history_window: i32, // hours
+    auto_blacklist: bool,
+    message_queue: Option<MessageQueue>, // Receives a ColonyAlert for every rug alert, and liquidity drops
+    error_tracker: Option<Arc<dyn ErrorReporter>>,
 }
 
 impl RugDetector {
@@ -63,9 +69,12 @@ impl RugDetector {
         let price_drop_threshold = config.get_float("ant_colony.rug_detector.price_drop_threshold")? as f64;
         let volume_drop_threshold = config.get_float("ant_colony.rug_detector.volume_drop_threshold")? as f64;
         let liquidity_drop_threshold = config.get_float("ant_colony.rug_detector.liquidity_drop_threshold")? as f64;
+        let liquidity_alert_threshold = config.get_float("ant_colony.rug_detector.liquidity_alert_threshold")
+            .unwrap_or(0.25);
         let holder_drop_threshold = config.get_float("ant_colony.rug_detector.holder_drop_threshold")? as f64;
         let contract_risk_threshold = config.get_float("ant_colony.rug_detector.contract_risk_threshold")? as f64;
         let history_window = config.get_int("ant_colony.rug_detector.history_window")? as i32;
+        let auto_blacklist = config.get_bool("ant_colony.rug_detector.auto_blacklist").unwrap_or(true);
 
         Ok(Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -75,12 +84,25 @@ impl RugDetector {
             price_drop_threshold,
             volume_drop_threshold,
             liquidity_drop_threshold,
+            liquidity_alert_threshold,
             holder_drop_threshold,
             contract_risk_threshold,
             history_window,
+            auto_blacklist,
+            message_queue: None,
+            error_tracker: None,
         })
     }
 
+    pub fn set_message_queue(&mut self, message_queue: MessageQueue) {
+        self.message_queue = Some(message_queue);
+    }
+
+    // Failures analyzing a monitored token are reported to it, tagged with the token
+    pub fn set_error_tracker(&mut self, error_tracker: Arc<dyn ErrorReporter>) {
+        self.error_tracker = Some(error_tracker);
+    }
+
     pub async fn start_monitoring(&mut self) -> Result<()> {
         self.is_active = true;
         info!("Rug Detector {} started monitoring", self.id);
@@ -105,11 +127,11 @@ impl RugDetector {
 
         // Update metrics for all monitored tokens
         for token in &mut self.monitored_tokens {
-            self.update_token_metrics(token).await?;
-            
-            // Check for rug indicators
-            if let Some(alert) = self.check_rug_indicators(token).await? {
-                self.handle_rug_alert(alert).await?;
+            if let Err(e) = self.analyze_token(token).await {
+                if let Some(error_tracker) = &self.error_tracker {
+                    error_tracker.capture_error_with_context(&e, &self.id, Some(&token.token_address));
+                }
+                return Err(e);
             }
         }
 
@@ -119,6 +141,17 @@ impl RugDetector {
         Ok(())
     }
 
+    async fn analyze_token(&mut self, token: &mut RugMetrics) -> Result<()> {
+        self.update_token_metrics(token).await?;
+        self.publish_liquidity_drop(token).await;
+
+        // Check for rug indicators
+        if let Some(alert) = self.check_rug_indicators(token).await? {
+            self.handle_rug_alert(alert).await?;
+        }
+        Ok(())
+    }
+
     async fn update_token_metrics(&mut self, token: &mut RugMetrics) -> Result<()> {
         let now = Utc::now();
         
@@ -210,7 +243,24 @@ impl RugDetector {
         Ok(None)
     }
 
-    async fn handle_rug_alert(&mut self, alert: RugAlert) -> Result<()> {
+    // Liquidity drops short of a rug still matter to the sniping core's killswitch and DCA
+    // runs, which apply their own thresholds to these alerts
+    async fn publish_liquidity_drop(&self, token: &RugMetrics) {
+        let Some(message_queue) = &self.message_queue else {
+            return;
+        };
+        let window_start = Utc::now() - chrono::Duration::hours(self.history_window as i64);
+        if let Some((peak, current)) = self.liquidity_peak_and_current(token, window_start) {
+            if (peak - current) / peak >= self.liquidity_alert_threshold {
+                message_queue.publish(Message::LiquidityAlert(LiquidityAlert {
+                    threshold_value: self.liquidity_alert_threshold,
+                    ..LiquidityAlert::drop(&token.token_address, peak, current)
+                })).await;
+            }
+        }
+    }
+
+    pub async fn handle_rug_alert(&mut self, alert: RugAlert) -> Result<()> {
         // Log the alert
         match alert.severity {
             RugAlertSeverity::Critical => error!("CRITICAL RUG ALERT: {}", alert.details),
@@ -218,6 +268,28 @@ impl RugDetector {
             RugAlertSeverity::Medium => warn!("MEDIUM RUG ALERT: {}", alert.details),
             RugAlertSeverity::Low => info!("LOW RUG ALERT: {}", alert.details),
         }
+        self.state.read().await.session.record_alert();
+
+        if let Some(message_queue) = &self.message_queue {
+            message_queue.publish(Message::ColonyAlert(ColonyAlert {
+                source: "rug_detector".to_string(),
+                token_address: Some(alert.token_address.clone()),
+                severity: match alert.severity {
+                    RugAlertSeverity::Critical => AlertSeverity::Critical,
+                    RugAlertSeverity::High => AlertSeverity::High,
+                    RugAlertSeverity::Medium => AlertSeverity::Medium,
+                    RugAlertSeverity::Low => AlertSeverity::Low,
+                },
+                message: format!("{:?}: {}", alert.alert_type, alert.details),
+                timestamp: alert.timestamp,
+            })).await;
+        }
+
+        // Honeypots and critical alerts mean the token must never be re-entered
+        if self.auto_blacklist && self.should_blacklist(&alert) {
+            let reason = format!("{:?}: {}", alert.alert_type, alert.details);
+            self.state.read().await.blacklist.add(&alert.token_address, &reason)?;
+        }
 
         // If critical, trigger emergency exit
         if matches!(alert.severity, RugAlertSeverity::Critical) {
@@ -227,6 +299,11 @@ impl RugDetector {
         Ok(())
     }
 
+    fn should_blacklist(&self, alert: &RugAlert) -> bool {
+        matches!(alert.alert_type, RugAlertType::HoneypotDetected) ||
+        matches!(alert.severity, RugAlertSeverity::Critical)
+    }
+
     async fn trigger_emergency_exit(&self, token_address: &str) -> Result<()> {
         // Placeholder for emergency exit logic
         // This would involve:
@@ -289,7 +366,28 @@ impl RugDetector {
         }
     }
 
-    // Similar helper methods for volume, liquidity, and holder drops
+    // Fraction lost since the highest liquidity in the window
+    fn calculate_liquidity_drop(&self, token: &RugMetrics, window_start: DateTime<Utc>) -> Option<f64> {
+        self.liquidity_peak_and_current(token, window_start)
+            .map(|(peak, current)| (peak - current) / peak)
+    }
+
+    // Highest liquidity in the window and the latest reading, once there are two readings
+    fn liquidity_peak_and_current(&self, token: &RugMetrics, window_start: DateTime<Utc>) -> Option<(f64, f64)> {
+        let recent: Vec<f64> = token.liquidity_history
+            .iter()
+            .filter(|(t, _)| *t >= window_start)
+            .map(|(_, liquidity)| *liquidity)
+            .collect();
+
+        let peak = recent.iter().fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        match recent.last() {
+            Some(current) if recent.len() >= 2 && peak > 0.0 => Some((peak, *current)),
+            _ => None,
+        }
+    }
+
+    // Similar helper methods for volume and holder drops
     // ... (implement these similarly to calculate_price_drop)
 
     pub async fn add_token(&mut self, token_address: String) -> Result<()> {
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use config::Config;
+
+// Decides how many princesses the colony should run. Shares its thresholds and bounds with
+// the PerformanceMonitor so both scale on the same success rates.
+#[derive(Debug, Clone)]
+pub struct ColonyScaler {
+    success_rate_threshold_low: f64,  // Below this the colony shrinks
+    success_rate_threshold_high: f64, // Above this the colony grows
+    min_princesses: usize,
+    max_princesses: usize,
+    scale_up_factor: f64,
+    scale_down_factor: f64,
+    last_success_rate: Option<f64>,
+}
+
+impl ColonyScaler {
+    pub fn new(config: &Config) -> Self {
+        let min_princesses = config.get_int("ant_colony.performance_monitor.min_workers").unwrap_or(1).max(0) as usize;
+        let max_princesses = config.get_int("ant_colony.performance_monitor.max_workers").unwrap_or(50).max(0) as usize;
+        Self {
+            success_rate_threshold_low: config.get_float("ant_colony.performance_monitor.success_rate_threshold_low").unwrap_or(0.3),
+            success_rate_threshold_high: config.get_float("ant_colony.performance_monitor.success_rate_threshold_high").unwrap_or(0.6),
+            min_princesses,
+            max_princesses: max_princesses.max(min_princesses),
+            scale_up_factor: config.get_float("ant_colony.performance_monitor.scaling.scale_up_factor").unwrap_or(1.2).max(1.0),
+            scale_down_factor: config.get_float("ant_colony.performance_monitor.scaling.scale_down_factor").unwrap_or(0.7).clamp(0.0, 1.0),
+            last_success_rate: None,
+        }
+    }
+
+    pub fn record_success_rate(&mut self, success_rate: f64) -> Result<()> {
+        if !(0.0..=1.0).contains(&success_rate) {
+            return Err(anyhow::anyhow!("Success rate must be between 0 and 1, got {}", success_rate));
+        }
+        self.last_success_rate = Some(success_rate);
+        Ok(())
+    }
+
+    pub fn last_success_rate(&self) -> Option<f64> {
+        self.last_success_rate
+    }
+
+    // A rate sitting exactly on a threshold holds the colony where it is
+    pub fn should_scale_up(&self) -> bool {
+        self.last_success_rate.map_or(false, |rate| rate > self.success_rate_threshold_high)
+    }
+
+    pub fn should_scale_down(&self) -> bool {
+        self.last_success_rate.map_or(false, |rate| rate < self.success_rate_threshold_low)
+    }
+
+    // Always moves by at least one princess, unless a bound is already reached
+    pub fn target(&self, current: usize, grow: bool) -> usize {
+        let target = if grow {
+            ((current as f64 * self.scale_up_factor).ceil() as usize).max(current + 1)
+        } else {
+            ((current as f64 * self.scale_down_factor).floor() as usize).min(current.saturating_sub(1))
+        };
+        target.clamp(self.min_princesses, self.max_princesses)
+    }
+}
@@ -4,6 +4,7 @@ use log::{info, error, warn};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use crate::ant_colony::ColonyState;
+use crate::ant_colony::monitor_budget::{MonitorBudget, MonitorPriority, MonitorAdmission};
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 
@@ -49,6 +50,7 @@ pub struct Sentry {
     is_active: bool,
     check_interval: u64,
     max_monitors: u32,
+    monitor_budget: Arc<MonitorBudget>, // Colony-wide cap shared with the other sentries
     risk_thresholds: RiskThresholds,
 }
 
@@ -78,6 +80,7 @@ impl Sentry {
             last_check_time: None,
             active_monitors: Vec::new(),
         }));
+        let monitor_budget = state.read().await.monitor_budget.clone();
 
         Ok(Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -86,6 +89,7 @@ impl Sentry {
             is_active: false,
             check_interval,
             max_monitors,
+            monitor_budget,
             risk_thresholds,
         })
     }
@@ -96,16 +100,32 @@ impl Sentry {
         Ok(())
     }
 
-    pub async fn monitor_token(&self, token_address: &str) -> Result<()> {
+    pub async fn monitor_token(&self, token_address: &str, priority: MonitorPriority) -> Result<()> {
+        self.sync_monitors().await;
         let mut sentry_state = self.sentry_state.write().await;
 
         // Validate monitoring
-        if !self.can_monitor_token(token_address).await? {
+        if !self.can_monitor_token(&sentry_state, token_address) {
             warn!("Sentry {} cannot monitor token {}: max monitors reached", 
                   self.id, token_address);
             return Ok(());
         }
 
+        // The colony-wide budget decides whether this token is worth a slot
+        match self.monitor_budget.acquire(token_address, &self.id, priority) {
+            MonitorAdmission::Admitted { evicted } => {
+                if let Some(evicted) = evicted {
+                    info!("Sentry {} token {} was dropped from monitoring for {}", self.id, evicted, token_address);
+                }
+            }
+            MonitorAdmission::AlreadyMonitored => return Ok(()),
+            MonitorAdmission::Rejected => {
+                warn!("Sentry {} cannot monitor token {}: colony monitor budget reached", 
+                      self.id, token_address);
+                return Ok(());
+            }
+        }
+
         // Add to monitored tokens
         sentry_state.monitored_tokens.push(token_address.to_string());
         sentry_state.active_monitors.push(token_address.to_string());
@@ -114,20 +134,24 @@ impl Sentry {
         Ok(())
     }
 
-    async fn can_monitor_token(&self, token_address: &str) -> Result<bool> {
-        let sentry_state = self.sentry_state.read().await;
-        
+    fn can_monitor_token(&self, sentry_state: &SentryState, token_address: &str) -> bool {
         // Check if we've reached max monitors
         if sentry_state.active_monitors.len() >= self.max_monitors as usize {
-            return Ok(false);
+            return false;
         }
 
         // Check if we're already monitoring this token
-        if sentry_state.active_monitors.contains(&token_address.to_string()) {
-            return Ok(false);
-        }
+        !sentry_state.active_monitors.contains(&token_address.to_string())
+    }
 
-        Ok(true)
+    // Drops tokens the budget evicted, possibly to make room for another sentry's token
+    pub async fn sync_monitors(&self) {
+        let mut sentry_state = self.sentry_state.write().await;
+        sentry_state.active_monitors.retain(|token_address| self.monitor_budget.holds(token_address, &self.id));
+    }
+
+    pub async fn active_monitors(&self) -> Vec<String> {
+        self.sentry_state.read().await.active_monitors.clone()
     }
 
     pub async fn check_risk(&self, token_address: &str) -> Result<()> {
@@ -264,6 +288,8 @@ impl Sentry {
 
     pub async fn run(&self) -> Result<()> {
         while self.is_active {
+            self.sync_monitors().await;
+
             // Monitor active tokens
             self.monitor_active_tokens().await?;
 
@@ -292,22 +318,25 @@ impl Sentry {
         let mut sentry_state = self.sentry_state.write().await;
         let now = Utc::now();
 
-        sentry_state.active_monitors.retain(|token_address| {
-            if let Some(last_check) = sentry_state.last_check_time {
-                let duration = now.signed_duration_since(last_check);
-                if duration.num_seconds() > self.check_interval as i64 {
-                    warn!(
-                        "Sentry {} monitoring timeout for token: {}",
-                        self.id, token_address
-                    );
-                    return false;
-                }
+        let timed_out = sentry_state.last_check_time
+            .map_or(false, |last_check| now.signed_duration_since(last_check).num_seconds() > self.check_interval as i64);
+        if timed_out {
+            for token_address in sentry_state.active_monitors.drain(..) {
+                warn!(
+                    "Sentry {} monitoring timeout for token: {}",
+                    self.id, token_address
+                );
+                self.monitor_budget.release(&token_address, &self.id);
             }
-            true
-        });
+        }
         Ok(())
     }
 
+    // True while the colony's daily loss or trade limit has frozen new entries
+    pub async fn should_freeze_trading(&self) -> bool {
+        self.state.read().await.risk_governor.should_freeze_trading()
+    }
+
     pub async fn shutdown(&self) -> Result<()> {
         self.is_active = false;
         
@@ -316,6 +345,7 @@ impl Sentry {
         for token_address in &sentry_state.active_monitors {
             // TODO: Implement graceful monitoring finalization
             warn!("Sentry {} finalizing monitoring for token: {}", self.id, token_address);
+            self.monitor_budget.release(token_address, &self.id);
         }
         sentry_state.active_monitors.clear();
 
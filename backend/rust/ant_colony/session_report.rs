@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Mutex;
+
+// Journal id under which the end-of-session summary is recorded
+pub const SESSION_JOURNAL_ID: &str = "session";
+
+#[derive(Debug, Default)]
+struct SessionCounters {
+    trades_taken: u32,
+    closed_trades: u32,
+    winning_trades: u32,
+    net_pnl: f64,    // SOL, realized and net of fees
+    peak_pnl: f64,   // Highest net_pnl reached this session
+    fees_spent: f64, // SOL
+    rpc_calls: HashMap<String, u64>,
+    alerts_raised: u32,
+}
+
+// Running totals for the current session, shared through `ColonyState` so every
+// component can record into it without holding the state lock
+#[derive(Debug)]
+pub struct SessionStats {
+    started_at: DateTime<Utc>,
+    counters: Mutex<SessionCounters>,
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self {
+            started_at: Utc::now(),
+            counters: Mutex::new(SessionCounters::default()),
+        }
+    }
+}
+
+impl SessionStats {
+    pub fn record_trade_opened(&self) {
+        self.counters.lock().unwrap().trades_taken += 1;
+    }
+
+    // Profit banked by a sell, already net of the fees it cost
+    pub fn record_realized(&self, net_profit: f64, fees: f64) {
+        let mut counters = self.counters.lock().unwrap();
+        counters.net_pnl += net_profit;
+        counters.fees_spent += fees;
+        counters.peak_pnl = counters.peak_pnl.max(counters.net_pnl);
+    }
+
+    // Fraction of `capital` given back since the session's best realized PnL
+    pub fn drawdown(&self, capital: f64) -> f64 {
+        if capital <= 0.0 {
+            return 0.0;
+        }
+        let counters = self.counters.lock().unwrap();
+        ((counters.peak_pnl - counters.net_pnl) / capital).max(0.0)
+    }
+
+    pub fn record_trade_closed(&self, total_realized: f64) {
+        let mut counters = self.counters.lock().unwrap();
+        counters.closed_trades += 1;
+        if total_realized > 0.0 {
+            counters.winning_trades += 1;
+        }
+    }
+
+    pub fn record_rpc_call(&self, provider: &str) {
+        *self.counters.lock().unwrap().rpc_calls.entry(provider.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn record_alert(&self) {
+        self.counters.lock().unwrap().alerts_raised += 1;
+    }
+
+    pub fn report(&self) -> SessionReport {
+        let counters = self.counters.lock().unwrap();
+        let win_rate = if counters.closed_trades > 0 {
+            counters.winning_trades as f64 / counters.closed_trades as f64
+        } else {
+            0.0
+        };
+
+        SessionReport {
+            started_at: self.started_at,
+            ended_at: Utc::now(),
+            trades_taken: counters.trades_taken,
+            closed_trades: counters.closed_trades,
+            win_rate,
+            net_pnl: counters.net_pnl,
+            fees_spent: counters.fees_spent,
+            rpc_usage: counters.rpc_calls.iter().map(|(k, v)| (k.clone(), *v)).collect(),
+            alerts_raised: counters.alerts_raised,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub trades_taken: u32,
+    pub closed_trades: u32,
+    pub win_rate: f64, // Fraction of closed trades that realized a profit
+    pub net_pnl: f64,
+    pub fees_spent: f64,
+    pub rpc_usage: BTreeMap<String, u64>, // Calls per provider, sorted for stable output
+    pub alerts_raised: u32,
+}
+
+impl fmt::Display for SessionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Session summary ({} - {})",
+                 self.started_at.format("%Y-%m-%d %H:%M:%S"),
+                 self.ended_at.format("%Y-%m-%d %H:%M:%S"))?;
+        writeln!(f, "  Trades taken:  {} ({} closed)", self.trades_taken, self.closed_trades)?;
+        writeln!(f, "  Win rate:      {:.1}%", self.win_rate * 100.0)?;
+        writeln!(f, "  Net PnL:       {:.4} SOL", self.net_pnl)?;
+        writeln!(f, "  Fees spent:    {:.4} SOL", self.fees_spent)?;
+        writeln!(f, "  Alerts raised: {}", self.alerts_raised)?;
+        if self.rpc_usage.is_empty() {
+            writeln!(f, "  RPC usage:     none")?;
+        } else {
+            writeln!(f, "  RPC usage:")?;
+            for (provider, calls) in &self.rpc_usage {
+                writeln!(f, "    {:<12} {}", provider, calls)?;
+            }
+        }
+        Ok(())
+    }
+}
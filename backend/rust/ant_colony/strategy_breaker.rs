@@ -0,0 +1,160 @@
+use chrono::{DateTime, Duration, Utc};
+use config::Config;
+use log::{error, info};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error)]
+#[error("Strategy {strategy} is disabled: realized PnL {window_pnl} SOL over the window fell below {min_window_pnl} SOL")]
+pub struct StrategyDisabled {
+    pub strategy: String,
+    pub window_pnl: f64,
+    pub min_window_pnl: f64,
+    pub disabled_at: DateTime<Utc>,
+    pub until: Option<DateTime<Utc>>, // None until re-enabled by hand
+}
+
+#[derive(Debug, Default)]
+struct StrategyPnl {
+    realized: VecDeque<(DateTime<Utc>, f64)>, // (closed at, realized PnL in SOL)
+    disabled: Option<StrategyDisabled>,
+}
+
+impl StrategyPnl {
+    fn window_pnl(&mut self, now: DateTime<Utc>, window: Duration) -> f64 {
+        while let Some((at, _)) = self.realized.front() {
+            if now - *at <= window {
+                break;
+            }
+            self.realized.pop_front();
+        }
+        self.realized.iter().map(|(_, pnl)| pnl).sum()
+    }
+}
+
+// Disables a single strategy's entries once its realized PnL over a rolling window drops
+// below a floor, independently of the colony-wide risk limits. Other strategies keep
+// trading. A tripped strategy comes back after `cooldown`, or only through `reset` when
+// no cooldown is configured.
+#[derive(Debug)]
+pub struct StrategyBreakers {
+    enabled: bool,
+    window: Duration,
+    min_window_pnl: f64, // SOL; usually negative, e.g. -2.0 trips after losing 2 SOL in the window
+    cooldown: Option<Duration>,
+    strategies: Mutex<HashMap<String, StrategyPnl>>,
+}
+
+impl Default for StrategyBreakers {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window: Duration::hours(1),
+            min_window_pnl: -1.0,
+            cooldown: None,
+            strategies: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl StrategyBreakers {
+    pub fn new(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: config.get_bool("ant_colony.strategy_breaker.enabled").unwrap_or(false),
+            window: config.get_int("ant_colony.strategy_breaker.window_secs")
+                .map(Duration::seconds)
+                .unwrap_or(defaults.window),
+            min_window_pnl: config.get_float("ant_colony.strategy_breaker.min_window_pnl")
+                .unwrap_or(defaults.min_window_pnl),
+            // Zero or unset means the strategy stays off until someone re-enables it
+            cooldown: config.get_int("ant_colony.strategy_breaker.cooldown_secs")
+                .ok()
+                .filter(|secs| *secs > 0)
+                .map(Duration::seconds),
+            strategies: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Called before a strategy opens a position; Err while it is disabled
+    pub fn check(&self, strategy: &str) -> Result<(), StrategyDisabled> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let mut strategies = self.strategies.lock().unwrap();
+        let pnl = match strategies.get_mut(strategy) {
+            Some(pnl) => pnl,
+            None => return Ok(()),
+        };
+
+        match &pnl.disabled {
+            Some(disabled) if disabled.until.map_or(true, |until| now < until) => Err(disabled.clone()),
+            Some(_) => {
+                info!("Strategy {} re-enabled after its cooldown", strategy);
+                pnl.disabled = None;
+                pnl.realized.clear();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Returns true when this result tripped the strategy's breaker
+    pub fn record_pnl(&self, strategy: &str, realized_pnl: f64) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let now = Utc::now();
+        let mut strategies = self.strategies.lock().unwrap();
+        let pnl = strategies.entry(strategy.to_string()).or_default();
+        pnl.realized.push_back((now, realized_pnl));
+
+        let window_pnl = pnl.window_pnl(now, self.window);
+        if pnl.disabled.is_some() || window_pnl >= self.min_window_pnl {
+            return false;
+        }
+
+        let disabled = StrategyDisabled {
+            strategy: strategy.to_string(),
+            window_pnl,
+            min_window_pnl: self.min_window_pnl,
+            disabled_at: now,
+            until: self.cooldown.map(|cooldown| now + cooldown),
+        };
+        error!("ALERT: disabling strategy {}: realized PnL {} SOL over the last {}s is below {} SOL",
+               strategy, window_pnl, self.window.num_seconds(), self.min_window_pnl);
+        pnl.disabled = Some(disabled);
+        true
+    }
+
+    // Manual re-enable; the strategy starts over with an empty window
+    pub fn reset(&self, strategy: &str) {
+        if let Some(pnl) = self.strategies.lock().unwrap().get_mut(strategy) {
+            if pnl.disabled.take().is_some() {
+                info!("Strategy {} re-enabled manually", strategy);
+            }
+            pnl.realized.clear();
+        }
+    }
+
+    pub fn is_disabled(&self, strategy: &str) -> bool {
+        self.check(strategy).is_err()
+    }
+
+    pub fn disabled_count(&self) -> usize {
+        let now = Utc::now();
+        self.strategies.lock().unwrap().values()
+            .filter(|pnl| pnl.disabled.as_ref().map_or(false, |d| d.until.map_or(true, |until| now < until)))
+            .count()
+    }
+
+    pub fn window_pnl(&self, strategy: &str) -> f64 {
+        let mut strategies = self.strategies.lock().unwrap();
+        strategies.get_mut(strategy)
+            .map_or(0.0, |pnl| pnl.window_pnl(Utc::now(), self.window))
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::Result;
+use config::Config;
+use log::info;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// Kelly criterion f = W - (1 - W) / R, R being the average win over the average loss,
+// clamped to [0, 1]; zero means no edge. With no losses on record the full win rate is staked.
+pub fn kelly_fraction(win_rate: f64, avg_win: f64, avg_loss: f64) -> f64 {
+    if avg_win <= 0.0 {
+        return 0.0;
+    }
+    if avg_loss <= 0.0 {
+        return win_rate.clamp(0.0, 1.0);
+    }
+    (win_rate - (1.0 - win_rate) / (avg_win / avg_loss)).clamp(0.0, 1.0)
+}
+
+// Realized outcomes for one token or token class. Counts are scaled down once they pass
+// the sample cap, so old results fade instead of outweighing recent ones forever.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenPerformance {
+    pub key: String,
+    pub wins: f64,
+    pub losses: f64,
+    pub total_win: f64,  // SOL won across winning trades
+    pub total_loss: f64, // SOL lost across losing trades, as a positive amount
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TokenPerformance {
+    pub fn trades(&self) -> f64 {
+        self.wins + self.losses
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.trades() == 0.0 {
+            return 0.0;
+        }
+        self.wins / self.trades()
+    }
+
+    pub fn average_pnl(&self) -> f64 {
+        if self.trades() == 0.0 {
+            return 0.0;
+        }
+        (self.total_win - self.total_loss) / self.trades()
+    }
+
+    pub fn kelly(&self) -> f64 {
+        let average = |total: f64, count: f64| if count == 0.0 { 0.0 } else { total / count };
+        kelly_fraction(self.win_rate(), average(self.total_win, self.wins), average(self.total_loss, self.losses))
+    }
+}
+
+// Historical win rate and PnL per token (or class of token), persisted as JSON like the
+// blacklist so position sizing keeps what it learned across restarts
+#[derive(Debug, Default)]
+pub struct TokenStats {
+    enabled: bool,
+    min_samples: f64,  // Below this many trades a key doesn't influence sizing
+    max_samples: f64,  // Counts are scaled back to this once exceeded
+    max_age: Option<Duration>,
+    max_entries: usize,
+    entries: HashMap<String, TokenPerformance>,
+    path: Option<PathBuf>,
+}
+
+impl TokenStats {
+    pub fn load(path: PathBuf, config: &Config) -> Result<Self> {
+        let mut stats = Self {
+            enabled: config.get_bool("ant_colony.token_stats.enabled").unwrap_or(false),
+            min_samples: config.get_int("ant_colony.token_stats.min_samples").unwrap_or(5) as f64,
+            max_samples: config.get_int("ant_colony.token_stats.max_samples").unwrap_or(100).max(1) as f64,
+            max_age: config.get_int("ant_colony.token_stats.max_age_days")
+                .ok()
+                .filter(|days| *days > 0)
+                .map(Duration::days),
+            max_entries: config.get_int("ant_colony.token_stats.max_entries").unwrap_or(1000) as usize,
+            entries: HashMap::new(),
+            path: Some(path.clone()),
+        };
+
+        if path.exists() {
+            let entries: Vec<TokenPerformance> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            stats.entries = entries.into_iter().map(|e| (e.key.clone(), e)).collect();
+        }
+        stats.prune();
+
+        info!("Loaded performance stats for {} tokens from {:?}", stats.entries.len(), path);
+        Ok(stats)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // Records a closed trade's realized PnL in SOL under `key`. Only memory changes; write
+    // a `snapshot` to disk once whatever lock guards the stats is released.
+    pub fn record(&mut self, key: &str, pnl: f64) {
+        if !self.enabled {
+            return;
+        }
+
+        let entry = self.entries.entry(key.to_string()).or_insert_with(|| TokenPerformance {
+            key: key.to_string(),
+            ..TokenPerformance::default()
+        });
+        if pnl > 0.0 {
+            entry.wins += 1.0;
+            entry.total_win += pnl;
+        } else {
+            entry.losses += 1.0;
+            entry.total_loss += -pnl;
+        }
+        entry.updated_at = Utc::now();
+
+        let trades = entry.trades();
+        if trades > self.max_samples {
+            let scale = self.max_samples / trades;
+            entry.wins *= scale;
+            entry.losses *= scale;
+            entry.total_win *= scale;
+            entry.total_loss *= scale;
+        }
+
+        self.prune();
+    }
+
+    // The entries to persist; None when the stats are disabled or have nowhere to go
+    pub fn snapshot(&self) -> Option<TokenStatsSnapshot> {
+        if !self.enabled {
+            return None;
+        }
+        Some(TokenStatsSnapshot {
+            path: self.path.clone()?,
+            entries: self.entries.values().cloned().collect(),
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&TokenPerformance> {
+        self.entries.get(key)
+    }
+
+    // None until the key has enough history to be trusted
+    pub fn kelly_fraction(&self, key: &str) -> Option<f64> {
+        if !self.enabled {
+            return None;
+        }
+        self.entries.get(key)
+            .filter(|entry| entry.trades() >= self.min_samples)
+            .map(|entry| entry.kelly())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    // Drops entries past the age limit, then the stalest ones beyond the entry cap
+    fn prune(&mut self) {
+        if let Some(max_age) = self.max_age {
+            let cutoff = Utc::now() - max_age;
+            self.entries.retain(|_, entry| entry.updated_at >= cutoff);
+        }
+
+        if self.entries.len() > self.max_entries {
+            let mut by_age: Vec<(String, DateTime<Utc>)> = self.entries.iter()
+                .map(|(key, entry)| (key.clone(), entry.updated_at))
+                .collect();
+            by_age.sort_by_key(|(_, updated_at)| *updated_at);
+            let excess = self.entries.len() - self.max_entries;
+            for (key, _) in by_age.into_iter().take(excess) {
+                self.entries.remove(&key);
+            }
+        }
+    }
+
+}
+
+// Stats copied out of TokenStats, so the file write happens without holding its lock
+pub struct TokenStatsSnapshot {
+    path: PathBuf,
+    entries: Vec<TokenPerformance>,
+}
+
+impl TokenStatsSnapshot {
+    pub async fn save(self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&self.path, serde_json::to_string_pretty(&self.entries)?).await?;
+        Ok(())
+    }
+}
@@ -6,17 +6,71 @@ use tokio::sync::RwLock;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 use solana_client::rpc_client::RpcClient;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use crate::ant_colony::session_report::SessionStats;
+use crate::ant_colony::blockhash_cache::{BlockhashCache, CachedBlockhash};
+use crate::ant_colony::wallet_guard::CompromiseGuard;
+use crate::logging::ErrorReporter;
+use solana_transaction_status::UiTransactionEncoding;
+use solana_client::nonce_utils::nonblocking::{data_from_account, get_account_with_commitment};
 use solana_sdk::{
     transaction::Transaction,
-    signature::Signature,
+    signature::{Keypair, Signature, Signer, read_keypair_file},
     commitment_config::CommitmentConfig,
+    hash::Hash,
+    nonce::State as NonceState,
     pubkey::Pubkey,
+    system_instruction,
+    compute_budget::{self, ComputeBudgetInstruction},
+    message::Message,
 };
+use crate::common::{prepend_instruction, decompile_instructions};
+use base64::Engine;
+use reqwest::Client;
+use serde_json::json;
+use std::str::FromStr;
+
+// One of the block engine's published tip accounts
+const DEFAULT_JITO_TIP_ACCOUNT: &str = "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5";
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse<T> {
+    result: Option<T>,
+    error: Option<serde_json::Value>,
+}
+
+impl<T> JsonRpcResponse<T> {
+    fn into_result(self, method: &str) -> Result<T> {
+        match (self.result, self.error) {
+            (_, Some(error)) => Err(anyhow::anyhow!("Jito {} failed: {}", method, error)),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(anyhow::anyhow!("Jito {} returned no result", method)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BundleStatuses {
+    value: Vec<Option<BundleStatus>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BundleStatus {
+    transactions: Vec<String>,
+    confirmation_status: Option<String>,
+    #[serde(default)]
+    err: serde_json::Value, // {"Ok": null} once landed cleanly
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionBundle {
     pub transactions: Vec<Transaction>,
-    pub priority_fee: u64,
+    pub priority_fee: u64, // Micro-lamports per compute unit
     pub timestamp: DateTime<Utc>,
 }
 
@@ -30,17 +84,62 @@ pub struct TransactionResult {
     pub gas_price: u64,
 }
 
+// What the node expects a transaction to do if it were sent now
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationResult {
+    pub error: Option<String>, // The transaction would revert with this
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+}
+
+impl SimulationResult {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// A trade paper mode would have submitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperTrade {
+    pub signature: Signature, // Synthetic; never seen on chain
+    pub bundle: TransactionBundle,
+}
+
 pub struct TransactionHandler {
+    id: String,
     jito_client: RpcClient,
-    helius_client: RpcClient,
-    is_jito_available: bool,
-    last_jito_check: DateTime<Utc>,
+    helius_client: NonblockingRpcClient, // Also serves fee, signature status and transaction meta lookups
+    helius_skip_preflight: bool,
+    simulate_before_send: bool, // Reject transactions that would revert before paying fees for them
+    is_jito_available: AtomicBool, // Sends only read the handler, so concurrent swaps don't queue on a lock
+    last_jito_check: Mutex<DateTime<Utc>>,
     jito_check_interval: i32, // seconds
     max_retries: u32,
     retry_delay_ms: u64,
     bundle_size: usize,
     min_priority_fee: u64,
     max_priority_fee: u64,
+    priority_fee_percentile: f64, // Of the fees recently paid for the same accounts
+    priority_fee_cache_ttl: std::time::Duration,
+    priority_fee_cache: Mutex<HashMap<Vec<Pubkey>, (Instant, u64)>>,
+    compute_unit_limit: u32,
+    confirmation_commitment: CommitmentConfig,
+    confirmation_timeout: std::time::Duration,
+    confirmation_poll: std::time::Duration,
+    http_client: Client,
+    block_engine_url: String,
+    jito_tip_account: Pubkey,
+    jito_tip_payer: Option<Keypair>, // Signs the tip transaction appended to each bundle
+    jito_tip_lamports: u64,
+    bundle_status_poll: std::time::Duration,
+    bundle_status_timeout: std::time::Duration,
+    session: Option<Arc<SessionStats>>,
+    paper_trading: bool, // Record and fake-fill every submission instead of sending it
+    paper_trades: Mutex<Vec<PaperTrade>>,
+    error_tracker: Option<Arc<dyn ErrorReporter>>,
+    nonce_account: Option<Pubkey>, // Durable nonce that pre-signed transactions are built on, when enabled
+    blockhash_cache: Option<Arc<BlockhashCache>>, // Without one, blockhashes are fetched per transaction
+    compromise_guard: Option<Arc<CompromiseGuard>>, // Told about every signature before it is sent
 }
 
 impl TransactionHandler {
@@ -54,38 +153,282 @@ impl TransactionHandler {
         let min_priority_fee = config.get_int("ant_colony.transaction_handler.min_priority_fee")? as u64;
         let max_priority_fee = config.get_int("ant_colony.transaction_handler.max_priority_fee")? as u64;
 
+        let jito_tip_account = Pubkey::from_str(
+            &config.get_string("ant_colony.transaction_handler.jito.tip_account")
+                .unwrap_or_else(|_| DEFAULT_JITO_TIP_ACCOUNT.to_string())
+        )?;
+        let jito_tip_payer = match config.get_string("ant_colony.transaction_handler.jito.tip_keypair_path") {
+            Ok(path) if !path.is_empty() => Some(read_keypair_file(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read Jito tip keypair {}: {}", path, e))?),
+            _ => None,
+        };
+        let jito_tip_lamports = config.get_int("ant_colony.transaction_handler.jito.tip_lamports")
+            .unwrap_or(10_000) as u64;
+        let bundle_status_poll = std::time::Duration::from_millis(
+            config.get_int("ant_colony.transaction_handler.jito.status_poll_interval_ms").unwrap_or(500) as u64
+        );
+        let bundle_status_timeout = std::time::Duration::from_millis(
+            config.get_int("ant_colony.transaction_handler.jito.status_timeout_ms").unwrap_or(30_000) as u64
+        );
+        let block_engine_url = jito_url.trim_end_matches('/').to_string();
+        let priority_fee_percentile = config.get_float("ant_colony.transaction_handler.priority_fee_percentile")
+            .unwrap_or(0.9)
+            .clamp(0.0, 1.0);
+        let priority_fee_cache_ttl = std::time::Duration::from_millis(
+            config.get_int("ant_colony.transaction_handler.priority_fee_cache_ms").unwrap_or(2000) as u64
+        );
+        let compute_unit_limit = config.get_int("ant_colony.transaction_handler.compute_unit_limit")
+            .unwrap_or(200_000) as u32;
+        let confirmation_commitment = CommitmentConfig::from_str(
+            &config.get_string("ant_colony.transaction_handler.confirmation.commitment")
+                .unwrap_or_else(|_| "confirmed".to_string())
+        )?;
+        let confirmation_timeout = std::time::Duration::from_millis(
+            config.get_int("ant_colony.transaction_handler.confirmation.timeout_ms").unwrap_or(30_000) as u64
+        );
+        let confirmation_poll = std::time::Duration::from_millis(
+            config.get_int("ant_colony.transaction_handler.confirmation.poll_interval_ms").unwrap_or(400) as u64
+        );
+        let helius_skip_preflight = config.get_bool("ant_colony.transaction_handler.helius.skip_preflight")
+            .unwrap_or(false);
+        // Off for the hot path by default; when enabled it still gives way to skip_preflight,
+        // which was set for speed
+        let simulate_before_send = config.get_bool("ant_colony.transaction_handler.preflight.enabled")
+            .unwrap_or(false) && !helius_skip_preflight;
+        let nonce_account = if config.get_bool("ant_colony.transaction_handler.nonce.enabled").unwrap_or(false) {
+            Some(Pubkey::from_str(&config.get_string("ant_colony.transaction_handler.nonce.account")?)?)
+        } else {
+            None
+        };
+        let paper_trading = config.get_bool("general.paper_trading").unwrap_or(false);
+        if paper_trading {
+            warn!("Paper trading: transactions are recorded and reported as filled, never sent");
+        }
+
         let jito_client = RpcClient::new_with_commitment(
             jito_url,
             CommitmentConfig::confirmed(),
         );
 
-        let helius_client = RpcClient::new_with_commitment(
-            helius_url,
+        let helius_client = NonblockingRpcClient::new_with_commitment(
+            helius_url.to_string(),
             CommitmentConfig::confirmed(),
         );
 
         Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
             jito_client,
             helius_client,
-            is_jito_available: true,
-            last_jito_check: Utc::now(),
+            helius_skip_preflight,
+            simulate_before_send,
+            is_jito_available: AtomicBool::new(true),
+            last_jito_check: Mutex::new(Utc::now()),
             jito_check_interval,
             max_retries,
             retry_delay_ms: retry_delay,
             bundle_size,
             min_priority_fee,
             max_priority_fee,
+            priority_fee_percentile,
+            priority_fee_cache_ttl,
+            priority_fee_cache: Mutex::new(HashMap::new()),
+            compute_unit_limit,
+            confirmation_commitment,
+            confirmation_timeout,
+            confirmation_poll,
+            http_client: Client::new(),
+            block_engine_url,
+            jito_tip_account,
+            jito_tip_payer,
+            jito_tip_lamports,
+            bundle_status_poll,
+            bundle_status_timeout,
+            session: None,
+            paper_trading,
+            paper_trades: Mutex::new(Vec::new()),
+            error_tracker: None,
+            nonce_account,
+            blockhash_cache: None,
+            compromise_guard: None,
         })
     }
 
-    pub async fn execute_transaction(&mut self, transaction: Transaction) -> Result<TransactionResult> {
+    // Tags the handler's errors in the error tracker
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn set_jito_tip_payer(&mut self, tip_payer: Keypair) {
+        self.jito_tip_payer = Some(tip_payer);
+    }
+
+    // Counts submissions per provider towards the session report
+    pub fn set_session(&mut self, session: Arc<SessionStats>) {
+        self.session = Some(session);
+    }
+
+    pub fn set_paper_trading(&mut self, paper_trading: bool) {
+        self.paper_trading = paper_trading;
+    }
+
+    // Bundles that exhaust their retries are reported to it
+    pub fn set_error_tracker(&mut self, error_tracker: Arc<dyn ErrorReporter>) {
+        self.error_tracker = Some(error_tracker);
+    }
+
+    // Every transaction sent from here, tips included, is vouched for with the guard so its
+    // wallet scans don't mistake the bot's own sends for a compromised key
+    pub fn set_compromise_guard(&mut self, compromise_guard: Arc<CompromiseGuard>) {
+        self.compromise_guard = Some(compromise_guard);
+    }
+
+    fn expect_sent(&self, transaction: &Transaction) {
+        if let (Some(compromise_guard), Some(signature)) = (&self.compromise_guard, transaction.signatures.first()) {
+            compromise_guard.expect(&signature.to_string());
+        }
+    }
+
+    pub fn set_blockhash_cache(&mut self, blockhash_cache: Arc<BlockhashCache>) {
+        self.blockhash_cache = Some(blockhash_cache);
+    }
+
+    // Blockhash for a new transaction, from the cache when one is set
+    pub async fn recent_blockhash(&self) -> Result<Hash> {
+        match &self.blockhash_cache {
+            Some(cache) => cache.blockhash().await,
+            None => Ok(self.helius_client.get_latest_blockhash().await?),
+        }
+    }
+
+    pub fn nonce_account(&self) -> Option<Pubkey> {
+        self.nonce_account
+    }
+
+    // Decimals of an SPL mint, for converting UI amounts into raw ones
+    pub async fn mint_decimals(&self, mint: &Pubkey) -> Result<u8> {
+        self.record_rpc_call("helius");
+        Ok(self.helius_client.get_token_supply(mint).await?.decimals)
+    }
+
+    // Creates and funds a nonce account owned by `authority`, rent-exempt at current rates
+    pub async fn create_nonce_account(
+        &mut self,
+        payer: &Keypair,
+        nonce_account: &Keypair,
+        authority: &Pubkey,
+    ) -> Result<TransactionResult> {
+        let lamports = self.helius_client
+            .get_minimum_balance_for_rent_exemption(NonceState::size())
+            .await?;
+        let instructions = system_instruction::create_nonce_account(
+            &payer.pubkey(),
+            &nonce_account.pubkey(),
+            authority,
+            lamports,
+        );
+        let blockhash = self.recent_blockhash().await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[payer, nonce_account],
+            blockhash,
+        );
+        self.execute_transaction(transaction).await
+    }
+
+    // The nonce currently stored in the account, which stands in for a recent blockhash
+    pub async fn fetch_nonce(&self, nonce_account: &Pubkey) -> Result<Hash> {
+        let account = get_account_with_commitment(&self.helius_client, nonce_account, self.confirmation_commitment)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch nonce account {}: {}", nonce_account, e))?;
+        let data = data_from_account(&account)
+            .map_err(|e| anyhow::anyhow!("{} is not an initialized nonce account: {}", nonce_account, e))?;
+        Ok(data.blockhash())
+    }
+
+    // Rebuilds `transaction` on the durable nonce so it stays valid until the nonce is
+    // advanced, rather than for ~60s like a blockhash. The fee payer must be the nonce
+    // authority, and the result must be signed again.
+    pub async fn build_with_nonce(&self, transaction: Transaction, nonce_account: &Pubkey) -> Result<Transaction> {
+        let nonce = self.fetch_nonce(nonce_account).await?;
+        let authority = *transaction.message.account_keys.first()
+            .ok_or_else(|| anyhow::anyhow!("Transaction has no fee payer to authorize the nonce"))?;
+        let mut transaction = prepend_instruction(
+            &transaction,
+            system_instruction::advance_nonce_account(nonce_account, &authority),
+        );
+        transaction.message.recent_blockhash = nonce;
+        Ok(transaction)
+    }
+
+    // Builds and signs a transaction on the configured nonce account, ready to be sent the
+    // moment it is needed
+    pub async fn presign(&self, transaction: Transaction, signers: &[&Keypair]) -> Result<Transaction> {
+        let nonce_account = self.nonce_account
+            .ok_or_else(|| anyhow::anyhow!("Nonce-based sending is disabled"))?;
+        let transaction = self.with_compute_budget(&transaction).await?;
+        let mut transaction = self.build_with_nonce(transaction, &nonce_account).await?;
+        let nonce = transaction.message.recent_blockhash;
+        transaction.try_sign(signers, nonce)?;
+        Ok(transaction)
+    }
+
+    // Rebuilds `transaction` to request the configured compute unit limit at the current
+    // priority fee per unit, replacing any compute budget it already asked for. The result
+    // must be signed again.
+    pub async fn with_compute_budget(&self, transaction: &Transaction) -> Result<Transaction> {
+        let message = &transaction.message;
+        let priority_fee = self.calculate_priority_fee(&message.account_keys).await?;
+        let mut instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(self.compute_unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
+        ];
+        instructions.extend(decompile_instructions(transaction).into_iter()
+            .filter(|instruction| instruction.program_id != compute_budget::id()));
+
+        Ok(Transaction::new_unsigned(Message::new_with_blockhash(
+            &instructions,
+            message.account_keys.first(),
+            &message.recent_blockhash,
+        )))
+    }
+
+    pub fn is_paper_trading(&self) -> bool {
+        self.paper_trading
+    }
+
+    pub fn paper_trades(&self) -> Vec<PaperTrade> {
+        self.paper_trades.lock().unwrap().clone()
+    }
+
+    fn record_rpc_call(&self, provider: &str) {
+        if let Some(session) = &self.session {
+            session.record_rpc_call(provider);
+        }
+    }
+
+    pub async fn execute_transaction(&self, transaction: Transaction) -> Result<TransactionResult> {
+        // Fee estimation and the Jito check are RPC calls too, so paper mode skips them
+        if self.paper_trading {
+            return Ok(self.paper_fill(TransactionBundle {
+                transactions: vec![transaction],
+                priority_fee: self.min_priority_fee,
+                timestamp: Utc::now(),
+            }));
+        }
+
+        if self.simulate_before_send {
+            self.preflight(&transaction).await?;
+        }
+
         // Check Jito availability
         self.check_jito_availability().await?;
 
         // Create a single-transaction bundle
+        let priority_fee = self.calculate_priority_fee(&transaction.message.account_keys).await?;
         let bundle = TransactionBundle {
             transactions: vec![transaction],
-            priority_fee: self.calculate_priority_fee().await?,
+            priority_fee,
             timestamp: Utc::now(),
         };
 
@@ -93,13 +436,62 @@ impl TransactionHandler {
         self.execute_bundle(bundle).await
     }
 
-    pub async fn execute_bundle(&mut self, bundle: TransactionBundle) -> Result<TransactionResult> {
+    // Runs `transaction` against the current bank without sending it. Signatures aren't
+    // verified, so partially signed transactions can be checked too.
+    pub async fn simulate(&self, transaction: &Transaction) -> Result<SimulationResult> {
+        self.record_rpc_call("helius");
+        let response = self.helius_client
+            .simulate_transaction_with_config(transaction, RpcSimulateTransactionConfig {
+                sig_verify: false,
+                commitment: Some(self.confirmation_commitment),
+                encoding: Some(UiTransactionEncoding::Base64),
+                ..RpcSimulateTransactionConfig::default()
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("Helius simulateTransaction failed: {}", e))?;
+
+        Ok(SimulationResult {
+            error: response.value.err.map(|e| e.to_string()),
+            logs: response.value.logs.unwrap_or_default(),
+            units_consumed: response.value.units_consumed,
+        })
+    }
+
+    // Errs with the program logs when the simulation reverts. A node that can't simulate at
+    // all doesn't block the send; Helius still runs its own preflight unless skipped.
+    async fn preflight(&self, transaction: &Transaction) -> Result<()> {
+        let simulation = match self.simulate(transaction).await {
+            Ok(simulation) => simulation,
+            Err(e) => {
+                warn!("Sending without a preflight simulation: {}", e);
+                return Ok(());
+            }
+        };
+        match simulation.error {
+            None => Ok(()),
+            Some(error) => {
+                let signature = transaction.signatures.first().copied().unwrap_or_default();
+                Err(anyhow::anyhow!("Transaction {} would fail, not sent: {}\nProgram logs:\n{}",
+                                    signature, error, simulation.logs.join("\n")))
+            }
+        }
+    }
+
+    pub async fn execute_bundle(&self, bundle: TransactionBundle) -> Result<TransactionResult> {
+        if self.paper_trading {
+            return Ok(self.paper_fill(bundle));
+        }
+
         let start_time = Utc::now();
         let mut retries = 0;
+        for transaction in &bundle.transactions {
+            self.expect_sent(transaction);
+        }
 
         while retries < self.max_retries {
             // Try Jito first if available
-            if self.is_jito_available {
+            if self.is_jito_available.load(Ordering::SeqCst) {
+                self.record_rpc_call("jito");
                 match self.execute_with_jito(&bundle).await {
                     Ok(result) => {
                         let execution_time = (Utc::now() - start_time).num_milliseconds() as u64;
@@ -114,12 +506,13 @@ impl TransactionHandler {
                     }
                     Err(e) => {
                         warn!("Jito execution failed: {}", e);
-                        self.is_jito_available = false;
+                        self.is_jito_available.store(false, Ordering::SeqCst);
                     }
                 }
             }
 
             // Fallback to Helius
+            self.record_rpc_call("helius");
             match self.execute_with_helius(&bundle).await {
                 Ok(result) => {
                     let execution_time = (Utc::now() - start_time).num_milliseconds() as u64;
@@ -142,55 +535,229 @@ impl TransactionHandler {
             }
         }
 
-        Err(anyhow::anyhow!("Max retries exceeded for transaction execution"))
+        let error = anyhow::anyhow!("Max retries exceeded for transaction execution");
+        if let Some(error_tracker) = &self.error_tracker {
+            error_tracker.capture_error_with_context(&error, &self.id, None);
+        }
+        Err(error)
     }
 
+    // Any error here marks Jito unavailable in `execute_bundle`, which then falls back to Helius
     async fn execute_with_jito(&self, bundle: &TransactionBundle) -> Result<TransactionResult> {
-        // Placeholder for Jito-specific execution
-        // This would involve:
-        // 1. Preparing the bundle with priority fee
-        // 2. Submitting to Jito RPC
-        // 3. Monitoring confirmation
-        // 4. Handling any errors
-        Ok(TransactionResult {
-            signature: Signature::default(),
+        let tip_payer = self.jito_tip_payer.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No Jito tip payer configured"))?;
+        if bundle.transactions.is_empty() {
+            return Err(anyhow::anyhow!("Cannot submit an empty bundle"));
+        }
+
+        // The tip is a flat transfer in its own transaction at the end of the bundle, on top of
+        // the priority fee the transactions already pay; bundles land whole or not at all, so it
+        // is only paid if the rest lands. It gets a fresh blockhash of its own, since the
+        // bundle's transactions may be presigned on a durable nonce the tip doesn't advance.
+        let tip_transaction = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(&tip_payer.pubkey(), &self.jito_tip_account, self.jito_tip_lamports)],
+            Some(&tip_payer.pubkey()),
+            &[tip_payer],
+            self.recent_blockhash().await?,
+        );
+
+        self.expect_sent(&tip_transaction);
+
+        let encoded = bundle.transactions.iter()
+            .chain(std::iter::once(&tip_transaction))
+            .map(|transaction| Ok(base64::engine::general_purpose::STANDARD.encode(bincode::serialize(transaction)?)))
+            .collect::<Result<Vec<String>>>()?;
+
+        let submitted_at = std::time::Instant::now();
+        let bundle_id: String = self.block_engine_call("sendBundle", json!([encoded, {"encoding": "base64"}])).await?;
+        info!("Submitted Jito bundle {} with {} transactions", bundle_id, encoded.len());
+
+        let deadline = tokio::time::Instant::now() + self.bundle_status_timeout;
+        loop {
+            let statuses: BundleStatuses = self.block_engine_call("getBundleStatuses", json!([[bundle_id]])).await?;
+            if let Some(status) = statuses.value.into_iter().flatten().next() {
+                if matches!(status.confirmation_status.as_deref(), Some("confirmed") | Some("finalized")) {
+                    let signature = match status.transactions.first() {
+                        Some(signature) => Signature::from_str(signature)?,
+                        None => bundle.transactions[0].signatures.first().copied().unwrap_or_default(),
+                    };
+                    let landed_cleanly = status.err.is_null() || status.err.get("Ok").is_some();
+                    if !landed_cleanly {
+                        return Ok(TransactionResult {
+                            signature,
+                            success: false,
+                            error: Some(status.err.to_string()),
+                            execution_time_ms: submitted_at.elapsed().as_millis() as u64,
+                            gas_used: 0,
+                            gas_price: self.jito_tip_lamports,
+                        });
+                    }
+
+                    let mut result = self.await_confirmation(signature, None, submitted_at).await?;
+                    result.gas_price += self.jito_tip_lamports; // The tip is paid on top of the fee
+                    return Ok(result);
+                }
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(anyhow::anyhow!("Jito bundle {} not confirmed within {:?}", bundle_id, self.bundle_status_timeout));
+            }
+            tokio::time::sleep(self.bundle_status_poll).await;
+        }
+    }
+
+    async fn block_engine_call<T: serde::de::DeserializeOwned>(&self, method: &str, params: serde_json::Value) -> Result<T> {
+        let response: JsonRpcResponse<T> = self.http_client
+            .post(format!("{}/api/v1/bundles", self.block_engine_url))
+            .json(&json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        response.into_result(method)
+    }
+
+    // Without a bundle there's no atomicity, so transactions go out one at a time and each
+    // must confirm before the next is sent
+    async fn execute_with_helius(&self, bundle: &TransactionBundle) -> Result<TransactionResult> {
+        let submitted_at = std::time::Instant::now();
+        let mut combined: Option<TransactionResult> = None;
+
+        for transaction in &bundle.transactions {
+            let signature = self.helius_client
+                .send_transaction_with_config(transaction, RpcSendTransactionConfig {
+                    skip_preflight: self.helius_skip_preflight,
+                    preflight_commitment: Some(self.confirmation_commitment.commitment),
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    ..RpcSendTransactionConfig::default()
+                })
+                .await
+                .map_err(|e| anyhow::anyhow!("Helius sendTransaction failed: {}", e))?;
+            info!("Submitted transaction {} via Helius", signature);
+            let result = self.await_confirmation(signature, Some(transaction.message.recent_blockhash), submitted_at).await?;
+            combined = Some(match combined {
+                Some(mut first) => {
+                    first.gas_used += result.gas_used;
+                    first.gas_price += result.gas_price;
+                    first.execution_time_ms = result.execution_time_ms;
+                    first
+                }
+                None => result,
+            });
+        }
+
+        combined.ok_or_else(|| anyhow::anyhow!("Cannot submit an empty bundle"))
+    }
+
+    // Reports the bundle as landed without sending it, so callers book the fill as usual
+    fn paper_fill(&self, bundle: TransactionBundle) -> TransactionResult {
+        let signature = Signature::new_unique();
+        info!("Paper trade {}: {} transaction(s) at priority fee {}",
+              signature, bundle.transactions.len(), bundle.priority_fee);
+        let gas_price = bundle.priority_fee;
+        self.paper_trades.lock().unwrap().push(PaperTrade { signature, bundle });
+
+        TransactionResult {
+            signature,
             success: true,
             error: None,
             execution_time_ms: 0,
             gas_used: 0,
-            gas_price: 0,
-        })
+            gas_price,
+        }
     }
 
-    async fn execute_with_helius(&self, bundle: &TransactionBundle) -> Result<TransactionResult> {
-        // Placeholder for Helius-specific execution
-        // This would involve:
-        // 1. Preparing the transaction
-        // 2. Submitting to Helius RPC
-        // 3. Monitoring confirmation
-        // 4. Handling any errors
+    // Polls until the signature reaches the configured commitment. Ok(false) on timeout,
+    // Err if the transaction landed but failed.
+    pub async fn confirm_signature(&self, signature: &Signature, timeout: std::time::Duration) -> Result<bool> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let statuses = self.helius_client.get_signature_statuses(&[*signature]).await?;
+            if let Some(status) = statuses.value.into_iter().flatten().next() {
+                if let Some(err) = status.err {
+                    return Err(anyhow::anyhow!("Transaction {} failed: {}", signature, err));
+                }
+                if status.satisfies_commitment(self.confirmation_commitment) {
+                    return Ok(true);
+                }
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(self.confirmation_poll).await;
+        }
+    }
+
+    // Past the timeout, a transaction built on a cached blockhash is still waited for until that
+    // blockhash expires: it can land until then, and reporting it failed invites a second fill
+    async fn await_confirmation(&self, signature: Signature, blockhash: Option<Hash>, submitted_at: std::time::Instant) -> Result<TransactionResult> {
+        let built_on = blockhash.zip(self.blockhash_cache.as_ref())
+            .and_then(|(blockhash, cache)| cache.lookup(&blockhash));
+        let mut confirmed = self.confirm_signature(&signature, self.confirmation_timeout).await?;
+        if let (false, Some(built_on)) = (confirmed, built_on) {
+            confirmed = self.confirm_until_expired(&signature, &built_on).await?;
+        }
+        if !confirmed {
+            return Err(anyhow::anyhow!("Transaction {} not {:?} within {:?}",
+                                       signature, self.confirmation_commitment.commitment, self.confirmation_timeout));
+        }
+        let execution_time_ms = submitted_at.elapsed().as_millis() as u64;
+
+        // The transaction has landed either way, so missing meta only costs us the cost figures
+        let (gas_used, gas_price) = match self.confirmed_costs(&signature).await {
+            Ok(costs) => costs,
+            Err(e) => {
+                warn!("Failed to fetch meta for confirmed transaction {}: {}", signature, e);
+                (0, 0)
+            }
+        };
+
         Ok(TransactionResult {
-            signature: Signature::default(),
+            signature,
             success: true,
             error: None,
-            execution_time_ms: 0,
-            gas_used: 0,
-            gas_price: 0,
+            execution_time_ms,
+            gas_used,
+            gas_price,
         })
     }
 
-    async fn check_jito_availability(&mut self) -> Result<()> {
+    async fn confirm_until_expired(&self, signature: &Signature, built_on: &CachedBlockhash) -> Result<bool> {
+        loop {
+            if self.confirm_signature(signature, self.confirmation_poll).await? {
+                return Ok(true);
+            }
+            if built_on.is_expired(self.helius_client.get_block_height().await?) {
+                // It may still have landed in the last block it was valid for
+                return self.confirm_signature(signature, std::time::Duration::ZERO).await;
+            }
+        }
+    }
+
+    // (compute units consumed, lamports paid in fees) from the confirmed transaction's meta
+    async fn confirmed_costs(&self, signature: &Signature) -> Result<(u64, u64)> {
+        let confirmed = self.helius_client.get_transaction(signature, UiTransactionEncoding::Base64).await?;
+        let meta = confirmed.transaction.meta
+            .ok_or_else(|| anyhow::anyhow!("No meta for transaction {}", signature))?;
+        let compute_units: Option<u64> = meta.compute_units_consumed.into();
+        Ok((compute_units.unwrap_or(0), meta.fee))
+    }
+
+    async fn check_jito_availability(&self) -> Result<()> {
         let now = Utc::now();
-        if (now - self.last_jito_check).num_seconds() >= self.jito_check_interval {
+        let last_check = *self.last_jito_check.lock().unwrap();
+        if (now - last_check).num_seconds() >= self.jito_check_interval {
             // Check Jito health endpoint
             match self.check_jito_health().await {
                 Ok(available) => {
-                    self.is_jito_available = available;
-                    self.last_jito_check = now;
+                    self.is_jito_available.store(available, Ordering::SeqCst);
+                    *self.last_jito_check.lock().unwrap() = now;
                 }
                 Err(e) => {
                     warn!("Failed to check Jito health: {}", e);
-                    self.is_jito_available = false;
+                    self.is_jito_available.store(false, Ordering::SeqCst);
                 }
             }
         }
@@ -206,13 +773,54 @@ impl TransactionHandler {
         Ok(true)
     }
 
-    async fn calculate_priority_fee(&self) -> Result<u64> {
-        // Placeholder for priority fee calculation
-        // This would involve:
-        // 1. Getting current network conditions
-        // 2. Calculating optimal priority fee
-        // 3. Ensuring it's within bounds
-        Ok(self.min_priority_fee)
+    // The bid for a transaction with no particular accounts, i.e. across the whole network.
+    // Recent fees are often mostly zero, so this usually lands on min_priority_fee.
+    pub async fn network_priority_fee(&self) -> Result<u64> {
+        self.calculate_priority_fee(&[]).await
+    }
+
+    // Bids what recent transactions touching the same accounts paid, at the configured
+    // percentile. Cached briefly per account set so bursts of sends share one RPC call.
+    async fn calculate_priority_fee(&self, accounts: &[Pubkey]) -> Result<u64> {
+        let mut key = accounts.to_vec();
+        key.sort();
+        key.dedup();
+
+        if let Some((fetched_at, fee)) = self.priority_fee_cache.lock().unwrap().get(&key) {
+            if fetched_at.elapsed() < self.priority_fee_cache_ttl {
+                return Ok(*fee);
+            }
+        }
+
+        let fee = match self.helius_client.get_recent_prioritization_fees(&key).await {
+            Ok(recent) => {
+                let samples: Vec<u64> = recent.iter().map(|f| f.prioritization_fee).collect();
+                Self::priority_fee_from_recent(&samples, self.priority_fee_percentile,
+                                               self.min_priority_fee, self.max_priority_fee)
+            }
+            Err(e) => {
+                warn!("Failed to fetch recent prioritization fees, using minimum: {}", e);
+                return Ok(self.min_priority_fee);
+            }
+        };
+
+        let mut cache = self.priority_fee_cache.lock().unwrap();
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < self.priority_fee_cache_ttl);
+        cache.insert(key, (Instant::now(), fee));
+        Ok(fee)
+    }
+
+    // Nearest-rank percentile of the recent fees, clamped into [min_fee, max_fee]
+    pub fn priority_fee_from_recent(fees: &[u64], percentile: f64, min_fee: u64, max_fee: u64) -> u64 {
+        if fees.is_empty() {
+            return min_fee;
+        }
+
+        let mut sorted = fees.to_vec();
+        sorted.sort_unstable();
+        let rank = (percentile.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+        let fee = sorted[rank.saturating_sub(1).min(sorted.len() - 1)];
+        fee.clamp(min_fee, max_fee.max(min_fee))
     }
 
     pub async fn shutdown(&self) -> Result<()> {
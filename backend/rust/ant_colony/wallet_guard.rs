@@ -0,0 +1,169 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::{error, info};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::Mutex;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::UiTransactionEncoding;
+use thiserror::Error;
+use crate::ant_colony::sentry::AlertSeverity;
+
+// A transaction seen on-chain for a wallet
+#[derive(Debug, Clone)]
+pub struct ObservedTransaction {
+    pub signature: String,
+    pub outgoing: bool, // Signed by the wallet, i.e. someone used its key
+    pub block_time: Option<DateTime<Utc>>,
+}
+
+// Where the guard reads what a wallet has actually done on-chain
+#[async_trait]
+pub trait WalletActivitySource: Send + Sync {
+    async fn recent_transactions(&self, wallet: &str, limit: usize) -> Result<Vec<ObservedTransaction>>;
+}
+
+pub struct RpcWalletActivity {
+    rpc_client: RpcClient,
+}
+
+impl RpcWalletActivity {
+    pub fn new(rpc_url: String) -> Self {
+        Self {
+            rpc_client: RpcClient::new(rpc_url),
+        }
+    }
+}
+
+#[async_trait]
+impl WalletActivitySource for RpcWalletActivity {
+    async fn recent_transactions(&self, wallet: &str, limit: usize) -> Result<Vec<ObservedTransaction>> {
+        let wallet_key = Pubkey::from_str(wallet)?;
+        let signatures = self.rpc_client.get_signatures_for_address(&wallet_key).await?;
+
+        let mut observed = Vec::new();
+        for status in signatures.into_iter().take(limit) {
+            let signature = Signature::from_str(&status.signature)?;
+            let confirmed = self.rpc_client.get_transaction(&signature, UiTransactionEncoding::Base64).await?;
+            // Incoming transfers only list the wallet as a writable account, never as a signer
+            let outgoing = confirmed.transaction.transaction.decode()
+                .map(|transaction| {
+                    let signers = transaction.message.header().num_required_signatures as usize;
+                    transaction.message.static_account_keys().iter().take(signers).any(|key| *key == wallet_key)
+                })
+                .unwrap_or(false);
+
+            observed.push(ObservedTransaction {
+                signature: status.signature,
+                outgoing,
+                block_time: status.block_time.and_then(|t| DateTime::from_timestamp(t, 0)),
+            });
+        }
+        Ok(observed)
+    }
+}
+
+#[derive(Debug, Clone, Error)]
+#[error("Wallet {wallet} is halted: unexplained outgoing transaction {signature} suggests its key is compromised")]
+pub struct WalletCompromised {
+    pub wallet: String,
+    pub signature: String,
+    pub severity: AlertSeverity,
+    pub detected_at: DateTime<Utc>,
+}
+
+// Compares what a wallet signed on-chain against what the bot itself sent. An outgoing
+// transaction nobody here initiated means someone else holds the key, so the wallet is
+// halted for good; only an operator clearing it puts the key back in use.
+#[derive(Debug, Default)]
+pub struct CompromiseGuard {
+    enabled: bool,
+    lookback: usize, // Most recent transactions inspected per scan
+    expected: Mutex<HashSet<String>>, // Bot-initiated signatures not in the journal, e.g. tips
+    compromised: Mutex<HashMap<String, WalletCompromised>>,
+}
+
+impl CompromiseGuard {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            enabled: config.get_bool("ant_colony.compromise_guard.enabled").unwrap_or(false),
+            lookback: config.get_int("ant_colony.compromise_guard.lookback").unwrap_or(50) as usize,
+            expected: Mutex::new(HashSet::new()),
+            compromised: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // Vouches for a signature the bot sent without journaling it
+    pub fn expect(&self, signature: &str) {
+        self.expected.lock().unwrap().insert(signature.to_string());
+    }
+
+    // Called before a wallet signs anything; Err once it has been halted
+    pub fn check(&self, wallet: &str) -> Result<(), WalletCompromised> {
+        match self.compromised.lock().unwrap().get(wallet) {
+            Some(compromised) => Err(compromised.clone()),
+            None => Ok(()),
+        }
+    }
+
+    // `known` holds every signature the journal recorded as sent. Returns the alert when
+    // this scan halted the wallet.
+    pub async fn scan(
+        &self,
+        wallet: &str,
+        source: &dyn WalletActivitySource,
+        known: &HashSet<String>,
+    ) -> Result<Option<WalletCompromised>> {
+        if !self.enabled || self.check(wallet).is_err() {
+            return Ok(None);
+        }
+
+        let observed = source.recent_transactions(wallet, self.lookback).await?;
+        let unexplained = {
+            let expected = self.expected.lock().unwrap();
+            observed.into_iter().find(|transaction| {
+                transaction.outgoing
+                    && !known.contains(&transaction.signature)
+                    && !expected.contains(&transaction.signature)
+            })
+        };
+
+        let transaction = match unexplained {
+            Some(transaction) => transaction,
+            None => return Ok(None),
+        };
+
+        let alert = WalletCompromised {
+            wallet: wallet.to_string(),
+            signature: transaction.signature,
+            severity: AlertSeverity::Critical,
+            detected_at: Utc::now(),
+        };
+        error!("CRITICAL: halting wallet {}: outgoing transaction {} was not sent by the bot; treat its key as compromised",
+               wallet, alert.signature);
+        self.compromised.lock().unwrap().insert(wallet.to_string(), alert.clone());
+        Ok(Some(alert))
+    }
+
+    // Manual clearance once the operator has rotated or vetted the key
+    pub fn clear(&self, wallet: &str) {
+        if self.compromised.lock().unwrap().remove(wallet).is_some() {
+            info!("Wallet {} cleared after compromise review", wallet);
+        }
+    }
+
+    pub fn is_halted(&self, wallet: &str) -> bool {
+        self.check(wallet).is_err()
+    }
+
+    pub fn halted_count(&self) -> usize {
+        self.compromised.lock().unwrap().len()
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, Duration, Utc};
+use config::Config;
+use log::{error, info};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use thiserror::Error;
+
+#[derive(Debug, Clone, Error)]
+#[error("Wallet {wallet} is paused until {until} after {failures} of its last {samples} transactions failed")]
+pub struct WalletPaused {
+    pub wallet: String,
+    pub until: DateTime<Utc>,
+    pub failures: usize,
+    pub samples: usize,
+}
+
+#[derive(Debug, Default)]
+struct WalletHistory {
+    outcomes: VecDeque<(DateTime<Utc>, bool)>, // (submitted at, succeeded)
+    paused: Option<WalletPaused>,
+}
+
+impl WalletHistory {
+    fn prune(&mut self, now: DateTime<Utc>, window: Duration) {
+        while let Some((at, _)) = self.outcomes.front() {
+            if now - *at <= window {
+                break;
+            }
+            self.outcomes.pop_front();
+        }
+    }
+
+    fn failures(&self) -> usize {
+        self.outcomes.iter().filter(|(_, succeeded)| !succeeded).count()
+    }
+}
+
+// Stops a wallet from submitting once too many of its recent transactions fail, e.g. behind a
+// bad RPC, an underpriced fee or a targeted attack, since every further attempt burns fees.
+// Other wallets keep trading; the paused one resumes with a clean window after `pause_duration`.
+#[derive(Debug)]
+pub struct WalletHealthMonitor {
+    enabled: bool,
+    window: Duration,
+    min_samples: usize,    // Fewer outcomes than this in the window never pause a wallet
+    max_failure_rate: f64, // Fraction of failed outcomes in the window that triggers a pause
+    pause_duration: Duration,
+    wallets: Mutex<HashMap<String, WalletHistory>>,
+}
+
+impl Default for WalletHealthMonitor {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window: Duration::seconds(300),
+            min_samples: 5,
+            max_failure_rate: 0.5,
+            pause_duration: Duration::seconds(600),
+            wallets: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl WalletHealthMonitor {
+    pub fn new(config: &Config) -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: config.get_bool("ant_colony.wallet_health.enabled").unwrap_or(false),
+            window: config.get_int("ant_colony.wallet_health.window_secs")
+                .map(Duration::seconds)
+                .unwrap_or(defaults.window),
+            min_samples: config.get_int("ant_colony.wallet_health.min_samples")
+                .map(|n| n as usize)
+                .unwrap_or(defaults.min_samples),
+            max_failure_rate: config.get_float("ant_colony.wallet_health.max_failure_rate")
+                .unwrap_or(defaults.max_failure_rate),
+            pause_duration: config.get_int("ant_colony.wallet_health.pause_secs")
+                .map(Duration::seconds)
+                .unwrap_or(defaults.pause_duration),
+            wallets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Called before submitting; Err while the wallet is paused
+    pub fn check(&self, wallet: &str) -> Result<(), WalletPaused> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let mut wallets = self.wallets.lock().unwrap();
+        let history = match wallets.get_mut(wallet) {
+            Some(history) => history,
+            None => return Ok(()),
+        };
+
+        match &history.paused {
+            Some(paused) if now < paused.until => Err(paused.clone()),
+            Some(_) => {
+                info!("Wallet {} resuming submissions after its failure pause", wallet);
+                history.paused = None;
+                history.outcomes.clear();
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Returns true when this outcome pushed the wallet over the threshold and paused it
+    pub fn record(&self, wallet: &str, succeeded: bool) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let now = Utc::now();
+        let mut wallets = self.wallets.lock().unwrap();
+        let history = wallets.entry(wallet.to_string()).or_default();
+        history.outcomes.push_back((now, succeeded));
+        history.prune(now, self.window);
+
+        let samples = history.outcomes.len();
+        let failures = history.failures();
+        if history.paused.is_some() || samples < self.min_samples.max(1) {
+            return false;
+        }
+        if (failures as f64 / samples as f64) <= self.max_failure_rate {
+            return false;
+        }
+
+        let paused = WalletPaused {
+            wallet: wallet.to_string(),
+            until: now + self.pause_duration,
+            failures,
+            samples,
+        };
+        error!("ALERT: pausing wallet {} until {}: {} of its last {} transactions failed",
+               wallet, paused.until, failures, samples);
+        history.paused = Some(paused);
+        true
+    }
+
+    pub fn is_paused(&self, wallet: &str) -> bool {
+        self.check(wallet).is_err()
+    }
+
+    pub fn paused_count(&self) -> usize {
+        let now = Utc::now();
+        self.wallets.lock().unwrap().values()
+            .filter(|history| history.paused.as_ref().map_or(false, |paused| now < paused.until))
+            .count()
+    }
+
+    // Failure rate across every wallet's window
+    pub fn overall_failure_rate(&self) -> f64 {
+        let now = Utc::now();
+        let mut wallets = self.wallets.lock().unwrap();
+        let (mut failures, mut samples) = (0, 0);
+        for history in wallets.values_mut() {
+            history.prune(now, self.window);
+            failures += history.failures();
+            samples += history.outcomes.len();
+        }
+        if samples == 0 {
+            0.0
+        } else {
+            failures as f64 / samples as f64
+        }
+    }
+
+    pub fn failure_rate(&self, wallet: &str) -> f64 {
+        let mut wallets = self.wallets.lock().unwrap();
+        match wallets.get_mut(wallet) {
+            Some(history) => {
+                history.prune(Utc::now(), self.window);
+                if history.outcomes.is_empty() {
+                    0.0
+                } else {
+                    history.failures() as f64 / history.outcomes.len() as f64
+                }
+            }
+            None => 0.0,
+        }
+    }
+}
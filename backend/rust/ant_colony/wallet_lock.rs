@@ -0,0 +1,133 @@
+use anyhow::Result;
+use config::Config;
+use log::{info, warn};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use std::fs::OpenOptions;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub instance_id: String,
+    pub pid: u32,
+    pub acquired_at: DateTime<Utc>,
+    pub heartbeat_at: DateTime<Utc>,
+}
+
+// Exclusive claim on the colony's wallets. Two instances trading the same wallets race each
+// other's nonces, so a second instance refuses to start while the owner keeps heartbeating.
+// A lock whose heartbeat is older than the stale timeout is assumed to belong to a crashed
+// instance and is taken over.
+#[derive(Debug)]
+pub struct WalletLock {
+    path: PathBuf,
+    owner: LockOwner,
+    stale_timeout: chrono::Duration,
+}
+
+impl WalletLock {
+    pub fn acquire_from_config(config: &Config) -> Result<Option<Self>> {
+        if !config.get_bool("general.wallet_lock.enabled").unwrap_or(true) {
+            return Ok(None);
+        }
+
+        let path = PathBuf::from(config.get_string("general.wallet_lock.path")?);
+        let stale_timeout = chrono::Duration::seconds(config.get_int("general.wallet_lock.stale_timeout_secs")?);
+        Self::acquire(path, stale_timeout).map(Some)
+    }
+
+    pub fn acquire(path: PathBuf, stale_timeout: chrono::Duration) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let now = Utc::now();
+        let lock = Self {
+            path,
+            owner: LockOwner {
+                instance_id: uuid::Uuid::new_v4().to_string(),
+                pid: std::process::id(),
+                acquired_at: now,
+                heartbeat_at: now,
+            },
+            stale_timeout,
+        };
+
+        if lock.try_create()? {
+            info!("Acquired wallet lock {:?}", lock.path);
+            return Ok(lock);
+        }
+
+        let holder = Self::read_owner(&lock.path)?;
+        let age = Utc::now() - holder.heartbeat_at;
+        if age < stale_timeout {
+            return Err(anyhow::anyhow!(
+                "Wallet lock {:?} is held by instance {} (pid {}), last heartbeat {}s ago",
+                lock.path, holder.instance_id, holder.pid, age.num_seconds()
+            ));
+        }
+
+        warn!("Taking over stale wallet lock from instance {} (pid {}), last heartbeat {}s ago",
+              holder.instance_id, holder.pid, age.num_seconds());
+        std::fs::remove_file(&lock.path)?;
+        if !lock.try_create()? {
+            // Another instance won the takeover race
+            return Err(anyhow::anyhow!("Wallet lock {:?} was claimed by another instance", lock.path));
+        }
+
+        info!("Acquired wallet lock {:?}", lock.path);
+        Ok(lock)
+    }
+
+    // Keeps the lock live; must run more often than the stale timeout
+    pub fn refresh(&mut self) -> Result<()> {
+        let holder = Self::read_owner(&self.path)?;
+        if holder.instance_id != self.owner.instance_id {
+            return Err(anyhow::anyhow!("Wallet lock {:?} was taken over by instance {}", self.path, holder.instance_id));
+        }
+
+        self.owner.heartbeat_at = Utc::now();
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.owner)?)?;
+        Ok(())
+    }
+
+    pub fn owner(&self) -> &LockOwner {
+        &self.owner
+    }
+
+    pub fn stale_timeout(&self) -> chrono::Duration {
+        self.stale_timeout
+    }
+
+    // create_new makes acquisition atomic: exactly one instance can create the file
+    fn try_create(&self) -> Result<bool> {
+        match OpenOptions::new().write(true).create_new(true).open(&self.path) {
+            Ok(mut file) => {
+                file.write_all(serde_json::to_string_pretty(&self.owner)?.as_bytes())?;
+                Ok(true)
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn read_owner(path: &Path) -> Result<LockOwner> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+impl Drop for WalletLock {
+    fn drop(&mut self) {
+        // Only release the lock if it is still ours
+        match Self::read_owner(&self.path) {
+            Ok(holder) if holder.instance_id == self.owner.instance_id => {
+                if let Err(e) = std::fs::remove_file(&self.path) {
+                    warn!("Failed to release wallet lock {:?}: {}", self.path, e);
+                }
+            }
+            _ => {}
+        }
+    }
+}
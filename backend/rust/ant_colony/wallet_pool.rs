@@ -0,0 +1,196 @@
+use aes_gcm::{Aes256Gcm, Nonce, aead::{Aead, AeadCore, KeyInit, OsRng, rand_core::RngCore}};
+use anyhow::{Context, Result};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use config::Config;
+use log::{info, warn};
+use pbkdf2::pbkdf2_hmac;
+use serde::{Serialize, Deserialize};
+use sha2::{Digest, Sha256};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use crate::common::{associated_token_address, create_associated_token_account};
+
+// PBKDF2-HMAC-SHA256 iterations turning the passphrase into the AES key
+const KDF_ROUNDS: u32 = 600_000;
+const PLACEHOLDER_KEY: &str = "YOUR_ENCRYPTION_KEY";
+
+// On-disk layout: the keypairs' bytes as JSON, sealed with AES-256-GCM under a key derived
+// from the passphrase and `salt`. Pools written before the salt was added have none and were
+// sealed under a bare SHA-256 of the passphrase; they are resealed the first time they load.
+#[derive(Serialize, Deserialize)]
+struct SealedPool {
+    #[serde(default)]
+    salt: Option<String>,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Default)]
+struct Rotation {
+    current: usize,
+    trades_on_current: u32,
+    token_accounts: HashSet<(Pubkey, Pubkey)>, // (wallet, mint) pairs already known to exist
+}
+
+// Signing wallets of one princess. The active wallet changes every `rotate_every` trades
+// and wraps back to the first, so activity is spread across addresses instead of one
+// wallet that copy-traders can follow.
+pub struct WalletPool {
+    keypairs: Vec<Arc<Keypair>>,
+    rotate_every: u32,
+    rotation: Mutex<Rotation>,
+}
+
+impl WalletPool {
+    pub fn new(keypairs: Vec<Keypair>, rotate_every: u32) -> Result<Self> {
+        if keypairs.is_empty() {
+            return Err(anyhow::anyhow!("A wallet pool needs at least one keypair"));
+        }
+        Ok(Self {
+            keypairs: keypairs.into_iter().map(Arc::new).collect(),
+            rotate_every: rotate_every.max(1),
+            rotation: Mutex::new(Rotation::default()),
+        })
+    }
+
+    pub fn generate(size: usize, rotate_every: u32) -> Result<Self> {
+        Self::new((0..size).map(|_| Keypair::new()).collect(), rotate_every)
+    }
+
+    // The pool for the princess at `index`, or None when rotation is disabled. Keypairs are
+    // generated on first use and persisted encrypted with `security.encryption_key`.
+    pub fn from_config(config: &Config, index: usize) -> Result<Option<Self>> {
+        if !config.get_bool("ant_colony.wallet_pool.enabled").unwrap_or(false) {
+            return Ok(None);
+        }
+        let size = config.get_int("ant_colony.wallet_pool.wallets_per_princess").unwrap_or(3).max(1) as usize;
+        let rotate_every = config.get_int("ant_colony.wallet_pool.rotate_every").unwrap_or(5).max(1) as u32;
+        let dir = config.get_string("ant_colony.wallet_pool.dir")
+            .unwrap_or_else(|_| "./data/wallets".to_string());
+        let encryption_key = config.get_string("security.encryption_key")
+            .context("Wallet pools are encrypted with security.encryption_key, which is not set")?;
+        if encryption_key.is_empty() || encryption_key == PLACEHOLDER_KEY {
+            return Err(anyhow::anyhow!("Set security.encryption_key to a real passphrase before enabling wallet pools"));
+        }
+
+        let path = PathBuf::from(dir).join(format!("princess_{}.pool", index));
+        Self::load_or_create(&path, &encryption_key, size, rotate_every).map(Some)
+    }
+
+    pub fn load_or_create(path: &PathBuf, encryption_key: &str, size: usize, rotate_every: u32) -> Result<Self> {
+        if path.exists() {
+            let pool = Self::load(path, encryption_key, rotate_every)?;
+            info!("Loaded {} wallets from {:?}", pool.len(), path);
+            return Ok(pool);
+        }
+
+        let pool = Self::generate(size, rotate_every)?;
+        pool.save(path, encryption_key)?;
+        info!("Created {} wallets in {:?}", pool.len(), path);
+        Ok(pool)
+    }
+
+    pub fn load(path: &PathBuf, encryption_key: &str, rotate_every: u32) -> Result<Self> {
+        let sealed: SealedPool = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let nonce = BASE64.decode(sealed.nonce)?;
+        let cipher = match &sealed.salt {
+            Some(salt) => cipher(encryption_key, &BASE64.decode(salt)?),
+            None => legacy_cipher(encryption_key),
+        };
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), BASE64.decode(sealed.ciphertext)?.as_ref())
+            .map_err(|_| anyhow::anyhow!("Failed to decrypt wallet pool {:?}; wrong encryption key?", path))?;
+
+        let keypairs = serde_json::from_slice::<Vec<Vec<u8>>>(&plaintext)?
+            .iter()
+            .map(|bytes| Keypair::from_bytes(bytes).map_err(|e| anyhow::anyhow!("Invalid keypair in {:?}: {}", path, e)))
+            .collect::<Result<Vec<_>>>()?;
+        let pool = Self::new(keypairs, rotate_every)?;
+        if sealed.salt.is_none() {
+            warn!("Resealing wallet pool {:?} under a derived key", path);
+            pool.save(path, encryption_key)?;
+        }
+        Ok(pool)
+    }
+
+    pub fn save(&self, path: &PathBuf, encryption_key: &str) -> Result<()> {
+        let keypairs: Vec<Vec<u8>> = self.keypairs.iter().map(|keypair| keypair.to_bytes().to_vec()).collect();
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher(encryption_key, &salt)
+            .encrypt(&nonce, serde_json::to_vec(&keypairs)?.as_ref())
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt wallet pool"))?;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let sealed = SealedPool {
+            salt: Some(BASE64.encode(salt)),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        std::fs::write(path, serde_json::to_string_pretty(&sealed)?)?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.keypairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keypairs.is_empty()
+    }
+
+    pub fn addresses(&self) -> Vec<Pubkey> {
+        self.keypairs.iter().map(|keypair| keypair.pubkey()).collect()
+    }
+
+    // The wallet the next trade should be signed with
+    pub fn current(&self) -> Arc<Keypair> {
+        self.keypairs[self.rotation.lock().unwrap().current].clone()
+    }
+
+    // Counts a trade against the current wallet, moving to the next once it has made
+    // `rotate_every` trades
+    pub fn record_trade(&self) {
+        let mut rotation = self.rotation.lock().unwrap();
+        rotation.trades_on_current += 1;
+        if rotation.trades_on_current >= self.rotate_every {
+            rotation.current = (rotation.current + 1) % self.keypairs.len();
+            rotation.trades_on_current = 0;
+        }
+    }
+
+    // The wallet's associated token account for `mint`, plus an instruction creating it
+    // unless it is already known to exist. Creation is idempotent, so including it for an
+    // account that does exist only costs compute.
+    pub fn token_account_setup(&self, wallet: &Pubkey, mint: &Pubkey) -> (Pubkey, Option<Instruction>) {
+        let token_account = associated_token_address(wallet, mint);
+        if self.rotation.lock().unwrap().token_accounts.contains(&(*wallet, *mint)) {
+            return (token_account, None);
+        }
+        (token_account, Some(create_associated_token_account(wallet, wallet, mint)))
+    }
+
+    // Called once a transaction that created the account has landed
+    pub fn mark_token_account(&self, wallet: &Pubkey, mint: &Pubkey) {
+        self.rotation.lock().unwrap().token_accounts.insert((*wallet, *mint));
+    }
+}
+
+fn cipher(encryption_key: &str, salt: &[u8]) -> Aes256Gcm {
+    let mut key = [0u8; 32];
+    pbkdf2_hmac::<Sha256>(encryption_key.as_bytes(), salt, KDF_ROUNDS, &mut key);
+    Aes256Gcm::new(&key.into())
+}
+
+fn legacy_cipher(encryption_key: &str) -> Aes256Gcm {
+    Aes256Gcm::new(&Sha256::digest(encryption_key.as_bytes()))
+}
@@ -1,9 +1,11 @@
 use anyhow::Result;
 use config::Config;
 use log::{info, error, warn};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 use crate::ant_colony::ColonyState;
+use crate::common::TradeError;
+use crate::fund_management::FundManager;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
 
@@ -16,15 +18,26 @@ pub struct WorkerState {
     pub active_collections: Vec<String>,
 }
 
+// How one `distribute_profits` call split the collected profits
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProfitDistribution {
+    pub queen_share: f64,  // Added to the colony's capital
+    pub worker_share: f64, // Kept by the worker for reinvestment
+}
+
 pub struct Worker {
     id: String,
     state: Arc<RwLock<ColonyState>>,
     worker_state: Arc<RwLock<WorkerState>>,
     is_active: bool,
     reinvestment_rate: f64,
+    fund_manager: FundManager, // Splits each collection between reinvestment and the vault
     collection_interval: u64,
     min_profit_threshold: f64,
     max_collections: u32,
+    reinvestment_threshold: f64,
+    profit_distribution: f64,       // Queen's fraction of distributed profits
+    collected_profits: Mutex<f64>,  // Recorded but not yet distributed
 }
 
 impl Worker {
@@ -33,6 +46,10 @@ impl Worker {
         let collection_interval = config.get_int("ant_colony.worker.collection_interval")? as u64;
         let min_profit_threshold = config.get_float("ant_colony.worker.min_profit_threshold")? as f64;
         let max_collections = config.get_int("ant_colony.worker.max_collections")? as u32;
+        let reinvestment_threshold = config.get_float("ant_colony.worker.reinvestment_threshold").unwrap_or(100.0);
+        let profit_distribution = config.get_float("ant_colony.worker.profit_distribution")
+            .unwrap_or(0.5)
+            .clamp(0.0, 1.0);
 
         let worker_state = Arc::new(RwLock::new(WorkerState {
             total_collected_profits: 0.0,
@@ -48,12 +65,57 @@ impl Worker {
             worker_state,
             is_active: false,
             reinvestment_rate,
+            fund_manager: FundManager::with_rates(reinvestment_rate, 1.0 - reinvestment_rate)?,
             collection_interval,
             min_profit_threshold,
             max_collections,
+            reinvestment_threshold,
+            profit_distribution,
+            collected_profits: Mutex::new(0.0),
         })
     }
 
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    pub fn get_collected_profits(&self) -> f64 {
+        *self.collected_profits.lock().unwrap()
+    }
+
+    pub fn get_reinvestment_threshold(&self) -> f64 {
+        self.reinvestment_threshold
+    }
+
+    // Losses are not profits to distribute, so only non-negative amounts are accepted
+    pub async fn record_profit(&self, profit: f64) -> Result<()> {
+        if !profit.is_finite() || profit < 0.0 {
+            return Err(TradeError::InvalidAmount(profit).into());
+        }
+        *self.collected_profits.lock().unwrap() += profit;
+        Ok(())
+    }
+
+    // Splits everything recorded so far between the queen and this worker, moving the queen's
+    // share into the colony's capital. Collected profits are zero afterwards.
+    pub async fn distribute_profits(&self) -> Result<ProfitDistribution> {
+        let collected = std::mem::take(&mut *self.collected_profits.lock().unwrap());
+        let queen_share = collected * self.profit_distribution;
+        let distribution = ProfitDistribution {
+            queen_share,
+            worker_share: collected - queen_share,
+        };
+        if collected == 0.0 {
+            return Ok(distribution);
+        }
+
+        self.state.write().await.total_capital += distribution.queen_share;
+        self.worker_state.write().await.reinvested_amount += distribution.worker_share;
+        info!("Worker {} distributed profits - Queen: {}, Worker: {}",
+              self.id, distribution.queen_share, distribution.worker_share);
+        Ok(distribution)
+    }
+
     pub async fn init(&mut self) -> Result<()> {
         self.is_active = true;
         info!("Worker {} initialized with reinvestment rate: {}%", 
@@ -87,7 +149,7 @@ impl Worker {
         );
 
         // Distribute profits
-        self.distribute_profits(reinvestment, vault).await?;
+        self.distribute_collection(reinvestment, vault).await?;
 
         Ok(())
     }
@@ -109,12 +171,10 @@ impl Worker {
     }
 
     fn calculate_profit_distribution(&self, profit: f64) -> (f64, f64) {
-        let reinvestment = profit * self.reinvestment_rate;
-        let vault = profit - reinvestment;
-        (reinvestment, vault)
+        self.fund_manager.split(profit)
     }
 
-    async fn distribute_profits(&self, reinvestment: f64, vault: f64) -> Result<()> {
+    async fn distribute_collection(&self, reinvestment: f64, vault: f64) -> Result<()> {
         let mut colony_state = self.state.write().await;
 
         // Add reinvestment to colony capital
@@ -1,76 +1,186 @@
-use tokio_tungstenite::WebSocketStream;
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{future::BoxFuture, stream::SplitSink, SinkExt, StreamExt};
+use anyhow::Result;
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use governor::{
-    middleware::StateInformationMiddleware,
-    state::{InMemoryState, NotKeyed},
-    Quota, Governor,
-};
+use tokio::sync::{Mutex, RwLock};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
 use axum::{
     routing::get,
     Router,
-    extract::ws::{Message, WebSocket, WebSocketUpgrade},
-    response::IntoResponse,
+    Json,
+    extract::{ConnectInfo, State, ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade}},
+    http::StatusCode,
+    response::{IntoResponse, Response},
 };
-use std::net::SocketAddr;
-use crate::common::Message as BotMessage;
+use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroU32;
+use serde::Deserialize;
+use crate::common::{Message as BotMessage, MessageKind, MessageQueue, Metrics};
+use crate::ant_colony::{HealthSummary, HealthVerdict};
+
+// Messages a dashboard client may send. The first must be `{"auth": "<token>"}`; after
+// that `{"subscribe": ["trade_signal", ...]}` narrows which updates it is sent.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ClientRequest {
+    Auth { auth: String },
+    Subscribe { subscribe: Vec<String> },
+}
+
+type ClientSink = Arc<Mutex<SplitSink<WebSocket, Message>>>;
 
+// Each sink has its own lock, so a slow client never holds up the registry
+struct ClientConnection {
+    sink: ClientSink,
+    authenticated: bool,
+    topics: MessageKind, // Every topic until the client subscribes to specific ones
+}
+
+// Cheap to clone: every clone shares the same client registry, so the copy handed to
+// the router and the one used for broadcasting see the same connections
+#[derive(Clone)]
 pub struct WebSocketServer {
-    clients: Arc<RwLock<HashMap<String, WebSocketStream>>>,
+    auth_token: Arc<String>,
+    clients: Arc<RwLock<HashMap<String, ClientConnection>>>,
+    health: Arc<RwLock<Option<HealthSummary>>>, // Latest summary published by the colony
+    metrics: Metrics,
+    message_queue: Option<MessageQueue>, // Its depth is sampled on every scrape
 }
 
 impl WebSocketServer {
-    pub fn new() -> Self {
+    pub fn new(auth_token: impl Into<String>) -> Self {
         Self {
+            auth_token: Arc::new(auth_token.into()),
             clients: Arc::new(RwLock::new(HashMap::new())),
+            health: Arc::new(RwLock::new(None)),
+            metrics: Metrics::default(),
+            message_queue: None,
         }
     }
 
-    pub async fn start(&self, addr: SocketAddr) {
-        let limiter = Governor::builder()
-            .key_extractor(PeerIpKeyExtractor)
-            .quota(Quota::per_second(10))
-            .build()
-            .unwrap();
+    // Set before starting; clones taken earlier keep the previous registry
+    pub fn set_metrics(&mut self, metrics: Metrics) {
+        self.metrics = metrics;
+    }
+
+    pub fn set_message_queue(&mut self, message_queue: MessageQueue) {
+        self.message_queue = Some(message_queue);
+    }
+
+    pub async fn start(&self, addr: SocketAddr) -> Result<()> {
+        self.start_with_shutdown(addr, std::future::pending()).await
+    }
+
+    // Serves until `shutdown` resolves, then closes every client socket and returns
+    pub async fn start_with_shutdown(
+        &self,
+        addr: SocketAddr,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<()> {
+        let limiter = RateLimiter::keyed(Quota::per_second(NonZeroU32::new(10).unwrap()));
 
         let app = Router::new()
             .route("/ws", get(ws_handler))
+            .route("/status", get(status_handler))
+            .route("/metrics", get(metrics_handler))
+            .with_state(self.clone())
             .layer(GovernorLayer::new(limiter));
 
+        // Upgraded websockets are detached from the HTTP server, so graceful shutdown
+        // alone would leave them open; close them as soon as shutdown is requested
+        let server = self.clone();
+        let shutdown = async move {
+            shutdown.await;
+            server.close_all_clients().await;
+        };
+
         println!("WebSocket server listening on {}", addr);
-        axum::Server::bind(&addr)
-            .serve(app.into_make_service())
-            .await
-            .unwrap();
+        axum::Server::try_bind(&addr)?
+            .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(shutdown)
+            .await?;
+
+        println!("WebSocket server on {} shut down", addr);
+        Ok(())
+    }
+
+    async fn close_all_clients(&self) {
+        let clients = std::mem::take(&mut *self.clients.write().await);
+        for (client_id, client) in clients {
+            let close = Message::Close(Some(CloseFrame {
+                code: close_code::AWAY,
+                reason: "server shutting down".into(),
+            }));
+            if let Err(e) = client.sink.lock().await.send(close).await {
+                eprintln!("Error closing client {}: {}", client_id, e);
+            }
+        }
+    }
+
+    // An empty token would let any client in, so it never authenticates
+    fn accepts_token(&self, token: &str) -> bool {
+        !self.auth_token.is_empty() && token == self.auth_token.as_str()
+    }
+
+    pub async fn client_count(&self) -> usize {
+        self.clients.read().await.len()
+    }
+
+    pub async fn authenticated_count(&self) -> usize {
+        self.clients.read().await.values().filter(|c| c.authenticated).count()
+    }
+
+    // Served from `/status` until the next summary replaces it
+    pub async fn publish_health(&self, summary: HealthSummary) {
+        *self.health.write().await = Some(summary);
     }
 
     pub async fn broadcast_update(&self, update: BotMessage) {
-        let clients = self.clients.read().await;
+        let kind = update.kind();
         let message = serde_json::to_string(&update).unwrap();
-        
-        for client in clients.values() {
-            if let Err(e) = client.send(Message::Text(message.clone())).await {
-                eprintln!("Error sending message to client: {}", e);
+        let recipients: Vec<(String, ClientSink)> = self.clients.read().await.iter()
+            .filter(|(_, client)| client.authenticated && client.topics.contains(kind))
+            .map(|(client_id, client)| (client_id.clone(), client.sink.clone()))
+            .collect();
+        let mut disconnected = Vec::new();
+
+        for (client_id, sink) in recipients {
+            if let Err(e) = sink.lock().await.send(Message::Text(message.clone())).await {
+                eprintln!("Dropping client {} after send error: {}", client_id, e);
+                disconnected.push(client_id);
+            }
+        }
+
+        // A failed send means the socket is gone; stop broadcasting to it
+        if !disconnected.is_empty() {
+            let mut clients = self.clients.write().await;
+            for client_id in disconnected {
+                clients.remove(&client_id);
             }
         }
     }
 
     async fn handle_connection(&self, ws: WebSocket, client_id: String) {
-        let (mut sender, mut receiver) = ws.split();
+        let (sender, mut receiver) = ws.split();
         
-        // Add client to active connections
+        // Add client to active connections; nothing is broadcast to it until it authenticates
         {
             let mut clients = self.clients.write().await;
-            clients.insert(client_id.clone(), sender);
+            clients.insert(client_id.clone(), ClientConnection {
+                sink: Arc::new(Mutex::new(sender)),
+                authenticated: false,
+                topics: MessageKind::all(),
+            });
         }
 
         // Handle incoming messages
         while let Some(Ok(msg)) = receiver.next().await {
             match msg {
                 Message::Text(text) => {
-                    println!("Received message from {}: {}", client_id, text);
+                    if !self.handle_client_request(&client_id, &text).await {
+                        break;
+                    }
                 }
                 Message::Close(_) => {
                     break;
@@ -83,31 +193,113 @@ impl WebSocketServer {
         let mut clients = self.clients.write().await;
         clients.remove(&client_id);
     }
+
+    // Returns false once the connection should be dropped. The registry is only locked to
+    // update the client; the reply goes out after the lock is released.
+    async fn handle_client_request(&self, client_id: &str, text: &str) -> bool {
+        let request = serde_json::from_str::<ClientRequest>(text).ok();
+        let (sink, reply, keep) = {
+            let mut clients = self.clients.write().await;
+            let Some(client) = clients.get_mut(client_id) else {
+                return false;
+            };
+
+            if !client.authenticated {
+                match request {
+                    Some(ClientRequest::Auth { auth }) if self.accepts_token(&auth) => {
+                        client.authenticated = true;
+                        let ack = serde_json::json!({ "authenticated": true }).to_string();
+                        (client.sink.clone(), Message::Text(ack), true)
+                    }
+                    _ => {
+                        eprintln!("Rejecting client {}: first message was not a valid auth", client_id);
+                        let sink = client.sink.clone();
+                        clients.remove(client_id);
+                        let close = Message::Close(Some(CloseFrame {
+                            code: close_code::POLICY,
+                            reason: "authentication required".into(),
+                        }));
+                        (sink, close, false)
+                    }
+                }
+            } else {
+                match request {
+                    Some(ClientRequest::Subscribe { subscribe }) => {
+                        let mut topics = MessageKind::empty();
+                        let mut accepted = Vec::new();
+                        for topic in subscribe {
+                            match MessageKind::from_topic(&topic) {
+                                Some(kind) => {
+                                    topics |= kind;
+                                    accepted.push(topic);
+                                }
+                                None => eprintln!("Client {} requested unknown topic {}", client_id, topic),
+                            }
+                        }
+                        client.topics = topics;
+                        let ack = serde_json::json!({ "subscribed": accepted }).to_string();
+                        (client.sink.clone(), Message::Text(ack), true)
+                    }
+                    _ => {
+                        println!("Ignoring message from {}: {}", client_id, text);
+                        return true;
+                    }
+                }
+            }
+        };
+
+        let sent = sink.lock().await.send(reply).await.is_ok();
+        keep && sent
+    }
 }
 
-async fn ws_handler(ws: WebSocketUpgrade) -> impl IntoResponse {
-    ws.on_upgrade(|socket| async move {
+async fn ws_handler(
+    State(server): State<WebSocketServer>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
         let client_id = uuid::Uuid::new_v4().to_string();
-        let server = WebSocketServer::new();
         server.handle_connection(socket, client_id).await;
     })
 }
 
-struct PeerIpKeyExtractor;
-
-impl governor::key_extractor::KeyExtractor<SocketAddr> for PeerIpKeyExtractor {
-    type Key = SocketAddr;
+// 503 while critical so load balancers and uptime checks can act on the verdict alone
+async fn status_handler(State(server): State<WebSocketServer>) -> Response {
+    match server.health.read().await.clone() {
+        Some(summary) => {
+            let code = if summary.verdict == HealthVerdict::Critical {
+                StatusCode::SERVICE_UNAVAILABLE
+            } else {
+                StatusCode::OK
+            };
+            (code, Json(summary)).into_response()
+        }
+        None => (StatusCode::SERVICE_UNAVAILABLE, "health not yet reported").into_response(),
+    }
+}
 
-    fn extract(&self, addr: &SocketAddr) -> Self::Key {
-        *addr
+async fn metrics_handler(State(server): State<WebSocketServer>) -> Response {
+    if let Some(message_queue) = &server.message_queue {
+        server.metrics.sample_queue(message_queue).await;
+    }
+    match server.metrics.render() {
+        Ok(body) => ([(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
 
-pub struct GovernorLayer;
+// Requests are keyed by peer IP, which requires serving with
+// `into_make_service_with_connect_info::<SocketAddr>()`
+#[derive(Clone)]
+pub struct GovernorLayer {
+    limiter: Arc<DefaultKeyedRateLimiter<IpAddr>>,
+}
 
 impl GovernorLayer {
-    pub fn new(limiter: Governor<NotKeyed, InMemoryState, PeerIpKeyExtractor, StateInformationMiddleware>) -> Self {
-        Self
+    pub fn new(limiter: DefaultKeyedRateLimiter<IpAddr>) -> Self {
+        Self {
+            limiter: Arc::new(limiter),
+        }
     }
 }
 
@@ -115,27 +307,43 @@ impl<S> tower::Layer<S> for GovernorLayer {
     type Service = GovernorMiddleware<S>;
 
     fn layer(&self, inner: S) -> Self::Service {
-        GovernorMiddleware { inner }
+        GovernorMiddleware {
+            inner,
+            limiter: self.limiter.clone(),
+        }
     }
 }
 
+#[derive(Clone)]
 pub struct GovernorMiddleware<S> {
     inner: S,
+    limiter: Arc<DefaultKeyedRateLimiter<IpAddr>>,
 }
 
 impl<S, B> tower::Service<axum::http::Request<B>> for GovernorMiddleware<S>
 where
-    S: tower::Service<axum::http::Request<B>>,
+    S: tower::Service<axum::http::Request<B>, Response = Response>,
+    S::Future: Send + 'static,
 {
-    type Response = S::Response;
+    type Response = Response;
     type Error = S::Error;
-    type Future = S::Future;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
 
     fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
         self.inner.poll_ready(cx)
     }
 
     fn call(&mut self, req: axum::http::Request<B>) -> Self::Future {
-        self.inner.call(req)
+        let peer_ip = req.extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        if let Some(ip) = peer_ip {
+            if self.limiter.check_key(&ip).is_err() {
+                return Box::pin(async { Ok(StatusCode::TOO_MANY_REQUESTS.into_response()) });
+            }
+        }
+
+        Box::pin(self.inner.call(req))
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,3 @@
+mod websocket;
+
+pub use websocket::DashboardWebSocket;
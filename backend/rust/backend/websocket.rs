@@ -1,20 +1,50 @@
 use anyhow::Result;
-use tokio::sync::broadcast;
+use futures_util::SinkExt;
+use log::error;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
 use warp::ws::{Message, WebSocket};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use serde_json::json;
 use crate::ant_colony::ColonyState;
+use crate::common::{Message as BotMessage, MessageKind, MessageQueue, OverflowPolicy};
 
 pub struct DashboardWebSocket {
     state: Arc<RwLock<ColonyState>>,
     tx: broadcast::Sender<Message>,
+    shutdown: watch::Sender<bool>,
 }
 
 impl DashboardWebSocket {
     pub fn new(state: Arc<RwLock<ColonyState>>) -> Self {
         let (tx, _) = broadcast::channel(100);
-        Self { state, tx }
+        let (shutdown, _) = watch::channel(false);
+        Self { state, tx, shutdown }
+    }
+
+    // Pushes the dashboard payload to every connected client each `interval` until `shutdown`
+    pub fn start_broadcast_loop(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let mut shutdown = self.shutdown.subscribe();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            while !*shutdown.borrow() {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        if let Err(e) = self.broadcast_update().await {
+                            error!("Dashboard broadcast failed: {}", e);
+                        }
+                    }
+                    _ = shutdown.changed() => {}
+                }
+            }
+        })
+    }
+
+    pub fn shutdown(&self) {
+        let _ = self.shutdown.send(true);
     }
 
     pub async fn handle_connection(&self, ws: WebSocket) {
@@ -31,17 +61,30 @@ impl DashboardWebSocket {
         });
     }
 
-    pub async fn broadcast_update(&self) -> Result<()> {
-        let state = self.state.read().await;
-        
-        // Prepare dashboard data
-        let data = json!({
-            "workers": self.get_worker_status(&state).await?,
-            "metrics": self.get_trade_metrics(&state).await?,
-            "profitTiers": self.get_profit_tiers(&state).await?,
-            "alerts": self.get_alerts(&state).await?,
-            "performanceData": self.get_performance_data(&state).await?,
+    // Relays order submissions, confirmations and failures to every dashboard client as
+    // they are published, e.g. {"type": "TradeExecution", "status": "Confirmed", ...}
+    pub async fn forward_trade_executions(&self, message_queue: &MessageQueue) {
+        let mut subscription = message_queue.subscribe_filtered(
+            "dashboard_trade_executions".to_string(),
+            MessageKind::TRADE_EXECUTION,
+            message_queue.default_capacity(),
+            OverflowPolicy::DropOldest,
+        ).await;
+        let tx = self.tx.clone();
+
+        tokio::task::spawn(async move {
+            while let Some(message) = subscription.recv().await {
+                if let BotMessage::TradeExecution(_) = &message {
+                    if let Ok(text) = serde_json::to_string(&message) {
+                        let _ = tx.send(Message::text(text));
+                    }
+                }
+            }
         });
+    }
+
+    pub async fn broadcast_update(&self) -> Result<()> {
+        let data = self.dashboard_payload().await?;
 
         // Broadcast update
         if let Ok(msg) = Message::text(data.to_string()) {
@@ -51,6 +94,19 @@ impl DashboardWebSocket {
         Ok(())
     }
 
+    pub async fn dashboard_payload(&self) -> Result<serde_json::Value> {
+        let state = self.state.read().await;
+
+        Ok(json!({
+            "paperTrading": state.paper_trading,
+            "workers": self.get_worker_status(&state).await?,
+            "metrics": self.get_trade_metrics(&state).await?,
+            "profitTiers": self.get_profit_tiers(&state).await?,
+            "alerts": self.get_alerts(&state).await?,
+            "performanceData": self.get_performance_data(&state).await?,
+        }))
+    }
+
     async fn get_worker_status(&self, state: &ColonyState) -> Result<serde_json::Value> {
         let workers = state.active_workers.iter().map(|worker| {
             json!({
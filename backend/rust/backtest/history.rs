@@ -0,0 +1,66 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use std::path::Path;
+
+// One historical observation of a token's market
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PriceRecord {
+    pub timestamp: DateTime<Utc>,
+    pub price: f64,
+    pub volume: f64,    // 24h traded volume in USD
+    pub liquidity: f64, // Pool liquidity in USD
+}
+
+const COLUMNS: [&str; 4] = ["timestamp", "price", "volume", "liquidity"];
+
+pub fn load_csv(path: &Path) -> Result<Vec<PriceRecord>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read price history {}", path.display()))?;
+    parse_csv(&contents).with_context(|| format!("Invalid price history {}", path.display()))
+}
+
+// Header names the columns in any order, e.g. `timestamp,price,volume,liquidity`. Timestamps
+// are unix seconds or RFC 3339; blank lines and `#` comments are skipped. Records come back
+// oldest first.
+pub fn parse_csv(contents: &str) -> Result<Vec<PriceRecord>> {
+    let mut lines = contents.lines().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+
+    let (_, header) = lines.next().ok_or_else(|| anyhow::anyhow!("Price history is empty"))?;
+    let header: Vec<&str> = header.split(',').map(str::trim).collect();
+    let mut positions = [0; 4];
+    for (position, column) in positions.iter_mut().zip(COLUMNS) {
+        *position = header.iter().position(|name| name.eq_ignore_ascii_case(column))
+            .ok_or_else(|| anyhow::anyhow!("Price history header has no {} column", column))?;
+    }
+
+    let mut records = Vec::new();
+    for (index, line) in lines {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let field = |position: usize| fields.get(position).copied()
+            .ok_or_else(|| anyhow::anyhow!("Line {} has {} fields, expected {}", index + 1, fields.len(), header.len()));
+        let number = |position: usize| -> Result<f64> {
+            let value = field(position)?;
+            value.parse().map_err(|_| anyhow::anyhow!("Line {}: {:?} is not a number", index + 1, value))
+        };
+
+        records.push(PriceRecord {
+            timestamp: parse_timestamp(field(positions[0])?)
+                .ok_or_else(|| anyhow::anyhow!("Line {}: invalid timestamp", index + 1))?,
+            price: number(positions[1])?,
+            volume: number(positions[2])?,
+            liquidity: number(positions[3])?,
+        });
+    }
+
+    records.sort_by_key(|record| record.timestamp);
+    Ok(records)
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    match value.parse::<i64>() {
+        Ok(seconds) => DateTime::from_timestamp(seconds, 0),
+        Err(_) => DateTime::parse_from_rfc3339(value).ok().map(|time| time.with_timezone(&Utc)),
+    }
+}
@@ -0,0 +1,272 @@
+mod history;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::info;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use crate::ant_colony::{ProfitManager, TradeProfit};
+use crate::common::{MarketData, MarketDataProvider};
+use crate::sniping_core::{ExitManager, ActiveTrade};
+
+pub use history::{PriceRecord, load_csv, parse_csv};
+
+// Below this a position is rounding, not holdings
+const DUST_AMOUNT: f64 = 1e-9;
+
+// How the backtester opens its position, relative to the entry price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryRule {
+    pub amount: f64,
+    pub stop_loss_pct: f64,
+    pub take_profit_pct: f64,   // 0 leaves profit taking to the ProfitManager, if any
+    pub trailing_stop_pct: f64, // 0 disables it
+    pub min_liquidity: f64,     // No entry until the pool holds at least this much USD
+}
+
+impl Default for EntryRule {
+    fn default() -> Self {
+        Self {
+            amount: 1.0,
+            stop_loss_pct: 10.0,
+            take_profit_pct: 0.0,
+            trailing_stop_pct: 0.0,
+            min_liquidity: 0.0,
+        }
+    }
+}
+
+// One sell made during a replay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestFill {
+    pub timestamp: DateTime<Utc>,
+    pub price: f64,
+    pub amount: f64,
+    pub reason: String, // Exit type from the ExitManager, or profit_tier
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestTrade {
+    pub trade_id: String,
+    pub token_address: String,
+    pub entry_time: DateTime<Utc>,
+    pub entry_price: f64,
+    pub amount: f64,
+    pub exit_time: Option<DateTime<Utc>>, // None if the data ran out with the position open
+    pub fills: Vec<BacktestFill>,
+    pub pnl: f64,          // Realized, plus what's left marked at the last price
+    pub return_pct: f64,
+    pub max_drawdown: f64, // Largest peak-to-trough fall in position value, as a fraction
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BacktestSummary {
+    pub total_return: f64, // Combined PnL over combined cost
+    pub win_rate: f64,
+    pub max_drawdown: f64, // Worst of the per-trade drawdowns
+    pub trades: Vec<BacktestTrade>,
+}
+
+// Serves whatever record the replay is currently at, in place of live market data
+#[derive(Default)]
+struct ReplayMarketData {
+    current: Mutex<HashMap<String, MarketData>>,
+}
+
+impl ReplayMarketData {
+    fn set(&self, token_address: &str, record: &PriceRecord) {
+        self.current.lock().unwrap().insert(token_address.to_string(), MarketData {
+            price: record.price,
+            volume_24h: record.volume,
+        });
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for ReplayMarketData {
+    async fn market_data(&self, token_address: &str) -> Result<MarketData> {
+        self.current.lock().unwrap().get(token_address).copied()
+            .ok_or_else(|| anyhow::anyhow!("No replayed data for {}", token_address))
+    }
+}
+
+// Replays historical records through the exit stack without touching RPC. Each run opens one
+// position and steps it through the records in time order: the ProfitManager's tiers get
+// the first chance to sell at each record, then the ExitManager's stops and targets.
+pub struct Backtester {
+    exit_manager: ExitManager,
+    profit_manager: Option<ProfitManager>,
+    market: Arc<ReplayMarketData>,
+    entry: EntryRule,
+    trades: Vec<BacktestTrade>,
+}
+
+impl Backtester {
+    pub fn new(mut exit_manager: ExitManager, entry: EntryRule) -> Self {
+        let market = Arc::new(ReplayMarketData::default());
+        exit_manager.set_market_data(market.clone());
+        Self {
+            exit_manager,
+            profit_manager: None,
+            market,
+            entry,
+            trades: Vec::new(),
+        }
+    }
+
+    // The ProfitManager must not have a swap executor, or its sells would go on chain
+    pub fn with_profit_manager(mut self, profit_manager: ProfitManager) -> Result<Self> {
+        if profit_manager.has_swap_executor() {
+            return Err(anyhow::anyhow!("Refusing to backtest with a ProfitManager that submits real swaps"));
+        }
+        self.profit_manager = Some(profit_manager);
+        Ok(self)
+    }
+
+    // Replays one position in `token_address`; None if no record met the entry rule
+    pub async fn run(&mut self, token_address: &str, records: &[PriceRecord]) -> Result<Option<BacktestTrade>> {
+        let min_liquidity = self.entry.min_liquidity;
+        let mut records = records.iter()
+            .skip_while(|record| record.liquidity < min_liquidity || record.price <= 0.0);
+        let entry = match records.next() {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+
+        let mut trade = BacktestTrade {
+            trade_id: uuid::Uuid::new_v4().to_string(),
+            token_address: token_address.to_string(),
+            entry_time: entry.timestamp,
+            entry_price: entry.price,
+            amount: self.entry.amount,
+            exit_time: None,
+            fills: Vec::new(),
+            pnl: 0.0,
+            return_pct: 0.0,
+            max_drawdown: 0.0,
+        };
+        self.open(&trade, entry).await?;
+
+        let mut remaining = trade.amount;
+        let mut proceeds = 0.0;
+        let mut peak = trade.amount * entry.price;
+        let mut last_price = entry.price;
+        for record in records {
+            last_price = record.price;
+            self.market.set(token_address, record);
+
+            if let Some(profit_manager) = self.profit_manager.as_mut() {
+                let before = profit_manager.get_trade_profits(&trade.trade_id).await
+                    .map_or(0.0, |position| position.position_size);
+                profit_manager.update_trade_price(&trade.trade_id, record.price).await?;
+                profit_manager.check_profit_tiers().await?;
+                let after = profit_manager.get_trade_profits(&trade.trade_id).await
+                    .map_or(0.0, |position| position.position_size);
+                Self::fill(&mut trade, &mut remaining, &mut proceeds, record, before - after, "profit_tier");
+            }
+
+            for exit in self.exit_manager.check_exit_conditions().await? {
+                if exit.token_address.as_deref() == Some(token_address) {
+                    let reason = format!("{:?}", exit.exit_type);
+                    Self::fill(&mut trade, &mut remaining, &mut proceeds, record, exit.amount, &reason);
+                }
+            }
+
+            let value = proceeds + remaining * record.price;
+            peak = peak.max(value);
+            if peak > 0.0 {
+                trade.max_drawdown = trade.max_drawdown.max((peak - value) / peak);
+            }
+
+            if remaining <= DUST_AMOUNT {
+                trade.exit_time = Some(record.timestamp);
+                break;
+            }
+        }
+
+        self.close(&trade);
+        let cost = trade.amount * trade.entry_price;
+        trade.pnl = proceeds + remaining.max(0.0) * last_price - cost;
+        trade.return_pct = if cost > 0.0 { trade.pnl / cost * 100.0 } else { 0.0 };
+        info!("Backtest of {} from {} at {}: {} fills, PnL {} ({}%)",
+              token_address, trade.entry_time, trade.entry_price, trade.fills.len(), trade.pnl, trade.return_pct);
+
+        self.trades.push(trade.clone());
+        Ok(Some(trade))
+    }
+
+    async fn open(&mut self, trade: &BacktestTrade, entry: &PriceRecord) -> Result<()> {
+        self.market.set(&trade.token_address, entry);
+
+        let take_profit = if self.entry.take_profit_pct > 0.0 {
+            entry.price * (1.0 + self.entry.take_profit_pct / 100.0)
+        } else {
+            0.0
+        };
+        self.exit_manager.add_trade(ActiveTrade {
+            token_address: trade.token_address.clone(),
+            entry_price: entry.price,
+            amount: trade.amount,
+            stop_loss: entry.price * (1.0 - self.entry.stop_loss_pct / 100.0),
+            take_profit,
+            trailing_stop: self.entry.trailing_stop_pct,
+            ..ActiveTrade::default()
+        }).await?;
+
+        if let Some(profit_manager) = self.profit_manager.as_mut() {
+            profit_manager.add_trade(TradeProfit {
+                trade_id: trade.trade_id.clone(),
+                token_address: trade.token_address.clone(),
+                entry_price: entry.price,
+                entry_time: entry.timestamp,
+                current_price: entry.price,
+                position_size: trade.amount,
+                gas_fees: 0.0,
+                realized_profits: 0.0,
+                unrealized_profits: 0.0,
+                profit_tiers_hit: Vec::new(),
+                pool_address: String::new(),
+            }).await?;
+        }
+        Ok(())
+    }
+
+    // Either manager may have sold part of the position the other still tracks, so both
+    // drop it once the run is over
+    fn close(&mut self, trade: &BacktestTrade) {
+        self.exit_manager.remove_trades(&trade.token_address);
+        if let Some(profit_manager) = self.profit_manager.as_mut() {
+            profit_manager.remove_trade(&trade.trade_id);
+        }
+    }
+
+    fn fill(trade: &mut BacktestTrade, remaining: &mut f64, proceeds: &mut f64, record: &PriceRecord, amount: f64, reason: &str) {
+        let amount = amount.min(*remaining);
+        if amount <= DUST_AMOUNT {
+            return;
+        }
+        *remaining -= amount;
+        *proceeds += amount * record.price;
+        trade.fills.push(BacktestFill {
+            timestamp: record.timestamp,
+            price: record.price,
+            amount,
+            reason: reason.to_string(),
+        });
+    }
+
+    pub fn summary(&self) -> BacktestSummary {
+        let cost: f64 = self.trades.iter().map(|trade| trade.amount * trade.entry_price).sum();
+        let pnl: f64 = self.trades.iter().map(|trade| trade.pnl).sum();
+        let wins = self.trades.iter().filter(|trade| trade.pnl > 0.0).count();
+
+        BacktestSummary {
+            total_return: if cost > 0.0 { pnl / cost } else { 0.0 },
+            win_rate: if self.trades.is_empty() { 0.0 } else { wins as f64 / self.trades.len() as f64 },
+            max_drawdown: self.trades.iter().map(|trade| trade.max_drawdown).fold(0.0, f64::max),
+            trades: self.trades.clone(),
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use anyhow::Result;
+use base64::Engine;
+use config::Config;
+use log::{debug, warn};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::{Keypair, Signer, read_keypair_file}, transaction::Transaction};
+use tokio::sync::RwLock;
+use crate::ant_colony::{BlockhashCache, TransactionHandler};
+use crate::common::{AtaResolver, prepend_instruction};
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const SOL_DECIMALS: u8 = 9;
+
+// A Jupiter route, kept verbatim because /swap wants the quote response back unchanged
+#[derive(Debug, Clone)]
+pub struct SwapQuote {
+    pub input_mint: String,
+    pub output_mint: String,
+    pub in_amount: u64,
+    pub out_amount: u64,
+    pub price_impact: f64,
+    response: Value,
+}
+
+impl SwapQuote {
+    fn from_response(response: Value) -> Result<Self> {
+        let field = |name: &str| response.get(name).and_then(Value::as_str).unwrap_or_default().to_string();
+        Ok(Self {
+            input_mint: field("inputMint"),
+            output_mint: field("outputMint"),
+            in_amount: field("inAmount").parse()?,
+            out_amount: field("outAmount").parse()?,
+            price_impact: field("priceImpactPct").parse().unwrap_or(0.0),
+            response,
+        })
+    }
+}
+
+// Builds swap transactions from Jupiter's quote and swap endpoints. Swaps are requested as
+// legacy transactions so they go through the TransactionHandler like everything else.
+#[derive(Debug, Clone)]
+pub struct JupiterClient {
+    client: Client,
+    quote_url: String,
+    swap_url: String,
+    base_mint: String, // Paid in on buys and received on sells
+    slippage_bps: u64,
+}
+
+impl JupiterClient {
+    pub fn new(config: &Config) -> Result<Self> {
+        let timeout = Duration::from_millis(config.get_int("jupiter.timeout_ms").unwrap_or(5000) as u64);
+
+        Ok(Self {
+            client: Client::builder().timeout(timeout).build()?,
+            quote_url: config.get_string("jupiter.quote_url")
+                .unwrap_or_else(|_| "https://quote-api.jup.ag/v6/quote".to_string()),
+            swap_url: config.get_string("jupiter.swap_url")
+                .unwrap_or_else(|_| "https://quote-api.jup.ag/v6/swap".to_string()),
+            base_mint: config.get_string("jupiter.base_mint").unwrap_or_else(|_| SOL_MINT.to_string()),
+            slippage_bps: config.get_int("jupiter.slippage_bps").unwrap_or(100) as u64,
+        })
+    }
+
+    pub fn base_mint(&self) -> &str {
+        &self.base_mint
+    }
+
+    pub async fn quote(&self, input_mint: &str, output_mint: &str, amount: u64) -> Result<SwapQuote> {
+        let response = self.client.get(&self.quote_url)
+            .query(&[
+                ("inputMint", input_mint.to_string()),
+                ("outputMint", output_mint.to_string()),
+                ("amount", amount.to_string()),
+                ("slippageBps", self.slippage_bps.to_string()),
+                ("asLegacyTransaction", "true".to_string()),
+            ])
+            .send()
+            .await?;
+
+        let status = response.status();
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        let no_route = body.get("routePlan").and_then(Value::as_array).map_or(true, |plan| plan.is_empty());
+        if !status.is_success() || no_route {
+            let reason = body.get("error").and_then(Value::as_str).unwrap_or("empty route plan");
+            warn!("Jupiter found no route from {} to {} for {}: {} ({})", input_mint, output_mint, amount, reason, status);
+            return Err(anyhow::anyhow!("No Jupiter route from {} to {}: {}", input_mint, output_mint, reason));
+        }
+
+        SwapQuote::from_response(body)
+    }
+
+    // Unsigned swap for `user`, who pays fees and must sign before submission
+    pub async fn swap_transaction(&self, quote: &SwapQuote, user: &Pubkey) -> Result<Transaction> {
+        let body: Value = self.client.post(&self.swap_url)
+            .json(&json!({
+                "quoteResponse": quote.response,
+                "userPublicKey": user.to_string(),
+                "wrapAndUnwrapSol": true,
+                "asLegacyTransaction": true,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let encoded = body.get("swapTransaction").and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Jupiter swap response has no swapTransaction"))?;
+        let bytes = base64::engine::general_purpose::STANDARD.decode(encoded)?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+
+    pub async fn build_swap(&self, input_mint: &str, output_mint: &str, amount: u64, user: &Pubkey) -> Result<(SwapQuote, Transaction)> {
+        let quote = self.quote(input_mint, output_mint, amount).await?;
+        debug!("Jupiter quote {} {} -> {} {} ({}% impact)",
+               quote.in_amount, input_mint, quote.out_amount, output_mint, quote.price_impact * 100.0);
+        let transaction = self.swap_transaction(&quote, user).await?;
+        Ok((quote, transaction))
+    }
+}
+
+// Signs Jupiter swaps with the trading wallet and submits them through the shared handler
+pub struct SwapExecutor {
+    jupiter: JupiterClient,
+    signer: Arc<Keypair>,
+    transaction_handler: Arc<RwLock<TransactionHandler>>,
+    ata_resolver: Option<AtaResolver>, // Without one, buys assume the token account exists
+    blockhash_cache: Option<Arc<BlockhashCache>>, // Without one, the handler fetches a blockhash per swap
+    decimals: Mutex<HashMap<String, u8>>, // Per mint; a mint's decimals never change
+}
+
+impl SwapExecutor {
+    pub fn new(jupiter: JupiterClient, signer: Arc<Keypair>, transaction_handler: Arc<RwLock<TransactionHandler>>) -> Self {
+        Self {
+            jupiter,
+            signer,
+            transaction_handler,
+            ata_resolver: None,
+            blockhash_cache: None,
+            decimals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Builds the executor from `jupiter.keypair_path`, the trading wallet. None without one,
+    // which leaves every swap path a dry run. Buys check the wallet's token account on the
+    // handler's RPC node and create it when it is missing.
+    pub fn from_config(config: &Config, transaction_handler: Arc<RwLock<TransactionHandler>>) -> Result<Option<Self>> {
+        let path = match config.get_string("jupiter.keypair_path") {
+            Ok(path) if !path.is_empty() => path,
+            _ => return Ok(None),
+        };
+        let signer = read_keypair_file(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read trading keypair {}: {}", path, e))?;
+        let mut executor = Self::new(JupiterClient::new(config)?, Arc::new(signer), transaction_handler);
+        executor.set_ata_resolver(AtaResolver::new(config.get_string("ant_colony.transaction_handler.helius_rpc_url")?));
+        Ok(Some(executor))
+    }
+
+    pub fn set_ata_resolver(&mut self, ata_resolver: AtaResolver) {
+        self.ata_resolver = Some(ata_resolver);
+    }
+
+    pub fn set_blockhash_cache(&mut self, blockhash_cache: Arc<BlockhashCache>) {
+        self.blockhash_cache = Some(blockhash_cache);
+    }
+
+    // The trading wallet that pays for and signs every swap
+    pub fn pubkey(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    // Raw amount of `mint` for a UI amount, at the mint's own decimals
+    async fn raw_amount(&self, mint: &str, amount: f64) -> Result<u64> {
+        Ok((amount * 10f64.powi(self.decimals(mint).await? as i32)).round() as u64)
+    }
+
+    async fn decimals(&self, mint: &str) -> Result<u8> {
+        if mint == SOL_MINT {
+            return Ok(SOL_DECIMALS);
+        }
+        if let Some(decimals) = self.decimals.lock().unwrap().get(mint) {
+            return Ok(*decimals);
+        }
+        let decimals = self.transaction_handler.read().await.mint_decimals(&Pubkey::from_str(mint)?).await?;
+        self.decimals.lock().unwrap().insert(mint.to_string(), decimals);
+        Ok(decimals)
+    }
+
+    // Spends `amount` of the base mint (SOL unless `jupiter.base_mint` says otherwise) on `token_address`
+    pub async fn build_buy(&self, token_address: &str, amount: f64) -> Result<Transaction> {
+        let base_amount = self.raw_amount(self.jupiter.base_mint(), amount).await?;
+        let (_, mut transaction) = self.jupiter
+            .build_swap(self.jupiter.base_mint(), token_address, base_amount, &self.signer.pubkey())
+            .await?;
+        if let Some(ata_resolver) = &self.ata_resolver {
+            let mint = Pubkey::from_str(token_address)?;
+            ata_resolver.ensure_ata(&self.signer.pubkey(), &mint, &mut transaction).await?;
+        }
+        self.sign(transaction, &self.signer).await
+    }
+
+    // Like `build_buy`, but paid for and signed by `signer`, e.g. a princess's pool wallet,
+    // with `setup` (such as creating its token account) run ahead of the swap
+    pub async fn build_buy_as(&self, signer: &Keypair, setup: &[Instruction], token_address: &str, amount: f64) -> Result<Transaction> {
+        let base_amount = self.raw_amount(self.jupiter.base_mint(), amount).await?;
+        let (_, mut transaction) = self.jupiter
+            .build_swap(self.jupiter.base_mint(), token_address, base_amount, &signer.pubkey())
+            .await?;
+        for instruction in setup.iter().rev() {
+            transaction = prepend_instruction(&transaction, instruction.clone());
+        }
+        self.sign(transaction, signer).await
+    }
+
+    // Sells `amount` tokens, refusing routes that pay less than `min_price` of the base mint per token
+    pub async fn build_sell(&self, token_address: &str, amount: f64, min_price: f64) -> Result<Transaction> {
+        let raw_amount = self.raw_amount(token_address, amount).await?;
+        let (quote, transaction) = self.jupiter
+            .build_swap(token_address, self.jupiter.base_mint(), raw_amount, &self.signer.pubkey())
+            .await?;
+
+        let base_decimals = self.decimals(self.jupiter.base_mint()).await?;
+        let quoted_price = quote.out_amount as f64 / 10f64.powi(base_decimals as i32) / amount;
+        if quoted_price < min_price {
+            return Err(anyhow::anyhow!("Jupiter quotes {} at {} {} per token, below the minimum {}",
+                                     token_address, quoted_price, self.jupiter.base_mint(), min_price));
+        }
+        self.sign(transaction, &self.signer).await
+    }
+
+    // Restamps the swap with the handler's compute budget and a blockhash from the cache, so
+    // its validity window is the one the cache tracks rather than whatever Jupiter fetched.
+    // With nonce-based sending enabled the swap is presigned on the nonce instead, and stays
+    // valid until it is sent.
+    async fn sign(&self, transaction: Transaction, signer: &Keypair) -> Result<Transaction> {
+        let mut transaction = {
+            let handler = self.transaction_handler.read().await;
+            if handler.nonce_account().is_some() {
+                return handler.presign(transaction, &[signer]).await;
+            }
+            handler.with_compute_budget(&transaction).await?
+        };
+        let blockhash = match &self.blockhash_cache {
+            Some(cache) => cache.blockhash().await?,
+            None => self.transaction_handler.read().await.recent_blockhash().await?,
+        };
+        transaction.try_partial_sign(&[signer], blockhash)?;
+        Ok(transaction)
+    }
+
+    // Sending only needs shared access, so swaps confirm concurrently and signing the next
+    // one doesn't wait on this one's confirmation
+    pub async fn submit(&self, transaction: Transaction) -> Result<String> {
+        let result = self.transaction_handler.read().await.execute_transaction(transaction).await?;
+        if !result.success {
+            return Err(anyhow::anyhow!("Swap {} failed: {}", result.signature,
+                                     result.error.unwrap_or_else(|| "unknown error".to_string())));
+        }
+        Ok(result.signature.to_string())
+    }
+}
@@ -0,0 +1,83 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+
+// Live reading for one token: USD price and 24h traded volume in USD
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct MarketData {
+    pub price: f64,
+    pub volume_24h: f64,
+}
+
+// Where exit and monitoring logic get live numbers from, so they can be fed a fake in tests
+#[async_trait]
+pub trait MarketDataProvider: Send + Sync {
+    async fn market_data(&self, token_address: &str) -> Result<MarketData>;
+}
+
+// Reads price and volume from the token's most liquid DexScreener pair in one request
+pub struct DexScreenerMarketData {
+    client: Client,
+    base_url: String,
+}
+
+impl DexScreenerMarketData {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Client::new(),
+            base_url,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenPairsResponse {
+    #[serde(default)]
+    pairs: Option<Vec<Pair>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Pair {
+    price_usd: Option<String>,
+    #[serde(default)]
+    volume: PairVolume,
+    #[serde(default)]
+    liquidity: PairLiquidity,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PairVolume {
+    #[serde(default)]
+    h24: f64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PairLiquidity {
+    #[serde(default)]
+    usd: f64,
+}
+
+#[async_trait]
+impl MarketDataProvider for DexScreenerMarketData {
+    async fn market_data(&self, token_address: &str) -> Result<MarketData> {
+        let response: TokenPairsResponse = self.client
+            .get(format!("{}/{}", self.base_url.trim_end_matches('/'), token_address))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let pair = response.pairs.unwrap_or_default().into_iter()
+            .filter(|pair| pair.price_usd.is_some())
+            .max_by(|a, b| a.liquidity.usd.partial_cmp(&b.liquidity.usd).unwrap_or(std::cmp::Ordering::Equal))
+            .ok_or_else(|| anyhow::anyhow!("DexScreener has no priced pair for {}", token_address))?;
+
+        Ok(MarketData {
+            price: pair.price_usd.unwrap_or_default().parse()?,
+            volume_24h: pair.volume.h24,
+        })
+    }
+}
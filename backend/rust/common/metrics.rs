@@ -0,0 +1,88 @@
+use anyhow::Result;
+use prometheus::{Encoder, Gauge, Histogram, HistogramOpts, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+use std::time::Duration;
+use crate::common::MessageQueue;
+
+// Runtime metrics served from `/metrics` in the Prometheus text format. Cheap to clone:
+// every clone records into the same registry, so components each hold their own copy.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    active_trades: IntGauge,
+    total_profit: Gauge,
+    rpc_calls: IntCounterVec,
+    scan_duration: Histogram,
+    queue_depth: IntGauge,
+    success_rate: Gauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let metrics = Self {
+            active_trades: IntGauge::new("antbot_active_trades", "Trades currently open across the colony").unwrap(),
+            total_profit: Gauge::new("antbot_total_profit_sol", "Net SOL realized by profit-taking sells").unwrap(),
+            rpc_calls: IntCounterVec::new(
+                Opts::new("antbot_rpc_calls_total", "RPC clients checked out, by provider"),
+                &["provider"],
+            ).unwrap(),
+            scan_duration: Histogram::with_opts(
+                HistogramOpts::new("antbot_scan_duration_seconds", "Time taken by one coin scanner pass")
+                    .buckets(vec![0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+            ).unwrap(),
+            queue_depth: IntGauge::new("antbot_message_queue_depth", "Messages buffered across all queue subscribers").unwrap(),
+            success_rate: Gauge::new("antbot_trade_success_rate", "Successful trades as a fraction of all trades").unwrap(),
+            registry,
+        };
+
+        // Names are fixed above, so registration can only fail on a programming error
+        metrics.registry.register(Box::new(metrics.active_trades.clone())).unwrap();
+        metrics.registry.register(Box::new(metrics.total_profit.clone())).unwrap();
+        metrics.registry.register(Box::new(metrics.rpc_calls.clone())).unwrap();
+        metrics.registry.register(Box::new(metrics.scan_duration.clone())).unwrap();
+        metrics.registry.register(Box::new(metrics.queue_depth.clone())).unwrap();
+        metrics.registry.register(Box::new(metrics.success_rate.clone())).unwrap();
+        metrics
+    }
+
+    pub fn set_active_trades(&self, active_trades: u32) {
+        self.active_trades.set(active_trades as i64);
+    }
+
+    pub fn set_total_profit(&self, total_profit: f64) {
+        self.total_profit.set(total_profit);
+    }
+
+    pub fn record_rpc_call(&self, provider: &str) {
+        self.rpc_calls.with_label_values(&[provider]).inc();
+    }
+
+    pub fn observe_scan(&self, duration: Duration) {
+        self.scan_duration.observe(duration.as_secs_f64());
+    }
+
+    pub fn set_success_rate(&self, success_rate: f64) {
+        self.success_rate.set(success_rate);
+    }
+
+    // Queue depth is sampled rather than tracked on every publish
+    pub async fn sample_queue(&self, message_queue: &MessageQueue) {
+        self.queue_depth.set(message_queue.depth().await as i64);
+    }
+
+    pub fn rpc_calls(&self, provider: &str) -> u64 {
+        self.rpc_calls.with_label_values(&[provider]).get()
+    }
+
+    pub fn render(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
@@ -1,9 +1,42 @@
-use tokio::sync::mpsc;
+mod webhook;
+mod jupiter;
+mod market_data;
+mod notifier;
+mod metrics;
+mod token_account;
+
+use tokio::sync::Notify;
+use bitflags::bitflags;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use tokio::sync::RwLock;
+use thiserror::Error;
+
+pub use webhook::{TradeWebhook, TradeConfirmation, TradeOutcome, Delivery, DeadLetter};
+pub use jupiter::{JupiterClient, SwapQuote, SwapExecutor};
+pub use market_data::{MarketData, MarketDataProvider, DexScreenerMarketData};
+pub use notifier::{Notification, Notifier, TelegramNotifier, DiscordWebhookNotifier, AlertForwarder};
+pub use metrics::Metrics;
+pub use token_account::{AtaResolver, associated_token_address, create_associated_token_account, prepend_instruction, decompile_instructions};
+
+#[derive(Debug, Clone, Copy, Error)]
+pub enum TradeError {
+    #[error("Invalid trade amount {0}: must be positive and finite")]
+    InvalidAmount(f64),
+}
+
+// Guards every entry point that sizes a trade; zero, negative and NaN amounts would
+// otherwise flow silently into cost and position math
+pub fn validate_amount(amount: f64) -> Result<f64, TradeError> {
+    if amount.is_finite() && amount > 0.0 {
+        Ok(amount)
+    } else {
+        Err(TradeError::InvalidAmount(amount))
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeSignal {
@@ -38,10 +71,55 @@ pub struct LiquidityAlert {
     pub severity: AlertSeverity,
     pub current_value: f64,
     pub threshold_value: f64,
+    #[serde(default)]
+    pub previous_liquidity: Option<f64>, // Level a LiquidityDrop fell from, when the producer knows it
     pub timestamp: DateTime<Utc>,
     pub message: String,
 }
 
+impl LiquidityAlert {
+    // Liquidity falling from `previous_liquidity` to `current_liquidity`; the alert threshold
+    // is left at 0.0 for the producer to fill in
+    pub fn drop(token_address: &str, previous_liquidity: f64, current_liquidity: f64) -> Self {
+        let liquidity_drop = if previous_liquidity > 0.0 {
+            (1.0 - current_liquidity / previous_liquidity).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let severity = if liquidity_drop >= 0.8 {
+            AlertSeverity::Critical
+        } else if liquidity_drop >= 0.5 {
+            AlertSeverity::High
+        } else if liquidity_drop >= 0.25 {
+            AlertSeverity::Medium
+        } else {
+            AlertSeverity::Low
+        };
+
+        Self {
+            pool_address: String::new(),
+            token_address: token_address.to_string(),
+            alert_type: AlertType::LiquidityDrop,
+            severity,
+            current_value: current_liquidity,
+            threshold_value: 0.0,
+            previous_liquidity: Some(previous_liquidity),
+            timestamp: Utc::now(),
+            message: format!("Liquidity dropped by {:.0}%", liquidity_drop * 100.0),
+        }
+    }
+
+    // Fraction of liquidity lost, for LiquidityDrop alerts that report the level they fell from
+    pub fn liquidity_drop(&self) -> Option<f64> {
+        match (&self.alert_type, self.previous_liquidity) {
+            (AlertType::LiquidityDrop, Some(previous)) if previous > 0.0 => {
+                Some((1.0 - self.current_value / previous).clamp(0.0, 1.0))
+            }
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlertType {
     LowLiquidity,
@@ -49,68 +127,343 @@ pub enum AlertType {
     LiquiditySurge,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AlertSeverity {
+    Critical,
     High,
     Medium,
     Low,
 }
 
-#[derive(Debug)]
+// Something an operator should hear about, e.g. a critical rug alert or a trading freeze
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColonyAlert {
+    pub source: String, // Component that raised it, e.g. "rug_detector"
+    pub token_address: Option<String>,
+    pub severity: AlertSeverity,
+    pub message: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TradeEventStatus {
+    Submitted,
+    Confirmed,
+    Failed,
+    Simulated, // Dry run without a swap executor; nothing was sent
+}
+
+// One step of an order's lifecycle, published as it is sent and again once it lands or fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeExecutionEvent {
+    pub token_address: String,
+    pub action: TradeAction,
+    pub amount: f64,
+    pub status: TradeEventStatus,
+    pub signature: Option<String>,
+    pub realized_pnl: Option<f64>, // SOL, once a sell is confirmed
+    pub error: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TradeExecutionEvent {
+    pub fn new(token_address: &str, action: TradeAction, amount: f64, status: TradeEventStatus) -> Self {
+        Self {
+            token_address: token_address.to_string(),
+            action,
+            amount,
+            status,
+            signature: None,
+            realized_pnl: None,
+            error: None,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+// Serialized internally tagged, e.g. {"type": "TradeSignal", "token_address": ...},
+// so websocket clients can discriminate on `type`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum Message {
     TradeSignal(TradeSignal),
     RiskUpdate(RiskUpdate),
     LiquidityAlert(LiquidityAlert),
+    ColonyAlert(ColonyAlert),
+    TradeExecution(TradeExecutionEvent),
+}
+
+bitflags! {
+    // Set of message variants a subscriber is interested in
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct MessageKind: u8 {
+        const TRADE_SIGNAL = 1 << 0;
+        const RISK_UPDATE = 1 << 1;
+        const LIQUIDITY_ALERT = 1 << 2;
+        const COLONY_ALERT = 1 << 3;
+        const TRADE_EXECUTION = 1 << 4;
+    }
 }
 
+impl MessageKind {
+    // Topic names used by websocket clients, e.g. {"subscribe": ["trade_signal"]}
+    pub fn from_topic(topic: &str) -> Option<Self> {
+        match topic {
+            "trade_signal" => Some(MessageKind::TRADE_SIGNAL),
+            "risk_update" => Some(MessageKind::RISK_UPDATE),
+            "liquidity_alert" => Some(MessageKind::LIQUIDITY_ALERT),
+            "colony_alert" => Some(MessageKind::COLONY_ALERT),
+            "trade_execution" => Some(MessageKind::TRADE_EXECUTION),
+            _ => None,
+        }
+    }
+}
+
+impl Message {
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Message::TradeSignal(_) => MessageKind::TRADE_SIGNAL,
+            Message::RiskUpdate(_) => MessageKind::RISK_UPDATE,
+            Message::LiquidityAlert(_) => MessageKind::LIQUIDITY_ALERT,
+            Message::ColonyAlert(_) => MessageKind::COLONY_ALERT,
+            Message::TradeExecution(_) => MessageKind::TRADE_EXECUTION,
+        }
+    }
+}
+
+// What a subscriber's buffer does with a new message once it is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    DropOldest, // Evict the oldest buffered message to make room
+    DropNewest, // Discard the incoming message
+    Block,      // Make the publisher wait until the subscriber catches up
+}
+
+type Subscribers = RwLock<HashMap<String, Arc<SubscriberChannel>>>;
+
+// Fan-out queue: every subscriber receives every message of the kinds it subscribed to that
+// is published after it subscribed. Each subscriber has its own bounded buffer, so a slow
+// subscriber only affects others under `OverflowPolicy::Block`; messages discarded by the drop
+// policies are counted per subscriber and exposed through `dropped_count`. Dropping a
+// `Subscription` unsubscribes it.
+#[derive(Clone)]
 pub struct MessageQueue {
-    sender: mpsc::Sender<Message>,
-    receiver: mpsc::Receiver<Message>,
-    subscribers: Arc<RwLock<HashMap<String, mpsc::Sender<Message>>>>,
+    default_capacity: usize,
+    subscribers: Arc<Subscribers>,
 }
 
 impl MessageQueue {
     pub fn new(buffer_size: usize) -> Self {
-        let (sender, receiver) = mpsc::channel(buffer_size);
         Self {
-            sender,
-            receiver,
+            default_capacity: buffer_size,
             subscribers: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
-    pub async fn subscribe(&self, id: String) -> mpsc::Receiver<Message> {
-        let (tx, rx) = mpsc::channel(100);
+    // Subscribing again with the same id ends the previous subscription
+    pub async fn subscribe(&self, id: String, capacity: usize, policy: OverflowPolicy) -> Subscription {
+        self.subscribe_filtered(id, MessageKind::all(), capacity, policy).await
+    }
+
+    pub async fn subscribe_filtered(
+        &self,
+        id: String,
+        filter: MessageKind,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Subscription {
+        let channel = Arc::new(SubscriberChannel::new(filter, capacity, policy));
         let mut subscribers = self.subscribers.write().await;
-        subscribers.insert(id, tx);
-        rx
+        if let Some(previous) = subscribers.insert(id.clone(), channel.clone()) {
+            previous.close();
+        }
+        Subscription { id, channel, subscribers: Arc::downgrade(&self.subscribers) }
     }
 
     pub async fn unsubscribe(&self, id: &str) {
         let mut subscribers = self.subscribers.write().await;
-        subscribers.remove(id);
+        if let Some(channel) = subscribers.remove(id) {
+            channel.close();
+        }
     }
 
     pub async fn publish(&self, message: Message) {
+        // Snapshot the subscribers so a blocking subscriber doesn't hold the map lock
+        let (channels, has_closed) = {
+            let subscribers = self.subscribers.read().await;
+            let channels: Vec<Arc<SubscriberChannel>> = subscribers.values()
+                .filter(|channel| channel.filter.contains(message.kind()) && !channel.is_closed())
+                .cloned()
+                .collect();
+            (channels, subscribers.values().any(|channel| channel.is_closed()))
+        };
+
+        // Subscriptions dropped while the map was locked couldn't remove themselves
+        if has_closed {
+            self.subscribers.write().await.retain(|_, channel| !channel.is_closed());
+        }
+
+        for channel in channels {
+            channel.push(message.clone()).await;
+        }
+    }
+
+    pub async fn dropped_count(&self, id: &str) -> Option<u64> {
         let subscribers = self.subscribers.read().await;
-        for subscriber in subscribers.values() {
-            if let Err(e) = subscriber.send(message.clone()).await {
-                eprintln!("Error sending message to subscriber: {}", e);
+        subscribers.get(id).map(|channel| channel.dropped.load(Ordering::Relaxed))
+    }
+
+    pub async fn subscriber_count(&self) -> usize {
+        self.subscribers.read().await.values().filter(|channel| !channel.is_closed()).count()
+    }
+
+    // Messages published but not yet received, summed over every subscriber
+    pub async fn depth(&self) -> usize {
+        let subscribers = self.subscribers.read().await;
+        subscribers.values().map(|channel| channel.buffer.lock().unwrap().len()).sum()
+    }
+
+    pub fn default_capacity(&self) -> usize {
+        self.default_capacity
+    }
+}
+
+struct SubscriberChannel {
+    buffer: std::sync::Mutex<VecDeque<Message>>,
+    filter: MessageKind,
+    capacity: usize,
+    policy: OverflowPolicy,
+    dropped: AtomicU64,
+    closed: AtomicBool,
+    readable: Notify,
+    writable: Notify,
+}
+
+impl SubscriberChannel {
+    fn new(filter: MessageKind, capacity: usize, policy: OverflowPolicy) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            buffer: std::sync::Mutex::new(VecDeque::with_capacity(capacity)),
+            filter,
+            capacity,
+            policy,
+            dropped: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            readable: Notify::new(),
+            writable: Notify::new(),
+        }
+    }
+
+    async fn push(&self, message: Message) {
+        loop {
+            // Register before checking so a pop between the check and the wait isn't missed
+            let writable = self.writable.notified();
+            {
+                let mut buffer = self.buffer.lock().unwrap();
+                if self.closed.load(Ordering::Acquire) {
+                    return;
+                }
+
+                if buffer.len() < self.capacity {
+                    buffer.push_back(message);
+                    drop(buffer);
+                    self.readable.notify_one();
+                    return;
+                }
+
+                match self.policy {
+                    OverflowPolicy::DropOldest => {
+                        buffer.pop_front();
+                        buffer.push_back(message);
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        drop(buffer);
+                        self.readable.notify_one();
+                        return;
+                    }
+                    OverflowPolicy::DropNewest => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    OverflowPolicy::Block => {}
+                }
             }
+            writable.await;
+        }
+    }
+
+    fn pop(&self) -> Option<Message> {
+        let message = self.buffer.lock().unwrap().pop_front();
+        if message.is_some() {
+            self.writable.notify_one();
         }
+        message
     }
 
-    pub async fn receive(&mut self) -> Option<Message> {
-        self.receiver.recv().await
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.readable.notify_waiters();
+        self.writable.notify_waiters();
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
     }
 }
 
-impl Clone for MessageQueue {
-    fn clone(&self) -> Self {
-        Self {
-            sender: self.sender.clone(),
-            receiver: self.receiver.clone(),
-            subscribers: self.subscribers.clone(),
+pub struct Subscription {
+    id: String,
+    channel: Arc<SubscriberChannel>,
+    subscribers: Weak<Subscribers>,
+}
+
+impl Subscription {
+    // Waits for the next message; None once unsubscribed
+    pub async fn recv(&mut self) -> Option<Message> {
+        loop {
+            let readable = self.channel.readable.notified();
+            if self.channel.is_closed() {
+                return None;
+            }
+            if let Some(message) = self.channel.pop() {
+                return Some(message);
+            }
+            readable.await;
+        }
+    }
+
+    // Returns an already buffered message without waiting
+    pub fn try_recv(&mut self) -> Option<Message> {
+        if self.channel.is_closed() {
+            return None;
         }
+        self.channel.pop()
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn filter(&self) -> MessageKind {
+        self.channel.filter
+    }
+
+    pub fn dropped_count(&self) -> u64 {
+        self.channel.dropped.load(Ordering::Relaxed)
     }
-} 
\ No newline at end of file
+}
+
+impl Drop for Subscription {
+    // Closing wakes any publisher blocked on this buffer. The map entry is removed here when
+    // the lock is free, otherwise by the next publish.
+    fn drop(&mut self) {
+        self.channel.close();
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            if let Ok(mut subscribers) = subscribers.try_write() {
+                // A later subscription may have taken over the id
+                if subscribers.get(&self.id).is_some_and(|channel| Arc::ptr_eq(channel, &self.channel)) {
+                    subscribers.remove(&self.id);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::{info, warn};
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::common::{AlertSeverity, ColonyAlert, Message, MessageKind, MessageQueue, OverflowPolicy};
+
+// Push message for an operator, rendered to plain text by each sink
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    pub severity: AlertSeverity,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn from_alert(alert: &ColonyAlert) -> Self {
+        let title = match &alert.token_address {
+            Some(token_address) => format!("{:?} alert from {} on {}", alert.severity, alert.source, token_address),
+            None => format!("{:?} alert from {}", alert.severity, alert.source),
+        };
+        Self {
+            title,
+            body: alert.message.clone(),
+            severity: alert.severity,
+            timestamp: alert.timestamp,
+        }
+    }
+
+    fn text(&self) -> String {
+        format!("{}\n{}", self.title, self.body)
+    }
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, notification: Notification) -> Result<()>;
+}
+
+pub struct TelegramNotifier {
+    client: Client,
+    api_url: String,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramNotifier {
+    pub fn new(api_url: String, bot_token: String, chat_id: String) -> Self {
+        Self { client: Client::new(), api_url, bot_token, chat_id }
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    async fn send(&self, notification: Notification) -> Result<()> {
+        self.client.post(format!("{}/bot{}/sendMessage", self.api_url, self.bot_token))
+            .json(&json!({"chat_id": self.chat_id, "text": notification.text()}))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+pub struct DiscordWebhookNotifier {
+    client: Client,
+    webhook_url: String,
+}
+
+impl DiscordWebhookNotifier {
+    pub fn new(webhook_url: String) -> Self {
+        Self { client: Client::new(), webhook_url }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordWebhookNotifier {
+    async fn send(&self, notification: Notification) -> Result<()> {
+        self.client.post(&self.webhook_url)
+            .json(&json!({"content": notification.text()}))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+// Forwards Critical and High colony alerts from the message queue to every notifier.
+// Repeats of the same alert within the coalesce window are dropped so a flapping
+// condition cannot flood the operator's phone.
+pub struct AlertForwarder {
+    notifiers: Vec<Arc<dyn Notifier>>,
+    coalesce_window: Duration,
+    last_sent: HashMap<String, Instant>, // Keyed by source, token and message
+}
+
+impl AlertForwarder {
+    pub fn new(config: &Config) -> Result<Self> {
+        let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+        if config.get_bool("notifications.telegram.enabled").unwrap_or(false) {
+            notifiers.push(Arc::new(TelegramNotifier::new(
+                config.get_string("notifications.telegram.api_url")
+                    .unwrap_or_else(|_| "https://api.telegram.org".to_string()),
+                config.get_string("notifications.telegram.bot_token")?,
+                config.get_string("notifications.telegram.chat_id")?,
+            )));
+        }
+        if config.get_bool("notifications.discord.enabled").unwrap_or(false) {
+            notifiers.push(Arc::new(DiscordWebhookNotifier::new(
+                config.get_string("notifications.discord.webhook_url")?,
+            )));
+        }
+
+        Ok(Self {
+            notifiers,
+            coalesce_window: Duration::from_secs(
+                config.get_int("notifications.coalesce_secs").unwrap_or(300).max(0) as u64
+            ),
+            last_sent: HashMap::new(),
+        })
+    }
+
+    pub fn add_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        self.notifiers.push(notifier);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.notifiers.is_empty()
+    }
+
+    // Runs until the subscription is closed
+    pub async fn run(mut self, message_queue: MessageQueue) {
+        let mut subscription = message_queue
+            .subscribe_filtered("alert_forwarder".to_string(), MessageKind::COLONY_ALERT, 64, OverflowPolicy::DropOldest)
+            .await;
+        while let Some(message) = subscription.recv().await {
+            if let Message::ColonyAlert(alert) = message {
+                self.forward(&alert).await;
+            }
+        }
+    }
+
+    // True if the alert went out, false if it was below High or coalesced
+    pub async fn forward(&mut self, alert: &ColonyAlert) -> bool {
+        if !matches!(alert.severity, AlertSeverity::Critical | AlertSeverity::High) {
+            return false;
+        }
+
+        let key = format!("{}|{}|{}", alert.source, alert.token_address.as_deref().unwrap_or(""), alert.message);
+        let now = Instant::now();
+        if self.last_sent.get(&key).map_or(false, |sent| now.duration_since(*sent) < self.coalesce_window) {
+            info!("Coalesced repeat alert from {}: {}", alert.source, alert.message);
+            return false;
+        }
+        self.last_sent.retain(|_, sent| now.duration_since(*sent) < self.coalesce_window);
+        self.last_sent.insert(key, now);
+
+        let notification = Notification::from_alert(alert);
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.send(notification.clone()).await {
+                warn!("Failed to send {:?} alert notification: {}", alert.severity, e);
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use log::info;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    system_program,
+    transaction::Transaction,
+};
+use std::str::FromStr;
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGCPFHBQ6DFvaSoahXZ7WHRW";
+const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
+pub fn associated_token_program_id() -> Pubkey {
+    Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID).unwrap()
+}
+
+// The account an SPL token program wallet holds `mint` in
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    let token_program = Pubkey::from_str(TOKEN_PROGRAM_ID).unwrap();
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &associated_token_program_id(),
+    ).0
+}
+
+// The idempotent variant, so a race with another transaction creating the same account
+// doesn't fail the buy it is attached to
+pub fn create_associated_token_account(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: associated_token_program_id(),
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(Pubkey::from_str(TOKEN_PROGRAM_ID).unwrap(), false),
+        ],
+        data: vec![1], // CreateIdempotent
+    }
+}
+
+// Rebuilds `transaction` with `instruction` first. The message changes, so any existing
+// signatures are dropped and the result must be signed again.
+pub fn prepend_instruction(transaction: &Transaction, instruction: Instruction) -> Transaction {
+    let message = &transaction.message;
+    let mut instructions = vec![instruction];
+    instructions.extend(decompile_instructions(transaction));
+
+    Transaction::new_unsigned(Message::new_with_blockhash(
+        &instructions,
+        message.account_keys.first(),
+        &message.recent_blockhash,
+    ))
+}
+
+// The transaction's instructions with their account indexes resolved back to keys
+pub fn decompile_instructions(transaction: &Transaction) -> Vec<Instruction> {
+    let message = &transaction.message;
+    message.instructions.iter().map(|compiled| Instruction {
+        program_id: message.account_keys[compiled.program_id_index as usize],
+        accounts: compiled.accounts.iter()
+            .map(|&index| {
+                let index = index as usize;
+                AccountMeta {
+                    pubkey: message.account_keys[index],
+                    is_signer: message.is_signer(index),
+                    is_writable: message.is_writable(index),
+                }
+            })
+            .collect(),
+        data: compiled.data.clone(),
+    }).collect()
+}
+
+// Makes sure a buy's recipient can hold the token it is buying
+pub struct AtaResolver {
+    rpc_client: RpcClient,
+}
+
+impl AtaResolver {
+    pub fn new(rpc_url: String) -> Self {
+        Self { rpc_client: RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed()) }
+    }
+
+    // The owner's token account for `mint`. If it doesn't exist yet, an instruction creating
+    // it, paid for by the owner, is prepended to `transaction`, which then needs signing.
+    pub async fn ensure_ata(&self, owner: &Pubkey, mint: &Pubkey, transaction: &mut Transaction) -> Result<Pubkey> {
+        let address = associated_token_address(owner, mint);
+        let exists = self.rpc_client.get_account_with_commitment(&address, self.rpc_client.commitment())
+            .await?
+            .value
+            .is_some();
+
+        if !exists {
+            info!("Creating token account {} for {} to hold {}", address, owner, mint);
+            *transaction = prepend_instruction(transaction, create_associated_token_account(owner, owner, mint));
+        }
+        Ok(address)
+    }
+}
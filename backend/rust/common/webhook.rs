@@ -0,0 +1,160 @@
+use anyhow::Result;
+use config::Config;
+use log::{error, info, warn};
+use reqwest::Client;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeOutcome {
+    Filled,
+    Exited,
+    Failed,
+    Cancelled,
+}
+
+// Body POSTed to the webhook; `trade` carries the full TradeExecution or TradeProfit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeConfirmation {
+    pub outcome: TradeOutcome,
+    pub trade_id: String,
+    pub token_address: String,
+    pub timestamp: DateTime<Utc>,
+    pub trade: serde_json::Value,
+}
+
+impl TradeConfirmation {
+    pub fn new<T: Serialize>(outcome: TradeOutcome, trade_id: &str, token_address: &str, trade: &T) -> Result<Self> {
+        Ok(Self {
+            outcome,
+            trade_id: trade_id.to_string(),
+            token_address: token_address.to_string(),
+            timestamp: Utc::now(),
+            trade: serde_json::to_value(trade)?,
+        })
+    }
+}
+
+// One line of the dead-letter log, enough to redeliver the confirmation by hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub failed_at: DateTime<Utc>,
+    pub attempts: u32,
+    pub error: String,
+    pub confirmation: TradeConfirmation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Delivery {
+    Disabled,
+    Delivered { attempts: u32 },
+    DeadLettered { attempts: u32, error: String },
+}
+
+// Posts a confirmation for every terminal trade outcome to an external endpoint. Failed
+// deliveries are retried with a linear backoff; once attempts run out the confirmation is
+// appended to the dead-letter log instead of being lost.
+#[derive(Debug, Clone)]
+pub struct TradeWebhook {
+    client: Client,
+    url: Option<String>, // None when disabled
+    max_attempts: u32,
+    retry_delay: Duration,
+    dead_letter_path: PathBuf,
+}
+
+impl TradeWebhook {
+    pub fn new(config: &Config) -> Result<Self> {
+        let url = if config.get_bool("trade_webhook.enabled").unwrap_or(false) {
+            Some(config.get_string("trade_webhook.url")?)
+        } else {
+            None
+        };
+        let timeout = Duration::from_millis(config.get_int("trade_webhook.timeout_ms").unwrap_or(5000) as u64);
+
+        Ok(Self {
+            client: Client::builder().timeout(timeout).build()?,
+            url,
+            max_attempts: config.get_int("trade_webhook.max_attempts").unwrap_or(3).max(1) as u32,
+            retry_delay: Duration::from_millis(config.get_int("trade_webhook.retry_delay_ms").unwrap_or(500) as u64),
+            dead_letter_path: PathBuf::from(config.get_string("trade_webhook.dead_letter_path")
+                .unwrap_or_else(|_| "./data/webhook_dead_letters.jsonl".to_string())),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.url.is_some()
+    }
+
+    // Fire and forget, so a slow endpoint never holds up trading
+    pub fn notify(&self, confirmation: TradeConfirmation) {
+        if !self.is_enabled() {
+            return;
+        }
+        let webhook = self.clone();
+        tokio::spawn(async move {
+            webhook.deliver(&confirmation).await;
+        });
+    }
+
+    pub async fn deliver(&self, confirmation: &TradeConfirmation) -> Delivery {
+        let url = match &self.url {
+            Some(url) => url,
+            None => return Delivery::Disabled,
+        };
+
+        let mut last_error = String::new();
+        for attempt in 1..=self.max_attempts {
+            match self.post(url, confirmation).await {
+                Ok(()) => {
+                    info!("Delivered {:?} confirmation for trade {} (attempt {})",
+                          confirmation.outcome, confirmation.trade_id, attempt);
+                    return Delivery::Delivered { attempts: attempt };
+                }
+                Err(e) => {
+                    warn!("Webhook delivery for trade {} failed (attempt {}/{}): {}",
+                          confirmation.trade_id, attempt, self.max_attempts, e);
+                    last_error = e.to_string();
+                    if attempt < self.max_attempts {
+                        tokio::time::sleep(self.retry_delay * attempt).await;
+                    }
+                }
+            }
+        }
+
+        if let Err(e) = self.dead_letter(confirmation, &last_error) {
+            error!("Failed to dead-letter confirmation for trade {}: {}", confirmation.trade_id, e);
+        }
+        Delivery::DeadLettered { attempts: self.max_attempts, error: last_error }
+    }
+
+    async fn post(&self, url: &str, confirmation: &TradeConfirmation) -> Result<()> {
+        self.client.post(url)
+            .json(confirmation)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    fn dead_letter(&self, confirmation: &TradeConfirmation, error: &str) -> Result<()> {
+        if let Some(parent) = self.dead_letter_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let entry = DeadLetter {
+            failed_at: Utc::now(),
+            attempts: self.max_attempts,
+            error: error.to_string(),
+            confirmation: confirmation.clone(),
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.dead_letter_path)?;
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,14 @@
-use serde::Deserialize;
-use validator::Validate;
+use serde::{Deserialize, de::DeserializeOwned};
+use validator::{Validate, ValidationErrors, ValidationErrorsKind};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use notify::{Watcher, RecursiveMode, watcher};
+use tokio::sync::{watch, RwLock};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
 use std::time::Duration;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use crate::rpc::{ErrorPenalties, PoolSettings};
+use crate::logging::LogRotation;
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct Settings {
@@ -36,6 +39,52 @@ pub struct Settings {
     pub log_level: String,
     pub data_dir: String,
     pub temp_dir: String,
+
+    #[serde(default)]
+    pub secrets_file_policy: SecretsFilePolicy,
+
+    #[serde(default)]
+    pub log_rotation: LogRotation,
+}
+
+// What to do when api_keys.toml can be read by users other than the bot's own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretsFilePolicy {
+    Ignore,
+    #[default]
+    Warn,
+    Refuse,
+}
+
+// Secrets should be readable by their owner only (e.g. mode 600). Only enforced on unix,
+// where group and world permission bits exist.
+pub fn check_secrets_permissions(path: &Path, policy: SecretsFilePolicy) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if policy == SecretsFilePolicy::Ignore {
+            return Ok(());
+        }
+
+        let mode = std::fs::metadata(path)?.permissions().mode() & 0o777;
+        if mode & 0o044 != 0 {
+            let message = format!(
+                "{} is readable by group or others (mode {:o}); restrict it with `chmod 600 {}`",
+                path.display(), mode, path.display()
+            );
+            if policy == SecretsFilePolicy::Refuse {
+                return Err(anyhow::anyhow!("Refusing to start: {}", message));
+            }
+            eprintln!("Warning: {}", message);
+        }
+    }
+
+    #[cfg(not(unix))]
+    let _ = (path, policy);
+
+    Ok(())
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -44,13 +93,24 @@ pub struct RpcConfig {
     pub triton: RpcEndpoint,
     pub jito: RpcEndpoint,
     pub rpc_strategy: RpcStrategy,
+    #[serde(default)]
+    pub pool: PoolSettings,
 }
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct RpcEndpoint {
     pub mainnet: String,
     pub devnet: String,
-    pub testnet: String,
+    #[serde(default)]
+    pub testnet: String, // Jito has none
+    #[serde(default)]
+    pub ws_mainnet: Option<String>, // Derived from `mainnet` when unset, e.g. https:// becomes wss://
+}
+
+impl RpcEndpoint {
+    pub fn websocket_url(&self) -> String {
+        self.ws_mainnet.clone().unwrap_or_else(|| self.mainnet.replacen("http", "ws", 1))
+    }
 }
 
 #[derive(Debug, Deserialize, Validate)]
@@ -62,30 +122,161 @@ pub struct RpcStrategy {
     pub fallback_rpcs: Vec<String>,
     pub retry_delay_ms: u64,
     pub max_fallback_attempts: u32,
+    #[serde(default)]
+    pub error_penalties: ErrorPenalties,
+}
+
+// Credentials for every external service, read once at startup from api_keys.toml.
+// Each key must be present and non-empty; `ApiKeys::missing_keys` names the ones that aren't.
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct ApiKeys {
+    #[serde(default)]
+    #[validate]
+    pub exchanges: ExchangeKeys,
+    #[serde(default)]
+    #[validate]
+    pub network: NetworkKeys,
+    #[serde(default)]
+    #[validate]
+    pub monitoring: MonitoringKeys,
+    #[serde(default)]
+    #[validate]
+    pub ai_services: AiServiceKeys,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Validate)]
+pub struct ExchangeKeys {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub dex_screener_api_key: String,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub pump_fun_api_key: String,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub birdeye_api_key: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Validate)]
+pub struct NetworkKeys {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub helius_api_key: String,
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub jito_auth_token: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Validate)]
+pub struct MonitoringKeys {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub sentry_dsn: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Validate)]
+pub struct AiServiceKeys {
+    #[serde(default)]
+    #[validate(length(min = 1))]
+    pub openai_api_key: String,
+}
+
+impl ApiKeys {
+    // Dotted paths of every key that is missing or empty, e.g. "network.helius_api_key"
+    pub fn missing_keys(&self) -> Vec<String> {
+        let mut missing = Vec::new();
+        if let Err(errors) = self.validate() {
+            Self::collect_missing(&errors, "", &mut missing);
+        }
+        missing.sort();
+        missing
+    }
+
+    fn collect_missing(errors: &ValidationErrors, prefix: &str, missing: &mut Vec<String>) {
+        for (field, kind) in errors.errors() {
+            let path = if prefix.is_empty() { field.to_string() } else { format!("{}.{}", prefix, field) };
+            match kind {
+                ValidationErrorsKind::Struct(nested) => Self::collect_missing(nested, &path, missing),
+                _ => missing.push(path),
+            }
+        }
+    }
+}
+
+// An editor save usually fires several filesystem events; wait this long after the
+// first one for the burst to settle before reloading once
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+// A save can be observed mid-write, so a failed read or parse is retried before giving up
+const RELOAD_ATTEMPTS: u32 = 3;
+const RELOAD_RETRY_DELAY: Duration = Duration::from_millis(200);
+const WATCHED_FILES: [&str; 2] = ["settings.toml", "rpc.toml"];
+
+// A profile's overrides live next to the base file as settings.<profile>.toml, e.g.
+// settings.prod.toml, and only need the keys that differ from settings.toml
+pub fn profile_settings_file(profile: &str) -> String {
+    format!("settings.{}.toml", profile)
 }
 
+// Environment variables layered over the TOML files, e.g. `ANTBOT_RPC__HELIUS__MAINNET`
+// overrides `helius.mainnet` in rpc.toml and `ANTBOT_SETTINGS__MAX_CONCURRENT_TRADES`
+// overrides `max_concurrent_trades` in settings.toml. Nesting levels are separated by a
+// double underscore so single underscores inside key names survive.
+pub const SETTINGS_ENV_PREFIX: &str = "ANTBOT_SETTINGS";
+pub const RPC_ENV_PREFIX: &str = "ANTBOT_RPC";
+pub const API_KEYS_ENV_PREFIX: &str = "ANTBOT_API_KEYS";
+const ENV_SEPARATOR: &str = "__";
+
+// The environment layer for one file's keys, e.g. `env_overrides(RPC_ENV_PREFIX)` for rpc.toml
+pub fn env_overrides(env_prefix: &str) -> ::config::Environment {
+    ::config::Environment::with_prefix(env_prefix)
+        .separator(ENV_SEPARATOR)
+        .try_parsing(true)
+}
+
+// Bumped on every successful reload; the initial load is version 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ConfigVersion(pub u64);
+
 pub struct ConfigManager {
     settings: Arc<RwLock<Settings>>,
     rpc_config: Arc<RwLock<RpcConfig>>,
+    api_keys: ApiKeys,
     config_dir: PathBuf,
+    profile: Option<String>,
+    version: watch::Sender<ConfigVersion>,
 }
 
 impl ConfigManager {
     pub async fn new(config_dir: PathBuf) -> Result<Self> {
-        let settings = Self::load_settings(&config_dir).await?;
+        Self::with_profile(config_dir, None).await
+    }
+
+    pub async fn with_profile(config_dir: PathBuf, profile: Option<String>) -> Result<Self> {
+        let settings = Self::load_settings(&config_dir, profile.as_deref()).await?;
         let rpc_config = Self::load_rpc_config(&config_dir).await?;
+        check_secrets_permissions(&config_dir.join("api_keys.toml"), settings.secrets_file_policy)?;
+        let api_keys = Self::load_api_keys(&config_dir).await?;
         
         Ok(Self {
             settings: Arc::new(RwLock::new(settings)),
             rpc_config: Arc::new(RwLock::new(rpc_config)),
+            api_keys,
             config_dir,
+            profile,
+            version: watch::channel(ConfigVersion::default()).0,
         })
     }
 
-    async fn load_settings(config_dir: &PathBuf) -> Result<Settings> {
+    async fn load_settings(config_dir: &PathBuf, profile: Option<&str>) -> Result<Settings> {
         let settings_path = config_dir.join("settings.toml");
-        let contents = tokio::fs::read_to_string(&settings_path).await?;
-        let settings: Settings = toml::from_str(&contents)?;
+        let mut layers = vec![tokio::fs::read_to_string(&settings_path).await?];
+        if let Some(profile) = profile {
+            let profile_path = config_dir.join(profile_settings_file(profile));
+            let overrides = tokio::fs::read_to_string(&profile_path).await
+                .map_err(|e| anyhow::anyhow!("Failed to read profile {} at {}: {}", profile, profile_path.display(), e))?;
+            layers.push(overrides);
+        }
+        let settings: Settings = Self::with_env_overrides(&layers, SETTINGS_ENV_PREFIX)?;
         settings.validate()?;
         Ok(settings)
     }
@@ -93,40 +284,121 @@ impl ConfigManager {
     async fn load_rpc_config(config_dir: &PathBuf) -> Result<RpcConfig> {
         let rpc_path = config_dir.join("rpc.toml");
         let contents = tokio::fs::read_to_string(&rpc_path).await?;
-        let config: RpcConfig = toml::from_str(&contents)?;
+        let config: RpcConfig = Self::with_env_overrides(&[contents], RPC_ENV_PREFIX)?;
         config.validate()?;
         Ok(config)
     }
 
-    pub async fn watch_for_changes(&self) {
-        let settings = self.settings.clone();
-        let rpc_config = self.rpc_config.clone();
-        let config_dir = self.config_dir.clone();
-
-        let mut watcher = watcher(move |res| {
-            if let Ok(_) = res {
-                let settings = settings.clone();
-                let rpc_config = rpc_config.clone();
-                let config_dir = config_dir.clone();
-                
-                tokio::spawn(async move {
-                    if let Err(e) = Self::reload_configs(&config_dir, &settings, &rpc_config).await {
-                        eprintln!("Error reloading configs: {}", e);
-                    }
-                });
+    // Fails with every missing key at once rather than letting the first component that
+    // needs one trip over it later
+    async fn load_api_keys(config_dir: &PathBuf) -> Result<ApiKeys> {
+        let api_keys_path = config_dir.join("api_keys.toml");
+        let contents = tokio::fs::read_to_string(&api_keys_path).await
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", api_keys_path.display(), e))?;
+        let api_keys: ApiKeys = Self::with_env_overrides(&[contents], API_KEYS_ENV_PREFIX)?;
+
+        let missing = api_keys.missing_keys();
+        if !missing.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Missing or empty API keys in {}: {}",
+                api_keys_path.display(), missing.join(", ")
+            ));
+        }
+        Ok(api_keys)
+    }
+
+    // Later layers win key by key and the environment wins over all of them. Validation
+    // runs on the merged result, so an override can't sneak past it.
+    fn with_env_overrides<T: DeserializeOwned>(layers: &[String], env_prefix: &str) -> Result<T> {
+        let mut builder = ::config::Config::builder();
+        for contents in layers {
+            builder = builder.add_source(::config::File::from_str(contents, ::config::FileFormat::Toml));
+        }
+        let merged = builder.add_source(env_overrides(env_prefix)).build()?;
+        Ok(merged.try_deserialize()?)
+    }
+
+    // Runs until the watcher goes away; the watcher lives as long as this future
+    pub async fn watch_for_changes(&self) -> Result<()> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<Event>();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            match res {
+                Ok(event) => {
+                    let _ = tx.send(event);
+                }
+                Err(e) => eprintln!("Config watch error: {}", e),
+            }
+        })?;
+        watcher.watch(&self.config_dir, RecursiveMode::NonRecursive)?;
+
+        while let Some(event) = rx.recv().await {
+            let mut changed = BTreeSet::new();
+            Self::collect_changed_files(&event, self.profile.as_deref(), &mut changed);
+
+            let deadline = tokio::time::Instant::now() + RELOAD_DEBOUNCE;
+            while let Ok(Some(event)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+                Self::collect_changed_files(&event, self.profile.as_deref(), &mut changed);
+            }
+
+            if changed.is_empty() {
+                continue;
             }
-        }, Duration::from_secs(1)).unwrap();
 
-        watcher.watch(&self.config_dir, RecursiveMode::NonRecursive).unwrap();
+            let files: Vec<&str> = changed.iter().map(String::as_str).collect();
+            println!("Config files changed: {}", files.join(", "));
+            match Self::reload_configs(&self.config_dir, self.profile.as_deref(), &self.settings, &self.rpc_config).await {
+                Ok(()) => {
+                    self.version.send_modify(|version| version.0 += 1);
+                    println!("Config reloaded to version {} after changes to {}",
+                             self.version.borrow().0, files.join(", "));
+                }
+                Err(e) => eprintln!("Config reload failed, keeping previous config: {}", e),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn collect_changed_files(event: &Event, profile: Option<&str>, changed: &mut BTreeSet<String>) {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        let profile_file = profile.map(profile_settings_file);
+        for path in &event.paths {
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                if WATCHED_FILES.contains(&name) || profile_file.as_deref() == Some(name) {
+                    changed.insert(name.to_string());
+                }
+            }
+        }
     }
 
+    // Both files are loaded and validated before either is swapped in, so a bad edit
+    // leaves the running config untouched
     async fn reload_configs(
         config_dir: &PathBuf,
+        profile: Option<&str>,
         settings: &Arc<RwLock<Settings>>,
         rpc_config: &Arc<RwLock<RpcConfig>>,
     ) -> Result<()> {
-        let new_settings = Self::load_settings(config_dir).await?;
-        let new_rpc_config = Self::load_rpc_config(config_dir).await?;
+        let mut attempt = 1;
+        let (new_settings, new_rpc_config) = loop {
+            let loaded = async {
+                let settings = Self::load_settings(config_dir, profile).await?;
+                let rpc_config = Self::load_rpc_config(config_dir).await?;
+                Ok::<_, anyhow::Error>((settings, rpc_config))
+            }.await;
+
+            match loaded {
+                Ok(configs) => break configs,
+                Err(e) if attempt < RELOAD_ATTEMPTS => {
+                    eprintln!("Config reload attempt {} failed, retrying: {}", attempt, e);
+                    attempt += 1;
+                    tokio::time::sleep(RELOAD_RETRY_DELAY).await;
+                }
+                Err(e) => return Err(e),
+            }
+        };
 
         let mut settings = settings.write().await;
         *settings = new_settings;
@@ -137,6 +409,19 @@ impl ConfigManager {
         Ok(())
     }
 
+    // Components holding a receiver can await `changed()` and re-read what they cache
+    pub fn subscribe_changes(&self) -> watch::Receiver<ConfigVersion> {
+        self.version.subscribe()
+    }
+
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    pub fn current_version(&self) -> ConfigVersion {
+        *self.version.borrow()
+    }
+
     pub async fn get_settings(&self) -> Settings {
         self.settings.read().await.clone()
     }
@@ -144,4 +429,8 @@ impl ConfigManager {
     pub async fn get_rpc_config(&self) -> RpcConfig {
         self.rpc_config.read().await.clone()
     }
+
+    pub fn get_api_keys(&self) -> &ApiKeys {
+        &self.api_keys
+    }
 } 
\ No newline at end of file
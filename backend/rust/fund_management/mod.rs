@@ -0,0 +1,80 @@
+mod vault;
+
+pub use vault::{VaultManager, VaultTransfer, HandlerVaultTransfer};
+
+use anyhow::Result;
+use config::Config;
+use log::info;
+use crate::common::TradeError;
+
+// Splits realised profits between capital that goes back into trading and a reserve
+// that is moved out to the vault wallet
+#[derive(Debug, Clone)]
+pub struct FundManager {
+    reinvestment_rate: f64,
+    reserve_rate: f64,
+}
+
+impl Default for FundManager {
+    fn default() -> Self {
+        Self {
+            reinvestment_rate: 0.8,
+            reserve_rate: 0.2,
+        }
+    }
+}
+
+impl FundManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rates(reinvestment_rate: f64, reserve_rate: f64) -> Result<Self> {
+        if !(0.0..=1.0).contains(&reinvestment_rate) || !(0.0..=1.0).contains(&reserve_rate) {
+            return Err(anyhow::anyhow!(
+                "Fund rates must be between 0 and 1, got reinvestment {} and reserve {}",
+                reinvestment_rate, reserve_rate
+            ));
+        }
+        if (reinvestment_rate + reserve_rate - 1.0).abs() > 1e-9 {
+            return Err(anyhow::anyhow!(
+                "Reinvestment and reserve rates must sum to 1.0, got {}",
+                reinvestment_rate + reserve_rate
+            ));
+        }
+        Ok(Self { reinvestment_rate, reserve_rate })
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let defaults = Self::default();
+        Self::with_rates(
+            config.get_float("fund_management.reinvestment_rate").unwrap_or(defaults.reinvestment_rate),
+            config.get_float("fund_management.reserve_rate").unwrap_or(defaults.reserve_rate),
+        )
+    }
+
+    pub fn reinvestment_rate(&self) -> f64 {
+        self.reinvestment_rate
+    }
+
+    pub fn reserve_rate(&self) -> f64 {
+        self.reserve_rate
+    }
+
+    // Returns (reinvestment, vault); the vault takes whatever isn't reinvested, so the
+    // two always add up to `profits`
+    pub fn split(&self, profits: f64) -> (f64, f64) {
+        let reinvestment = profits * self.reinvestment_rate;
+        (reinvestment, profits - reinvestment)
+    }
+
+    pub async fn distribute_profits(&self, profits: f64) -> Result<(f64, f64)> {
+        if !profits.is_finite() || profits < 0.0 {
+            return Err(TradeError::InvalidAmount(profits).into());
+        }
+
+        let (reinvestment, vault) = self.split(profits);
+        info!("Distributed {} SOL profit - Reinvestment: {}, Vault: {}", profits, reinvestment, vault);
+        Ok((reinvestment, vault))
+    }
+}
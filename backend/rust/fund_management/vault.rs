@@ -0,0 +1,135 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::{info, warn};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+use solana_sdk::{
+    native_token::sol_to_lamports,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use crate::ant_colony::TransactionHandler;
+use crate::common::validate_amount;
+
+// Moves SOL from the trading wallet to the vault wallet
+#[async_trait]
+pub trait VaultTransfer: Send + Sync {
+    async fn transfer(&self, lamports: u64) -> Result<Signature>;
+}
+
+// Sends the transfer through the colony's TransactionHandler
+pub struct HandlerVaultTransfer {
+    handler: Arc<RwLock<TransactionHandler>>,
+    payer: Keypair,
+    vault_address: Pubkey,
+}
+
+impl HandlerVaultTransfer {
+    pub fn new(handler: Arc<RwLock<TransactionHandler>>, payer: Keypair, vault_address: &str) -> Result<Self> {
+        Ok(Self {
+            handler,
+            payer,
+            vault_address: Pubkey::from_str(vault_address)
+                .map_err(|e| anyhow::anyhow!("Invalid vault address {}: {}", vault_address, e))?,
+        })
+    }
+
+    // Pays from the trading wallet (`jupiter.keypair_path`) to `fund_management.vault_address`;
+    // None when either is unset
+    pub fn from_config(config: &Config, handler: Arc<RwLock<TransactionHandler>>) -> Result<Option<Self>> {
+        let vault_address = match config.get_string("fund_management.vault_address") {
+            Ok(address) if !address.is_empty() => address,
+            _ => return Ok(None),
+        };
+        let path = match config.get_string("jupiter.keypair_path") {
+            Ok(path) if !path.is_empty() => path,
+            _ => return Ok(None),
+        };
+        let payer = read_keypair_file(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read trading keypair {}: {}", path, e))?;
+        Ok(Some(Self::new(handler, payer, &vault_address)?))
+    }
+}
+
+#[async_trait]
+impl VaultTransfer for HandlerVaultTransfer {
+    async fn transfer(&self, lamports: u64) -> Result<Signature> {
+        let instruction = system_instruction::transfer(&self.payer.pubkey(), &self.vault_address, lamports);
+        let handler = self.handler.read().await;
+        let blockhash = handler.recent_blockhash().await?;
+        let mut transaction = handler
+            .with_compute_budget(&Transaction::new_with_payer(&[instruction], Some(&self.payer.pubkey())))
+            .await?;
+        transaction.try_sign(&[&self.payer], blockhash)?;
+
+        let result = handler.execute_transaction(transaction).await?;
+        if !result.success {
+            return Err(anyhow::anyhow!(
+                "Vault transfer {} failed: {}",
+                result.signature, result.error.unwrap_or_default()
+            ));
+        }
+        Ok(result.signature)
+    }
+}
+
+// Tracks what has been set aside in the vault. Without a transfer attached deposits are
+// only recorded, which is what paper trading and tests want.
+pub struct VaultManager {
+    transfer: Option<Arc<dyn VaultTransfer>>,
+    balance: Mutex<f64>, // Cumulative SOL deposited
+}
+
+impl Default for VaultManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VaultManager {
+    pub fn new() -> Self {
+        Self {
+            transfer: None,
+            balance: Mutex::new(0.0),
+        }
+    }
+
+    // Moves funds when `fund_management.vault_address` is set, records them otherwise
+    pub fn from_config(config: &Config, handler: Arc<RwLock<TransactionHandler>>) -> Result<Self> {
+        let mut manager = Self::new();
+        if let Some(transfer) = HandlerVaultTransfer::from_config(config, handler)? {
+            manager.set_transfer(Arc::new(transfer));
+        }
+        Ok(manager)
+    }
+
+    pub fn set_transfer(&mut self, transfer: Arc<dyn VaultTransfer>) {
+        self.transfer = Some(transfer);
+    }
+
+    pub fn vault_balance(&self) -> f64 {
+        *self.balance.lock().unwrap()
+    }
+
+    // The balance only moves once the transfer has landed
+    pub async fn deposit_to_vault(&self, amount: f64) -> Result<Option<Signature>> {
+        let amount = validate_amount(amount)?;
+
+        let signature = match &self.transfer {
+            Some(transfer) => Some(transfer.transfer(sol_to_lamports(amount)).await?),
+            None => {
+                warn!("No vault transfer configured, recording {} SOL deposit without moving funds", amount);
+                None
+            }
+        };
+
+        let mut balance = self.balance.lock().unwrap();
+        *balance += amount;
+        info!("Deposited {} SOL to vault, balance now {}", amount, *balance);
+        Ok(signature)
+    }
+}
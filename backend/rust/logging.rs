@@ -2,18 +2,30 @@ use anyhow::Result;
 use chrono::Local;
 use log::{LevelFilter, Record};
 use log4rs::{
-    append::file::FileAppender,
+    append::rolling_file::{
+        policy::compound::{roll::fixed_window::FixedWindowRoller, trigger::size::SizeTrigger, CompoundPolicy},
+        RollingFileAppender,
+    },
     config::{Appender, Config, Root},
     encode::pattern::PatternEncoder,
 };
-use std::path::PathBuf;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tracing::{info, error, Level};
 use tracing_subscriber::FmtSubscriber;
 use sentry::Client;
 
 pub struct Logger {
     log_dir: PathBuf,
-    error_tracker: Option<ErrorTracker>,
+    error_tracker: Option<Arc<ErrorTracker>>,
+    rotation: LogRotation,
+    level: LevelFilter,
+}
+
+// Where components report failures they would otherwise only log
+pub trait ErrorReporter: Send + Sync {
+    fn capture_error_with_context(&self, error: &anyhow::Error, component_id: &str, token_address: Option<&str>);
 }
 
 pub struct ErrorTracker {
@@ -27,32 +39,47 @@ impl ErrorTracker {
     }
 
     pub fn capture_error(&self, error: &anyhow::Error) {
-        self.client.capture_error(error);
+        let event = sentry::event_from_error(AsRef::<dyn std::error::Error>::as_ref(error));
+        self.client.capture_event(event, None);
+    }
+}
+
+impl ErrorReporter for ErrorTracker {
+    // The component and token are sent as tags so Sentry can group and filter on them
+    fn capture_error_with_context(&self, error: &anyhow::Error, component_id: &str, token_address: Option<&str>) {
+        let mut event = sentry::event_from_error(AsRef::<dyn std::error::Error>::as_ref(error));
+        event.tags.insert("component_id".to_string(), component_id.to_string());
+        if let Some(token_address) = token_address {
+            event.tags.insert("token_address".to_string(), token_address.to_string());
+        }
+        self.client.capture_event(event, None);
     }
 }
 
 impl Logger {
     pub fn new(log_dir: PathBuf, sentry_dsn: Option<&str>) -> Result<Self> {
-        let error_tracker = sentry_dsn.map(|dsn| ErrorTracker::new(dsn)).transpose()?;
-        Ok(Self { log_dir, error_tracker })
+        let error_tracker = sentry_dsn.map(|dsn| ErrorTracker::new(dsn).map(Arc::new)).transpose()?;
+        Ok(Self { log_dir, error_tracker, rotation: LogRotation::default(), level: LevelFilter::Info })
+    }
+
+    // Takes effect on the next `initialize`
+    pub fn set_rotation(&mut self, rotation: LogRotation) {
+        self.rotation = rotation;
+    }
+
+    // Takes effect on the next `initialize`
+    pub fn set_level(&mut self, level: LevelFilter) {
+        self.level = level;
     }
 
     pub fn initialize(&self) -> Result<()> {
         // Create log directory if it doesn't exist
         std::fs::create_dir_all(&self.log_dir)?;
 
-        // Configure log files
-        let sniping_core_log = FileAppender::builder()
-            .encoder(Box::new(PatternEncoder::new("{d} - {l} - {m}\n")))
-            .build(self.log_dir.join("sniping_core.log"))?;
-
-        let ant_colony_log = FileAppender::builder()
-            .encoder(Box::new(PatternEncoder::new("{d} - {l} - {m}\n")))
-            .build(self.log_dir.join("ant_colony.log"))?;
-
-        let error_log = FileAppender::builder()
-            .encoder(Box::new(PatternEncoder::new("{d} - {l} - {m}\n")))
-            .build(self.log_dir.join("error.log"))?;
+        // Configure log files; each rolls over on its own once it reaches the size limit
+        let sniping_core_log = self.rotation.appender(&self.log_dir.join("sniping_core.log"))?;
+        let ant_colony_log = self.rotation.appender(&self.log_dir.join("ant_colony.log"))?;
+        let error_log = self.rotation.appender(&self.log_dir.join("error.log"))?;
 
         // Configure loggers
         let config = Config::builder()
@@ -63,7 +90,7 @@ impl Logger {
                 .appender("sniping_core")
                 .appender("ant_colony")
                 .appender("error")
-                .build(LevelFilter::Info))?;
+                .build(self.level))?;
 
         // Initialize logging
         log4rs::init_config(config)?;
@@ -92,6 +119,11 @@ impl Logger {
             tracker.capture_error(error);
         }
     }
+
+    // For handing to components' `set_error_tracker`; None without a Sentry DSN
+    pub fn error_tracker(&self) -> Option<Arc<dyn ErrorReporter>> {
+        self.error_tracker.clone().map(|tracker| tracker as Arc<dyn ErrorReporter>)
+    }
 }
 
 // Custom log formatter for Python logs
@@ -106,10 +138,21 @@ pub fn format_python_log(record: &Record) -> String {
     )
 }
 
-// Log rotation configuration
+// Log rotation configuration, read from `[log_rotation]` in settings.toml
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
 pub struct LogRotation {
-    max_size: u64,
-    max_files: u32,
+    max_size: u64,  // bytes
+    max_files: u32, // Rotated files kept per log; older ones are deleted
+}
+
+impl Default for LogRotation {
+    fn default() -> Self {
+        Self {
+            max_size: 10 * 1024 * 1024,
+            max_files: 5,
+        }
+    }
 }
 
 impl LogRotation {
@@ -134,6 +177,21 @@ impl LogRotation {
             extension.to_string_lossy()
         ))
     }
+
+    // Appends to `path`, moving it to `get_rotated_filename(path, 1)` once it reaches
+    // `max_size` and shifting older files up one index, up to `max_files`
+    pub fn appender(&self, path: &Path) -> Result<RollingFileAppender> {
+        let pattern = self.get_rotated_filename(&path.to_path_buf(), 0).to_string_lossy()
+            .replacen(".0.", ".{}.", 1);
+        let roller = FixedWindowRoller::builder()
+            .base(1)
+            .build(&pattern, self.max_files.max(1))?;
+        let policy = CompoundPolicy::new(Box::new(SizeTrigger::new(self.max_size)), Box::new(roller));
+
+        Ok(RollingFileAppender::builder()
+            .encoder(Box::new(PatternEncoder::new("{d} - {l} - {m}\n")))
+            .build(path, Box::new(policy))?)
+    }
 }
 
 // Log categories for different components
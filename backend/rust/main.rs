@@ -1,12 +1,21 @@
 mod ant_colony;
 mod sniping_core;
+mod api;
+mod backend;
+mod fund_management;
+mod config;
+mod rpc;
+mod backtest;
+mod common;
+mod logging;
 
 use anyhow::{Result, Context};
 use clap::Parser;
 use log::{info, error, LevelFilter};
-use std::path::PathBuf;
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
 use tokio::signal;
-use config::Config;
+use ::config::Config;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -26,6 +35,19 @@ struct Args {
     /// Path to Python virtual environment
     #[arg(short, long)]
     venv_path: Option<PathBuf>,
+
+    /// Deployment profile (e.g. dev, staging, prod) whose settings.<profile>.toml is
+    /// layered over settings.toml
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Simulate fills instead of submitting transactions
+    #[arg(long)]
+    paper_trading: bool,
+
+    /// Print the journaled timeline of a past trade and exit
+    #[arg(long, value_name = "TRADE_ID")]
+    replay_trade: Option<String>,
 }
 
 #[tokio::main]
@@ -33,33 +55,100 @@ async fn main() -> Result<()> {
     // Parse command line arguments
     let args = Args::parse();
 
+    // Load configurations
+    let config = load_configs(&args.config_dir, args.profile.as_deref(), args.paper_trading)?;
+
     // Initialize logging with specified level
     let log_level = args.log_level.parse::<LevelFilter>()
         .context("Failed to parse log level")?;
-    env_logger::Builder::new()
-        .filter_level(log_level)
-        .init();
+    let logger = init_logger(&config, log_level)?;
+
+    if let Some(trade_id) = args.replay_trade {
+        return replay_trade(&config, &trade_id);
+    }
 
     info!("Starting AntBot...");
     info!("Network: {}", args.network);
+    info!("Profile: {}", args.profile.as_deref().unwrap_or("base"));
+    if config.get_bool("general.paper_trading").unwrap_or(false) {
+        info!("Paper trading: ON - no transaction will be submitted");
+    }
 
-    // Load configurations
-    let config = load_configs(&args.config_dir)?;
+    // Refuse to trade wallets another live instance is already using
+    let wallet_lock = ant_colony::WalletLock::acquire_from_config(&config)
+        .context("Another AntBot instance holds the wallet lock")?;
+    let lock_heartbeat = wallet_lock.map(|mut lock| {
+        tokio::spawn(async move {
+            let interval = (lock.stale_timeout() / 3).to_std()
+                .unwrap_or(std::time::Duration::from_secs(10));
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = lock.refresh() {
+                    error!("Failed to refresh wallet lock: {}", e);
+                }
+            }
+        })
+    });
 
     // Initialize Python environment if specified
     if let Some(venv_path) = args.venv_path {
         init_python_env(&venv_path)?;
     }
 
+    // The dashboard needs a token; without one it isn't served at all
+    let mut dashboard = match config.get_string("api.websocket_auth_token") {
+        Ok(token) if !token.is_empty() => Some(api::WebSocketServer::new(token)),
+        _ => None,
+    };
+
     // Initialize components
     info!("Initializing Ant Colony System...");
-    if let Err(e) = ant_colony::init(&config).await {
+    if let Err(e) = ant_colony::init(&config, dashboard.clone(), logger.error_tracker()).await {
         error!("Failed to initialize Ant Colony: {}", e);
         return Err(e.into());
     }
 
+    // The server exports the colony's own registry, so it is set before the server starts
+    let services = ant_colony::services().await;
+    if let Some(dashboard) = &mut dashboard {
+        if let Some(metrics) = &services.metrics {
+            dashboard.set_metrics(metrics.clone());
+        }
+        if let Some(message_queue) = &services.message_queue {
+            dashboard.set_message_queue(message_queue.clone());
+        }
+    }
+    let dashboard_task = match &dashboard {
+        Some(dashboard) => {
+            let host = config.get_string("api.host").unwrap_or_else(|_| "localhost".to_string());
+            let port = config.get_int("api.port").unwrap_or(8080) as u16;
+            let addr = (host.as_str(), port).to_socket_addrs()?.next()
+                .ok_or_else(|| anyhow::anyhow!("Dashboard address {}:{} does not resolve", host, port))?;
+            let dashboard = dashboard.clone();
+            Some(tokio::spawn(async move {
+                if let Err(e) = dashboard.start(addr).await {
+                    error!("Dashboard server failed: {}", e);
+                }
+            }))
+        }
+        None => None,
+    };
+
+    // The colony's dashboard feed has no auth of its own, so it only listens on loopback
+    let dashboard_feed_task = match (config.get_int("api.dashboard_port"), ant_colony::dashboard_feed().await) {
+        (Ok(port), Some(feed)) => {
+            use warp::Filter;
+            let route = warp::path("dashboard").and(warp::ws()).map(move |ws: warp::ws::Ws| {
+                let feed = feed.clone();
+                ws.on_upgrade(move |socket| async move { feed.handle_connection(socket).await })
+            });
+            Some(tokio::spawn(warp::serve(route).run(([127, 0, 0, 1], port as u16))))
+        }
+        _ => None,
+    };
+
     info!("Initializing Sniping Core...");
-    if let Err(e) = sniping_core::init(&config).await {
+    if let Err(e) = sniping_core::init(&config, services).await {
         error!("Failed to initialize Sniping Core: {}", e);
         return Err(e.into());
     }
@@ -93,33 +182,213 @@ async fn main() -> Result<()> {
     info!("Initiating graceful shutdown...");
     ant_colony::shutdown().await?;
     sniping_core::shutdown().await?;
+    if let Some(dashboard_task) = dashboard_task {
+        dashboard_task.abort();
+    }
+    if let Some(dashboard_feed_task) = dashboard_feed_task {
+        dashboard_feed_task.abort();
+    }
+
+    // Dropping the heartbeat task releases the wallet lock
+    if let Some(heartbeat) = lock_heartbeat {
+        heartbeat.abort();
+        let _ = heartbeat.await;
+    }
     info!("AntBot shutdown complete");
 
     Ok(())
 }
 
-fn load_configs(config_dir: &PathBuf) -> Result<Config> {
-    let settings = Config::builder()
-        .add_source(config::File::from(config_dir.join("settings.toml")))
-        .add_source(config::File::from(config_dir.join("rpc.toml")))
-        .add_source(config::File::from(config_dir.join("api_keys.toml")))
+fn load_configs(config_dir: &PathBuf, profile: Option<&str>, paper_trading: bool) -> Result<Config> {
+    let mut builder = Config::builder()
+        .add_source(::config::File::from(config_dir.join("settings.toml")));
+    if let Some(profile) = profile {
+        // Only the keys that differ per environment; everything else comes from the base
+        builder = builder.add_source(::config::File::from(config_dir.join(crate::config::profile_settings_file(profile))));
+    }
+    // The environment wins over the files, as it does for ConfigManager
+    builder = builder
+        .add_source(::config::File::from(config_dir.join("rpc.toml")))
+        .add_source(crate::config::env_overrides(crate::config::SETTINGS_ENV_PREFIX))
+        .add_source(crate::config::env_overrides(crate::config::RPC_ENV_PREFIX));
+    // Checked before the secrets are read, under the policy the rest of the config sets
+    let secrets_file_policy = builder.clone()
+        .build()
+        .context("Failed to load configuration files")?
+        .get::<crate::config::SecretsFilePolicy>("general.secrets_file_policy")
+        .unwrap_or_default();
+    let api_keys_path = config_dir.join("api_keys.toml");
+    crate::config::check_secrets_permissions(&api_keys_path, secrets_file_policy)
+        .with_context(|| format!("Failed to check {}", api_keys_path.display()))?;
+    builder = builder
+        .add_source(::config::File::from(api_keys_path.clone()))
+        .add_source(crate::config::env_overrides(crate::config::API_KEYS_ENV_PREFIX));
+    // The flag can only switch paper trading on; a config that enables it stays enabled
+    if paper_trading {
+        builder = builder.set_override("general.paper_trading", true)?;
+    }
+    let settings = builder
         .build()
         .context("Failed to load configuration files")?;
 
+    // Fails with every missing key at once rather than letting the first component that
+    // needs one trip over it later
+    let api_keys: crate::config::ApiKeys = settings.clone().try_deserialize()
+        .with_context(|| format!("Failed to parse {}", api_keys_path.display()))?;
+    let missing = api_keys.missing_keys();
+    if !missing.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Missing or empty API keys in {}: {}",
+            api_keys_path.display(), missing.join(", ")
+        ));
+    }
+
     Ok(settings)
 }
 
+// Writes the rolling log files under the directory of `logging.ant_colony_log`, rotated
+// per `[log_rotation]`. Errors go to Sentry only when a real DSN is configured.
+fn init_logger(config: &Config, level: LevelFilter) -> Result<logging::Logger> {
+    let log_dir = config.get_string("logging.ant_colony_log").ok()
+        .and_then(|path| Path::new(&path).parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("./logs"));
+    let sentry_dsn = config.get_string("monitoring.sentry_dsn").ok()
+        .filter(|dsn| !dsn.is_empty() && !dsn.starts_with("YOUR_"));
+
+    let mut logger = logging::Logger::new(log_dir, sentry_dsn.as_deref())?;
+    logger.set_rotation(config.get::<logging::LogRotation>("log_rotation").unwrap_or_default());
+    logger.set_level(level);
+    logger.initialize()?;
+    Ok(logger)
+}
+
+fn replay_trade(config: &Config, trade_id: &str) -> Result<()> {
+    let journal = ant_colony::TradeJournal::from_config(config)?;
+    let entries = journal.replay(trade_id)?;
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!("No journal entries found for trade {}", trade_id));
+    }
+
+    print!("{}", ant_colony::TradeJournal::format_timeline(trade_id, &entries));
+    Ok(())
+}
+
 fn init_python_env(venv_path: &PathBuf) -> Result<()> {
     // Verify Python virtual environment exists
     if !venv_path.exists() {
         return Err(anyhow::anyhow!("Python virtual environment not found at: {:?}", venv_path));
     }
 
+    let python_path = venv_python_path(venv_path);
+    if !python_path.exists() {
+        return Err(anyhow::anyhow!("Python interpreter not found at: {:?}", python_path));
+    }
+
     // Set up Python environment variables
     std::env::set_var("VIRTUAL_ENV", venv_path);
-    let python_path = venv_path.join("Scripts").join("python");
     std::env::set_var("PYTHON_PATH", python_path);
 
     info!("Python environment initialized at: {:?}", venv_path);
     Ok(())
-} 
\ No newline at end of file
+}
+
+// Windows virtualenvs keep the interpreter under Scripts/, everything else under bin/
+fn venv_python_path(venv_path: &Path) -> PathBuf {
+    if cfg!(windows) {
+        venv_path.join("Scripts").join("python.exe")
+    } else {
+        venv_path.join("bin").join("python")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn venv_python_path_follows_platform_layout() {
+        let python_path = venv_python_path(Path::new("venv"));
+        if cfg!(windows) {
+            assert!(python_path.ends_with("Scripts/python.exe"));
+        } else {
+            assert!(python_path.ends_with("bin/python"));
+        }
+    }
+
+    // A config directory whose settings.toml sets `policy` and whose api_keys.toml has `mode`
+    #[cfg(unix)]
+    fn config_dir_with_secrets(policy: &str, mode: u32) -> tempfile::TempDir {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("settings.toml"),
+                       format!("[general]\nsecrets_file_policy = \"{}\"\n", policy)).unwrap();
+        std::fs::write(dir.path().join("rpc.toml"), "").unwrap();
+        let api_keys_path = dir.path().join("api_keys.toml");
+        std::fs::write(&api_keys_path, concat!(
+            "[exchanges]\ndex_screener_api_key = \"a\"\npump_fun_api_key = \"b\"\nbirdeye_api_key = \"c\"\n",
+            "[network]\nhelius_api_key = \"d\"\njito_auth_token = \"e\"\n",
+            "[monitoring]\nsentry_dsn = \"f\"\n",
+            "[ai_services]\nopenai_api_key = \"g\"\n",
+        )).unwrap();
+        std::fs::set_permissions(&api_keys_path, std::fs::Permissions::from_mode(mode)).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn load_configs_applies_the_secrets_file_policy() {
+        // Readable by others under "refuse" stops startup before the keys are read
+        let dir = config_dir_with_secrets("refuse", 0o644);
+        let error = load_configs(&dir.path().to_path_buf(), None, false).unwrap_err();
+        assert!(format!("{:#}", error).contains("Refusing to start"));
+
+        // Owner-only keys load, as do readable ones under "warn"
+        let dir = config_dir_with_secrets("refuse", 0o600);
+        assert!(load_configs(&dir.path().to_path_buf(), None, false).is_ok());
+        let dir = config_dir_with_secrets("warn", 0o644);
+        assert!(load_configs(&dir.path().to_path_buf(), None, false).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn load_configs_names_every_missing_api_key() {
+        let dir = config_dir_with_secrets("warn", 0o600);
+        let api_keys_path = dir.path().join("api_keys.toml");
+        let contents = std::fs::read_to_string(&api_keys_path).unwrap()
+            .replace("helius_api_key = \"d\"", "helius_api_key = \"\"")
+            .replace("openai_api_key = \"g\"\n", "");
+        std::fs::write(&api_keys_path, contents).unwrap();
+
+        let error = load_configs(&dir.path().to_path_buf(), None, false).unwrap_err();
+        assert!(error.to_string().contains("ai_services.openai_api_key, network.helius_api_key"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn load_configs_layers_prefixed_environment_overrides() {
+        let dir = config_dir_with_secrets("warn", 0o600);
+        std::fs::write(dir.path().join("rpc.toml"), "[helius]\nmainnet = \"https://file.example\"\n").unwrap();
+        // Keys no other test reads, since the environment is shared across tests
+        std::env::set_var("ANTBOT_RPC__HELIUS__MAINNET", "https://env.example");
+        std::env::set_var("ANTBOT_SETTINGS__GENERAL__MESSAGE_QUEUE_CAPACITY", "64");
+        std::env::set_var("ANTBOT_API_KEYS__NETWORK__JITO_AUTH_TOKEN", "from-env");
+
+        let config = load_configs(&dir.path().to_path_buf(), None, false);
+        std::env::remove_var("ANTBOT_RPC__HELIUS__MAINNET");
+        std::env::remove_var("ANTBOT_SETTINGS__GENERAL__MESSAGE_QUEUE_CAPACITY");
+        std::env::remove_var("ANTBOT_API_KEYS__NETWORK__JITO_AUTH_TOKEN");
+
+        let config = config.unwrap();
+        assert_eq!(config.get_string("helius.mainnet").unwrap(), "https://env.example");
+        assert_eq!(config.get_int("general.message_queue_capacity").unwrap(), 64);
+        assert_eq!(config.get_string("network.jito_auth_token").unwrap(), "from-env");
+    }
+
+    #[test]
+    fn init_python_env_rejects_venv_without_interpreter() {
+        let venv = tempfile::tempdir().unwrap();
+        let error = init_python_env(&venv.path().to_path_buf()).unwrap_err();
+        assert!(error.to_string().contains("Python interpreter not found"));
+    }
+}
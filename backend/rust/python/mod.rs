@@ -1,5 +1,5 @@
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PyList, PyTuple};
 use anyhow::Result;
 use std::path::PathBuf;
 
@@ -26,32 +26,69 @@ impl std::fmt::Display for PythonError {
 
 impl std::error::Error for PythonError {}
 
-pub struct PythonContext {
-    py: Python,
-}
+// Holds no GIL token: a `Python<'py>` can't outlive the `with_gil` closure that produced it,
+// so every call acquires the GIL itself and hands back GIL-independent `PyObject`s
+#[derive(Debug, Default)]
+pub struct PythonContext;
 
 impl PythonContext {
     pub fn new() -> Result<Self> {
-        Python::with_gil(|py| {
-            Ok(Self { py })
-        })
+        pyo3::prepare_freethreaded_python();
+        Ok(Self)
     }
 
     pub fn init_python_modules(&self, python_path: &PathBuf) -> Result<()> {
-        let sys = self.py.import("sys")?;
-        let path = sys.getattr("path")?;
-        path.call_method1("append", (python_path.to_str().unwrap(),))?;
-        Ok(())
+        let python_path = python_path.to_str()
+            .ok_or_else(|| anyhow::anyhow!("Python module path is not valid UTF-8: {:?}", python_path))?;
+        Python::with_gil(|py| {
+            let sys = py.import("sys")?;
+            let path = sys.getattr("path")?;
+            path.call_method1("append", (python_path,))?;
+            Ok(())
+        })
     }
 
-    pub fn import_module(&self, module_name: &str) -> Result<PyResult<PyObject>> {
-        Ok(self.py.import(module_name))
+    pub fn import_module(&self, module_name: &str) -> Result<PyObject> {
+        Python::with_gil(|py| {
+            let module = py.import(module_name)?;
+            Ok(module.into())
+        })
     }
 
     pub fn call_function(&self, module: &str, function: &str, args: &[PyObject]) -> Result<PyObject> {
-        let module = self.py.import(module)?;
-        let func = module.getattr(function)?;
-        Ok(func.call(args, None)?)
+        Python::with_gil(|py| {
+            let module = py.import(module)?;
+            let func = module.getattr(function)?;
+            let args = PyTuple::new(py, args);
+            Ok(func.call1(args)?.into())
+        })
+    }
+
+    // For async callers: the call runs on the blocking pool with the GIL held only there, and
+    // arguments and result cross the boundary as JSON so no `PyObject` lives across an await
+    pub async fn call_function_async(
+        &self,
+        module: &str,
+        function: &str,
+        args: Vec<serde_json::Value>,
+    ) -> Result<String> {
+        let module = module.to_string();
+        let function = function.to_string();
+        let args = serde_json::to_string(&args)?;
+
+        tokio::task::spawn_blocking(move || {
+            Python::with_gil(|py| {
+                let json = py.import("json")?;
+                let args: &PyList = json.call_method1("loads", (args,))?
+                    .downcast()
+                    .map_err(PyErr::from)?;
+                let result = py.import(module.as_str())?
+                    .getattr(function.as_str())?
+                    .call1(args.to_tuple())?;
+                let result: String = json.call_method1("dumps", (result,))?.extract()?;
+                Ok(result)
+            })
+        }).await?
     }
 }
 
@@ -0,0 +1,160 @@
+use serde::Deserialize;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::RpcError;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::rpc::RpcProvider;
+
+// JSON-RPC server error codes reported by nodes that are lagging the cluster
+const NODE_UNHEALTHY: i64 = -32005;
+const MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RpcErrorKind {
+    RateLimited,
+    Unauthorized,
+    Timeout,
+    NodeBehind,
+    Other,
+}
+
+impl RpcErrorKind {
+    pub fn classify(error: &anyhow::Error) -> Self {
+        if let Some(client_error) = error.downcast_ref::<ClientError>() {
+            if let Some(kind) = Self::classify_client_error(client_error) {
+                return kind;
+            }
+        }
+        if let Some(reqwest_error) = error.downcast_ref::<reqwest::Error>() {
+            if let Some(kind) = Self::classify_reqwest_error(reqwest_error) {
+                return kind;
+            }
+        }
+        Self::classify_message(&error.to_string())
+    }
+
+    fn classify_client_error(error: &ClientError) -> Option<Self> {
+        match error.kind() {
+            ClientErrorKind::Reqwest(e) => Self::classify_reqwest_error(e),
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                if *code == NODE_UNHEALTHY || *code == MIN_CONTEXT_SLOT_NOT_REACHED => Some(RpcErrorKind::NodeBehind),
+            _ => None,
+        }
+    }
+
+    fn classify_reqwest_error(error: &reqwest::Error) -> Option<Self> {
+        if error.is_timeout() {
+            return Some(RpcErrorKind::Timeout);
+        }
+        match error.status().map(|status| status.as_u16()) {
+            Some(429) => Some(RpcErrorKind::RateLimited),
+            Some(401) | Some(403) => Some(RpcErrorKind::Unauthorized),
+            _ => None,
+        }
+    }
+
+    // Last resort for errors that only survive as text, e.g. after being re-wrapped
+    fn classify_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        if message.contains("429") || message.contains("too many requests") || message.contains("rate limit") {
+            RpcErrorKind::RateLimited
+        } else if message.contains("401") || message.contains("403") || message.contains("unauthorized") || message.contains("forbidden") {
+            RpcErrorKind::Unauthorized
+        } else if message.contains("timed out") || message.contains("timeout") {
+            RpcErrorKind::Timeout
+        } else if message.contains("node is behind") || message.contains("node is unhealthy") || message.contains("minimum context slot") {
+            RpcErrorKind::NodeBehind
+        } else {
+            RpcErrorKind::Other
+        }
+    }
+}
+
+// How strongly recent errors of each kind push a provider down the routing order
+#[derive(Debug, Clone, Deserialize)]
+pub struct ErrorPenalties {
+    pub unauthorized: f64,
+    pub rate_limited: f64,
+    pub timeout: f64,
+    pub node_behind: f64,
+    pub other: f64,
+    pub window_secs: u64,
+}
+
+impl Default for ErrorPenalties {
+    fn default() -> Self {
+        Self {
+            unauthorized: 10.0,
+            rate_limited: 5.0,
+            timeout: 1.0,
+            node_behind: 2.0,
+            other: 0.5,
+            window_secs: 60,
+        }
+    }
+}
+
+impl ErrorPenalties {
+    fn weight(&self, kind: RpcErrorKind) -> f64 {
+        match kind {
+            RpcErrorKind::Unauthorized => self.unauthorized,
+            RpcErrorKind::RateLimited => self.rate_limited,
+            RpcErrorKind::Timeout => self.timeout,
+            RpcErrorKind::NodeBehind => self.node_behind,
+            RpcErrorKind::Other => self.other,
+        }
+    }
+}
+
+// Lifetime counters per provider and kind, plus a recent window used for routing
+pub struct ProviderErrorTracker {
+    penalties: ErrorPenalties,
+    counts: Mutex<HashMap<(RpcProvider, RpcErrorKind), u64>>,
+    recent: Mutex<Vec<(Instant, RpcProvider, RpcErrorKind)>>,
+}
+
+impl ProviderErrorTracker {
+    pub fn new(penalties: ErrorPenalties) -> Self {
+        Self {
+            penalties,
+            counts: Mutex::new(HashMap::new()),
+            recent: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn record(&self, provider: RpcProvider, error: &anyhow::Error) -> RpcErrorKind {
+        let kind = RpcErrorKind::classify(error);
+        *self.counts.lock().unwrap().entry((provider, kind)).or_insert(0) += 1;
+        self.recent.lock().unwrap().push((Instant::now(), provider, kind));
+        kind
+    }
+
+    pub fn count(&self, provider: RpcProvider, kind: RpcErrorKind) -> u64 {
+        self.counts.lock().unwrap().get(&(provider, kind)).copied().unwrap_or(0)
+    }
+
+    // Snapshot for metrics export
+    pub fn counts(&self) -> HashMap<(RpcProvider, RpcErrorKind), u64> {
+        self.counts.lock().unwrap().clone()
+    }
+
+    pub fn penalty(&self, provider: RpcProvider) -> f64 {
+        let window = Duration::from_secs(self.penalties.window_secs);
+        let mut recent = self.recent.lock().unwrap();
+        recent.retain(|(at, _, _)| at.elapsed() <= window);
+        recent.iter()
+            .filter(|(_, p, _)| *p == provider)
+            .map(|(_, _, kind)| self.penalties.weight(*kind))
+            .sum()
+    }
+
+    // Keeps the configured order among equally healthy providers
+    pub fn rank(&self, providers: &[RpcProvider]) -> Vec<RpcProvider> {
+        let mut ranked: Vec<(RpcProvider, f64)> = providers.iter()
+            .map(|p| (*p, self.penalty(*p)))
+            .collect();
+        ranked.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.into_iter().map(|(p, _)| p).collect()
+    }
+}
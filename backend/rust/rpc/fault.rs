@@ -0,0 +1,39 @@
+use anyhow::Result;
+use rand::Rng;
+use std::time::Duration;
+use crate::rpc::RpcProvider;
+
+// Test-only chaos layer for `RpcClientManager::get_client`: every call waits `delay`, then
+// fails with probability `failure_rate`
+#[derive(Debug, Clone, Copy)]
+pub struct FaultInjector {
+    failure_rate: f64,
+    delay: Duration,
+}
+
+impl FaultInjector {
+    pub fn new(failure_rate: f64, delay: Duration) -> Self {
+        Self {
+            failure_rate: failure_rate.clamp(0.0, 1.0),
+            delay,
+        }
+    }
+
+    pub fn failure_rate(&self) -> f64 {
+        self.failure_rate
+    }
+
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    pub async fn apply(&self, provider: RpcProvider) -> Result<()> {
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        if rand::thread_rng().gen_bool(self.failure_rate) {
+            return Err(anyhow::anyhow!("Injected fault: {:?} connection refused", provider));
+        }
+        Ok(())
+    }
+}
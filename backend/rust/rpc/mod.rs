@@ -1,92 +1,185 @@
-use deadpool::managed::Manager;
+mod errors;
+mod pool;
+mod subscription;
+#[cfg(feature = "fault-injection")]
+mod fault;
+
+use deadpool::managed::{Manager, RecycleResult};
 use anyhow::Result;
+use log::warn;
 use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use std::str::FromStr;
 use std::time::Duration;
+use tokio::task::JoinHandle;
 use crate::config::RpcConfig;
+use crate::common::Metrics;
+
+pub use errors::{RpcErrorKind, ErrorPenalties, ProviderErrorTracker};
+pub use pool::{PoolSettings, PooledClient, HealthCheck, check_health};
+pub use subscription::{ProgramNotification, LogsNotification};
+#[cfg(feature = "fault-injection")]
+pub use fault::FaultInjector;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RpcProvider {
     Helius,
     Triton,
     Jito,
 }
 
+impl FromStr for RpcProvider {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "helius" => Ok(RpcProvider::Helius),
+            "triton" => Ok(RpcProvider::Triton),
+            "jito" => Ok(RpcProvider::Jito),
+            other => Err(anyhow::anyhow!("Unknown RPC provider: {}", other)),
+        }
+    }
+}
+
 pub struct RpcClientManager {
     helius: deadpool::managed::Pool<HeliusManager>,
     triton: deadpool::managed::Pool<TritonManager>,
     jito: deadpool::managed::Pool<JitoManager>,
+    failover_order: Vec<RpcProvider>,
+    websocket_url: String, // The monitoring provider's, for subscriptions
+    error_tracker: ProviderErrorTracker,
+    metrics: Metrics,
+    #[cfg(feature = "fault-injection")]
+    fault_injector: std::sync::Mutex<Option<FaultInjector>>,
 }
 
 struct HeliusManager {
     endpoint: String,
+    health: HealthCheck,
 }
 
 struct TritonManager {
     endpoint: String,
+    health: HealthCheck,
 }
 
 struct JitoManager {
     endpoint: String,
     auth_token: String,
+    health: HealthCheck,
 }
 
 impl Manager for HeliusManager {
-    type Type = RpcClient;
+    type Type = PooledClient;
     type Error = anyhow::Error;
 
-    async fn create(&self) -> Result<RpcClient, Self::Error> {
-        Ok(RpcClient::new(&self.endpoint))
+    async fn create(&self) -> Result<PooledClient, Self::Error> {
+        Ok(PooledClient::new(RpcClient::new(&self.endpoint)))
+    }
+
+    async fn recycle(&self, client: &mut PooledClient) -> RecycleResult<Self::Error> {
+        self.health.recycle(client).await
     }
 }
 
 impl Manager for TritonManager {
-    type Type = RpcClient;
+    type Type = PooledClient;
     type Error = anyhow::Error;
 
-    async fn create(&self) -> Result<RpcClient, Self::Error> {
-        Ok(RpcClient::new(&self.endpoint))
+    async fn create(&self) -> Result<PooledClient, Self::Error> {
+        Ok(PooledClient::new(RpcClient::new(&self.endpoint)))
+    }
+
+    async fn recycle(&self, client: &mut PooledClient) -> RecycleResult<Self::Error> {
+        self.health.recycle(client).await
     }
 }
 
 impl Manager for JitoManager {
-    type Type = RpcClient;
+    type Type = PooledClient;
     type Error = anyhow::Error;
 
-    async fn create(&self) -> Result<RpcClient, Self::Error> {
+    async fn create(&self) -> Result<PooledClient, Self::Error> {
         let mut client = RpcClient::new(&self.endpoint);
         client.set_auth_token(&self.auth_token);
-        Ok(client)
+        Ok(PooledClient::new(client))
+    }
+
+    async fn recycle(&self, client: &mut PooledClient) -> RecycleResult<Self::Error> {
+        self.health.recycle(client).await
     }
 }
 
 impl RpcClientManager {
     pub async fn new(config: &RpcConfig) -> Result<Self> {
-        let helius = deadpool::managed::Pool::builder(HeliusManager {
+        let helius = config.pool.build(HeliusManager {
             endpoint: config.helius.mainnet.clone(),
-        })
-        .max_size(10)
-        .build()?;
+            health: HealthCheck::new(&config.helius.mainnet, &config.pool),
+        })?;
 
-        let triton = deadpool::managed::Pool::builder(TritonManager {
+        let triton = config.pool.build(TritonManager {
             endpoint: config.triton.mainnet.clone(),
-        })
-        .max_size(10)
-        .build()?;
+            health: HealthCheck::new(&config.triton.mainnet, &config.pool),
+        })?;
 
-        let jito = deadpool::managed::Pool::builder(JitoManager {
+        let jito = config.pool.build(JitoManager {
             endpoint: config.jito.mainnet.clone(),
             auth_token: "YOUR_JITO_AUTH_TOKEN".to_string(), // TODO: Load from config
-        })
-        .max_size(10)
-        .build()?;
+            health: HealthCheck::new(&config.jito.mainnet, &config.pool),
+        })?;
+
+        let websocket_url = match RpcProvider::from_str(&config.rpc_strategy.monitoring)? {
+            RpcProvider::Helius => config.helius.websocket_url(),
+            RpcProvider::Triton => config.triton.websocket_url(),
+            RpcProvider::Jito => config.jito.websocket_url(),
+        };
+
+        let mut failover_order = vec![RpcProvider::from_str(&config.rpc_strategy.primary_rpc)?];
+        for fallback in &config.rpc_strategy.fallback_rpcs {
+            let provider = RpcProvider::from_str(fallback)?;
+            if !failover_order.contains(&provider) {
+                failover_order.push(provider);
+            }
+        }
 
         Ok(Self {
             helius,
             triton,
             jito,
+            failover_order,
+            websocket_url,
+            error_tracker: ProviderErrorTracker::new(config.rpc_strategy.error_penalties.clone()),
+            metrics: Metrics::default(),
+            #[cfg(feature = "fault-injection")]
+            fault_injector: std::sync::Mutex::new(None),
         })
     }
 
+    pub fn set_metrics(&mut self, metrics: Metrics) {
+        self.metrics = metrics;
+    }
+
+    // Takes `&self` so chaos tests can turn faults on and off on a shared manager
+    #[cfg(feature = "fault-injection")]
+    pub fn set_fault_injection(&self, failure_rate: f64, delay: Duration) {
+        *self.fault_injector.lock().unwrap() = Some(FaultInjector::new(failure_rate, delay));
+    }
+
+    #[cfg(feature = "fault-injection")]
+    pub fn clear_fault_injection(&self) {
+        *self.fault_injector.lock().unwrap() = None;
+    }
+
     pub async fn get_client(&self, provider: RpcProvider) -> Result<RpcClient> {
+        self.metrics.record_rpc_call(&format!("{:?}", provider).to_lowercase());
+        #[cfg(feature = "fault-injection")]
+        {
+            let fault_injector = *self.fault_injector.lock().unwrap();
+            if let Some(fault_injector) = fault_injector {
+                fault_injector.apply(provider).await?;
+            }
+        }
         match provider {
             RpcProvider::Helius => self.helius.get().await.map_err(|e| e.into()),
             RpcProvider::Triton => self.triton.get().await.map_err(|e| e.into()),
@@ -102,21 +195,112 @@ impl RpcClientManager {
         let result = f(&client)?;
         Ok(result)
     }
+
+    // Tries providers in failover order, moving those with recent auth or rate-limit
+    // errors to the back, and records every failure by provider and kind
+    pub async fn execute_failover<T, F>(&self, f: F) -> Result<T>
+    where
+        F: Fn(&RpcClient) -> Result<T>,
+    {
+        let mut last_error = None;
+        for provider in self.error_tracker.rank(&self.failover_order) {
+            let result = match self.get_client(provider).await {
+                Ok(client) => f(&client),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    let kind = self.error_tracker.record(provider, &e);
+                    warn!("RPC provider {:?} failed ({:?}): {}", provider, kind, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No RPC providers configured")))
+    }
+
+    // Streams account changes for `program_id` over the monitoring provider's websocket,
+    // e.g. new pools as a DEX program creates them. The subscription lives as long as the
+    // returned task.
+    pub async fn subscribe_program<F>(&self, program_id: Pubkey, callback: F) -> Result<JoinHandle<()>>
+    where
+        F: Fn(ProgramNotification) + Send + 'static,
+    {
+        subscription::subscribe_program(&self.websocket_url, program_id, callback).await
+    }
+
+    // Streams the logs of successful transactions that invoke `program_id`, over the
+    // monitoring provider's websocket
+    pub async fn subscribe_logs<F>(&self, program_id: Pubkey, callback: F) -> Result<JoinHandle<()>>
+    where
+        F: Fn(LogsNotification) + Send + 'static,
+    {
+        subscription::subscribe_logs(&self.websocket_url, program_id, callback).await
+    }
+
+    pub fn error_tracker(&self) -> &ProviderErrorTracker {
+        &self.error_tracker
+    }
+
+    pub fn provider_order(&self) -> Vec<RpcProvider> {
+        self.error_tracker.rank(&self.failover_order)
+    }
+
+    // (healthy, configured), where a provider is healthy while it has no errors in the
+    // routing window
+    pub fn provider_health(&self) -> (usize, usize) {
+        let healthy = self.failover_order.iter()
+            .filter(|provider| self.error_tracker.penalty(**provider) == 0.0)
+            .count();
+        (healthy, self.failover_order.len())
+    }
 }
 
 pub struct RpcClientWrapper {
     client: RpcClient,
     provider: RpcProvider,
+    priority_fee: u64, // lamports bid on top of the base fee
+    jito_tip: u64,     // lamports tipped per bundle; only paid through Jito
 }
 
 impl RpcClientWrapper {
-    pub fn new(client: RpcClient, provider: RpcProvider) -> Self {
+    // Bids the transaction handler's floor: its minimum price per compute unit over the
+    // compute units each transaction requests, plus its Jito tip
+    pub fn new(client: RpcClient, provider: RpcProvider, config: &config::Config) -> Self {
+        let min_priority_fee = config.get_int("ant_colony.transaction_handler.min_priority_fee").unwrap_or(1000).max(0) as u64;
+        let compute_unit_limit = config.get_int("ant_colony.transaction_handler.compute_unit_limit").unwrap_or(200_000).max(0) as u64;
         Self {
             client,
             provider,
+            priority_fee: min_priority_fee * compute_unit_limit / 1_000_000,
+            jito_tip: config.get_int("ant_colony.transaction_handler.jito.tip_lamports").unwrap_or(10_000).max(0) as u64,
         }
     }
 
+    pub fn set_priority_fee(&mut self, lamports: u64) {
+        self.priority_fee = lamports;
+    }
+
+    pub fn set_jito_tip(&mut self, lamports: u64) {
+        self.jito_tip = lamports;
+    }
+
+    // Total lamports landing `transaction` through this provider would cost: the base fee
+    // (signatures x lamports per signature, as quoted by getFeeForMessage), the priority fee
+    // and, for Jito, the tip
+    pub async fn estimate_transaction_cost(&self, transaction: &Transaction) -> Result<u64> {
+        // The blocking client must not stall the runtime's worker thread
+        let base_fee = tokio::task::block_in_place(|| self.client.get_fee_for_message(&transaction.message))?;
+        let tip = match self.provider {
+            RpcProvider::Jito => self.jito_tip,
+            RpcProvider::Helius | RpcProvider::Triton => 0,
+        };
+        Ok(base_fee + self.priority_fee + tip)
+    }
+
     pub async fn execute_with_retry<T, F>(&self, f: F, max_retries: u32) -> Result<T>
     where
         F: Fn(&RpcClient) -> Result<T>,
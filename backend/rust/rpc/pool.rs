@@ -0,0 +1,106 @@
+use anyhow::Result;
+use deadpool::managed::{Pool, PoolBuilder, Manager, RecycleError, RecycleResult};
+use deadpool::Runtime;
+use serde::Deserialize;
+use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
+use solana_client::rpc_client::RpcClient;
+use std::ops::Deref;
+use std::time::{Duration, Instant};
+
+// Size and timeouts of each provider's connection pool
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PoolSettings {
+    pub max_size: usize,
+    pub wait_timeout_ms: u64,    // Waiting for a free connection
+    pub create_timeout_ms: u64,  // Opening a new one
+    pub recycle_timeout_ms: u64, // Health-checking an idle one before reuse
+    pub health_check_idle_secs: u64, // Connections used more recently skip the check
+}
+
+impl Default for PoolSettings {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            wait_timeout_ms: 5000,
+            create_timeout_ms: 5000,
+            recycle_timeout_ms: 2000,
+            health_check_idle_secs: 30,
+        }
+    }
+}
+
+impl PoolSettings {
+    pub fn build<M: Manager>(&self, manager: M) -> Result<Pool<M>> {
+        let builder: PoolBuilder<M> = Pool::builder(manager);
+        Ok(builder
+            .max_size(self.max_size)
+            .wait_timeout(Some(Duration::from_millis(self.wait_timeout_ms)))
+            .create_timeout(Some(Duration::from_millis(self.create_timeout_ms)))
+            .recycle_timeout(Some(Duration::from_millis(self.recycle_timeout_ms)))
+            .runtime(Runtime::Tokio1)
+            .build()?)
+    }
+}
+
+// Pings the endpoint with getHealth; an unhealthy, unreachable or slow node fails the check
+pub async fn check_health(client: &AsyncRpcClient, timeout: Duration) -> Result<()> {
+    match tokio::time::timeout(timeout, client.get_health()).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(anyhow::anyhow!("RPC node {} failed health check: {}", client.url(), e)),
+        Err(_) => Err(anyhow::anyhow!("RPC node {} failed health check: no answer within {:?}", client.url(), timeout)),
+    }
+}
+
+// A pooled connection and when the pool last handed it out
+pub struct PooledClient {
+    client: RpcClient,
+    last_used: Instant,
+}
+
+impl PooledClient {
+    pub fn new(client: RpcClient) -> Self {
+        Self { client, last_used: Instant::now() }
+    }
+
+    pub fn idle_for(&self) -> Duration {
+        self.last_used.elapsed()
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = RpcClient;
+
+    fn deref(&self) -> &RpcClient {
+        &self.client
+    }
+}
+
+// Checks a provider's idle connections before the pool reuses them. One nonblocking
+// client per provider does the pinging, so a slow node can't hold a worker thread.
+pub struct HealthCheck {
+    client: AsyncRpcClient,
+    timeout: Duration,
+    idle_after: Duration,
+}
+
+impl HealthCheck {
+    pub fn new(endpoint: &str, settings: &PoolSettings) -> Self {
+        let timeout = Duration::from_millis(settings.recycle_timeout_ms);
+        Self {
+            client: AsyncRpcClient::new_with_timeout(endpoint.to_string(), timeout),
+            timeout,
+            idle_after: Duration::from_secs(settings.health_check_idle_secs),
+        }
+    }
+
+    // Connections that fail the check are dropped, so the pool creates a fresh one
+    pub async fn recycle(&self, pooled: &mut PooledClient) -> RecycleResult<anyhow::Error> {
+        if pooled.idle_for() >= self.idle_after {
+            check_health(&self.client, self.timeout).await
+                .map_err(|e| RecycleError::Message(e.to_string()))?;
+        }
+        pooled.last_used = Instant::now();
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use futures_util::StreamExt;
+use log::{info, warn};
+use std::str::FromStr;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+// An account owned by a subscribed program was created or changed
+#[derive(Debug, Clone)]
+pub struct ProgramNotification {
+    pub slot: u64,
+    pub pubkey: Pubkey,
+    pub account: Account,
+}
+
+// A successful transaction that invoked a subscribed program, with its log lines
+#[derive(Debug, Clone)]
+pub struct LogsNotification {
+    pub slot: u64,
+    pub signature: Signature,
+    pub logs: Vec<String>,
+}
+
+// Connects to `ws_url` and calls `callback` with every account notification for
+// `program_id` until the connection drops. Returns once the subscription is confirmed, so
+// a bad URL or a rejected subscribe surfaces here rather than in the background task.
+pub(crate) async fn subscribe_program<F>(ws_url: &str, program_id: Pubkey, callback: F) -> Result<JoinHandle<()>>
+where
+    F: Fn(ProgramNotification) + Send + 'static,
+{
+    let ws_url = ws_url.to_string();
+    let (ready, subscribed) = oneshot::channel::<Result<()>>();
+
+    let handle = tokio::spawn(async move {
+        let pubsub = match PubsubClient::new(&ws_url).await {
+            Ok(pubsub) => pubsub,
+            Err(e) => {
+                let _ = ready.send(Err(e.into()));
+                return;
+            }
+        };
+        let config = RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (mut notifications, _unsubscribe) = match pubsub.program_subscribe(&program_id, Some(config)).await {
+            Ok(subscription) => subscription,
+            Err(e) => {
+                let _ = ready.send(Err(e.into()));
+                return;
+            }
+        };
+        let _ = ready.send(Ok(()));
+        info!("Subscribed to accounts of program {}", program_id);
+
+        while let Some(response) = notifications.next().await {
+            let keyed = response.value;
+            let pubkey = match Pubkey::from_str(&keyed.pubkey) {
+                Ok(pubkey) => pubkey,
+                Err(e) => {
+                    warn!("Program {} notification has a bad pubkey {}: {}", program_id, keyed.pubkey, e);
+                    continue;
+                }
+            };
+            match keyed.account.decode::<Account>() {
+                Some(account) => callback(ProgramNotification { slot: response.context.slot, pubkey, account }),
+                None => warn!("Program {} notification for {} could not be decoded", program_id, pubkey),
+            }
+        }
+        warn!("Subscription to program {} ended", program_id);
+    });
+
+    subscribed.await
+        .map_err(|_| anyhow::anyhow!("Subscription task for program {} exited early", program_id))??;
+    Ok(handle)
+}
+
+// Connects to `ws_url` and calls `callback` with the logs of every successful transaction
+// that mentions `program_id`. Unlike `subscribe_program`, which sees every write to the
+// program's accounts, this lets callers pick out a single instruction by its log line.
+pub(crate) async fn subscribe_logs<F>(ws_url: &str, program_id: Pubkey, callback: F) -> Result<JoinHandle<()>>
+where
+    F: Fn(LogsNotification) + Send + 'static,
+{
+    let ws_url = ws_url.to_string();
+    let (ready, subscribed) = oneshot::channel::<Result<()>>();
+
+    let handle = tokio::spawn(async move {
+        let pubsub = match PubsubClient::new(&ws_url).await {
+            Ok(pubsub) => pubsub,
+            Err(e) => {
+                let _ = ready.send(Err(e.into()));
+                return;
+            }
+        };
+        let filter = RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]);
+        let config = RpcTransactionLogsConfig { commitment: Some(CommitmentConfig::confirmed()) };
+        let (mut notifications, _unsubscribe) = match pubsub.logs_subscribe(filter, config).await {
+            Ok(subscription) => subscription,
+            Err(e) => {
+                let _ = ready.send(Err(e.into()));
+                return;
+            }
+        };
+        let _ = ready.send(Ok(()));
+        info!("Subscribed to logs of program {}", program_id);
+
+        while let Some(response) = notifications.next().await {
+            let logs = response.value;
+            if logs.err.is_some() {
+                continue;
+            }
+            match Signature::from_str(&logs.signature) {
+                Ok(signature) => callback(LogsNotification { slot: response.context.slot, signature, logs: logs.logs }),
+                Err(e) => warn!("Program {} logs have a bad signature {}: {}", program_id, logs.signature, e),
+            }
+        }
+        warn!("Log subscription to program {} ended", program_id);
+    });
+
+    subscribed.await
+        .map_err(|_| anyhow::anyhow!("Log subscription task for program {} exited early", program_id))??;
+    Ok(handle)
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use config::Config;
+use log::info;
+
+// Scan batch size that backs off when sources rate-limit us and creeps back up while
+// they are healthy (multiplicative decrease, additive increase), within fixed bounds
+#[derive(Debug, Clone)]
+pub struct AdaptiveBatchSize {
+    enabled: bool,
+    current: usize,
+    min: usize,
+    max: usize,
+    shrink_factor: f64, // Multiplier applied after a rate-limited scan
+    grow_step: usize,   // Added after each healthy scan
+}
+
+impl AdaptiveBatchSize {
+    pub fn new(config: &Config, base: usize) -> Result<Self> {
+        let enabled = config.get_bool("sniping_core.coin_scanner.adaptive_batch.enabled").unwrap_or(false);
+        let min = config.get_int("sniping_core.coin_scanner.adaptive_batch.min_batch_size")
+            .map(|v| v as usize)
+            .unwrap_or(base)
+            .max(1);
+        let max = config.get_int("sniping_core.coin_scanner.adaptive_batch.max_batch_size")
+            .map(|v| v as usize)
+            .unwrap_or(base)
+            .max(min);
+        let shrink_factor = config.get_float("sniping_core.coin_scanner.adaptive_batch.shrink_factor")
+            .unwrap_or(0.5)
+            .clamp(0.0, 1.0);
+        let grow_step = config.get_int("sniping_core.coin_scanner.adaptive_batch.grow_step")
+            .map(|v| v as usize)
+            .unwrap_or(10);
+
+        Ok(Self {
+            enabled,
+            current: base.clamp(min, max),
+            min,
+            max,
+            shrink_factor,
+            grow_step,
+        })
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn record_scan(&mut self, rate_limited: bool) {
+        if !self.enabled {
+            return;
+        }
+
+        let previous = self.current;
+        self.current = if rate_limited {
+            ((self.current as f64 * self.shrink_factor) as usize).max(self.min)
+        } else {
+            (self.current + self.grow_step).min(self.max)
+        };
+
+        if self.current != previous {
+            info!("Scan batch size {} -> {} ({})", previous, self.current,
+                  if rate_limited { "rate limited" } else { "sources healthy" });
+        }
+    }
+}
@@ -1,11 +1,25 @@
 use anyhow::Result;
 use config::Config;
 use log::{info, error, warn};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tokio::sync::RwLock;
 use crate::sniping_core::{SnipingState, radar::TokenOpportunity};
+use crate::sniping_core::slippage::{AdaptiveSlippage, LiquidityClass};
+use crate::sniping_core::launch_observer::{LaunchObserver, PoolMonitor, ObservationOutcome};
+use crate::sniping_core::exit_liquidity::{ExitLiquidityCheck, ExitQuoter};
+use crate::sniping_core::quote_freshness::{QuoteFreshness, EntryQuoter, EntryQuote};
+use crate::sniping_core::killswitch::SentimentKillswitch;
+use crate::sniping_core::price_feed::PriceFeed;
+use crate::sniping_core::sizing::PositionSizer;
+use crate::sniping_core::exit_strategies::ExitManager;
+use async_trait::async_trait;
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
+use crate::common::{validate_amount, TradeWebhook, TradeConfirmation, TradeOutcome, SwapExecutor};
+use crate::common::{Message, MessageQueue, TradeAction, TradeExecutionEvent, TradeEventStatus};
+use crate::ant_colony::{Portfolio, RiskGovernor, TokenBlacklist};
+use solana_sdk::transaction::Transaction;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeExecution {
@@ -18,6 +32,29 @@ pub struct TradeExecution {
     pub error: Option<String>,
     pub total_costs: f64,  // Track all costs including gas and fees
     pub min_sell_price: f64,  // Minimum price to ensure profit
+    #[serde(default)]
+    pub liquidity_class: LiquidityClass,  // Pool depth bucket used for adaptive slippage
+    #[serde(default)]
+    pub trigger: TriggerCondition,        // When a pending buy may fire
+}
+
+// Price condition a pending buy waits for before it executes
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum TriggerCondition {
+    #[default]
+    Immediate,
+    LimitBelow(f64), // Buy once the price falls to or below this
+    StopAbove(f64),  // Buy once the price rises to or above this, e.g. on a breakout
+}
+
+impl TriggerCondition {
+    pub fn is_met(&self, price: f64) -> bool {
+        match *self {
+            TriggerCondition::Immediate => true,
+            TriggerCondition::LimitBelow(limit) => price <= limit,
+            TriggerCondition::StopAbove(stop) => price >= stop,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,18 +63,50 @@ pub enum TradeStatus {
     Executing,
     Completed,
     Failed,
+    Cancelled,
 }
 
 pub struct BuyEngine {
     id: String,
     state: Arc<RwLock<SnipingState>>,
-    is_active: bool,
+    is_active: AtomicBool,
     max_slippage: f64,
+    slippage: Mutex<AdaptiveSlippage>,
+    launch_observer: LaunchObserver,
+    exit_check: ExitLiquidityCheck,
+    quote_freshness: QuoteFreshness,
     gas_multiplier: f64,
     min_liquidity: f64,
     max_position_size: f64,
-    pending_trades: Vec<TradeExecution>,
-    active_trades: Vec<TradeExecution>,
+    pending_expiry: Option<chrono::Duration>, // Pending buys older than this are cancelled
+    limit_order_ttl: chrono::Duration,        // Replaces pending_expiry for buys waiting on a trigger
+    recheck_pending_entry: bool,              // Also cancel pending buys whose pool no longer qualifies
+    webhook: TradeWebhook,
+    liquidity_source: Option<Arc<dyn LiquiditySource>>,
+    swap_executor: Option<Arc<SwapExecutor>>, // Without one buys are built and sent as dry runs
+    risk_governor: Option<Arc<RiskGovernor>>, // The colony's daily limits, when snipes count toward them
+    blacklist: TokenBlacklist,                // Shared with the colony; empty until one is set
+    killswitch: Option<Arc<SentimentKillswitch>>, // Halts new buys once tripped
+    message_queue: Option<MessageQueue>,      // Receives a TradeExecution event at each step of a buy
+    price_feed: Option<Arc<PriceFeed>>,       // Live prices; triggered buys stay pending without one
+    sizer: PositionSizer,
+    kelly_capital: f64,                       // SOL Kelly sizing stakes from, less what active buys hold
+    portfolio: Option<Arc<Portfolio>>,        // Trade history for Kelly sizing
+    exit_manager: Option<Arc<ExitManager>>,   // Takes over each filled buy's stops and take profit
+    // Shared so concurrent `execute_trade` calls can move trades between them; when both
+    // are needed, pending is always locked before active
+    pending_trades: Arc<RwLock<Vec<TradeExecution>>>,
+    active_trades: Arc<RwLock<Vec<TradeExecution>>>,
+}
+
+// Current pool liquidity for a token, in the same units as `min_liquidity`
+#[async_trait]
+pub trait LiquiditySource: Send + Sync {
+    async fn liquidity(&self, token_address: &str) -> Result<f64>;
+
+    // SOL side of the pool, the side a buy pays into, so it compares directly with the
+    // buy amount. Not derived from `liquidity`, which is valued in USD.
+    async fn reserve_in(&self, token_address: &str) -> Result<f64>;
 }
 
 impl BuyEngine {
@@ -46,102 +115,442 @@ impl BuyEngine {
         let gas_multiplier = config.get_float("sniping_core.buy_engine.gas_multiplier")? as f64;
         let min_liquidity = config.get_float("sniping_core.buy_engine.min_liquidity")? as f64;
         let max_position_size = config.get_float("sniping_core.buy_engine.max_position_size")? as f64;
+        let slippage = AdaptiveSlippage::new(config, max_slippage)?;
+        let sizer = PositionSizer::new(config, max_position_size)?;
+        let launch_observer = LaunchObserver::new(config)?;
+        let exit_check = ExitLiquidityCheck::new(config)?;
+        let quote_freshness = QuoteFreshness::new(config)?;
+        let pending_expiry = if config.get_bool("sniping_core.buy_engine.pending_expiry.enabled").unwrap_or(true) {
+            Some(chrono::Duration::milliseconds(
+                config.get_int("sniping_core.buy_engine.pending_expiry.max_age_ms").unwrap_or(30_000)
+            ))
+        } else {
+            None
+        };
+        let recheck_pending_entry = config.get_bool("sniping_core.buy_engine.pending_expiry.recheck_entry")
+            .unwrap_or(true);
+        let limit_order_ttl = chrono::Duration::milliseconds(
+            config.get_int("sniping_core.buy_engine.limit_orders.ttl_ms").unwrap_or(3_600_000)
+        );
+        let webhook = TradeWebhook::new(config)?;
 
         Ok(Self {
             id: uuid::Uuid::new_v4().to_string(),
             state,
-            is_active: false,
+            is_active: AtomicBool::new(false),
             max_slippage,
+            slippage: Mutex::new(slippage),
+            launch_observer,
+            exit_check,
+            quote_freshness,
             gas_multiplier,
             min_liquidity,
             max_position_size,
-            pending_trades: Vec::new(),
-            active_trades: Vec::new(),
+            pending_expiry,
+            limit_order_ttl,
+            recheck_pending_entry,
+            webhook,
+            liquidity_source: None,
+            swap_executor: None,
+            risk_governor: None,
+            blacklist: TokenBlacklist::default(),
+            killswitch: None,
+            message_queue: None,
+            price_feed: None,
+            sizer,
+            kelly_capital: config.get_float("sniping_core.buy_engine.kelly.capital").unwrap_or(0.0),
+            portfolio: None,
+            exit_manager: None,
+            pending_trades: Arc::new(RwLock::new(Vec::new())),
+            active_trades: Arc::new(RwLock::new(Vec::new())),
         })
     }
 
-    pub async fn init(&mut self) -> Result<()> {
-        self.is_active = true;
+    pub async fn init(&self) -> Result<()> {
+        self.is_active.store(true, Ordering::SeqCst);
         info!("Buy Engine {} initialized", self.id);
         Ok(())
     }
 
-    pub async fn execute_trade(&self, token_address: &str, amount: f64) -> Result<TradeExecution> {
-        // Validate trade parameters
+    pub fn set_liquidity_source(&mut self, source: Arc<dyn LiquiditySource>) {
+        self.liquidity_source = Some(source);
+    }
+
+    pub fn set_swap_executor(&mut self, swap_executor: Arc<SwapExecutor>) {
+        self.swap_executor = Some(swap_executor);
+    }
+
+    pub fn set_risk_governor(&mut self, risk_governor: Arc<RiskGovernor>) {
+        self.risk_governor = Some(risk_governor);
+    }
+
+    pub fn set_blacklist(&mut self, blacklist: TokenBlacklist) {
+        self.blacklist = blacklist;
+    }
+
+    pub fn set_killswitch(&mut self, killswitch: Arc<SentimentKillswitch>) {
+        self.killswitch = Some(killswitch);
+    }
+
+    pub fn set_message_queue(&mut self, message_queue: MessageQueue) {
+        self.message_queue = Some(message_queue);
+    }
+
+    pub fn set_price_feed(&mut self, price_feed: Arc<PriceFeed>) {
+        self.price_feed = Some(price_feed);
+    }
+
+    pub fn set_portfolio(&mut self, portfolio: Arc<Portfolio>) {
+        self.portfolio = Some(portfolio);
+    }
+
+    pub fn set_exit_manager(&mut self, exit_manager: Arc<ExitManager>) {
+        self.exit_manager = Some(exit_manager);
+    }
+
+    async fn publish_execution(&self, event: TradeExecutionEvent) {
+        if let Some(message_queue) = &self.message_queue {
+            message_queue.publish(Message::TradeExecution(event)).await;
+        }
+    }
+
+    // Checks every buy has to pass right before it executes, queued ones included:
+    // the killswitch, blacklist, risk governor, position limits and pool liquidity
+    async fn check_entry(&self, token_address: &str, amount: f64) -> Result<()> {
         if !self.can_execute_trade(token_address, amount).await? {
             return Err(anyhow::anyhow!("Trade validation failed"));
         }
+        Ok(())
+    }
+
+    fn in_flight(pending_trades: &[TradeExecution], active_trades: &[TradeExecution], token_address: &str) -> bool {
+        pending_trades.iter().chain(active_trades.iter()).any(|t| t.token_address == token_address)
+    }
 
-        // Create trade execution
+    pub async fn execute_trade(&self, token_address: &str, amount: f64) -> Result<TradeExecution> {
+        validate_amount(amount)?;
+        self.check_entry(token_address, amount).await?;
+
+        // Create trade execution; it is in flight from the start, so the run loop leaves it alone
         let trade = TradeExecution {
             token_address: token_address.to_string(),
             amount,
             price: 0.0, // Will be set during execution
             timestamp: Utc::now(),
-            status: TradeStatus::Pending,
+            status: TradeStatus::Executing,
             transaction_hash: None,
             error: None,
             total_costs: 0.0,
             min_sell_price: 0.0,
+            liquidity_class: LiquidityClass::default(),
+            trigger: TriggerCondition::Immediate,
         };
 
-        // Add to pending trades
-        self.pending_trades.push(trade.clone());
+        // Add to pending trades, unless another call already has this token in flight;
+        // checked under the lock so two concurrent buys can't both get through
+        {
+            let mut pending_trades = self.pending_trades.write().await;
+            let active_trades = self.active_trades.read().await;
+            if Self::in_flight(&pending_trades, &active_trades, token_address) {
+                return Err(anyhow::anyhow!("Trade already in flight for token {}", token_address));
+            }
+            pending_trades.push(trade.clone());
+        }
 
         // Execute trade
-        match self._execute_trade(&trade).await {
+        match self._execute_trade(&trade, true).await {
             Ok(executed_trade) => {
                 // Move from pending to active
-                if let Some(pos) = self.pending_trades.iter()
+                let mut pending_trades = self.pending_trades.write().await;
+                let mut active_trades = self.active_trades.write().await;
+                if let Some(pos) = pending_trades.iter()
                     .position(|t| t.token_address == token_address) {
-                    self.pending_trades.remove(pos);
+                    pending_trades.remove(pos);
                 }
-                self.active_trades.push(executed_trade.clone());
+                active_trades.push(executed_trade.clone());
+                drop((pending_trades, active_trades));
+                self.on_filled(&executed_trade).await;
                 Ok(executed_trade)
             }
             Err(e) => {
-                // Update trade status
-                if let Some(trade) = self.pending_trades.iter_mut()
-                    .find(|t| t.token_address == token_address) {
-                    trade.status = TradeStatus::Failed;
-                    trade.error = Some(e.to_string());
+                // A failed buy isn't retried; clearing it also frees the token for a new one
+                self.pending_trades.write().await.retain(|t| !Self::same_order(t, &trade));
+                let mut failed_trade = trade;
+                failed_trade.status = TradeStatus::Failed;
+                failed_trade.error = Some(e.to_string());
+                self.confirm(TradeOutcome::Failed, &failed_trade);
+                Err(e)
+            }
+        }
+    }
+
+    // Queues a buy for the run loop instead of executing it immediately
+    pub async fn queue_buy(&self, token_address: &str, amount: f64) -> Result<()> {
+        self.queue_limit_buy(token_address, amount, TriggerCondition::Immediate).await
+    }
+
+    // Queues a buy that only fires once the live price meets `trigger`. Unfilled orders
+    // expire after the limit order TTL.
+    pub async fn queue_limit_buy(&self, token_address: &str, amount: f64, trigger: TriggerCondition) -> Result<()> {
+        validate_amount(amount)?;
+
+        let mut pending_trades = self.pending_trades.write().await;
+        if Self::in_flight(&pending_trades, &self.active_trades.read().await, token_address) {
+            return Err(anyhow::anyhow!("Trade already in flight for token {}", token_address));
+        }
+        pending_trades.push(TradeExecution {
+            token_address: token_address.to_string(),
+            amount,
+            price: 0.0,
+            timestamp: Utc::now(),
+            status: TradeStatus::Pending,
+            transaction_hash: None,
+            error: None,
+            total_costs: 0.0,
+            min_sell_price: 0.0,
+            liquidity_class: LiquidityClass::default(),
+            trigger,
+        });
+        Ok(())
+    }
+
+    // Drops pending buys whose moment has passed: older than the expiry, or whose pool no
+    // longer meets the entry liquidity. A late fill on a stale opportunity is worse than none.
+    pub async fn sweep_stale_pending(&self) -> Result<Vec<TradeExecution>> {
+        let now = Utc::now();
+        let mut cancelled = Vec::new();
+
+        // Judge a snapshot so the liquidity lookups don't hold the lock; buys already
+        // executing are left to finish
+        let snapshot: Vec<TradeExecution> = self.pending_trades.read().await.iter()
+            .filter(|trade| matches!(trade.status, TradeStatus::Pending))
+            .cloned()
+            .collect();
+        for mut trade in snapshot {
+            // Orders waiting on a trigger are meant to sit, so only their TTL applies
+            let waiting = trade.trigger != TriggerCondition::Immediate;
+            let max_age = if waiting { Some(self.limit_order_ttl) } else { self.pending_expiry };
+            let reason = if max_age.map_or(false, |max_age| now - trade.timestamp > max_age) {
+                Some(format!("pending for {}ms", (now - trade.timestamp).num_milliseconds()))
+            } else if !waiting
+                && self.recheck_pending_entry
+                && self.get_token_liquidity(&trade.token_address).await? < self.min_liquidity {
+                Some("pool no longer meets entry liquidity".to_string())
+            } else {
+                None
+            };
+
+            if let Some(reason) = reason {
+                info!("Buy Engine {} cancelled pending buy for {}: {}", self.id, trade.token_address, reason);
+                trade.status = TradeStatus::Cancelled;
+                trade.error = Some(reason);
+                self.confirm(TradeOutcome::Cancelled, &trade);
+                cancelled.push(trade);
+            }
+        }
+
+        self.pending_trades.write().await.retain(|trade| {
+            !matches!(trade.status, TradeStatus::Pending) || !cancelled.iter().any(|c| Self::same_order(c, trade))
+        });
+        Ok(cancelled)
+    }
+
+    // Bookkeeping shared by every path that fills a buy
+    async fn on_filled(&self, trade: &TradeExecution) {
+        if let Some(risk_governor) = &self.risk_governor {
+            risk_governor.record_trade().await;
+        }
+        self.confirm(TradeOutcome::Filled, trade);
+
+        if let Some(exit_manager) = &self.exit_manager {
+            // The buy spent `amount` SOL; the exit manager tracks the tokens it bought
+            if trade.price <= 0.0 {
+                warn!("Buy Engine {} filled {} without a price; its exits are not tracked", self.id, trade.token_address);
+            } else if let Err(e) = exit_manager.track_fill(&trade.token_address, trade.price, trade.amount / trade.price).await {
+                error!("Buy Engine {} could not track exits for {}: {}", self.id, trade.token_address, e);
+            }
+        }
+    }
+
+    // Terminal outcomes go to the confirmation webhook, keyed by token since the engine
+    // holds at most one trade per token
+    fn confirm(&self, outcome: TradeOutcome, trade: &TradeExecution) {
+        if !self.webhook.is_enabled() {
+            return;
+        }
+        match TradeConfirmation::new(outcome, &trade.token_address, &trade.token_address, trade) {
+            Ok(confirmation) => self.webhook.notify(confirmation),
+            Err(e) => warn!("Buy Engine {} could not build confirmation for {}: {}", self.id, trade.token_address, e),
+        }
+    }
+
+    // Entry point for fresh launches: optionally watches the pool, then makes sure the
+    // full position could be sold back before buying. Returns None when either skipped it.
+    pub async fn enter_launch(
+        &self,
+        opportunity: &TokenOpportunity,
+        amount: f64,
+        monitor: &dyn PoolMonitor,
+        exit_quoter: &dyn ExitQuoter,
+    ) -> Result<Option<TradeExecution>> {
+        if let ObservationOutcome::Skip(reason) = self.launch_observer.observe(opportunity, monitor).await? {
+            info!("Buy Engine {} skipped launch {}: {}", self.id, opportunity.token_address, reason);
+            return Ok(None);
+        }
+
+        match self.check_exit_liquidity(opportunity, amount, exit_quoter).await? {
+            ObservationOutcome::Enter => self.execute_trade(&opportunity.token_address, amount).await.map(Some),
+            ObservationOutcome::Skip(reason) => {
+                info!("Buy Engine {} skipped launch {}: {}", self.id, opportunity.token_address, reason);
+                Ok(None)
+            }
+        }
+    }
+
+    pub async fn check_exit_liquidity(
+        &self,
+        opportunity: &TokenOpportunity,
+        amount: f64,
+        exit_quoter: &dyn ExitQuoter,
+    ) -> Result<ObservationOutcome> {
+        self.exit_check.check(opportunity, amount, exit_quoter).await
+    }
+
+    // Buys exactly `amount` more of a token, on top of any position already held. Planned
+    // accumulation such as a DCA slice is sized by its plan, so the sizer is skipped; the
+    // position as a whole still has to fit the max position size and the pool.
+    pub async fn add_to_position(&self, token_address: &str, amount: f64) -> Result<TradeExecution> {
+        validate_amount(amount)?;
+        let held: f64 = self.active_trades.read().await.iter()
+            .filter(|t| t.token_address == token_address)
+            .map(|t| t.amount)
+            .sum();
+        if !self.entry_allowed(token_address).await? || !self.fits_position(token_address, held + amount).await? {
+            return Err(anyhow::anyhow!("Trade validation failed"));
+        }
+
+        let trade = TradeExecution {
+            token_address: token_address.to_string(),
+            amount,
+            price: 0.0,
+            timestamp: Utc::now(),
+            status: TradeStatus::Executing,
+            transaction_hash: None,
+            error: None,
+            total_costs: 0.0,
+            min_sell_price: 0.0,
+            liquidity_class: LiquidityClass::default(),
+            trigger: TriggerCondition::Immediate,
+        };
+        {
+            let mut pending_trades = self.pending_trades.write().await;
+            if pending_trades.iter().any(|t| t.token_address == token_address) {
+                return Err(anyhow::anyhow!("Trade already in flight for token {}", token_address));
+            }
+            pending_trades.push(trade.clone());
+        }
+
+        let result = self._execute_trade(&trade, false).await;
+        self.pending_trades.write().await
+            .retain(|t| !(t.token_address == token_address && t.timestamp == trade.timestamp));
+        match result {
+            Ok(fill) => {
+                let mut active_trades = self.active_trades.write().await;
+                match active_trades.iter_mut().find(|t| t.token_address == token_address) {
+                    Some(position) => Self::merge_fill(position, &fill),
+                    None => active_trades.push(fill.clone()),
                 }
+                drop(active_trades);
+                self.on_filled(&fill).await;
+                Ok(fill)
+            }
+            Err(e) => {
+                let mut failed_trade = trade;
+                failed_trade.status = TradeStatus::Failed;
+                failed_trade.error = Some(e.to_string());
+                self.confirm(TradeOutcome::Failed, &failed_trade);
                 Err(e)
             }
         }
     }
 
-    async fn can_execute_trade(&self, token_address: &str, amount: f64) -> Result<bool> {
+    // Folds another fill into a held position, averaging the entry over the tokens bought
+    fn merge_fill(position: &mut TradeExecution, fill: &TradeExecution) {
+        let tokens = |t: &TradeExecution| if t.price > 0.0 { t.amount / t.price } else { 0.0 };
+        let total_tokens = tokens(position) + tokens(fill);
+        position.amount += fill.amount;
+        if total_tokens > 0.0 {
+            position.price = position.amount / total_tokens;
+        }
+        position.total_costs += fill.total_costs;
+        position.min_sell_price = position.min_sell_price.max(fill.min_sell_price);
+        position.transaction_hash = fill.transaction_hash.clone();
+    }
+
+    // Whether the engine may open or add to a position in this token at all
+    async fn entry_allowed(&self, token_address: &str) -> Result<bool> {
+        if let Some(killswitch) = &self.killswitch {
+            if killswitch.should_activate().await {
+                return Err(anyhow::anyhow!("Sentiment killswitch active, not buying {}", token_address));
+            }
+        }
+
         // Check if engine is active
-        if !self.is_active {
+        if !self.is_active() {
             return Ok(false);
         }
 
-        // Get current market conditions
-        let liquidity = self.get_token_liquidity(token_address).await?;
-        let volatility = self.calculate_volatility(token_address).await?;
-        
-        // Dynamic position sizing based on market conditions
-        let position_multiplier = 1.0 - (volatility * 0.5); // Reduce position size as volatility increases
-        let adjusted_amount = amount * position_multiplier;
-        
+        if !self.blacklist.is_allowed(token_address) {
+            warn!("Buy Engine {} rejected trade for {}: blacklisted or not whitelisted", self.id, token_address);
+            return Ok(false);
+        }
+
+        if let Some(risk_governor) = &self.risk_governor {
+            if let Err(frozen) = risk_governor.check() {
+                warn!("Buy Engine {} cannot execute trade for {}: {}", self.id, token_address, frozen);
+                return Err(frozen.into());
+            }
+        }
+        Ok(true)
+    }
+
+    // Whether a position of `amount` in this token stays within the size and liquidity limits
+    async fn fits_position(&self, token_address: &str, amount: f64) -> Result<bool> {
         // Check amount against max position size
-        if adjusted_amount > self.max_position_size {
+        if amount > self.max_position_size {
             warn!("Adjusted trade amount {} exceeds max position size {}", 
-                  adjusted_amount, self.max_position_size);
+                  amount, self.max_position_size);
             return Ok(false);
         }
 
         // Enhanced liquidity check
-        let liquidity_ratio = liquidity / adjusted_amount;
+        let liquidity = self.get_token_liquidity(token_address).await?;
+        let liquidity_ratio = liquidity / amount;
         if liquidity_ratio < 3.0 { // Require at least 3x liquidity for safety
             warn!("Insufficient liquidity ratio {} for token {}", 
                   liquidity_ratio, token_address);
             return Ok(false);
         }
+        Ok(true)
+    }
+
+    async fn can_execute_trade(&self, token_address: &str, amount: f64) -> Result<bool> {
+        if !self.entry_allowed(token_address).await? {
+            return Ok(false);
+        }
+
+        let adjusted_amount = self.position_size(token_address, amount).await?;
+        if adjusted_amount <= 0.0 {
+            warn!("Buy Engine {} sized trade for {} to nothing under {:?} sizing",
+                  self.id, token_address, self.sizer.strategy());
+            return Ok(false);
+        }
+        if !self.fits_position(token_address, adjusted_amount).await? {
+            return Ok(false);
+        }
 
         // Check if we already have an active trade for this token
-        if self.active_trades.iter().any(|t| t.token_address == token_address) {
+        if self.active_trades.read().await.iter().any(|t| t.token_address == token_address) {
             warn!("Active trade already exists for token {}", token_address);
             return Ok(false);
         }
@@ -149,13 +558,23 @@ impl BuyEngine {
         Ok(true)
     }
 
+    // Size of the buy actually placed for a requested `amount`, per the sizing strategy
+    async fn position_size(&self, token_address: &str, amount: f64) -> Result<f64> {
+        let volatility = self.calculate_volatility(token_address).await?;
+        let stats = self.portfolio.as_ref().map(|portfolio| portfolio.trade_stats());
+        let committed: f64 = self.active_trades.read().await.iter().map(|trade| trade.amount).sum();
+        let available_capital = (self.kelly_capital - committed).max(0.0);
+        Ok(self.sizer.size(amount, volatility, stats, available_capital))
+    }
+
     async fn get_token_liquidity(&self, token_address: &str) -> Result<f64> {
-        // TODO: Implement liquidity fetching
-        // This would involve:
-        // 1. Fetching liquidity from DEX
-        // 2. Calculating total liquidity
-        // 3. Handling any errors
-        Ok(0.0) // Replace with actual implementation
+        if let Some(source) = &self.liquidity_source {
+            return source.liquidity(token_address).await;
+        }
+
+        // Without an on-chain source (see PoolLiquidity) no pool qualifies
+        warn!("No liquidity source configured; treating {} as illiquid", token_address);
+        Ok(0.0)
     }
 
     async fn calculate_volatility(&self, token_address: &str) -> Result<f64> {
@@ -167,50 +586,88 @@ impl BuyEngine {
         Ok(0.1) // Example value
     }
 
-    async fn _execute_trade(&self, trade: &TradeExecution) -> Result<TradeExecution> {
+    // `resize` runs the amount through the sizer; without it the buy is exactly `trade.amount`
+    async fn _execute_trade(&self, trade: &TradeExecution, resize: bool) -> Result<TradeExecution> {
         let mut executed_trade = trade.clone();
         executed_trade.status = TradeStatus::Executing;
 
-        // Get current price and market conditions
-        let current_price = self.get_current_price(&trade.token_address).await?;
-        let volatility = self.calculate_volatility(&trade.token_address).await?;
-        
-        // Adjust trade amount based on volatility
-        let position_multiplier = 1.0 - (volatility * 0.5);
-        let adjusted_amount = trade.amount * position_multiplier;
+        let adjusted_amount = if resize {
+            self.position_size(&trade.token_address, trade.amount).await?
+        } else {
+            trade.amount
+        };
         
         // Calculate initial costs
         let estimated_gas = self.estimate_gas_cost().await?;
         let initial_costs = estimated_gas * self.gas_multiplier;
         
-        executed_trade.price = current_price;
         executed_trade.amount = adjusted_amount;
         executed_trade.total_costs = initial_costs;
-        
-        // Calculate minimum sell price to ensure profit
-        let min_sell_price = current_price * (1.0 + (initial_costs / (adjusted_amount * current_price)));
-        executed_trade.min_sell_price = min_sell_price;
 
-        // Calculate price impact with enhanced safety checks
-        let price_impact = self.calculate_price_impact(&trade.token_address, adjusted_amount).await?;
-        if price_impact > self.max_slippage {
-            return Err(anyhow::anyhow!("Price impact {} exceeds max slippage {}", 
-                                     price_impact, self.max_slippage));
-        }
+        // Tolerate as much slippage as recent fills in this pool class actually needed
+        let liquidity = self.get_token_liquidity(&trade.token_address).await?;
+        executed_trade.liquidity_class = LiquidityClass::from_liquidity(liquidity);
+        let max_slippage = self.effective_slippage(executed_trade.liquidity_class);
 
-        // Build transaction with optimized gas settings
-        let transaction = self.build_buy_transaction(&executed_trade).await?;
+        // Build transaction with optimized gas settings, re-quoting if the quote goes stale meanwhile
+        let quoter = EngineQuoter { engine: self };
+        let quoted = self.quote_freshness.build_with_fresh_quote(
+            &trade.token_address,
+            adjusted_amount,
+            max_slippage,
+            &quoter,
+            |quote| {
+                let mut candidate = executed_trade.clone();
+                candidate.price = quote.price;
+                // Calculate minimum sell price to ensure profit
+                candidate.min_sell_price = quote.price * (1.0 + (initial_costs / (adjusted_amount * quote.price)));
+                async move {
+                    let transaction = self.build_buy_transaction(&candidate).await?;
+                    Ok((candidate, transaction))
+                }
+            },
+        ).await?;
+        let (quoted_trade, transaction) = quoted.built;
+        executed_trade = quoted_trade;
+        let current_price = executed_trade.price;
+        let min_sell_price = executed_trade.min_sell_price;
 
         // Execute transaction with enhanced monitoring
+        self.publish_execution(TradeExecutionEvent::new(
+            &trade.token_address, TradeAction::Buy, adjusted_amount, TradeEventStatus::Submitted,
+        )).await;
         match self.send_transaction(transaction).await {
-            Ok(hash) => {
-                executed_trade.status = TradeStatus::Completed;
-                executed_trade.transaction_hash = Some(hash);
+            Ok(Some(hash)) => {
                 info!("Buy Engine {} executed trade for token {}: {} (Amount: {}, Price: {}, Min Sell: {})", 
                       self.id, trade.token_address, hash, adjusted_amount, current_price, min_sell_price);
+                self.publish_execution(TradeExecutionEvent {
+                    signature: Some(hash.clone()),
+                    ..TradeExecutionEvent::new(&trade.token_address, TradeAction::Buy, adjusted_amount, TradeEventStatus::Confirmed)
+                }).await;
+                executed_trade.status = TradeStatus::Completed;
+                executed_trade.transaction_hash = Some(hash);
+                // The price right after the fill is where this buy actually landed
+                match self.get_current_price(&trade.token_address).await {
+                    Ok(realized_price) if realized_price > 0.0 => self.record_fill(&executed_trade, realized_price),
+                    Ok(_) => {}
+                    Err(e) => warn!("Buy Engine {} could not read the fill price for {}: {}", self.id, trade.token_address, e),
+                }
+                Ok(executed_trade)
+            }
+            Ok(None) => {
+                info!("Buy Engine {} simulated trade for token {} (Amount: {}, Price: {}, Min Sell: {})",
+                      self.id, trade.token_address, adjusted_amount, current_price, min_sell_price);
+                self.publish_execution(TradeExecutionEvent::new(
+                    &trade.token_address, TradeAction::Buy, adjusted_amount, TradeEventStatus::Simulated,
+                )).await;
+                executed_trade.status = TradeStatus::Completed;
                 Ok(executed_trade)
             }
             Err(e) => {
+                self.publish_execution(TradeExecutionEvent {
+                    error: Some(e.to_string()),
+                    ..TradeExecutionEvent::new(&trade.token_address, TradeAction::Buy, adjusted_amount, TradeEventStatus::Failed)
+                }).await;
                 executed_trade.status = TradeStatus::Failed;
                 executed_trade.error = Some(e.to_string());
                 error!("Buy Engine {} failed to execute trade for token {}: {}", 
@@ -220,7 +677,20 @@ impl BuyEngine {
         }
     }
 
+    // Feeds the realized fill price back so future trades in the same class adjust their slippage
+    pub fn record_fill(&self, trade: &TradeExecution, realized_price: f64) {
+        self.slippage.lock().unwrap().record_fill(trade.liquidity_class, trade.price, realized_price);
+    }
+
+    pub fn effective_slippage(&self, class: LiquidityClass) -> f64 {
+        self.slippage.lock().unwrap().effective_slippage(class)
+    }
+
     async fn get_current_price(&self, token_address: &str) -> Result<f64> {
+        if let Some(price_feed) = &self.price_feed {
+            return Ok(price_feed.price(token_address).await?.0);
+        }
+
         // TODO: Implement price fetching
         // This would involve:
         // 1. Fetching price from DEX
@@ -229,36 +699,43 @@ impl BuyEngine {
         Ok(0.0) // Replace with actual implementation
     }
 
+    // Constant-product impact of paying `amount` into the pool: amount / (reserve + amount).
+    // Without a liquidity source the reserve is unknown and the buy is treated as moving the
+    // whole pool, so the slippage guard rejects it.
     async fn calculate_price_impact(&self, token_address: &str, amount: f64) -> Result<f64> {
-        // TODO: Implement price impact calculation
-        // This would involve:
-        // 1. Getting current liquidity
-        // 2. Calculating impact based on amount
-        // 3. Adjusting for market conditions
-        Ok(0.0) // Replace with actual implementation
+        if amount <= 0.0 {
+            return Ok(0.0);
+        }
+        let reserve_in = match &self.liquidity_source {
+            Some(source) => source.reserve_in(token_address).await?.max(0.0),
+            None => 0.0,
+        };
+        Ok(amount / (reserve_in + amount))
     }
 
     async fn build_buy_transaction(&self, trade: &TradeExecution) -> Result<Transaction> {
-        // TODO: Implement transaction building
-        // This would involve:
-        // 1. Creating the buy instruction
-        // 2. Setting up the transaction
-        // 3. Adding necessary signatures
-        // 4. Setting appropriate fees
-        Ok(Transaction::default())
+        match &self.swap_executor {
+            Some(swap_executor) => swap_executor.build_buy(&trade.token_address, trade.amount).await,
+            None => Ok(Transaction::default()),
+        }
     }
 
-    async fn send_transaction(&self, transaction: Transaction) -> Result<String> {
-        // TODO: Implement transaction sending
-        // This would involve:
-        // 1. Sending the transaction
-        // 2. Waiting for confirmation
-        // 3. Handling any errors
-        Ok("transaction_hash".to_string()) // Replace with actual implementation
+    // None when there is no swap executor and the buy was only simulated
+    async fn send_transaction(&self, transaction: Transaction) -> Result<Option<String>> {
+        match &self.swap_executor {
+            Some(swap_executor) => swap_executor.submit(transaction).await.map(Some),
+            None => Ok(None),
+        }
     }
 
     pub async fn run(&self) -> Result<()> {
-        while self.is_active {
+        while self.is_active() {
+            // Cancel stale buys before any of them gets another attempt; a failed sweep,
+            // e.g. an unreachable liquidity source, is retried on the next pass
+            if let Err(e) = self.sweep_stale_pending().await {
+                error!("Buy Engine {} failed to sweep stale pending buys: {}", self.id, e);
+            }
+
             // Process pending trades
             self.process_pending_trades().await?;
 
@@ -271,18 +748,95 @@ impl BuyEngine {
         Ok(())
     }
 
-    async fn process_pending_trades(&self) -> Result<()> {
-        for trade in &self.pending_trades {
-            if let Err(e) = self._execute_trade(trade).await {
-                error!("Buy Engine {} error processing trade for token {}: {}", 
-                       self.id, trade.token_address, e);
+    fn same_order(a: &TradeExecution, b: &TradeExecution) -> bool {
+        a.token_address == b.token_address && a.timestamp == b.timestamp
+    }
+
+    // Marks a queued buy as executing, unless another pass got to it first
+    async fn claim_pending(&self, trade: &TradeExecution) -> bool {
+        let mut pending_trades = self.pending_trades.write().await;
+        match pending_trades.iter_mut().find(|t| Self::same_order(t, trade) && matches!(t.status, TradeStatus::Pending)) {
+            Some(t) => {
+                t.status = TradeStatus::Executing;
+                true
             }
+            None => false,
         }
-        Ok(())
+    }
+
+    // Drops a claimed buy that won't fill and reports why
+    async fn reject_pending(&self, trade: &TradeExecution, error: &anyhow::Error) {
+        self.pending_trades.write().await.retain(|t| !Self::same_order(t, trade));
+        let mut failed_trade = trade.clone();
+        failed_trade.status = TradeStatus::Failed;
+        failed_trade.error = Some(error.to_string());
+        self.confirm(TradeOutcome::Failed, &failed_trade);
+    }
+
+    // Executes the queued buys whose trigger is met and moves them to active; the rest
+    // wait for a later pass. Each goes through the same checks as `execute_trade` and is
+    // claimed before executing, so it runs at most once. Returns the buys that filled.
+    pub async fn process_pending_trades(&self) -> Result<Vec<TradeExecution>> {
+        let pending_trades: Vec<TradeExecution> = self.pending_trades.read().await.iter()
+            .filter(|trade| matches!(trade.status, TradeStatus::Pending))
+            .cloned()
+            .collect();
+        let mut filled = Vec::new();
+        for trade in &pending_trades {
+            if trade.trigger != TriggerCondition::Immediate {
+                let price = match &self.price_feed {
+                    Some(price_feed) => match price_feed.price(&trade.token_address).await {
+                        Ok((price, _)) => price,
+                        Err(e) => {
+                            warn!("Buy Engine {} has no price for {}; {:?} buy stays pending: {}",
+                                  self.id, trade.token_address, trade.trigger, e);
+                            continue;
+                        }
+                    },
+                    None => {
+                        warn!("Buy Engine {} has no price feed; {:?} buy for {} stays pending",
+                              self.id, trade.trigger, trade.token_address);
+                        continue;
+                    }
+                };
+                if !trade.trigger.is_met(price) {
+                    continue;
+                }
+                info!("Buy Engine {} trigger {:?} met at {} for {}", self.id, trade.trigger, price, trade.token_address);
+            }
+
+            if !self.claim_pending(trade).await {
+                continue;
+            }
+            if let Err(e) = self.check_entry(&trade.token_address, trade.amount).await {
+                warn!("Buy Engine {} dropped queued buy for {}: {}", self.id, trade.token_address, e);
+                self.reject_pending(trade, &e).await;
+                continue;
+            }
+
+            match self._execute_trade(trade, true).await {
+                Ok(executed_trade) => {
+                    let mut pending_trades = self.pending_trades.write().await;
+                    let mut active_trades = self.active_trades.write().await;
+                    pending_trades.retain(|t| !Self::same_order(t, trade));
+                    active_trades.push(executed_trade.clone());
+                    drop((pending_trades, active_trades));
+                    self.on_filled(&executed_trade).await;
+                    filled.push(executed_trade);
+                }
+                Err(e) => {
+                    error!("Buy Engine {} error processing trade for token {}: {}", 
+                           self.id, trade.token_address, e);
+                    self.reject_pending(trade, &e).await;
+                }
+            }
+        }
+        Ok(filled)
     }
 
     async fn monitor_active_trades(&self) -> Result<()> {
-        for trade in &self.active_trades {
+        let active_trades = self.active_trades.read().await.clone();
+        for trade in &active_trades {
             // Get current price
             let current_price = self.get_current_price(&trade.token_address).await?;
             
@@ -303,14 +857,14 @@ impl BuyEngine {
     }
 
     pub async fn shutdown(&self) -> Result<()> {
-        self.is_active = false;
+        self.is_active.store(false, Ordering::SeqCst);
         
         // Finalize all trades
-        for trade in &self.pending_trades {
+        for trade in self.pending_trades.read().await.iter() {
             warn!("Buy Engine {} finalizing pending trade for token: {}", 
                   self.id, trade.token_address);
         }
-        for trade in &self.active_trades {
+        for trade in self.active_trades.read().await.iter() {
             warn!("Buy Engine {} finalizing active trade for token: {}", 
                   self.id, trade.token_address);
         }
@@ -324,25 +878,31 @@ impl BuyEngine {
         &self.id
     }
 
-    pub fn get_pending_trades(&self) -> &[TradeExecution] {
-        &self.pending_trades
+    pub async fn get_pending_trades(&self) -> Vec<TradeExecution> {
+        self.pending_trades.read().await.clone()
     }
 
-    pub fn get_active_trades(&self) -> &[TradeExecution] {
-        &self.active_trades
+    pub async fn get_active_trades(&self) -> Vec<TradeExecution> {
+        self.active_trades.read().await.clone()
     }
 
     pub fn is_active(&self) -> bool {
-        self.is_active
+        self.is_active.load(Ordering::SeqCst)
     }
 }
 
-#[derive(Debug, Default)]
-struct Transaction {
-    // TODO: Implement transaction structure
-    // This would involve:
-    // 1. Transaction data
-    // 2. Signatures
-    // 3. Fees
-    // 4. Other metadata
-} 
\ No newline at end of file
+// Quotes from the engine's own price and impact sources
+struct EngineQuoter<'a> {
+    engine: &'a BuyEngine,
+}
+
+#[async_trait]
+impl EntryQuoter for EngineQuoter<'_> {
+    async fn quote_buy(&self, token_address: &str, amount: f64) -> Result<EntryQuote> {
+        Ok(EntryQuote {
+            price: self.engine.get_current_price(token_address).await?,
+            price_impact: self.engine.calculate_price_impact(token_address, amount).await?,
+            quoted_at: std::time::Instant::now(),
+        })
+    }
+}
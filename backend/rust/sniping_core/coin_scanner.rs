@@ -1,14 +1,39 @@
 use anyhow::Result;
-use config::Config;
+use config::{Config, ConfigError};
 use log::{info, error, warn};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::task::JoinSet;
 use crate::sniping_core::SnipingState;
-use serde::{Serialize, Deserialize};
-use chrono::{DateTime, Utc};
+use crate::sniping_core::adaptive_batch::AdaptiveBatchSize;
+use crate::sniping_core::whale_tracker::WhaleTracker;
+use crate::rpc::RpcErrorKind;
+use crate::ant_colony::TokenBlacklist;
+use crate::common::{Metrics, associated_token_address};
+use crate::logging::ErrorReporter;
+use serde::{Serialize, Deserialize, de::DeserializeOwned};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use reqwest::Client;
 use tokio::time::sleep;
+use std::str::FromStr;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcSimulateTransactionConfig, RpcSimulateTransactionAccountsConfig};
+use solana_account_decoder::UiAccountEncoding;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+// Swap API error codes that mean there is no route for the pair, rather than a failure to answer
+const NO_ROUTE_ERROR_CODES: [&str; 2] = ["COULD_NOT_FIND_ANY_ROUTE", "TOKEN_NOT_TRADABLE"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoinMetrics {
@@ -24,6 +49,8 @@ pub struct CoinMetrics {
     pub contract_audit_status: ContractAuditStatus,
     pub risk_score: f64,
     pub priority_score: f64,
+    #[serde(default)]
+    pub transfer_tax: f64, // Fraction lost to token taxes on a simulated round trip
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -34,33 +61,239 @@ pub enum ContractAuditStatus {
     Rugged,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoneypotResult {
+    pub token_address: String,
+    pub can_sell: bool,
+    pub transfer_tax: f64,
+    pub reason: Option<String>,
+}
+
+impl HoneypotResult {
+    fn unsellable(token_address: &str, reason: String) -> Self {
+        Self {
+            token_address: token_address.to_string(),
+            can_sell: false,
+            transfer_tax: 1.0,
+            reason: Some(reason),
+        }
+    }
+}
+
+// Relative weight of each signal in a coin's priority score; must sum to 1.0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriorityWeights {
+    pub liquidity: f64,
+    pub volume: f64,
+    pub holders: f64,
+    pub social: f64,
+    pub risk: f64,
+    #[serde(default)]
+    pub whales: f64, // Recent buys by tracked wallets; scores nothing without a WhaleTracker
+}
+
+impl Default for PriorityWeights {
+    fn default() -> Self {
+        Self {
+            liquidity: 0.3,
+            volume: 0.2,
+            holders: 0.2,
+            social: 0.15,
+            risk: 0.15,
+            whales: 0.0,
+        }
+    }
+}
+
+impl PriorityWeights {
+    pub fn validate(&self) -> Result<()> {
+        let weights = [self.liquidity, self.volume, self.holders, self.social, self.risk, self.whales];
+        if weights.iter().any(|w| *w < 0.0) {
+            return Err(anyhow::anyhow!("Priority weights must not be negative: {:?}", self));
+        }
+
+        let total: f64 = weights.iter().sum();
+        if (total - 1.0).abs() > 1e-6 {
+            return Err(anyhow::anyhow!("Priority weights must sum to 1.0, got {}", total));
+        }
+        Ok(())
+    }
+}
+
+// Subset of a Birdeye new-listing item; fields Birdeye omits fall back to zero
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BirdeyeToken {
+    address: String,
+    #[serde(default)]
+    pair_address: Option<String>,
+    #[serde(default)]
+    liquidity: f64,
+    #[serde(default, alias = "v24hUSD", alias = "volume24hUSD")]
+    volume_24h: f64,
+    #[serde(default)]
+    price: f64,
+    #[serde(default, alias = "holder")]
+    holders: u32,
+    #[serde(default, alias = "mc", alias = "marketcap")]
+    market_cap: f64,
+    #[serde(deserialize_with = "utc_timestamp")]
+    liquidity_added_at: DateTime<Utc>,
+}
+
+// Birdeye sends liquidityAddedAt without an offset; the time is already UTC
+fn utc_timestamp<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<DateTime<Utc>, D::Error> {
+    let raw = String::deserialize(deserializer)?;
+    if let Ok(at) = DateTime::parse_from_rfc3339(&raw) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(&raw, "%Y-%m-%dT%H:%M:%S%.f")
+        .map(|at| Utc.from_utc_datetime(&at))
+        .map_err(serde::de::Error::custom)
+}
+
+impl From<BirdeyeToken> for CoinMetrics {
+    fn from(token: BirdeyeToken) -> Self {
+        Self {
+            pair_address: token.pair_address.unwrap_or_default(),
+            token_address: token.address,
+            liquidity: token.liquidity,
+            volume_24h: token.volume_24h,
+            price: token.price,
+            holders: token.holders,
+            market_cap: token.market_cap,
+            created_at: token.liquidity_added_at,
+            social_volume: 0.0,
+            contract_audit_status: ContractAuditStatus::Unverified,
+            risk_score: 0.5,
+            priority_score: 0.0,
+            transfer_tax: 0.0,
+        }
+    }
+}
+
+// Decodes a source's items one at a time so a single malformed coin doesn't discard
+// the rest of the batch. Clones share the skipped counter.
+#[derive(Clone)]
+struct ItemDecoder {
+    lenient: bool,
+    skipped: Arc<AtomicU64>,
+}
+
+impl ItemDecoder {
+    fn decode<T: DeserializeOwned>(&self, source: &str, items: Vec<serde_json::Value>) -> Result<Vec<T>> {
+        let mut decoded = Vec::with_capacity(items.len());
+        for item in items {
+            match serde_json::from_value::<T>(item) {
+                Ok(value) => decoded.push(value),
+                Err(e) if self.lenient => {
+                    self.skipped.fetch_add(1, Ordering::Relaxed);
+                    warn!("Skipping malformed item from {}: {}", source, e);
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!("Malformed item from {}: {}", source, e));
+                }
+            }
+        }
+        Ok(decoded)
+    }
+}
+
+struct HoneypotCheck {
+    enabled: bool,
+    rpc_client: RpcClient,
+    swap_api_url: String,
+    simulation_wallet: Pubkey,
+    simulation_amount: u64, // lamports
+    max_transfer_tax: f64,
+    auto_blacklist: bool, // Add tokens that fail the sell leg to the shared blacklist
+}
+
 pub struct CoinScanner {
     id: String,
     state: Arc<RwLock<SnipingState>>,
     is_active: bool,
     scan_interval: u64,
-    batch_size: usize,
+    batch_size: AdaptiveBatchSize,
     max_concurrent_scans: usize,
     min_liquidity: f64,
     min_holders: u32,
     min_market_cap: f64,
+    weights: PriorityWeights,
+    whale_buys_for_full_score: u32, // Tracked-wallet buys that earn the whole whales weight
+    whale_tracker: Option<Arc<WhaleTracker>>,
     monitored_coins: Vec<CoinMetrics>,
     prioritized_coins: Vec<CoinMetrics>,
     http_client: Client,
+    dex_screener_url: String,
     dex_screener_api_key: String,
+    pump_fun_url: String,
     pump_fun_api_key: String,
+    birdeye_url: String,
+    birdeye_api_key: String,
+    decoder: ItemDecoder,
+    honeypot_check: HoneypotCheck,
+    blacklist: TokenBlacklist, // Shared with the colony; empty until one is set
+    metrics: Metrics,
+    error_tracker: Option<Arc<dyn ErrorReporter>>,
 }
 
 impl CoinScanner {
     pub async fn new(config: &Config, state: Arc<RwLock<SnipingState>>) -> Result<Self> {
         let scan_interval = config.get_int("sniping_core.coin_scanner.scan_interval")? as u64;
         let batch_size = config.get_int("sniping_core.coin_scanner.batch_size")? as usize;
+        let batch_size = AdaptiveBatchSize::new(config, batch_size)?;
         let max_concurrent_scans = config.get_int("sniping_core.coin_scanner.max_concurrent_scans")? as usize;
         let min_liquidity = config.get_float("sniping_core.coin_scanner.min_liquidity")? as f64;
         let min_holders = config.get_int("sniping_core.coin_scanner.min_holders")? as u32;
         let min_market_cap = config.get_float("sniping_core.coin_scanner.min_market_cap")? as f64;
-        let dex_screener_api_key = config.get_string("sniping_core.coin_scanner.dex_screener_api_key")?;
-        let pump_fun_api_key = config.get_string("sniping_core.coin_scanner.pump_fun_api_key")?;
+        let weights = match config.get::<PriorityWeights>("sniping_core.coin_scanner.weights") {
+            Ok(weights) => weights,
+            Err(ConfigError::NotFound(_)) => PriorityWeights::default(),
+            Err(e) => return Err(e.into()),
+        };
+        weights.validate()?;
+        let whale_buys_for_full_score = config.get_int("sniping_core.coin_scanner.whale_buys_for_full_score")
+            .unwrap_or(3)
+            .max(1) as u32;
+        let dex_screener_url = config.get_string("sniping_core.coin_scanner.dex_screener_url")
+            .unwrap_or_else(|_| "https://api.dexscreener.com/latest/dex/tokens/new".to_string());
+        // Keys normally come from api_keys.toml; a scanner-specific key still takes precedence
+        let dex_screener_api_key = config.get_string("sniping_core.coin_scanner.dex_screener_api_key")
+            .or_else(|_| config.get_string("exchanges.dex_screener_api_key"))?;
+        let pump_fun_url = config.get_string("sniping_core.coin_scanner.pump_fun_url")
+            .unwrap_or_else(|_| "https://api.pump.fun/v1/new-coins".to_string());
+        let pump_fun_api_key = config.get_string("sniping_core.coin_scanner.pump_fun_api_key")
+            .or_else(|_| config.get_string("exchanges.pump_fun_api_key"))?;
+        let birdeye_url = config.get_string("sniping_core.coin_scanner.birdeye_url")
+            .unwrap_or_else(|_| "https://public-api.birdeye.so/defi/v2/tokens/new_listing".to_string());
+        let birdeye_api_key = config.get_string("sniping_core.coin_scanner.birdeye_api_key")
+            .or_else(|_| config.get_string("exchanges.birdeye_api_key"))?;
+        let lenient_parsing = config.get_bool("sniping_core.coin_scanner.lenient_parsing")
+            .unwrap_or(true);
+
+        let honeypot_enabled = config.get_bool("sniping_core.coin_scanner.honeypot.enabled")?;
+        let simulation_wallet = config.get_string("sniping_core.coin_scanner.honeypot.simulation_wallet").unwrap_or_default();
+        // The simulation pays for the buy leg, so it has to run from a wallet that holds SOL
+        let simulation_wallet = if honeypot_enabled {
+            let wallet = Pubkey::from_str(&simulation_wallet).map_err(|_| anyhow::anyhow!(
+                "sniping_core.coin_scanner.honeypot.simulation_wallet must be a funded wallet, got {:?}", simulation_wallet))?;
+            if wallet == system_program::id() {
+                return Err(anyhow::anyhow!("sniping_core.coin_scanner.honeypot.simulation_wallet must be a funded wallet, not the System Program"));
+            }
+            wallet
+        } else {
+            Pubkey::default()
+        };
+        let honeypot_check = HoneypotCheck {
+            enabled: honeypot_enabled,
+            rpc_client: RpcClient::new(config.get_string("sniping_core.coin_scanner.honeypot.rpc_url")?),
+            swap_api_url: config.get_string("sniping_core.coin_scanner.honeypot.swap_api_url")?,
+            simulation_wallet,
+            simulation_amount: config.get_int("sniping_core.coin_scanner.honeypot.simulation_amount_lamports")? as u64,
+            max_transfer_tax: config.get_float("sniping_core.coin_scanner.honeypot.max_transfer_tax")?,
+            auto_blacklist: config.get_bool("sniping_core.coin_scanner.honeypot.auto_blacklist").unwrap_or(true),
+        };
 
         Ok(Self {
             id: uuid::Uuid::new_v4().to_string(),
@@ -72,14 +305,46 @@ impl CoinScanner {
             min_liquidity,
             min_holders,
             min_market_cap,
+            weights,
+            whale_buys_for_full_score,
+            whale_tracker: None,
             monitored_coins: Vec::new(),
             prioritized_coins: Vec::new(),
             http_client: Client::new(),
+            dex_screener_url,
             dex_screener_api_key,
+            pump_fun_url,
             pump_fun_api_key,
+            birdeye_url,
+            birdeye_api_key,
+            decoder: ItemDecoder {
+                lenient: lenient_parsing,
+                skipped: Arc::new(AtomicU64::new(0)),
+            },
+            honeypot_check,
+            blacklist: TokenBlacklist::default(),
+            metrics: Metrics::default(),
+            error_tracker: None,
         })
     }
 
+    pub fn set_blacklist(&mut self, blacklist: TokenBlacklist) {
+        self.blacklist = blacklist;
+    }
+
+    pub fn set_metrics(&mut self, metrics: Metrics) {
+        self.metrics = metrics;
+    }
+
+    pub fn set_whale_tracker(&mut self, whale_tracker: Arc<WhaleTracker>) {
+        self.whale_tracker = Some(whale_tracker);
+    }
+
+    // Failed scan passes are reported to it
+    pub fn set_error_tracker(&mut self, error_tracker: Arc<dyn ErrorReporter>) {
+        self.error_tracker = Some(error_tracker);
+    }
+
     pub async fn start_scanning(&mut self) -> Result<()> {
         self.is_active = true;
         info!("Coin Scanner {} started scanning", self.id);
@@ -87,6 +352,9 @@ impl CoinScanner {
         while self.is_active {
             if let Err(e) = self.scan_coins().await {
                 error!("Coin Scanner {} scanning error: {}", self.id, e);
+                if let Some(error_tracker) = &self.error_tracker {
+                    error_tracker.capture_error_with_context(&e, &self.id, None);
+                }
             }
             sleep(tokio::time::Duration::from_secs(self.scan_interval)).await;
         }
@@ -94,35 +362,92 @@ impl CoinScanner {
         Ok(())
     }
 
-    async fn scan_coins(&mut self) -> Result<()> {
-        let state = self.state.read().await;
-        
+    // The same loop for a scanner shared with the sniping core, locked one pass at a time so
+    // its coins can be read between scans
+    pub async fn run(coin_scanner: Arc<RwLock<Self>>) -> Result<()> {
+        let scan_interval = {
+            let mut scanner = coin_scanner.write().await;
+            scanner.is_active = true;
+            info!("Coin Scanner {} started scanning", scanner.id);
+            scanner.scan_interval
+        };
+
+        loop {
+            {
+                let mut scanner = coin_scanner.write().await;
+                if !scanner.is_active {
+                    break;
+                }
+                if let Err(e) = scanner.scan_coins().await {
+                    error!("Coin Scanner {} scanning error: {}", scanner.id, e);
+                    if let Some(error_tracker) = &scanner.error_tracker {
+                        error_tracker.capture_error_with_context(&e, &scanner.id, None);
+                    }
+                }
+            }
+            sleep(tokio::time::Duration::from_secs(scan_interval)).await;
+        }
+
+        Ok(())
+    }
+
+    pub async fn scan_coins(&mut self) -> Result<()> {
         // Skip if sniping core is not active
-        if !state.is_active {
+        if !self.state.read().await.is_active {
             return Ok(());
         }
+        let started = Instant::now();
+
+        // Spawned tasks must be 'static, so each source gets owned copies of what it needs
+        let batch_size = self.batch_size.current();
+        let mut set = JoinSet::new();
+        set.spawn(Self::scan_pump_fun(
+            self.http_client.clone(),
+            self.pump_fun_url.clone(),
+            self.pump_fun_api_key.clone(),
+            batch_size,
+            self.decoder.clone(),
+        ));
+        set.spawn(Self::scan_dex_screener(
+            self.http_client.clone(),
+            self.dex_screener_url.clone(),
+            self.dex_screener_api_key.clone(),
+            batch_size,
+            self.decoder.clone(),
+        ));
+        set.spawn(Self::scan_birdeye(
+            self.http_client.clone(),
+            self.birdeye_url.clone(),
+            self.birdeye_api_key.clone(),
+            batch_size,
+            self.decoder.clone(),
+        ));
 
-        // Create a JoinSet for parallel processing
-        let mut set = tokio::task::JoinSet::new();
-        
-        // Scan pump.fun and DexScreener in parallel
-        set.spawn(self.scan_pump_fun());
-        set.spawn(self.scan_dex_screener());
-
-        // Process results as they complete
+        // Collect results as they complete; a failing source only loses its own coins
+        let mut scanned = Vec::new();
+        let mut rate_limited = false;
         while let Some(result) = set.join_next().await {
             match result {
-                Ok(coins) => {
-                    for coin in coins {
-                        if self.evaluate_coin(&coin) {
-                            self.monitored_coins.push(coin);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Error in coin scanning task: {}", e);
+                Ok(Ok(coins)) => scanned.extend(coins),
+                Ok(Err(e)) => {
+                    rate_limited |= RpcErrorKind::classify(&e) == RpcErrorKind::RateLimited;
+                    warn!("Coin Scanner {} source request failed: {}", self.id, e);
                 }
+                Err(e) => error!("Coin Scanner {} scanning task failed: {}", self.id, e),
+            }
+        }
+        self.batch_size.record_scan(rate_limited);
+
+        for mut coin in Self::dedupe_coins(scanned) {
+            if !self.evaluate_coin(&coin) {
+                continue;
+            }
+            if self.honeypot_check.enabled && !self.passes_honeypot_check(&mut coin).await {
+                continue;
             }
+            // Refresh rather than duplicate coins seen in an earlier scan
+            self.monitored_coins.retain(|c| c.token_address != coin.token_address);
+            self.monitored_coins.push(coin);
         }
 
         // Update prioritization
@@ -131,42 +456,72 @@ impl CoinScanner {
         // Clean up old coins
         self.cleanup_old_coins().await?;
 
+        self.metrics.observe_scan(started.elapsed());
         Ok(())
     }
 
-    async fn scan_pump_fun(&self) -> Result<Vec<CoinMetrics>> {
-        let url = "https://api.pump.fun/v1/new-coins";
-        let response = self.http_client
-            .get(url)
-            .header("Authorization", format!("Bearer {}", self.pump_fun_api_key))
+    async fn scan_pump_fun(client: Client, url: String, api_key: String, limit: usize, decoder: ItemDecoder) -> Result<Vec<CoinMetrics>> {
+        Self::fetch_coins(client, "pump.fun", url, api_key, limit, decoder).await
+    }
+
+    async fn scan_dex_screener(client: Client, url: String, api_key: String, limit: usize, decoder: ItemDecoder) -> Result<Vec<CoinMetrics>> {
+        Self::fetch_coins(client, "DexScreener", url, api_key, limit, decoder).await
+    }
+
+    async fn scan_birdeye(client: Client, url: String, api_key: String, limit: usize, decoder: ItemDecoder) -> Result<Vec<CoinMetrics>> {
+        let response = client
+            .get(&url)
+            .query(&[("chain", "solana".to_string()), ("limit", limit.to_string())])
+            .header("X-API-KEY", api_key)
             .send()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to fetch from pump.fun: {}", response.status()));
+            return Err(anyhow::anyhow!("Failed to fetch from Birdeye: {}", response.status()));
         }
 
-        let coins: Vec<CoinMetrics> = response.json().await?;
-        Ok(coins)
+        let body: serde_json::Value = response.json().await?;
+        let items: Vec<serde_json::Value> = serde_json::from_value(body["data"]["items"].clone())?;
+        let tokens: Vec<BirdeyeToken> = decoder.decode("Birdeye", items)?;
+        Ok(tokens.into_iter().map(CoinMetrics::from).collect())
     }
 
-    async fn scan_dex_screener(&self) -> Result<Vec<CoinMetrics>> {
-        let url = "https://api.dexscreener.com/latest/dex/tokens/new";
-        let response = self.http_client
-            .get(url)
-            .header("Authorization", format!("Bearer {}", self.dex_screener_api_key))
+    // The same token often shows up from several sources; keep the freshest record of each
+    fn dedupe_coins(coins: Vec<CoinMetrics>) -> Vec<CoinMetrics> {
+        let mut unique: HashMap<String, CoinMetrics> = HashMap::new();
+        for coin in coins {
+            match unique.get(&coin.token_address) {
+                Some(existing) if existing.created_at >= coin.created_at => {}
+                _ => {
+                    unique.insert(coin.token_address.clone(), coin);
+                }
+            }
+        }
+        unique.into_values().collect()
+    }
+
+    async fn fetch_coins(client: Client, source: &str, url: String, api_key: String, limit: usize, decoder: ItemDecoder) -> Result<Vec<CoinMetrics>> {
+        let response = client
+            .get(&url)
+            .query(&[("limit", limit)])
+            .header("Authorization", format!("Bearer {}", api_key))
             .send()
             .await?;
 
         if !response.status().is_success() {
-            return Err(anyhow::anyhow!("Failed to fetch from DexScreener: {}", response.status()));
+            return Err(anyhow::anyhow!("Failed to fetch from {}: {}", source, response.status()));
         }
 
-        let coins: Vec<CoinMetrics> = response.json().await?;
-        Ok(coins)
+        let items: Vec<serde_json::Value> = response.json().await?;
+        decoder.decode(source, items)
     }
 
     fn evaluate_coin(&self, coin: &CoinMetrics) -> bool {
+        // Blacklisted tokens, and anything off the whitelist in whitelist-only mode
+        if !self.blacklist.is_allowed(&coin.token_address) {
+            return false;
+        }
+
         // Basic filtering criteria
         if coin.liquidity < self.min_liquidity ||
            coin.holders < self.min_holders ||
@@ -190,6 +545,201 @@ impl CoinScanner {
         true
     }
 
+    async fn passes_honeypot_check(&self, coin: &mut CoinMetrics) -> bool {
+        match self.simulate_buy_sell(&coin.token_address).await {
+            Ok(result) => {
+                coin.transfer_tax = result.transfer_tax;
+                if !result.can_sell {
+                    let reason = result.reason.unwrap_or_default();
+                    warn!("Coin Scanner {} rejected honeypot {}: {}", self.id, coin.token_address, reason);
+                    // A token that can't be sold must never be bought, whichever path finds it
+                    if self.honeypot_check.auto_blacklist {
+                        if let Err(e) = self.blacklist.add(&coin.token_address, &format!("Honeypot: {}", reason)) {
+                            error!("Coin Scanner {} failed to blacklist {}: {}", self.id, coin.token_address, e);
+                        }
+                    }
+                    return false;
+                }
+                if result.transfer_tax > self.honeypot_check.max_transfer_tax {
+                    warn!("Coin Scanner {} rejected {}: transfer tax {:.2}% exceeds {:.2}%",
+                          self.id, coin.token_address, result.transfer_tax * 100.0,
+                          self.honeypot_check.max_transfer_tax * 100.0);
+                    return false;
+                }
+                true
+            }
+            Err(e) => {
+                // Fail closed for this pass only: a failed simulation says nothing about the token
+                warn!("Coin Scanner {} honeypot simulation failed for {}: {}", self.id, coin.token_address, e);
+                false
+            }
+        }
+    }
+
+    // Simulates buying and immediately selling the token in a single transaction, so the
+    // sell leg runs against the post-buy state. A token is only a honeypot when the swap API
+    // has no route to sell it or the sell leg's instructions fail; anything else is an error
+    // and the check is retried on a later pass. SOL lost beyond the quoted round trip, the network fee and the rent locked in the new
+    // token account is attributed to transfer taxes.
+    pub async fn simulate_buy_sell(&self, token_address: &str) -> Result<HoneypotResult> {
+        let check = &self.honeypot_check;
+        let wallet = check.simulation_wallet;
+
+        let buy_quote = self.fetch_quote(SOL_MINT, token_address, check.simulation_amount).await?;
+        let tokens_out = Self::quote_out_amount(&buy_quote)?;
+
+        let sell_quote = match self.fetch_route(token_address, SOL_MINT, tokens_out).await? {
+            Some(quote) => quote,
+            None => return Ok(HoneypotResult::unsellable(token_address, "No sell route".to_string())),
+        };
+        let expected_sol_out = Self::quote_out_amount(&sell_quote)?;
+
+        let mut instructions = self.fetch_swap_instructions(&buy_quote, &wallet).await?;
+        let buy_instruction_count = instructions.len();
+        instructions.extend(self.fetch_swap_instructions(&sell_quote, &wallet).await?);
+        let blockhash = check.rpc_client.get_latest_blockhash().await?;
+        let transaction = Transaction::new_unsigned(Message::new_with_blockhash(&instructions, Some(&wallet), &blockhash));
+        let fee = check.rpc_client.get_fee_for_message(&transaction.message).await?;
+
+        let pre_balance = check.rpc_client.get_balance(&wallet).await?;
+        if pre_balance < check.simulation_amount + fee {
+            return Err(anyhow::anyhow!("Simulation wallet {} holds {} lamports, needs {}",
+                                       wallet, pre_balance, check.simulation_amount + fee));
+        }
+        // The buy leg creates the wallet's token account if it has none, and the sell leg
+        // leaves it open, so its rent stays locked up rather than being lost to the token
+        let token_account = associated_token_address(&wallet, &Pubkey::from_str(token_address)?);
+        let pre_token_account_balance = check.rpc_client.get_balance(&token_account).await?;
+        let simulation = check.rpc_client.simulate_transaction_with_config(
+            &transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: vec![wallet.to_string(), token_account.to_string()],
+                }),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        ).await?.value;
+
+        match simulation.err {
+            Some(TransactionError::InstructionError(index, err)) if index as usize >= buy_instruction_count => {
+                return Ok(HoneypotResult::unsellable(token_address, format!("Sell leg failed: {}", err)));
+            }
+            Some(err) => return Err(anyhow::anyhow!("Round trip simulation failed before the sell leg: {}", err)),
+            None => {}
+        }
+
+        let mut accounts = simulation.accounts.unwrap_or_default().into_iter();
+        let post_balance = accounts.next().flatten()
+            .map(|account| account.lamports)
+            .ok_or_else(|| anyhow::anyhow!("Simulation returned no wallet state"))?;
+        let post_token_account_balance = accounts.next().flatten().map_or(0, |account| account.lamports);
+        let rent = post_token_account_balance.saturating_sub(pre_token_account_balance);
+
+        // What the sell leg actually returned, net of the SOL spent on the buy leg, the fee and rent
+        let sol_returned = (post_balance + check.simulation_amount + fee + rent).saturating_sub(pre_balance);
+        let transfer_tax = if expected_sol_out > 0 {
+            (1.0 - sol_returned as f64 / expected_sol_out as f64).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        Ok(HoneypotResult {
+            token_address: token_address.to_string(),
+            can_sell: true,
+            transfer_tax,
+            reason: None,
+        })
+    }
+
+    async fn fetch_quote(&self, input_mint: &str, output_mint: &str, amount: u64) -> Result<serde_json::Value> {
+        self.fetch_route(input_mint, output_mint, amount).await?
+            .ok_or_else(|| anyhow::anyhow!("No route from {} to {}", input_mint, output_mint))
+    }
+
+    // None when the swap API answers that the pair has no route
+    async fn fetch_route(&self, input_mint: &str, output_mint: &str, amount: u64) -> Result<Option<serde_json::Value>> {
+        let response = self.http_client
+            .get(format!("{}/quote", self.honeypot_check.swap_api_url))
+            .query(&[
+                ("inputMint", input_mint.to_string()),
+                ("outputMint", output_mint.to_string()),
+                ("amount", amount.to_string()),
+            ])
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(Some(response.json().await?));
+        }
+        if status == reqwest::StatusCode::BAD_REQUEST {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            if body["errorCode"].as_str().map_or(false, |code| NO_ROUTE_ERROR_CODES.contains(&code)) {
+                return Ok(None);
+            }
+        }
+
+        Err(anyhow::anyhow!("Quote request failed: {}", status))
+    }
+
+    async fn fetch_swap_instructions(&self, quote: &serde_json::Value, wallet: &Pubkey) -> Result<Vec<Instruction>> {
+        let response = self.http_client
+            .post(format!("{}/swap-instructions", self.honeypot_check.swap_api_url))
+            .json(&serde_json::json!({
+                "quoteResponse": quote,
+                "userPublicKey": wallet.to_string(),
+                "asLegacyTransaction": true,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Swap instructions request failed: {}", response.status()));
+        }
+
+        let body: serde_json::Value = response.json().await?;
+        let mut instructions = Vec::new();
+        for key in ["setupInstructions", "swapInstruction", "cleanupInstruction"] {
+            match &body[key] {
+                serde_json::Value::Array(items) => {
+                    for item in items {
+                        instructions.push(Self::parse_instruction(item)?);
+                    }
+                }
+                serde_json::Value::Null => {}
+                item => instructions.push(Self::parse_instruction(item)?),
+            }
+        }
+
+        Ok(instructions)
+    }
+
+    fn parse_instruction(value: &serde_json::Value) -> Result<Instruction> {
+        let program_id = Pubkey::from_str(value["programId"].as_str().unwrap_or_default())?;
+        let accounts = value["accounts"].as_array()
+            .ok_or_else(|| anyhow::anyhow!("Instruction is missing accounts"))?
+            .iter()
+            .map(|account| Ok(AccountMeta {
+                pubkey: Pubkey::from_str(account["pubkey"].as_str().unwrap_or_default())?,
+                is_signer: account["isSigner"].as_bool().unwrap_or(false),
+                is_writable: account["isWritable"].as_bool().unwrap_or(false),
+            }))
+            .collect::<Result<Vec<_>>>()?;
+        let data = BASE64.decode(value["data"].as_str().unwrap_or_default())?;
+
+        Ok(Instruction { program_id, accounts, data })
+    }
+
+    fn quote_out_amount(quote: &serde_json::Value) -> Result<u64> {
+        quote["outAmount"].as_str()
+            .ok_or_else(|| anyhow::anyhow!("Quote is missing outAmount"))?
+            .parse()
+            .map_err(Into::into)
+    }
+
     async fn update_prioritization(&mut self) -> Result<()> {
         // Calculate priority scores for each coin
         for coin in &mut self.monitored_coins {
@@ -210,13 +760,17 @@ impl CoinScanner {
 
     fn calculate_priority_score(&self, coin: &CoinMetrics) -> f64 {
         // Weighted scoring system
-        let liquidity_score = (coin.liquidity / self.min_liquidity).min(1.0) * 0.3;
-        let volume_score = (coin.volume_24h / (self.min_liquidity * 2.0)).min(1.0) * 0.2;
-        let holders_score = (coin.holders as f64 / self.min_holders as f64).min(1.0) * 0.2;
-        let social_score = (coin.social_volume / 1000.0).min(1.0) * 0.15;
-        let risk_score = (1.0 - coin.risk_score) * 0.15;
-
-        liquidity_score + volume_score + holders_score + social_score + risk_score
+        let weights = &self.weights;
+        let liquidity_score = (coin.liquidity / self.min_liquidity).min(1.0) * weights.liquidity;
+        let volume_score = (coin.volume_24h / (self.min_liquidity * 2.0)).min(1.0) * weights.volume;
+        let holders_score = (coin.holders as f64 / self.min_holders as f64).min(1.0) * weights.holders;
+        let social_score = (coin.social_volume / 1000.0).min(1.0) * weights.social;
+        let risk_score = (1.0 - coin.risk_score) * weights.risk;
+        let whale_buys = self.whale_tracker.as_ref().map_or(0, |tracker| tracker.recent_buys(&coin.token_address));
+        let whale_score = (whale_buys as f64 / self.whale_buys_for_full_score as f64).min(1.0) * weights.whales;
+
+        // Penalize taxed tokens by the share of value the tax takes on a round trip
+        (liquidity_score + volume_score + holders_score + social_score + risk_score + whale_score) * (1.0 - coin.transfer_tax)
     }
 
     async fn cleanup_old_coins(&mut self) -> Result<()> {
@@ -244,13 +798,22 @@ impl CoinScanner {
         self.monitored_coins.clone()
     }
 
-    pub async fn shutdown(&self) -> Result<()> {
+    pub async fn shutdown(&mut self) -> Result<()> {
         self.is_active = false;
         info!("Coin Scanner {} shutdown complete", self.id);
         Ok(())
     }
 
+    // Malformed items dropped across all scans since startup
+    pub fn skipped_item_count(&self) -> u64 {
+        self.decoder.skipped.load(Ordering::Relaxed)
+    }
+
     // Getters
+    pub fn batch_size(&self) -> usize {
+        self.batch_size.current()
+    }
+
     pub fn get_id(&self) -> &str {
         &self.id
     }
@@ -0,0 +1,404 @@
+use anyhow::Result;
+use config::{Config, ConfigError};
+use log::{info, error, warn};
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_sdk::bs58;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{UiInstruction, UiTransactionEncoding};
+use crate::ant_colony::{ProfitManager, RiskGovernor, TokenBlacklist};
+use crate::common::TradeAction;
+use crate::sniping_core::buy_engine::BuyEngine;
+
+const PUMP_FUN_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+// A swap read out of a DEX instruction
+#[derive(Debug, Clone)]
+pub struct DecodedSwap {
+    pub program_id: Pubkey,
+    pub trader: Pubkey,
+    pub token_mint: Pubkey,
+    pub action: TradeAction,
+    pub token_amount: u64,
+    pub sol_amount: f64,             // SOL the swap pays for a buy or receives for a sell; the instruction's
+                                     // bound until the transaction's balances replace it
+    pub trader_holding: Option<u64>, // Tokens the trader held before, when the transaction shows it
+}
+
+// Recognises one DEX program's swap instructions
+pub trait SwapDecoder: Send + Sync {
+    fn program_id(&self) -> Pubkey;
+    // Ok(None) for the program's instructions that aren't swaps
+    fn decode(&self, instruction: &Instruction) -> Result<Option<DecodedSwap>>;
+}
+
+// Pump.fun bonding curve buys and sells: an 8 byte discriminator, the token amount, then
+// the SOL bound (max cost for a buy, min output for a sell) in lamports. The mint is the
+// third account and the trader the seventh. The bound is only an upper or lower limit on
+// what the swap moved, so `fetch_swaps` replaces it with the trader's balance change.
+pub struct PumpFunSwapDecoder {
+    program_id: Pubkey,
+}
+
+impl PumpFunSwapDecoder {
+    const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+    const SELL: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+
+    pub fn new(config: &Config) -> Result<Self> {
+        let program_id = config.get_string("sniping_core.buy_engine.pool_liquidity.program_id")
+            .unwrap_or_else(|_| PUMP_FUN_PROGRAM.to_string());
+        Ok(Self { program_id: Pubkey::from_str(&program_id)? })
+    }
+
+    fn account(instruction: &Instruction, index: usize) -> Result<Pubkey> {
+        instruction.accounts.get(index)
+            .map(|meta: &AccountMeta| meta.pubkey)
+            .ok_or_else(|| anyhow::anyhow!("Pump.fun swap is missing account {}", index))
+    }
+}
+
+impl SwapDecoder for PumpFunSwapDecoder {
+    fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    fn decode(&self, instruction: &Instruction) -> Result<Option<DecodedSwap>> {
+        let data = &instruction.data;
+        let action = match data.get(..8) {
+            Some(discriminator) if discriminator == Self::BUY => TradeAction::Buy,
+            Some(discriminator) if discriminator == Self::SELL => TradeAction::Sell,
+            _ => return Ok(None),
+        };
+        let read_u64 = |offset: usize| -> Result<u64> {
+            let bytes = data.get(offset..offset + 8)
+                .ok_or_else(|| anyhow::anyhow!("Pump.fun swap data too short: {} bytes", data.len()))?;
+            Ok(u64::from_le_bytes(bytes.try_into()?))
+        };
+
+        Ok(Some(DecodedSwap {
+            program_id: self.program_id,
+            trader: Self::account(instruction, 6)?,
+            token_mint: Self::account(instruction, 2)?,
+            action,
+            token_amount: read_u64(8)?,
+            sol_amount: read_u64(16)? as f64 / LAMPORTS_PER_SOL,
+            trader_holding: None,
+        }))
+    }
+}
+
+// What the copy trader did in response to a swap. `amount` is SOL for a buy and the
+// fraction of the mirrored position for a sell.
+#[derive(Debug, Clone)]
+pub struct MirroredTrade {
+    pub token_address: String,
+    pub action: TradeAction,
+    pub amount: f64,
+}
+
+// Caps are per mint; an array of tables because config keys are lowercased and mints are not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenCap {
+    pub mint: String,
+    pub max_sol: f64,
+}
+
+// Mirrors a target wallet's swaps on supported DEX programs at `scale_factor` of their
+// size. Buys are queued on the buy engine, within the blacklist, the risk governor and
+// a per-token cap on SOL committed; sells go through the profit manager as the same
+// fraction of our mirrored position the target sold of its own.
+pub struct CopyTrader {
+    target_wallet: Pubkey,
+    scale_factor: f64,
+    default_token_cap: f64, // SOL
+    token_caps: HashMap<String, f64>,
+    poll_interval: Duration,
+    rpc_client: RpcClient,
+    decoders: HashMap<Pubkey, Arc<dyn SwapDecoder>>,
+    buy_engine: Arc<BuyEngine>,
+    profit_manager: Option<Arc<RwLock<ProfitManager>>>, // Without one sells are not mirrored
+    risk_governor: Option<Arc<RiskGovernor>>,
+    blacklist: TokenBlacklist,
+    committed: Mutex<HashMap<String, f64>>, // SOL mirrored into each token and not yet sold
+    last_seen: Mutex<Option<Signature>>,
+}
+
+impl CopyTrader {
+    pub fn new(config: &Config, buy_engine: Arc<BuyEngine>) -> Result<Self> {
+        let target_wallet = Pubkey::from_str(&config.get_string("sniping_core.copy_trader.target_wallet")?)?;
+        let token_caps = match config.get::<Vec<TokenCap>>("sniping_core.copy_trader.token_caps") {
+            Ok(caps) => caps,
+            Err(ConfigError::NotFound(_)) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let scale_factor = config.get_float("sniping_core.copy_trader.scale_factor").unwrap_or(0.1);
+        if !(scale_factor > 0.0 && scale_factor <= 1.0) {
+            return Err(anyhow::anyhow!("Copy trader scale factor must be in (0, 1], got {}", scale_factor));
+        }
+        let pump_fun: Arc<dyn SwapDecoder> = Arc::new(PumpFunSwapDecoder::new(config)?);
+
+        Ok(Self {
+            target_wallet,
+            scale_factor,
+            default_token_cap: config.get_float("sniping_core.copy_trader.default_token_cap").unwrap_or(0.5),
+            token_caps: token_caps.into_iter().map(|cap| (cap.mint, cap.max_sol)).collect(),
+            poll_interval: Duration::from_millis(
+                config.get_int("sniping_core.copy_trader.poll_interval_ms").unwrap_or(2000) as u64
+            ),
+            rpc_client: RpcClient::new(config.get_string("sniping_core.copy_trader.rpc_url")
+                .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string())),
+            decoders: HashMap::from([(pump_fun.program_id(), pump_fun)]),
+            buy_engine,
+            profit_manager: None,
+            risk_governor: None,
+            blacklist: TokenBlacklist::default(),
+            committed: Mutex::new(HashMap::new()),
+            last_seen: Mutex::new(None),
+        })
+    }
+
+    // Adds a DEX whose swaps are mirrored
+    pub fn register(&mut self, decoder: Arc<dyn SwapDecoder>) {
+        self.decoders.insert(decoder.program_id(), decoder);
+    }
+
+    pub fn set_profit_manager(&mut self, profit_manager: Arc<RwLock<ProfitManager>>) {
+        self.profit_manager = Some(profit_manager);
+    }
+
+    pub fn set_risk_governor(&mut self, risk_governor: Arc<RiskGovernor>) {
+        self.risk_governor = Some(risk_governor);
+    }
+
+    pub fn set_blacklist(&mut self, blacklist: TokenBlacklist) {
+        self.blacklist = blacklist;
+    }
+
+    // SOL mirrored into `token_address` and not yet sold
+    pub fn committed(&self, token_address: &str) -> f64 {
+        self.committed.lock().unwrap().get(token_address).copied().unwrap_or(0.0)
+    }
+
+    // The target's swap in `instruction`, if it is one on a supported DEX
+    pub fn decode(&self, instruction: &Instruction) -> Result<Option<DecodedSwap>> {
+        let Some(decoder) = self.decoders.get(&instruction.program_id) else {
+            return Ok(None);
+        };
+        Ok(decoder.decode(instruction)?.filter(|swap| swap.trader == self.target_wallet))
+    }
+
+    pub async fn mirror(&self, swap: &DecodedSwap) -> Result<Option<MirroredTrade>> {
+        let token_address = swap.token_mint.to_string();
+        match swap.action {
+            TradeAction::Buy => self.mirror_buy(&token_address, swap.sol_amount).await,
+            TradeAction::Sell => {
+                // Without the prior holding the sell size is unknown, so follow it all the way out
+                let fraction = match swap.trader_holding {
+                    Some(holding) if holding > 0 => (swap.token_amount as f64 / holding as f64).min(1.0),
+                    _ => 1.0,
+                };
+                self.mirror_sell(&token_address, fraction).await
+            }
+            TradeAction::Hold => Ok(None),
+        }
+    }
+
+    async fn mirror_buy(&self, token_address: &str, sol_amount: f64) -> Result<Option<MirroredTrade>> {
+        if !self.blacklist.is_allowed(token_address) {
+            info!("Copy trader skipped buy of {}: blacklisted or not whitelisted", token_address);
+            return Ok(None);
+        }
+        if let Some(risk_governor) = &self.risk_governor {
+            if let Err(frozen) = risk_governor.check() {
+                warn!("Copy trader skipped buy of {}: {}", token_address, frozen);
+                return Ok(None);
+            }
+        }
+
+        self.release_unfilled().await;
+        let cap = self.token_caps.get(token_address).copied().unwrap_or(self.default_token_cap);
+        let amount = (sol_amount * self.scale_factor).min(cap - self.committed(token_address));
+        if amount <= 0.0 {
+            info!("Copy trader skipped buy of {}: {} SOL cap reached", token_address, cap);
+            return Ok(None);
+        }
+
+        // A held position is added to straight away; otherwise the buy is queued. The engine
+        // refuses a buy while another for the token is in flight, which isn't worth a retry:
+        // a copy that lands late is no longer the target's trade.
+        let holding = self.buy_engine.get_active_trades().await.iter().any(|t| t.token_address == token_address);
+        let queued = if holding {
+            self.buy_engine.add_to_position(token_address, amount).await.map(|fill| fill.amount)
+        } else {
+            self.buy_engine.queue_buy(token_address, amount).await.map(|_| amount)
+        };
+        let amount = match queued {
+            Ok(amount) => amount,
+            Err(e) => {
+                warn!("Copy trader skipped buy of {}: {}", token_address, e);
+                return Ok(None);
+            }
+        };
+
+        *self.committed.lock().unwrap().entry(token_address.to_string()).or_default() += amount;
+        info!("Copy trader {} {} SOL buy of {} after target bought {} SOL",
+              if holding { "filled" } else { "queued" }, amount, token_address, sol_amount);
+        Ok(Some(MirroredTrade { token_address: token_address.to_string(), action: TradeAction::Buy, amount }))
+    }
+
+    // A mirrored buy that failed or expired leaves nothing in the buy engine, so its SOL is
+    // no longer committed to the token
+    async fn release_unfilled(&self) {
+        let mut live = self.buy_engine.get_pending_trades().await;
+        live.extend(self.buy_engine.get_active_trades().await);
+        self.committed.lock().unwrap()
+            .retain(|token_address, _| live.iter().any(|t| &t.token_address == token_address));
+    }
+
+    async fn mirror_sell(&self, token_address: &str, fraction: f64) -> Result<Option<MirroredTrade>> {
+        let Some(profit_manager) = &self.profit_manager else {
+            warn!("Copy trader has no profit manager; target's sell of {} not mirrored", token_address);
+            return Ok(None);
+        };
+        if !profit_manager.write().await.sell_token_fraction(token_address, fraction, "copied wallet sold").await? {
+            return Ok(None);
+        }
+
+        if let Some(committed) = self.committed.lock().unwrap().get_mut(token_address) {
+            *committed *= 1.0 - fraction;
+        }
+        Ok(Some(MirroredTrade { token_address: token_address.to_string(), action: TradeAction::Sell, amount: fraction }))
+    }
+
+    // Polls the target wallet and mirrors each new swap, while the buy engine is active
+    pub async fn run(&self) -> Result<()> {
+        while self.buy_engine.is_active() {
+            if let Err(e) = self.poll_target().await {
+                error!("Copy trader poll of {} failed: {}", self.target_wallet, e);
+            }
+            tokio::time::sleep(self.poll_interval).await;
+        }
+        Ok(())
+    }
+
+    // Mirrors the target's swaps since the last poll, oldest first. The first poll only
+    // marks where to start, so history from before startup is never replayed. A signature
+    // is marked seen once its swaps were fetched and mirrored, so a failed fetch leaves it
+    // and everything after it for the next poll; a swap that fails to mirror is not retried.
+    pub async fn poll_target(&self) -> Result<Vec<MirroredTrade>> {
+        let until = *self.last_seen.lock().unwrap();
+        let signatures = self.rpc_client.get_signatures_for_address_with_config(
+            &self.target_wallet,
+            GetConfirmedSignaturesForAddress2Config { until, ..Default::default() },
+        ).await?;
+        if until.is_none() {
+            if let Some(newest) = signatures.first() {
+                *self.last_seen.lock().unwrap() = Some(Signature::from_str(&newest.signature)?);
+            }
+            return Ok(Vec::new());
+        }
+
+        let mut mirrored = Vec::new();
+        for status in signatures.iter().rev() {
+            let signature = Signature::from_str(&status.signature)?;
+            if status.err.is_none() {
+                for swap in self.fetch_swaps(&signature).await? {
+                    match self.mirror(&swap).await {
+                        Ok(Some(trade)) => mirrored.push(trade),
+                        Ok(None) => {}
+                        Err(e) => error!("Copy trader failed to mirror {:?} of {} in {}: {}",
+                                         swap.action, swap.token_mint, signature, e),
+                    }
+                }
+            }
+            *self.last_seen.lock().unwrap() = Some(signature);
+        }
+        Ok(mirrored)
+    }
+
+    async fn fetch_swaps(&self, signature: &Signature) -> Result<Vec<DecodedSwap>> {
+        let confirmed = self.rpc_client.get_transaction(signature, UiTransactionEncoding::Base64).await?;
+        let Some(transaction) = confirmed.transaction.transaction.decode() else {
+            return Ok(Vec::new());
+        };
+        let meta = confirmed.transaction.meta;
+
+        // Versioned transactions may pull accounts from lookup tables; they follow the static keys
+        let mut account_keys = transaction.message.static_account_keys().to_vec();
+        if let Some(OptionSerializer::Some(loaded)) = meta.as_ref().map(|meta| &meta.loaded_addresses) {
+            for key in loaded.writable.iter().chain(&loaded.readonly) {
+                account_keys.push(Pubkey::from_str(key)?);
+            }
+        }
+        let holdings: HashMap<String, u64> = match meta.as_ref().map(|meta| &meta.pre_token_balances) {
+            Some(OptionSerializer::Some(balances)) => balances.iter()
+                .filter(|balance| matches!(&balance.owner, OptionSerializer::Some(owner) if *owner == self.target_wallet.to_string()))
+                .filter_map(|balance| Some((balance.mint.clone(), balance.ui_token_amount.amount.parse().ok()?)))
+                .collect(),
+            _ => HashMap::new(),
+        };
+
+        // Swaps routed through an aggregator or another program only show up as inner instructions
+        let mut compiled: Vec<(u8, Vec<u8>, Vec<u8>)> = transaction.message.instructions().iter()
+            .map(|instruction| (instruction.program_id_index, instruction.accounts.clone(), instruction.data.clone()))
+            .collect();
+        if let Some(OptionSerializer::Some(inner)) = meta.as_ref().map(|meta| &meta.inner_instructions) {
+            for instruction in inner.iter().flat_map(|inner| &inner.instructions) {
+                if let UiInstruction::Compiled(instruction) = instruction {
+                    let data = bs58::decode(&instruction.data).into_vec()
+                        .map_err(|e| anyhow::anyhow!("Undecodable inner instruction in {}: {}", signature, e))?;
+                    compiled.push((instruction.program_id_index, instruction.accounts.clone(), data));
+                }
+            }
+        }
+
+        let mut swaps = Vec::new();
+        for (program_id_index, accounts, data) in compiled {
+            let key = |index: u8| account_keys.get(index as usize).copied()
+                .ok_or_else(|| anyhow::anyhow!("Instruction account {} out of range in {}", index, signature));
+            let instruction = Instruction {
+                program_id: key(program_id_index)?,
+                accounts: accounts.iter()
+                    .map(|index| Ok(AccountMeta::new_readonly(key(*index)?, false)))
+                    .collect::<Result<Vec<_>>>()?,
+                data,
+            };
+            match self.decode(&instruction) {
+                Ok(Some(mut swap)) => {
+                    swap.trader_holding = holdings.get(&swap.token_mint.to_string()).copied();
+                    swaps.push(swap);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Copy trader skipped an undecodable swap in {}: {}", signature, e),
+            }
+        }
+
+        // What the target actually paid or received, fees aside, split across the swaps of
+        // each direction when the transaction holds several
+        if let Some(sol_change) = meta.as_ref().and_then(|meta| {
+            let index = account_keys.iter().position(|key| *key == self.target_wallet)?;
+            let fee = if index == 0 { meta.fee } else { 0 };
+            let change = *meta.post_balances.get(index)? as i128 + fee as i128 - *meta.pre_balances.get(index)? as i128;
+            Some(change as f64 / LAMPORTS_PER_SOL)
+        }) {
+            for buys in [true, false] {
+                let is_side = |swap: &DecodedSwap| matches!(swap.action, TradeAction::Buy) == buys;
+                let count = swaps.iter().filter(|swap| is_side(swap)).count();
+                let moved = if buys { -sol_change } else { sol_change };
+                for swap in swaps.iter_mut().filter(|swap| is_side(swap)) {
+                    swap.sol_amount = moved.max(0.0) / count as f64;
+                }
+            }
+        }
+        Ok(swaps)
+    }
+}
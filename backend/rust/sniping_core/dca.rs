@@ -0,0 +1,182 @@
+use anyhow::{anyhow, Result};
+use config::Config;
+use log::{info, warn};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use crate::common::{
+    validate_amount, Message, MessageKind, MessageQueue, OverflowPolicy, Subscription, TradeAction,
+    TradeEventStatus, TradeExecutionEvent,
+};
+use crate::sniping_core::buy_engine::BuyEngine;
+use crate::sniping_core::price_feed::PriceFeed;
+
+// What to accumulate: `total_budget` SOL of `token_address`, split into `slices` equal buys
+// one `interval` apart. Slices stop once the price is above `price_ceiling`.
+#[derive(Debug, Clone)]
+pub struct DcaPlan {
+    pub token_address: String,
+    pub total_budget: f64,
+    pub slices: u32,
+    pub interval: Duration,
+    pub price_ceiling: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DcaStop {
+    Completed,
+    Cancelled,
+    RugAlert,
+    PriceCeiling(f64), // The price that crossed the ceiling
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcaProgress {
+    pub filled_slices: u32,    // Slices that actually bought; a failed slice isn't retried
+    pub remaining_budget: f64, // Budget less what the filled slices spent
+    pub stopped: Option<DcaStop>,
+}
+
+// Accumulates a position in slices, each bought through the buy engine at exactly its
+// planned size, publishing each fill as a TradeExecution event. A rug alert for the token
+// ends the run early, as does `cancel`.
+pub struct DcaStrategy {
+    plan: DcaPlan,
+    buy_engine: Arc<BuyEngine>,
+    price_feed: Option<Arc<PriceFeed>>,
+    message_queue: Option<MessageQueue>,
+    rug_drop_threshold: f64, // Liquidity lost, as a fraction, that counts as a rug
+    progress: Mutex<DcaProgress>,
+    cancel: watch::Sender<bool>,
+}
+
+impl DcaStrategy {
+    pub fn new(config: &Config, plan: DcaPlan, buy_engine: Arc<BuyEngine>) -> Result<Self> {
+        validate_amount(plan.total_budget)?;
+        if plan.slices == 0 {
+            return Err(anyhow!("DCA plan for {} needs at least one slice", plan.token_address));
+        }
+
+        let (cancel, _) = watch::channel(false);
+        Ok(Self {
+            progress: Mutex::new(DcaProgress {
+                filled_slices: 0,
+                remaining_budget: plan.total_budget,
+                stopped: None,
+            }),
+            plan,
+            buy_engine,
+            price_feed: None,
+            message_queue: None,
+            rug_drop_threshold: config.get_float("sniping_core.dca.rug_drop_threshold").unwrap_or(0.8),
+            cancel,
+        })
+    }
+
+    // Required for the price ceiling; without one the ceiling is not enforced
+    pub fn set_price_feed(&mut self, price_feed: Arc<PriceFeed>) {
+        self.price_feed = Some(price_feed);
+    }
+
+    // Progress is published here, and rug alerts are read from it
+    pub fn set_message_queue(&mut self, message_queue: MessageQueue) {
+        self.message_queue = Some(message_queue);
+    }
+
+    pub fn progress(&self) -> DcaProgress {
+        self.progress.lock().unwrap().clone()
+    }
+
+    pub fn cancel(&self) {
+        let _ = self.cancel.send(true);
+    }
+
+    // Buys the first slice right away and one more each interval until every slice has had
+    // its turn or a stop condition hits
+    pub fn start(self: Arc<Self>) -> JoinHandle<Result<DcaProgress>> {
+        let mut cancel = self.cancel.subscribe();
+        tokio::spawn(async move {
+            let mut alerts = match &self.message_queue {
+                Some(message_queue) => Some(message_queue.subscribe_filtered(
+                    format!("dca_{}_{}", self.plan.token_address, uuid::Uuid::new_v4()),
+                    MessageKind::LIQUIDITY_ALERT,
+                    message_queue.default_capacity(),
+                    OverflowPolicy::DropOldest,
+                ).await),
+                None => None,
+            };
+            let slice = self.plan.total_budget / self.plan.slices as f64;
+            let mut ticker = tokio::time::interval(self.plan.interval);
+            let mut attempted = 0;
+
+            let stop = loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = cancel.changed() => {}
+                }
+                if *cancel.borrow() {
+                    break DcaStop::Cancelled;
+                }
+                if self.rug_alerted(alerts.as_mut()) {
+                    break DcaStop::RugAlert;
+                }
+                if let (Some(ceiling), Some(price_feed)) = (self.plan.price_ceiling, &self.price_feed) {
+                    match price_feed.price(&self.plan.token_address).await {
+                        Ok((price, _)) if price > ceiling => break DcaStop::PriceCeiling(price),
+                        Ok(_) => {}
+                        Err(e) => {
+                            warn!("DCA for {} skipping a slice, no price to check the ceiling: {}",
+                                  self.plan.token_address, e);
+                            continue;
+                        }
+                    }
+                }
+
+                attempted += 1;
+                match self.buy_engine.add_to_position(&self.plan.token_address, slice).await {
+                    Ok(fill) => {
+                        {
+                            let mut progress = self.progress.lock().unwrap();
+                            progress.filled_slices += 1;
+                            progress.remaining_budget = (progress.remaining_budget - fill.amount).max(0.0);
+                        }
+                        if let Some(message_queue) = &self.message_queue {
+                            // A fill without a signature was a dry run
+                            let status = match fill.transaction_hash {
+                                Some(_) => TradeEventStatus::Confirmed,
+                                None => TradeEventStatus::Simulated,
+                            };
+                            message_queue.publish(Message::TradeExecution(TradeExecutionEvent {
+                                signature: fill.transaction_hash.clone(),
+                                ..TradeExecutionEvent::new(&self.plan.token_address, TradeAction::Buy, fill.amount, status)
+                            })).await;
+                        }
+                    }
+                    Err(e) => warn!("DCA for {} slice {} of {} failed: {}",
+                                    self.plan.token_address, attempted, self.plan.slices, e),
+                }
+                if attempted == self.plan.slices {
+                    break DcaStop::Completed;
+                }
+            };
+
+            info!("DCA for {} stopped: {:?}", self.plan.token_address, stop);
+            let mut progress = self.progress.lock().unwrap();
+            progress.stopped = Some(stop);
+            Ok(progress.clone())
+        })
+    }
+
+    fn rug_alerted(&self, alerts: Option<&mut Subscription>) -> bool {
+        let Some(alerts) = alerts else { return false };
+        // Drain everything so stale alerts don't linger into the next tick
+        std::iter::from_fn(|| alerts.try_recv()).fold(false, |rugged, message| match message {
+            Message::LiquidityAlert(alert) => rugged || (
+                alert.token_address == self.plan.token_address
+                    && alert.liquidity_drop().map_or(false, |drop| drop >= self.rug_drop_threshold)
+            ),
+            _ => rugged,
+        })
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::warn;
+use crate::sniping_core::radar::TokenOpportunity;
+use crate::sniping_core::launch_observer::ObservationOutcome;
+
+// Quote for selling a whole position back into the pool
+#[derive(Debug, Clone)]
+pub struct ExitQuote {
+    pub expected_out: f64, // SOL the sell would actually return
+    pub spot_out: f64,     // SOL the same tokens are worth at the current spot price
+}
+
+impl ExitQuote {
+    pub fn slippage(&self) -> f64 {
+        if self.spot_out <= 0.0 {
+            return 1.0;
+        }
+        (1.0 - self.expected_out / self.spot_out).max(0.0)
+    }
+}
+
+#[async_trait]
+pub trait ExitQuoter: Send + Sync {
+    async fn quote_sell(&self, token_address: &str, token_amount: f64) -> Result<ExitQuote>;
+}
+
+// Liquidity that lets us in doesn't guarantee we can get out: sell taxes, one-sided
+// pools and thin SOL reserves can make unwinding the full position far costlier than
+// entering it. This simulates the complete exit before any capital goes in.
+pub struct ExitLiquidityCheck {
+    enabled: bool,
+    max_exit_slippage: f64,
+}
+
+impl ExitLiquidityCheck {
+    pub fn new(config: &Config) -> Result<Self> {
+        let enabled = config.get_bool("sniping_core.buy_engine.exit_check.enabled").unwrap_or(false);
+        let max_exit_slippage = if enabled {
+            config.get_float("sniping_core.buy_engine.exit_check.max_exit_slippage")?
+        } else {
+            1.0
+        };
+
+        Ok(Self { enabled, max_exit_slippage })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    // `amount` is the intended position in SOL
+    pub async fn check(
+        &self,
+        opportunity: &TokenOpportunity,
+        amount: f64,
+        quoter: &dyn ExitQuoter,
+    ) -> Result<ObservationOutcome> {
+        if !self.enabled {
+            return Ok(ObservationOutcome::Enter);
+        }
+        if opportunity.price <= 0.0 {
+            return Ok(ObservationOutcome::Skip("no price to size the exit simulation".to_string()));
+        }
+
+        let token_amount = amount / opportunity.price;
+        let quote = quoter.quote_sell(&opportunity.token_address, token_amount).await?;
+        let slippage = quote.slippage();
+
+        if slippage > self.max_exit_slippage {
+            let reason = format!(
+                "simulated full exit loses {:.1}%, above the {:.1}% ceiling",
+                slippage * 100.0, self.max_exit_slippage * 100.0
+            );
+            warn!("Rejecting entry into {}: {}", opportunity.token_address, reason);
+            return Ok(ObservationOutcome::Skip(reason));
+        }
+
+        Ok(ObservationOutcome::Enter)
+    }
+}
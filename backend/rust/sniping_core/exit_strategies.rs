@@ -0,0 +1,350 @@
+use anyhow::Result;
+use config::Config;
+use log::{info, error, warn};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::RwLock;
+use crate::sniping_core::SnipingState;
+use crate::common::{MarketData, MarketDataProvider, DexScreenerMarketData, SwapExecutor, TradeWebhook, TradeConfirmation, TradeOutcome};
+use serde::{Serialize, Deserialize};
+
+// One rung of a scale-out: sell `fraction` of the original amount once price reaches the target
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TakeProfitLevel {
+    pub target_price: f64,
+    pub fraction: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActiveTrade {
+    pub token_address: String,
+    pub entry_price: f64,
+    pub amount: f64,
+    pub stop_loss: f64,
+    pub take_profit: f64,                        // Single full exit; ignored when levels are set
+    pub take_profit_levels: Vec<TakeProfitLevel>, // Scale-out ladder, sold rung by rung
+    pub trailing_stop: f64,      // Percent below the high-water mark; 0 disables it
+    pub highest_price: f64,      // High-water mark since entry
+    pub current_stop_loss: f64,  // Trailing stop level, ratcheted up on new highs
+    pub remaining_amount: f64,   // Still held after partial exits; starts at `amount`
+    pub levels_hit: usize,       // Ladder rungs already sold
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitType {
+    Hold,
+    StopLoss,
+    TakeProfit,
+    TrailingStop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitSignal {
+    pub should_exit: bool,
+    pub exit_type: ExitType,
+    pub token_address: Option<String>,
+    pub price: f64,
+    pub volume_24h: f64,
+    pub amount: f64,           // Sold by this exit
+    pub remaining_amount: f64, // Still held afterwards; zero once the trade is closed
+}
+
+pub type ExitStrategy = ExitManager;
+
+// Leftovers below this after a partial sell are rounding, not a position
+const DUST_AMOUNT: f64 = 1e-9;
+
+// Watches open snipes for stop loss, take profit and trailing stop exits
+pub struct ExitManager {
+    id: String,
+    state: Arc<RwLock<SnipingState>>,
+    is_active: AtomicBool,
+    check_interval: u64, // Milliseconds between price checks in the monitoring loop
+    market_data: Option<Arc<dyn MarketDataProvider>>,
+    swap_executor: Option<Arc<SwapExecutor>>, // Without one exits are logged as dry runs
+    max_sell_slippage: f64,                   // Lowest acceptable sell price, below the exit price
+    stop_loss_pct: f64,                       // Defaults for trades opened by fills, in percent
+    take_profit_pct: f64,
+    trailing_stop_pct: f64,
+    active_trades: Mutex<Vec<ActiveTrade>>,
+    webhook: TradeWebhook,
+}
+
+impl ExitManager {
+    pub async fn new(config: &Config, state: Arc<RwLock<SnipingState>>) -> Result<Self> {
+        let check_interval = config.get_int("sniping_core.exit_strategy.check_interval_ms").unwrap_or(1000) as u64;
+        let market_data: Option<Arc<dyn MarketDataProvider>> =
+            if config.get_bool("sniping_core.exit_strategy.market_data.enabled").unwrap_or(false) {
+                let base_url = config.get_string("sniping_core.exit_strategy.market_data.base_url")
+                    .unwrap_or_else(|_| "https://api.dexscreener.com/latest/dex/tokens".to_string());
+                Some(Arc::new(DexScreenerMarketData::new(base_url)))
+            } else {
+                None
+            };
+        let max_sell_slippage = config.get_float("sniping_core.exit_strategy.max_sell_slippage").unwrap_or(0.15);
+        let stop_loss_pct = config.get_float("sniping_core.exit_strategy.stop_loss_pct").unwrap_or(5.0);
+        let take_profit_pct = config.get_float("sniping_core.exit_strategy.take_profit_pct").unwrap_or(15.0);
+        let trailing_stop_pct = config.get_float("sniping_core.exit_strategy.trailing_stop_pct").unwrap_or(0.0);
+
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            state,
+            is_active: AtomicBool::new(true),
+            check_interval,
+            market_data,
+            swap_executor: None,
+            max_sell_slippage,
+            stop_loss_pct,
+            take_profit_pct,
+            trailing_stop_pct,
+            active_trades: Mutex::new(Vec::new()),
+            webhook: TradeWebhook::new(config)?,
+        })
+    }
+
+    pub async fn init(&self, _config: &Config) -> Result<()> {
+        info!("Exit Manager {} initialized", self.id);
+        Ok(())
+    }
+
+    pub fn set_market_data(&mut self, market_data: Arc<dyn MarketDataProvider>) {
+        self.market_data = Some(market_data);
+    }
+
+    pub fn set_swap_executor(&mut self, swap_executor: Arc<SwapExecutor>) {
+        self.swap_executor = Some(swap_executor);
+    }
+
+    // Tracks a filled buy of `amount` tokens at `entry_price` with the configured stops
+    pub async fn track_fill(&self, token_address: &str, entry_price: f64, amount: f64) -> Result<()> {
+        self.add_trade(ActiveTrade {
+            token_address: token_address.to_string(),
+            entry_price,
+            amount,
+            stop_loss: entry_price * (1.0 - self.stop_loss_pct / 100.0),
+            take_profit: entry_price * (1.0 + self.take_profit_pct / 100.0),
+            trailing_stop: self.trailing_stop_pct,
+            ..ActiveTrade::default()
+        }).await
+    }
+
+    pub async fn add_trade(&self, mut trade: ActiveTrade) -> Result<()> {
+        // The high-water mark starts at entry, so the trailing stop can only ratchet up from there
+        trade.highest_price = trade.highest_price.max(trade.entry_price);
+        if trade.trailing_stop > 0.0 {
+            trade.current_stop_loss = trade.current_stop_loss.max(Self::trailing_level(&trade));
+        }
+        if trade.remaining_amount <= 0.0 {
+            trade.remaining_amount = trade.amount;
+        }
+        trade.take_profit_levels.sort_by(|a, b| {
+            a.target_price.partial_cmp(&b.target_price).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        info!("Exit Manager {} tracking {} from {}", self.id, trade.token_address, trade.entry_price);
+        self.active_trades.lock().unwrap().push(trade);
+        Ok(())
+    }
+
+    // Fetches live market data for every tracked token and applies it to that token's
+    // trades. Every exit is reported, partial ones included; a trade is only removed once
+    // nothing of it remains. A token whose data can't be fetched
+    // is left untouched until the next check rather than exited on a bad reading.
+    pub async fn check_exit_conditions(&self) -> Result<Vec<ExitSignal>> {
+        Ok(self.collect_exits().await?.into_iter().map(|(signal, _)| signal).collect())
+    }
+
+    // Each exit paired with its trade as it was before the exit, to restore if the sell fails
+    async fn collect_exits(&self) -> Result<Vec<(ExitSignal, ActiveTrade)>> {
+        let market_data = self.market_data.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Exit Manager {} has no market data source", self.id))?;
+
+        let mut tokens: Vec<String> = self.active_trades.lock().unwrap().iter()
+            .map(|trade| trade.token_address.clone())
+            .collect();
+        tokens.sort();
+        tokens.dedup();
+
+        let mut exits = Vec::new();
+        for token_address in tokens {
+            let data = match market_data.market_data(&token_address).await {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("Exit Manager {} skipping {}: market data unavailable: {}", self.id, token_address, e);
+                    continue;
+                }
+            };
+            let mut trades = self.active_trades.lock().unwrap();
+            exits.extend(Self::apply_tick(&mut trades, &token_address, data));
+        }
+        Ok(exits)
+    }
+
+    fn apply_tick(trades: &mut Vec<ActiveTrade>, token_address: &str, data: MarketData) -> Vec<(ExitSignal, ActiveTrade)> {
+        let mut exits = Vec::new();
+        trades.retain_mut(|trade| {
+            if trade.token_address != token_address {
+                return true;
+            }
+            let before = trade.clone();
+            let (exit_type, sell_amount) = Self::evaluate(trade, data.price);
+            if exit_type == ExitType::Hold {
+                return true;
+            }
+
+            trade.remaining_amount -= sell_amount;
+            if trade.remaining_amount <= DUST_AMOUNT {
+                trade.remaining_amount = 0.0;
+            }
+            info!("Exit {:?} for {} at {}: sold {}, {} left (high {}, stop {}, 24h volume {})",
+                  exit_type, trade.token_address, data.price, sell_amount, trade.remaining_amount,
+                  trade.highest_price, trade.current_stop_loss, data.volume_24h);
+            exits.push((ExitSignal {
+                should_exit: true,
+                exit_type,
+                token_address: Some(trade.token_address.clone()),
+                price: data.price,
+                volume_24h: data.volume_24h,
+                amount: sell_amount,
+                remaining_amount: trade.remaining_amount,
+            }, before));
+            trade.remaining_amount > 0.0
+        });
+        exits
+    }
+
+    // What to do at this price and how much of the remaining position to sell
+    fn evaluate(trade: &mut ActiveTrade, price: f64) -> (ExitType, f64) {
+        // Ratchet the high-water mark and the trailing stop on new highs; never lower them
+        if price > trade.highest_price {
+            trade.highest_price = price;
+            if trade.trailing_stop > 0.0 {
+                trade.current_stop_loss = trade.current_stop_loss.max(Self::trailing_level(trade));
+            }
+        }
+
+        if trade.take_profit_levels.is_empty() {
+            if trade.take_profit > 0.0 && price >= trade.take_profit {
+                return (ExitType::TakeProfit, trade.remaining_amount);
+            }
+        } else {
+            // A jump through several rungs sells all of them at once. Each rung sells only its
+            // own fraction; whatever the ladder leaves rides on the stops.
+            let mut sell_amount = 0.0;
+            while let Some(level) = trade.take_profit_levels.get(trade.levels_hit) {
+                if price < level.target_price {
+                    break;
+                }
+                sell_amount += trade.amount * level.fraction;
+                trade.levels_hit += 1;
+            }
+            if sell_amount > 0.0 {
+                return (ExitType::TakeProfit, sell_amount.min(trade.remaining_amount));
+            }
+        }
+        if trade.trailing_stop > 0.0 && trade.current_stop_loss > trade.stop_loss && price <= trade.current_stop_loss {
+            return (ExitType::TrailingStop, trade.remaining_amount);
+        }
+        if price <= trade.stop_loss {
+            return (ExitType::StopLoss, trade.remaining_amount);
+        }
+        (ExitType::Hold, 0.0)
+    }
+
+    fn trailing_level(trade: &ActiveTrade) -> f64 {
+        trade.highest_price * (1.0 - trade.trailing_stop / 100.0)
+    }
+
+    pub async fn start_monitoring(&self) -> Result<()> {
+        info!("Exit Manager {} started monitoring", self.id);
+
+        while self.is_active() {
+            let colony_active = self.state.read().await.is_active;
+            if colony_active && self.market_data.is_some() {
+                for (signal, before) in self.collect_exits().await? {
+                    match self.sell(&signal).await {
+                        Ok(()) if signal.remaining_amount <= 0.0 => self.confirm_exit(&signal),
+                        Ok(()) => {}
+                        Err(e) => {
+                            error!("Exit Manager {} failed to sell {:?}: {}", self.id, signal.token_address, e);
+                            self.restore(before);
+                        }
+                    }
+                }
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(self.check_interval)).await;
+        }
+
+        Ok(())
+    }
+
+    // Sells what the exit released, accepting at most `max_sell_slippage` below the exit price
+    async fn sell(&self, signal: &ExitSignal) -> Result<()> {
+        let token_address = signal.token_address.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Exit signal without a token"))?;
+        let swap_executor = match &self.swap_executor {
+            Some(swap_executor) => swap_executor,
+            None => {
+                info!("Exit Manager {} dry run: would sell {} of {} at {}", self.id, signal.amount, token_address, signal.price);
+                return Ok(());
+            }
+        };
+        let min_price = signal.price * (1.0 - self.max_sell_slippage);
+        let transaction = swap_executor.build_sell(token_address, signal.amount, min_price).await?;
+        let signature = swap_executor.submit(transaction).await?;
+        info!("Exit Manager {} sold {} of {} ({:?}): {}", self.id, signal.amount, token_address, signal.exit_type, signature);
+        Ok(())
+    }
+
+    // A trade sold down to nothing is confirmed as exited, keyed by token like the buy engine's
+    fn confirm_exit(&self, signal: &ExitSignal) {
+        let Some(token_address) = signal.token_address.as_deref() else {
+            return;
+        };
+        if !self.webhook.is_enabled() {
+            return;
+        }
+        match TradeConfirmation::new(TradeOutcome::Exited, token_address, token_address, signal) {
+            Ok(confirmation) => self.webhook.notify(confirmation),
+            Err(e) => warn!("Exit Manager {} could not build confirmation for {}: {}", self.id, token_address, e),
+        }
+    }
+
+    // Puts back what a failed sell took off, so the exit fires again on the next check
+    fn restore(&self, before: ActiveTrade) {
+        let mut trades = self.active_trades.lock().unwrap();
+        let same_trade = |trade: &&mut ActiveTrade| {
+            trade.token_address == before.token_address
+                && trade.entry_price == before.entry_price
+                && trade.amount == before.amount
+        };
+        match trades.iter_mut().find(same_trade) {
+            Some(trade) => {
+                trade.remaining_amount = before.remaining_amount;
+                trade.levels_hit = before.levels_hit;
+            }
+            None => trades.push(before),
+        }
+    }
+
+    pub async fn shutdown(&self) -> Result<()> {
+        self.is_active.store(false, Ordering::SeqCst);
+        info!("Exit Manager {} shutting down", self.id);
+        Ok(())
+    }
+
+    // Stops tracking a token's trades without exiting them, e.g. once sold elsewhere
+    pub fn remove_trades(&self, token_address: &str) -> Vec<ActiveTrade> {
+        let mut trades = self.active_trades.lock().unwrap();
+        let (removed, kept) = trades.drain(..).partition(|trade| trade.token_address == token_address);
+        *trades = kept;
+        removed
+    }
+
+    pub fn get_active_trades(&self) -> Vec<ActiveTrade> {
+        self.active_trades.lock().unwrap().clone()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use config::Config;
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use std::sync::Mutex;
+use crate::common::{
+    AlertSeverity, ColonyAlert, LiquidityAlert, Message, MessageKind, MessageQueue, OverflowPolicy,
+    Subscription,
+};
+
+const SUBSCRIBER_ID: &str = "sentiment_killswitch";
+
+// A negative signal, kept until it falls out of the window
+struct NegativeSignal {
+    token_address: Option<String>, // None for sentiment, which isn't about one token
+    at: DateTime<Utc>,
+}
+
+// Halts new buys once the market turns: deep liquidity drops and strongly negative sentiment
+// each count as a negative signal, and enough of them within the signal window trip the
+// switch. Liquidity drops count once per token, so one rugged pool can't halt trading on its
+// own. The switch clears again once its signals age out, or on `reset`, and announces itself
+// with a critical colony alert when it trips.
+pub struct SentimentKillswitch {
+    message_queue: MessageQueue,
+    subscription: tokio::sync::Mutex<Subscription>,
+    liquidity_drop_threshold: f64, // Fraction of liquidity lost, e.g. 0.5 for a halving
+    sentiment_threshold: f64,      // Scores at or below this are negative signals
+    activation_signals: u32,       // Negative signals within the window needed to trip
+    signal_window: Duration,
+    poll_interval: std::time::Duration, // How often `run` drains liquidity alerts
+    signals: Mutex<Vec<NegativeSignal>>,
+    active: Mutex<bool>,
+}
+
+impl SentimentKillswitch {
+    pub async fn new(config: &Config, message_queue: MessageQueue) -> Self {
+        let subscription = message_queue.subscribe_filtered(
+            SUBSCRIBER_ID.to_string(),
+            MessageKind::LIQUIDITY_ALERT,
+            message_queue.default_capacity(),
+            OverflowPolicy::DropOldest,
+        ).await;
+
+        Self {
+            message_queue,
+            subscription: tokio::sync::Mutex::new(subscription),
+            liquidity_drop_threshold: config.get_float("sniping_core.killswitch.liquidity_drop_threshold").unwrap_or(0.5),
+            sentiment_threshold: config.get_float("sniping_core.killswitch.sentiment_threshold").unwrap_or(-0.5),
+            activation_signals: config.get_int("sniping_core.killswitch.activation_signals").unwrap_or(3).max(1) as u32,
+            signal_window: Duration::milliseconds(
+                config.get_int("sniping_core.killswitch.signal_window_ms").unwrap_or(600_000)
+            ),
+            poll_interval: std::time::Duration::from_millis(
+                config.get_int("sniping_core.killswitch.poll_interval_ms").unwrap_or(1000) as u64
+            ),
+            signals: Mutex::new(Vec::new()),
+            active: Mutex::new(false),
+        }
+    }
+
+    // Scores run from -1.0 (bearish) to 1.0 (bullish)
+    pub async fn record_sentiment(&self, score: f64) {
+        if score <= self.sentiment_threshold {
+            self.record_negative_signal(None, format!("Sentiment score {:.2}", score)).await;
+        }
+    }
+
+    // Drains liquidity alerts as they arrive, so the switch trips even while nothing is buying
+    pub async fn run(&self) -> Result<()> {
+        loop {
+            self.should_activate().await;
+            tokio::time::sleep(self.poll_interval).await;
+        }
+    }
+
+    // Consumes any liquidity alerts published since the last call
+    pub async fn should_activate(&self) -> bool {
+        let alerts: Vec<LiquidityAlert> = {
+            let mut subscription = self.subscription.lock().await;
+            std::iter::from_fn(|| subscription.try_recv())
+                .filter_map(|message| match message {
+                    Message::LiquidityAlert(alert) => Some(alert),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        for alert in alerts {
+            if let Some(drop) = alert.liquidity_drop() {
+                if drop >= self.liquidity_drop_threshold {
+                    self.record_negative_signal(Some(alert.token_address.clone()), format!(
+                        "Liquidity of {} dropped {:.0}%", alert.token_address, drop * 100.0
+                    )).await;
+                }
+            }
+        }
+
+        self.is_active()
+    }
+
+    pub fn is_active(&self) -> bool {
+        let live = self.negative_signals();
+        let mut active = self.active.lock().unwrap();
+        if *active && live < self.activation_signals {
+            info!("Sentiment killswitch cleared: {} negative signal(s) left in the window", live);
+            *active = false;
+        }
+        *active
+    }
+
+    // Signals within the window, liquidity drops counted once per token
+    pub fn negative_signals(&self) -> u32 {
+        let cutoff = Utc::now() - self.signal_window;
+        let mut signals = self.signals.lock().unwrap();
+        signals.retain(|signal| signal.at > cutoff);
+        signals.len() as u32
+    }
+
+    pub fn reset(&self) {
+        self.signals.lock().unwrap().clear();
+        *self.active.lock().unwrap() = false;
+    }
+
+    async fn record_negative_signal(&self, token_address: Option<String>, reason: String) {
+        {
+            let mut signals = self.signals.lock().unwrap();
+            let now = Utc::now();
+            match signals.iter_mut().find(|signal| token_address.is_some() && signal.token_address == token_address) {
+                Some(signal) => signal.at = now,
+                None => signals.push(NegativeSignal { token_address, at: now }),
+            }
+        }
+        let tripped = {
+            let live = self.negative_signals();
+            let mut active = self.active.lock().unwrap();
+            let tripped = !*active && live >= self.activation_signals;
+            *active |= tripped;
+            tripped
+        };
+
+        if tripped {
+            warn!("Sentiment killswitch activated: {}", reason);
+            self.message_queue.publish(Message::ColonyAlert(ColonyAlert {
+                source: SUBSCRIBER_ID.to_string(),
+                token_address: None,
+                severity: AlertSeverity::Critical,
+                message: format!("Trading frozen by sentiment killswitch, new buys halted: {}", reason),
+                timestamp: Utc::now(),
+            })).await;
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::{info, warn};
+use chrono::{DateTime, Utc};
+use tokio::time::{Duration, Instant};
+use crate::sniping_core::radar::TokenOpportunity;
+
+#[derive(Debug, Clone)]
+pub struct PoolSnapshot {
+    pub price: f64,
+    pub liquidity: f64,
+    pub sellable: bool, // False once a sell simulation fails
+    pub timestamp: DateTime<Utc>,
+}
+
+// Live view of a pool while it is being observed
+#[async_trait]
+pub trait PoolMonitor: Send + Sync {
+    async fn snapshot(&self, opportunity: &TokenOpportunity) -> Result<PoolSnapshot>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObservationOutcome {
+    Enter,
+    Skip(String),
+}
+
+// Watches a brand-new pool for a short window before capital is committed, skipping
+// launches that dump, pull liquidity or stop being sellable in their first moments
+pub struct LaunchObserver {
+    enabled: bool,
+    window: Duration,
+    poll_interval: Duration,
+    max_price_drop: f64,     // Fraction below the peak price seen during the window
+    max_liquidity_drop: f64, // Fraction below the liquidity at the start of the window
+}
+
+impl LaunchObserver {
+    pub fn new(config: &Config) -> Result<Self> {
+        let enabled = config.get_bool("sniping_core.launch_observer.enabled").unwrap_or(false);
+        if !enabled {
+            return Ok(Self {
+                enabled,
+                window: Duration::ZERO,
+                poll_interval: Duration::ZERO,
+                max_price_drop: 1.0,
+                max_liquidity_drop: 1.0,
+            });
+        }
+
+        Ok(Self {
+            enabled,
+            window: Duration::from_millis(config.get_int("sniping_core.launch_observer.window_ms")? as u64),
+            poll_interval: Duration::from_millis(config.get_int("sniping_core.launch_observer.poll_interval_ms")? as u64),
+            max_price_drop: config.get_float("sniping_core.launch_observer.max_price_drop")?,
+            max_liquidity_drop: config.get_float("sniping_core.launch_observer.max_liquidity_drop")?,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub async fn observe(&self, opportunity: &TokenOpportunity, monitor: &dyn PoolMonitor) -> Result<ObservationOutcome> {
+        if !self.enabled {
+            return Ok(ObservationOutcome::Enter);
+        }
+
+        let deadline = Instant::now() + self.window;
+        let baseline = monitor.snapshot(opportunity).await?;
+        let mut peak_price = baseline.price;
+        let mut snapshot = baseline.clone();
+
+        loop {
+            if let Some(reason) = self.red_flag(&baseline, peak_price, &snapshot) {
+                warn!("Skipping launch {} after observation: {}", opportunity.token_address, reason);
+                return Ok(ObservationOutcome::Skip(reason));
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            tokio::time::sleep(self.poll_interval.min(deadline - now)).await;
+
+            snapshot = monitor.snapshot(opportunity).await?;
+            peak_price = peak_price.max(snapshot.price);
+        }
+
+        info!("Launch {} stayed healthy for {:?}, entering", opportunity.token_address, self.window);
+        Ok(ObservationOutcome::Enter)
+    }
+
+    fn red_flag(&self, baseline: &PoolSnapshot, peak_price: f64, snapshot: &PoolSnapshot) -> Option<String> {
+        if !snapshot.sellable {
+            return Some("token stopped being sellable".to_string());
+        }
+
+        if peak_price > 0.0 {
+            let price_drop = (peak_price - snapshot.price) / peak_price;
+            if price_drop > self.max_price_drop {
+                return Some(format!("price dumped {:.1}% from its peak", price_drop * 100.0));
+            }
+        }
+
+        if baseline.liquidity > 0.0 {
+            let liquidity_drop = (baseline.liquidity - snapshot.liquidity) / baseline.liquidity;
+            if liquidity_drop > self.max_liquidity_drop {
+                return Some(format!("liquidity pulled by {:.1}%", liquidity_drop * 100.0));
+            }
+        }
+
+        None
+    }
+}
@@ -1,17 +1,77 @@
 mod radar;
 mod buy_engine;
 mod exit_strategies;
+mod coin_scanner;
+mod slippage;
+mod launch_observer;
+mod exit_liquidity;
+mod quote_freshness;
+mod price_feed;
+mod pool_liquidity;
+mod adaptive_batch;
+mod killswitch;
+mod dca;
+mod sizing;
+mod whale_tracker;
+mod copy_trader;
+mod supervisor;
 
 use anyhow::Result;
 use config::Config;
-use log::{info, error, warn};
+use log::{info, warn};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::Mutex;
+use tokio::sync::{OnceCell, RwLock};
+use tokio::task::JoinHandle;
+use crate::common::{MessageQueue, Metrics, SwapExecutor};
+use crate::config::RpcConfig;
+use crate::rpc::RpcClientManager;
+use crate::ant_colony::{Portfolio, RiskGovernor, TokenBlacklist};
+use crate::logging::ErrorReporter;
 
-// Re-export types for external use
-pub use radar::Radar;
-pub use buy_engine::BuyEngine;
-pub use exit_strategies::ExitStrategy;
+// The sniping core's public API. Submodules are private; everything callers need is
+// re-exported here, so import from `sniping_core::` rather than a submodule path.
+//   Discovery:  Radar, CoinScanner (with honeypot checks and priority weights), LaunchObserver,
+//               WhaleTracker for buys by known profitable wallets
+//   Entry:      BuyEngine with cost tracking, adaptive slippage, quote freshness and pool liquidity,
+//               sized by a PositionSizer
+//   Exit:       ExitManager (alias ExitStrategy) for stops and take profit levels, ExitLiquidityCheck
+//   Pricing:    PriceFeed and its PriceProvider sources
+//   Safety:     SentimentKillswitch, which halts new buys when the market turns
+//   Strategies: DcaStrategy, which accumulates a position in timed slices, and CopyTrader,
+//               which mirrors a target wallet's DEX swaps
+//   Runtime:    Supervisor, which restarts the core's background loops when they fail, and
+//               ColonyServices, what the core is given from the ant colony at init
+pub use radar::{Radar, TokenOpportunity};
+pub use buy_engine::{BuyEngine, TradeExecution, TradeStatus, LiquiditySource, TriggerCondition};
+pub use exit_strategies::{ExitStrategy, ExitManager, ActiveTrade, ExitType, ExitSignal, TakeProfitLevel};
+pub use coin_scanner::{CoinScanner, CoinMetrics, HoneypotResult, PriorityWeights};
+pub use slippage::{AdaptiveSlippage, LiquidityClass};
+pub use launch_observer::{LaunchObserver, PoolMonitor, PoolSnapshot, ObservationOutcome};
+pub use exit_liquidity::{ExitLiquidityCheck, ExitQuoter, ExitQuote};
+pub use quote_freshness::{QuoteFreshness, EntryQuoter, EntryQuote, QuotedBuild};
+pub use price_feed::{PriceFeed, PriceProvider, PriceSource, PriceSourceOverride};
+pub use pool_liquidity::{PoolLiquidity, PoolDecoder, PoolReserves, BondingCurveDecoder};
+pub use killswitch::SentimentKillswitch;
+pub use dca::{DcaStrategy, DcaPlan, DcaProgress, DcaStop};
+pub use sizing::{PositionSizer, SizingStrategy, kelly_fraction};
+pub use whale_tracker::WhaleTracker;
+pub use copy_trader::{CopyTrader, DecodedSwap, MirroredTrade, SwapDecoder, PumpFunSwapDecoder, TokenCap};
+pub use supervisor::Supervisor;
+
+// What the core shares with the ant colony, so both trade against one blacklist and one
+// set of risk limits. Anything missing leaves the core's components on their own defaults.
+#[derive(Clone, Default)]
+pub struct ColonyServices {
+    pub blacklist: Option<TokenBlacklist>,
+    pub risk_governor: Option<Arc<RiskGovernor>>,
+    pub message_queue: Option<MessageQueue>, // Liquidity alerts in, degradation alerts out
+    pub swap_executor: Option<Arc<SwapExecutor>>, // Without one, buys and exits are dry runs
+    pub portfolio: Option<Arc<Portfolio>>, // Closed-trade history behind Kelly sizing
+    pub metrics: Option<Metrics>, // The colony's registry, served from the dashboard's /metrics
+    pub rpc_manager: Option<Arc<RpcClientManager>>, // The colony's pools; without one the core builds its own
+    pub error_tracker: Option<Arc<dyn ErrorReporter>>, // Sentry, when a DSN is configured
+}
 
 // Shared state for the Sniping Core
 #[derive(Default)]
@@ -20,37 +80,151 @@ pub struct SnipingState {
     pub active_trades: Vec<String>,
     pub total_profits: f64,
     pub risk_level: f64,
+    pub degraded_components: Vec<String>, // Loops the supervisor gave up restarting
 }
 
 // Main Sniping Core struct that coordinates all components
 pub struct SnipingCore {
-    radar: Arc<RwLock<Radar>>,
-    buy_engine: Arc<RwLock<BuyEngine>>,
-    exit_strategy: Arc<RwLock<ExitStrategy>>,
+    radar: Arc<Radar>,
+    buy_engine: Arc<BuyEngine>,
+    exit_strategy: Arc<ExitStrategy>,
+    copy_trader: Option<Arc<CopyTrader>>, // Only when sniping_core.copy_trader.enabled
+    coin_scanner: Option<Arc<RwLock<CoinScanner>>>, // Only when sniping_core.coin_scanner.enabled
+    killswitch: Option<Arc<SentimentKillswitch>>, // Needs the colony's liquidity alerts
     state: Arc<RwLock<SnipingState>>,
+    supervisor: Supervisor,
+    tasks: Mutex<Vec<JoinHandle<()>>>, // Supervised background loops, aborted on shutdown
 }
 
 impl SnipingCore {
     pub async fn new(config: &Config) -> Result<Self> {
+        Self::with_services(config, &ColonyServices::default()).await
+    }
+
+    pub async fn with_services(config: &Config, services: &ColonyServices) -> Result<Self> {
         let state = Arc::new(RwLock::new(SnipingState::default()));
-        let radar = Arc::new(RwLock::new(Radar::new(config, state.clone()).await?));
-        let buy_engine = Arc::new(RwLock::new(BuyEngine::new(config, state.clone()).await?));
-        let exit_strategy = Arc::new(RwLock::new(ExitStrategy::new(config, state.clone()).await?));
+        let mut radar = Radar::new(config, state.clone()).await?;
+        // The radar subscribes through the providers in rpc.toml when they are loaded
+        if let Some(rpc_manager) = &services.rpc_manager {
+            radar.set_rpc_manager(rpc_manager.clone());
+        } else {
+            match config.clone().try_deserialize::<RpcConfig>() {
+                Ok(rpc_config) => {
+                    let mut rpc_manager = RpcClientManager::new(&rpc_config).await?;
+                    if let Some(metrics) = &services.metrics {
+                        rpc_manager.set_metrics(metrics.clone());
+                    }
+                    radar.set_rpc_manager(Arc::new(rpc_manager));
+                }
+                Err(e) => warn!("No RPC providers configured, the radar only polls: {}", e),
+            }
+        }
+        let radar = Arc::new(radar);
+        let mut exit_strategy = ExitStrategy::new(config, state.clone()).await?;
+        if let Some(swap_executor) = &services.swap_executor {
+            exit_strategy.set_swap_executor(swap_executor.clone());
+        }
+        let exit_strategy = Arc::new(exit_strategy);
+        // Filled buys are handed to the exit manager for their stops and take profit
+        let mut buy_engine = BuyEngine::new(config, state.clone()).await?;
+        buy_engine.set_exit_manager(exit_strategy.clone());
+        if let Some(risk_governor) = &services.risk_governor {
+            buy_engine.set_risk_governor(risk_governor.clone());
+        }
+        if let Some(blacklist) = &services.blacklist {
+            buy_engine.set_blacklist(blacklist.clone());
+        }
+        if let Some(swap_executor) = &services.swap_executor {
+            buy_engine.set_swap_executor(swap_executor.clone());
+        }
+        if let Some(portfolio) = &services.portfolio {
+            buy_engine.set_portfolio(portfolio.clone());
+        }
+        let mut supervisor = Supervisor::new(config, state.clone());
+        let killswitch = match &services.message_queue {
+            Some(message_queue) => {
+                let killswitch = Arc::new(SentimentKillswitch::new(config, message_queue.clone()).await);
+                buy_engine.set_killswitch(killswitch.clone());
+                buy_engine.set_message_queue(message_queue.clone());
+                supervisor.set_message_queue(message_queue.clone());
+                Some(killswitch)
+            }
+            None => None,
+        };
+        let buy_engine = Arc::new(buy_engine);
+
+        let copy_trader = if config.get_bool("sniping_core.copy_trader.enabled").unwrap_or(false) {
+            let mut copy_trader = CopyTrader::new(config, buy_engine.clone())?;
+            if let Some(blacklist) = &services.blacklist {
+                copy_trader.set_blacklist(blacklist.clone());
+            }
+            if let Some(risk_governor) = &services.risk_governor {
+                copy_trader.set_risk_governor(risk_governor.clone());
+            }
+            Some(Arc::new(copy_trader))
+        } else {
+            None
+        };
+
+        // Honeypots the scanner finds land on the colony's blacklist
+        let coin_scanner = if config.get_bool("sniping_core.coin_scanner.enabled").unwrap_or(false) {
+            let mut coin_scanner = CoinScanner::new(config, state.clone()).await?;
+            if let Some(blacklist) = &services.blacklist {
+                coin_scanner.set_blacklist(blacklist.clone());
+            }
+            if let Some(metrics) = &services.metrics {
+                coin_scanner.set_metrics(metrics.clone());
+            }
+            if let Some(error_tracker) = &services.error_tracker {
+                coin_scanner.set_error_tracker(error_tracker.clone());
+            }
+            Some(Arc::new(RwLock::new(coin_scanner)))
+        } else {
+            None
+        };
 
         Ok(Self {
             radar,
             buy_engine,
             exit_strategy,
+            copy_trader,
+            coin_scanner,
+            killswitch,
+            supervisor,
             state,
+            tasks: Mutex::new(Vec::new()),
         })
     }
 
+    // Degradation is published here when a background loop stops for good
+    pub fn set_message_queue(&mut self, message_queue: MessageQueue) {
+        self.supervisor.set_message_queue(message_queue);
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.state.read().await.is_active
+    }
+
+    // Shared with strategies that buy through the core's engine, such as DcaStrategy and CopyTrader
+    pub fn buy_engine(&self) -> Arc<BuyEngine> {
+        self.buy_engine.clone()
+    }
+
+    pub async fn degraded_components(&self) -> Vec<String> {
+        self.state.read().await.degraded_components.clone()
+    }
+
+    // A no-op while the core is running, so a second call doesn't start a second set of loops
     pub async fn init(&mut self, config: &Config) -> Result<()> {
+        if self.is_active().await {
+            info!("Sniping Core is already running");
+            return Ok(());
+        }
         info!("Initializing Sniping Core...");
 
         // Initialize components
         self.init_radar(config).await?;
-        self.init_buy_engine(config).await?;
+        self.init_buy_engine().await?;
         self.init_exit_strategy(config).await?;
 
         // Start monitoring and coordination
@@ -60,70 +234,120 @@ impl SnipingCore {
         Ok(())
     }
 
-    async fn init_radar(&mut self, config: &Config) -> Result<()> {
-        let radar = self.radar.write().await;
-        radar.init(config).await
+    async fn init_radar(&self, config: &Config) -> Result<()> {
+        self.radar.init(config).await
     }
 
-    async fn init_buy_engine(&mut self, config: &Config) -> Result<()> {
-        let buy_engine = self.buy_engine.write().await;
-        buy_engine.init(config).await
+    async fn init_buy_engine(&self) -> Result<()> {
+        self.buy_engine.init().await
     }
 
-    async fn init_exit_strategy(&mut self, config: &Config) -> Result<()> {
-        let exit_strategy = self.exit_strategy.write().await;
-        exit_strategy.init(config).await
+    async fn init_exit_strategy(&self, config: &Config) -> Result<()> {
+        self.exit_strategy.init(config).await
     }
 
+    // Each loop shares its component through an Arc rather than a lock, so callers can reach
+    // the components while the loops run
     async fn start_coordination(&self) -> Result<()> {
+        self.state.write().await.is_active = true;
+        let mut tasks = Vec::new();
+
         // Start radar scanning
         let radar = self.radar.clone();
-        tokio::spawn(async move {
-            if let Err(e) = radar.write().await.start_scanning().await {
-                error!("Radar scanning error: {}", e);
-            }
-        });
+        tasks.push(self.supervisor.supervise("Radar scanning", move || {
+            let radar = radar.clone();
+            async move { radar.start_scanning().await }
+        }));
 
-        // Start buy engine monitoring
+        // Start the buy engine's pending trade loop
         let buy_engine = self.buy_engine.clone();
-        tokio::spawn(async move {
-            if let Err(e) = buy_engine.write().await.start_monitoring().await {
-                error!("Buy engine monitoring error: {}", e);
-            }
-        });
+        tasks.push(self.supervisor.supervise("Buy engine", move || {
+            let buy_engine = buy_engine.clone();
+            async move { buy_engine.run().await }
+        }));
 
         // Start exit strategy monitoring
         let exit_strategy = self.exit_strategy.clone();
-        tokio::spawn(async move {
-            if let Err(e) = exit_strategy.write().await.start_monitoring().await {
-                error!("Exit strategy monitoring error: {}", e);
-            }
-        });
+        tasks.push(self.supervisor.supervise("Exit strategy monitoring", move || {
+            let exit_strategy = exit_strategy.clone();
+            async move { exit_strategy.start_monitoring().await }
+        }));
+
+        // Drain liquidity alerts even while nothing is buying
+        if let Some(killswitch) = &self.killswitch {
+            let killswitch = killswitch.clone();
+            tasks.push(self.supervisor.supervise("Sentiment killswitch", move || {
+                let killswitch = killswitch.clone();
+                async move { killswitch.run().await }
+            }));
+        }
+
+        if let Some(copy_trader) = &self.copy_trader {
+            let copy_trader = copy_trader.clone();
+            tasks.push(self.supervisor.supervise("Copy trader", move || {
+                let copy_trader = copy_trader.clone();
+                async move { copy_trader.run().await }
+            }));
+        }
 
+        if let Some(coin_scanner) = &self.coin_scanner {
+            let coin_scanner = coin_scanner.clone();
+            tasks.push(self.supervisor.supervise("Coin scanner", move || {
+                let coin_scanner = coin_scanner.clone();
+                async move { CoinScanner::run(coin_scanner).await }
+            }));
+        }
+
+        self.tasks.lock().unwrap().extend(tasks);
         Ok(())
     }
 
     pub async fn shutdown(&self) -> Result<()> {
         info!("Shutting down Sniping Core...");
-        
+        self.state.write().await.is_active = false;
+
+        // Stop the loops before the components they drive
+        let tasks: Vec<JoinHandle<()>> = self.tasks.lock().unwrap().drain(..).collect();
+        for task in tasks {
+            task.abort();
+            let _ = task.await;
+        }
+
         // Stop all components
-        self.radar.write().await.shutdown().await?;
-        self.buy_engine.write().await.shutdown().await?;
-        self.exit_strategy.write().await.shutdown().await?;
+        self.radar.shutdown().await?;
+        self.buy_engine.shutdown().await?;
+        self.exit_strategy.shutdown().await?;
+        if let Some(coin_scanner) = &self.coin_scanner {
+            coin_scanner.write().await.shutdown().await?;
+        }
 
         info!("Sniping Core shutdown complete");
         Ok(())
     }
 }
 
-// Initialize the Sniping Core system
-pub async fn init(config: &Config) -> Result<()> {
-    let mut core = SnipingCore::new(config).await?;
-    core.init(config).await
+// Global instance for the Sniping Core
+static SNIPING_CORE: OnceCell<Arc<RwLock<SnipingCore>>> = OnceCell::const_new();
+
+// Initialize the Sniping Core system on the colony's shared services
+pub async fn init(config: &Config, services: ColonyServices) -> Result<()> {
+    let core = SNIPING_CORE
+        .get_or_try_init(|| async {
+            Ok::<_, anyhow::Error>(Arc::new(RwLock::new(SnipingCore::with_services(config, &services).await?)))
+        })
+        .await?;
+    core.write().await.init(config).await
 }
 
-// Shutdown the Sniping Core system
+// Shutdown the Sniping Core system, stopping its background loops
 pub async fn shutdown() -> Result<()> {
-    // This will be implemented when we have a global core instance
+    if let Some(core) = SNIPING_CORE.get() {
+        core.read().await.shutdown().await?;
+    }
     Ok(())
-} 
\ No newline at end of file
+}
+
+// The running core, once `init` has been called
+pub fn instance() -> Option<Arc<RwLock<SnipingCore>>> {
+    SNIPING_CORE.get().cloned()
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::debug;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use solana_sdk::pubkey::Pubkey;
+use crate::rpc::RpcClientManager;
+use crate::sniping_core::buy_engine::LiquiditySource;
+use crate::sniping_core::price_feed::PriceFeed;
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const PUMP_FUN_PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+// Reserves of a SOL-paired pool, in whole units of each side
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolReserves {
+    pub sol: f64,
+    pub token: f64,
+}
+
+// Knows where a DEX keeps a token's pool and how to read reserves out of its account data
+pub trait PoolDecoder: Send + Sync {
+    fn pool_address(&self, token_mint: &Pubkey) -> Result<Pubkey>;
+    fn decode(&self, data: &[u8]) -> Result<PoolReserves>;
+}
+
+// Pump.fun bonding curves: one PDA per mint holding the real reserves after an
+// 8 byte discriminator and the two virtual reserve fields
+pub struct BondingCurveDecoder {
+    program_id: Pubkey,
+    token_decimals: u32,
+}
+
+impl BondingCurveDecoder {
+    pub fn new(config: &Config) -> Result<Self> {
+        let program_id = config.get_string("sniping_core.buy_engine.pool_liquidity.program_id")
+            .unwrap_or_else(|_| PUMP_FUN_PROGRAM.to_string());
+        Ok(Self {
+            program_id: Pubkey::from_str(&program_id)?,
+            token_decimals: config.get_int("sniping_core.buy_engine.pool_liquidity.token_decimals").unwrap_or(6) as u32,
+        })
+    }
+
+    fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+        let bytes = data.get(offset..offset + 8)
+            .ok_or_else(|| anyhow::anyhow!("Pool account too short: {} bytes", data.len()))?;
+        Ok(u64::from_le_bytes(bytes.try_into()?))
+    }
+}
+
+impl PoolDecoder for BondingCurveDecoder {
+    fn pool_address(&self, token_mint: &Pubkey) -> Result<Pubkey> {
+        let (address, _) = Pubkey::find_program_address(&[b"bonding-curve", token_mint.as_ref()], &self.program_id);
+        Ok(address)
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<PoolReserves> {
+        let real_token_reserves = Self::read_u64(data, 24)?;
+        let real_sol_reserves = Self::read_u64(data, 32)?;
+        Ok(PoolReserves {
+            sol: real_sol_reserves as f64 / LAMPORTS_PER_SOL,
+            token: real_token_reserves as f64 / 10f64.powi(self.token_decimals as i32),
+        })
+    }
+}
+
+// Reads a token's pool straight from chain and values it in USD. A constant product
+// pool holds equal value on both sides, so the total is twice the SOL side.
+pub struct PoolLiquidity {
+    rpc_manager: Arc<RpcClientManager>,
+    decoder: Arc<dyn PoolDecoder>,
+    price_feed: Arc<PriceFeed>,
+    pools: RwLock<HashMap<String, Pubkey>>, // Pool address per token, resolved once
+}
+
+impl PoolLiquidity {
+    pub fn new(rpc_manager: Arc<RpcClientManager>, decoder: Arc<dyn PoolDecoder>, price_feed: Arc<PriceFeed>) -> Self {
+        Self {
+            rpc_manager,
+            decoder,
+            price_feed,
+            pools: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn liquidity_from_account(decoder: &dyn PoolDecoder, data: &[u8], sol_usd: f64) -> Result<f64> {
+        let reserves = decoder.decode(data)?;
+        Ok(reserves.sol * 2.0 * sol_usd)
+    }
+
+    fn pool_for(&self, token_address: &str) -> Result<Pubkey> {
+        if let Some(pool) = self.pools.read().unwrap().get(token_address) {
+            return Ok(*pool);
+        }
+
+        let pool = self.decoder.pool_address(&Pubkey::from_str(token_address)?)?;
+        debug!("Resolved pool {} for token {}", pool, token_address);
+        self.pools.write().unwrap().insert(token_address.to_string(), pool);
+        Ok(pool)
+    }
+
+    pub fn cached_pools(&self) -> usize {
+        self.pools.read().unwrap().len()
+    }
+
+    async fn account_data(&self, token_address: &str) -> Result<Vec<u8>> {
+        let pool = self.pool_for(token_address)?;
+        self.rpc_manager
+            .execute_failover(|client| client.get_account_data(&pool).map_err(Into::into))
+            .await
+    }
+}
+
+#[async_trait]
+impl LiquiditySource for PoolLiquidity {
+    async fn liquidity(&self, token_address: &str) -> Result<f64> {
+        let data = self.account_data(token_address).await?;
+        let (sol_usd, _) = self.price_feed.price(SOL_MINT).await?;
+        Self::liquidity_from_account(self.decoder.as_ref(), &data, sol_usd)
+    }
+
+    // Buys pay SOL in, so the SOL side is the reserve that absorbs them
+    async fn reserve_in(&self, token_address: &str) -> Result<f64> {
+        let data = self.account_data(token_address).await?;
+        Ok(self.decoder.decode(&data)?.sol)
+    }
+}
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::{Config, ConfigError};
+use log::warn;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceSource {
+    Jupiter,
+    Raydium,
+    PumpFun,
+    Birdeye,
+}
+
+#[async_trait]
+pub trait PriceProvider: Send + Sync {
+    fn source(&self) -> PriceSource;
+    async fn price(&self, token_address: &str) -> Result<f64>;
+}
+
+// Pins one mint to a single source, e.g. a token that only trades on pump.fun
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceSourceOverride {
+    pub mint: String,
+    pub source: PriceSource,
+}
+
+// Prices a token from its override source if it has one, otherwise from the first
+// source in the default chain that answers
+pub struct PriceFeed {
+    providers: HashMap<PriceSource, Arc<dyn PriceProvider>>,
+    default_chain: Vec<PriceSource>,
+    overrides: HashMap<String, PriceSource>,
+}
+
+impl PriceFeed {
+    pub fn new(config: &Config) -> Result<Self> {
+        let default_chain = match config.get::<Vec<PriceSource>>("sniping_core.price_feed.default_chain") {
+            Ok(chain) => chain,
+            Err(ConfigError::NotFound(_)) => vec![PriceSource::Jupiter, PriceSource::Raydium, PriceSource::PumpFun],
+            Err(e) => return Err(e.into()),
+        };
+        // An array of tables rather than a map: config keys are lowercased, mints are not
+        let overrides = match config.get::<Vec<PriceSourceOverride>>("sniping_core.price_feed.overrides") {
+            Ok(overrides) => overrides,
+            Err(ConfigError::NotFound(_)) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self {
+            providers: HashMap::new(),
+            default_chain,
+            overrides: overrides.into_iter().map(|o| (o.mint, o.source)).collect(),
+        })
+    }
+
+    pub fn register(&mut self, provider: Arc<dyn PriceProvider>) {
+        self.providers.insert(provider.source(), provider);
+    }
+
+    pub fn source_for(&self, token_address: &str) -> Option<PriceSource> {
+        self.overrides.get(token_address).copied()
+    }
+
+    pub async fn price(&self, token_address: &str) -> Result<(f64, PriceSource)> {
+        // An overridden token is only priced from its designated source; falling back to
+        // a source the operator ruled out would defeat the override
+        if let Some(source) = self.source_for(token_address) {
+            let provider = self.providers.get(&source).ok_or_else(|| {
+                anyhow::anyhow!("No {:?} provider registered for overridden token {}", source, token_address)
+            })?;
+            return Ok((provider.price(token_address).await?, source));
+        }
+
+        for source in &self.default_chain {
+            let provider = match self.providers.get(source) {
+                Some(provider) => provider,
+                None => continue,
+            };
+            match provider.price(token_address).await {
+                Ok(price) => return Ok((price, *source)),
+                Err(e) => warn!("{:?} price lookup failed for {}: {}", source, token_address, e),
+            }
+        }
+
+        Err(anyhow::anyhow!("No price source in the default chain could price {}", token_address))
+    }
+}
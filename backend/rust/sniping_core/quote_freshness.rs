@@ -0,0 +1,107 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use config::Config;
+use log::{info, warn};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+// Price and impact for buying into a pool, as of `quoted_at`
+#[derive(Debug, Clone)]
+pub struct EntryQuote {
+    pub price: f64,
+    pub price_impact: f64,
+    pub quoted_at: Instant,
+}
+
+impl EntryQuote {
+    pub fn age(&self) -> Duration {
+        self.quoted_at.elapsed()
+    }
+}
+
+#[async_trait]
+pub trait EntryQuoter: Send + Sync {
+    async fn quote_buy(&self, token_address: &str, amount: f64) -> Result<EntryQuote>;
+}
+
+// What came out of the build step, together with the quote it was built from
+#[derive(Debug)]
+pub struct QuotedBuild<T> {
+    pub built: T,
+    pub quote: EntryQuote,
+    pub requotes: u32,
+}
+
+// During congestion building and signing can take long enough for the quote behind a
+// transaction to go stale. When the quote has outlived `max_age` by the time the build
+// finishes, the buy is re-quoted and rebuilt rather than submitted at an old price, and
+// abandoned when the new quote can't be fetched or no longer fits the slippage budget.
+pub struct QuoteFreshness {
+    max_age: Option<Duration>, // None disables re-quoting
+    max_requotes: u32,
+}
+
+impl QuoteFreshness {
+    pub fn new(config: &Config) -> Result<Self> {
+        let max_age = if config.get_bool("sniping_core.buy_engine.quote_freshness.enabled").unwrap_or(false) {
+            Some(Duration::from_millis(
+                config.get_int("sniping_core.buy_engine.quote_freshness.max_age_ms")? as u64
+            ))
+        } else {
+            None
+        };
+        let max_requotes = config.get_int("sniping_core.buy_engine.quote_freshness.max_requotes")
+            .unwrap_or(2) as u32;
+
+        Ok(Self { max_age, max_requotes })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_age.is_some()
+    }
+
+    // Quotes, builds from the quote, and repeats while the quote went stale during the build
+    pub async fn build_with_fresh_quote<T, F, Fut>(
+        &self,
+        token_address: &str,
+        amount: f64,
+        max_slippage: f64,
+        quoter: &dyn EntryQuoter,
+        mut build: F,
+    ) -> Result<QuotedBuild<T>>
+    where
+        F: FnMut(&EntryQuote) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut quote = quoter.quote_buy(token_address, amount).await?;
+        let mut requotes = 0;
+
+        loop {
+            if quote.price_impact > max_slippage {
+                return Err(anyhow::anyhow!("Price impact {} exceeds max slippage {}",
+                                         quote.price_impact, max_slippage));
+            }
+
+            let built = build(&quote).await?;
+
+            let max_age = match self.max_age {
+                Some(max_age) if quote.age() > max_age => max_age,
+                _ => return Ok(QuotedBuild { built, quote, requotes }),
+            };
+            if requotes >= self.max_requotes {
+                return Err(anyhow::anyhow!("Quote for {} still stale after {} re-quotes", token_address, requotes));
+            }
+
+            info!("Quote for {} is {}ms old, above the {}ms limit; re-quoting before submission",
+                  token_address, quote.age().as_millis(), max_age.as_millis());
+            requotes += 1;
+            quote = match quoter.quote_buy(token_address, amount).await {
+                Ok(fresh) => fresh,
+                Err(e) => {
+                    warn!("Re-quote for {} failed, abandoning the buy: {}", token_address, e);
+                    return Err(e.context(format!("Re-quote for {} failed", token_address)));
+                }
+            };
+        }
+    }
+}
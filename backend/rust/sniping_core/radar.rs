@@ -1,20 +1,34 @@
 use anyhow::Result;
 use config::Config;
 use log::{info, error, warn};
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{mpsc, RwLock};
+use tokio::task::JoinHandle;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status::UiTransactionEncoding;
+use crate::rpc::{LogsNotification, RpcClientManager};
 use crate::sniping_core::SnipingState;
 
 pub struct Radar {
     id: String,
     state: Arc<RwLock<SnipingState>>,
-    is_active: bool,
+    is_active: AtomicBool,
     scan_interval: u64,
     min_liquidity: f64,
     min_holders: u32,
     min_market_cap: f64,
-    monitored_pairs: Vec<String>,
-    opportunities: Vec<TokenOpportunity>,
+    monitored_pairs: Mutex<Vec<String>>,
+    max_monitored_pairs: usize, // Oldest pairs are dropped past this
+    opportunities: Mutex<Vec<TokenOpportunity>>,
+    pool_programs: Vec<Pubkey>, // DEX programs whose new pools are picked up as they are initialized
+    pool_init_log: String, // Log line of the pool programs' initialize instruction
+    pool_account_index: usize, // Position of the pool among that instruction's accounts
+    event_buffer: usize, // Pool events queued before new ones are dropped
+    rpc_manager: Option<Arc<RpcClientManager>>, // Without one the radar only polls
 }
 
 #[derive(Debug, Clone)]
@@ -36,37 +50,81 @@ impl Radar {
         let min_liquidity = config.get_float("sniping_core.radar.min_liquidity")? as f64;
         let min_holders = config.get_int("sniping_core.radar.min_holders")? as u32;
         let min_market_cap = config.get_float("sniping_core.radar.min_market_cap")? as f64;
+        let pool_programs = config.get::<Vec<String>>("sniping_core.radar.pool_programs")
+            .unwrap_or_default()
+            .iter()
+            .map(|program| Pubkey::from_str(program))
+            .collect::<Result<Vec<_>, _>>()?;
+        let pool_init_log = config.get_string("sniping_core.radar.pool_init_log")
+            .unwrap_or_else(|_| "initialize2".to_string());
+        let pool_account_index = config.get_int("sniping_core.radar.pool_account_index").unwrap_or(4) as usize;
+        let event_buffer = config.get_int("sniping_core.radar.event_buffer").unwrap_or(1024) as usize;
+        let max_monitored_pairs = config.get_int("sniping_core.radar.max_monitored_pairs").unwrap_or(500) as usize;
 
         Ok(Self {
             id: uuid::Uuid::new_v4().to_string(),
             state,
-            is_active: false,
+            is_active: AtomicBool::new(false),
             scan_interval,
             min_liquidity,
             min_holders,
             min_market_cap,
-            monitored_pairs: Vec::new(),
-            opportunities: Vec::new(),
+            monitored_pairs: Mutex::new(Vec::new()),
+            max_monitored_pairs,
+            opportunities: Mutex::new(Vec::new()),
+            pool_programs,
+            pool_init_log,
+            pool_account_index,
+            event_buffer,
+            rpc_manager: None,
         })
     }
 
-    pub async fn init(&mut self, config: &Config) -> Result<()> {
+    pub fn set_rpc_manager(&mut self, rpc_manager: Arc<RpcClientManager>) {
+        self.rpc_manager = Some(rpc_manager);
+    }
+
+    pub async fn init(&self, config: &Config) -> Result<()> {
         // Initialize monitoring pairs from config
         let pairs = config.get_array("sniping_core.radar.monitored_pairs")?;
+        let mut monitored_pairs = self.monitored_pairs.lock().unwrap();
         for pair in pairs {
-            self.monitored_pairs.push(pair.to_string());
+            monitored_pairs.push(pair.to_string());
         }
 
         info!("Radar {} initialized with {} pairs to monitor", 
-              self.id, self.monitored_pairs.len());
+              self.id, monitored_pairs.len());
         Ok(())
     }
 
-    pub async fn start_scanning(&mut self) -> Result<()> {
-        self.is_active = true;
+    pub async fn start_scanning(&self) -> Result<()> {
+        self.is_active.store(true, Ordering::SeqCst);
         info!("Radar {} started scanning", self.id);
 
-        while self.is_active {
+        // Pools arrive as the DEX creates them; polling is the fallback without a subscription
+        if let Some((mut pool_events, subscriptions)) = self.subscribe_pool_programs().await {
+            while self.is_active() {
+                tokio::select! {
+                    event = pool_events.recv() => match event {
+                        Some((program_id, notification)) => {
+                            if let Err(e) = self.handle_pool_event(program_id, notification).await {
+                                warn!("Radar {} failed to handle pool event: {}", self.id, e);
+                            }
+                        }
+                        None => {
+                            warn!("Radar {} pool subscriptions ended, falling back to polling", self.id);
+                            break;
+                        }
+                    },
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(self.scan_interval)) => {
+                        self.cleanup_opportunities().await?;
+                    }
+                }
+            }
+            subscriptions.iter().for_each(|subscription| subscription.abort());
+        }
+
+        while self.is_active() {
             if let Err(e) = self.scan_opportunities().await {
                 error!("Radar {} scanning error: {}", self.id, e);
             }
@@ -76,16 +134,84 @@ impl Radar {
         Ok(())
     }
 
-    async fn scan_opportunities(&mut self) -> Result<()> {
-        let state = self.state.read().await;
-        
+    // None when there is nothing to subscribe to or every subscription failed
+    async fn subscribe_pool_programs(&self) -> Option<(mpsc::Receiver<(Pubkey, LogsNotification)>, Vec<JoinHandle<()>>)> {
+        let rpc_manager = self.rpc_manager.as_ref()?;
+        if self.pool_programs.is_empty() {
+            return None;
+        }
+
+        let (tx, rx) = mpsc::channel(self.event_buffer);
+        let mut subscriptions = Vec::new();
+        for program_id in &self.pool_programs {
+            let tx = tx.clone();
+            let program = *program_id;
+            let pool_init_log = self.pool_init_log.clone();
+            let radar_id = self.id.clone();
+            match rpc_manager.subscribe_logs(*program_id, move |notification| {
+                if !notification.logs.iter().any(|line| line.contains(&pool_init_log)) {
+                    return;
+                }
+                if tx.try_send((program, notification)).is_err() {
+                    warn!("Radar {} pool event buffer is full, dropping a new pool", radar_id);
+                }
+            }).await {
+                Ok(subscription) => subscriptions.push(subscription),
+                Err(e) => warn!("Radar {} could not subscribe to program {}: {}", self.id, program_id, e),
+            }
+        }
+        (!subscriptions.is_empty()).then_some((rx, subscriptions))
+    }
+
+    // A transaction that ran a pool program's initialize instruction created the pool
+    // found among that instruction's accounts
+    async fn handle_pool_event(&self, program_id: Pubkey, notification: LogsNotification) -> Result<()> {
+        if !self.state.read().await.is_active {
+            return Ok(());
+        }
+        let pair_address = self.initialized_pool(&program_id, &notification).await?.to_string();
+        if self.monitored_pairs.lock().unwrap().contains(&pair_address) {
+            return Ok(());
+        }
+
+        info!("Radar {} detected new pool {} at slot {}", self.id, pair_address, notification.slot);
+        self.add_pair_to_monitor(&pair_address).await?;
+        self.analyze_pair(&pair_address).await
+    }
+
+    async fn initialized_pool(&self, program_id: &Pubkey, notification: &LogsNotification) -> Result<Pubkey> {
+        let rpc_manager = self.rpc_manager.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Radar {} has no RPC manager", self.id))?;
+        let signature = notification.signature;
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        };
+        let confirmed = rpc_manager
+            .execute_failover(|client| Ok(client.get_transaction_with_config(&signature, config)?))
+            .await?;
+        let transaction = confirmed.transaction.transaction.decode()
+            .ok_or_else(|| anyhow::anyhow!("Transaction {} could not be decoded", signature))?;
+
+        // Pools created through a lookup table aren't resolved; their keys aren't static
+        let keys = transaction.message.static_account_keys();
+        transaction.message.instructions().iter()
+            .find(|instruction| keys.get(instruction.program_id_index as usize) == Some(program_id))
+            .and_then(|instruction| instruction.accounts.get(self.pool_account_index))
+            .and_then(|index| keys.get(*index as usize))
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("No pool account in {}'s instruction in {}", program_id, signature))
+    }
+
+    async fn scan_opportunities(&self) -> Result<()> {
         // Skip if sniping core is not active
-        if !state.is_active {
+        if !self.state.read().await.is_active {
             return Ok(());
         }
 
         // Scan each monitored pair
-        for pair in &self.monitored_pairs {
+        for pair in &self.get_monitored_pairs() {
             if let Err(e) = self.analyze_pair(pair).await {
                 warn!("Error analyzing pair {}: {}", pair, e);
             }
@@ -97,7 +223,7 @@ impl Radar {
         Ok(())
     }
 
-    async fn analyze_pair(&mut self, pair_address: &str) -> Result<()> {
+    async fn analyze_pair(&self, pair_address: &str) -> Result<()> {
         // Placeholder for pair analysis logic
         // This would involve:
         // 1. Fetching pair data from DEX
@@ -121,7 +247,7 @@ impl Radar {
 
         // Add opportunity if it meets criteria
         if self.evaluate_opportunity(&opportunity) {
-            self.opportunities.push(opportunity);
+            self.opportunities.lock().unwrap().push(opportunity);
         }
 
         Ok(())
@@ -134,11 +260,11 @@ impl Radar {
         opportunity.risk_score < 0.7 // Risk threshold
     }
 
-    async fn cleanup_opportunities(&mut self) -> Result<()> {
+    async fn cleanup_opportunities(&self) -> Result<()> {
         let now = chrono::Utc::now();
         let max_age = chrono::Duration::minutes(5);
 
-        self.opportunities.retain(|opp| {
+        self.opportunities.lock().unwrap().retain(|opp| {
             now - opp.created_at < max_age
         });
 
@@ -146,27 +272,33 @@ impl Radar {
     }
 
     pub async fn get_opportunities(&self) -> Vec<TokenOpportunity> {
-        self.opportunities.clone()
+        self.opportunities.lock().unwrap().clone()
     }
 
-    pub async fn add_pair_to_monitor(&mut self, pair_address: String) -> Result<()> {
-        if !self.monitored_pairs.contains(&pair_address) {
-            self.monitored_pairs.push(pair_address);
+    pub async fn add_pair_to_monitor(&self, pair_address: &str) -> Result<()> {
+        let mut monitored_pairs = self.monitored_pairs.lock().unwrap();
+        if !monitored_pairs.iter().any(|p| p == pair_address) {
+            if monitored_pairs.len() >= self.max_monitored_pairs {
+                let dropped = monitored_pairs.remove(0);
+                info!("Radar {} stopped monitoring pair {} to make room", self.id, dropped);
+            }
+            monitored_pairs.push(pair_address.to_string());
             info!("Radar {} added pair {} to monitoring", self.id, pair_address);
         }
         Ok(())
     }
 
-    pub async fn remove_pair_from_monitor(&mut self, pair_address: &str) -> Result<()> {
-        if let Some(pos) = self.monitored_pairs.iter().position(|p| p == pair_address) {
-            self.monitored_pairs.remove(pos);
+    pub async fn remove_pair_from_monitor(&self, pair_address: &str) -> Result<()> {
+        let mut monitored_pairs = self.monitored_pairs.lock().unwrap();
+        if let Some(pos) = monitored_pairs.iter().position(|p| p == pair_address) {
+            monitored_pairs.remove(pos);
             info!("Radar {} removed pair {} from monitoring", self.id, pair_address);
         }
         Ok(())
     }
 
-    pub async fn shutdown(&mut self) -> Result<()> {
-        self.is_active = false;
+    pub async fn shutdown(&self) -> Result<()> {
+        self.is_active.store(false, Ordering::SeqCst);
         info!("Radar {} shutting down", self.id);
         Ok(())
     }
@@ -176,11 +308,11 @@ impl Radar {
         &self.id
     }
 
-    pub fn get_monitored_pairs(&self) -> &[String] {
-        &self.monitored_pairs
+    pub fn get_monitored_pairs(&self) -> Vec<String> {
+        self.monitored_pairs.lock().unwrap().clone()
     }
 
     pub fn is_active(&self) -> bool {
-        self.is_active
+        self.is_active.load(Ordering::SeqCst)
     }
 } 
\ No newline at end of file
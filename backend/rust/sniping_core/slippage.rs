@@ -0,0 +1,100 @@
+use anyhow::Result;
+use config::Config;
+use log::info;
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, VecDeque};
+
+// Tokens are grouped by pool depth, since thin pools fill worse than deep ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum LiquidityClass {
+    Thin,
+    #[default]
+    Moderate,
+    Deep,
+}
+
+impl LiquidityClass {
+    pub fn from_liquidity(liquidity: f64) -> Self {
+        if liquidity < 50_000.0 {
+            LiquidityClass::Thin
+        } else if liquidity < 250_000.0 {
+            LiquidityClass::Moderate
+        } else {
+            LiquidityClass::Deep
+        }
+    }
+}
+
+// Slippage tolerance learned from how far recent fills landed from their quotes.
+// Each class tolerates `headroom` times its average recent shortfall, clamped to
+// [min_slippage, max_slippage]; classes without history use the configured base.
+pub struct AdaptiveSlippage {
+    enabled: bool,
+    base_slippage: f64,
+    min_slippage: f64,
+    max_slippage: f64,
+    headroom: f64,
+    window: usize,
+    shortfalls: HashMap<LiquidityClass, VecDeque<f64>>,
+}
+
+impl AdaptiveSlippage {
+    pub fn new(config: &Config, base_slippage: f64) -> Result<Self> {
+        let enabled = config.get_bool("sniping_core.buy_engine.adaptive_slippage.enabled").unwrap_or(false);
+        let (min_slippage, max_slippage, headroom, window) = if enabled {
+            (
+                config.get_float("sniping_core.buy_engine.adaptive_slippage.min_slippage")?,
+                config.get_float("sniping_core.buy_engine.adaptive_slippage.max_slippage")?,
+                config.get_float("sniping_core.buy_engine.adaptive_slippage.headroom")?,
+                config.get_int("sniping_core.buy_engine.adaptive_slippage.window")? as usize,
+            )
+        } else {
+            (base_slippage, base_slippage, 1.0, 1)
+        };
+
+        if min_slippage > max_slippage {
+            return Err(anyhow::anyhow!("Adaptive slippage min {} exceeds max {}", min_slippage, max_slippage));
+        }
+
+        Ok(Self {
+            enabled,
+            base_slippage,
+            min_slippage,
+            max_slippage,
+            headroom,
+            window: window.max(1),
+            shortfalls: HashMap::new(),
+        })
+    }
+
+    // Buys that fill above the quote fall short; fills at or better than the quote count as zero
+    pub fn record_fill(&mut self, class: LiquidityClass, quoted_price: f64, realized_price: f64) {
+        if !self.enabled || quoted_price <= 0.0 {
+            return;
+        }
+
+        let shortfall = ((realized_price - quoted_price) / quoted_price).max(0.0);
+        let history = self.shortfalls.entry(class).or_default();
+        history.push_back(shortfall);
+        while history.len() > self.window {
+            history.pop_front();
+        }
+
+        info!("Recorded {:?} fill {:.2}% from quote, slippage now {:.2}%",
+              class, shortfall * 100.0, self.effective_slippage(class) * 100.0);
+    }
+
+    pub fn effective_slippage(&self, class: LiquidityClass) -> f64 {
+        if !self.enabled {
+            return self.base_slippage;
+        }
+
+        match self.shortfalls.get(&class) {
+            Some(history) if !history.is_empty() => {
+                let average = history.iter().sum::<f64>() / history.len() as f64;
+                (average * self.headroom).clamp(self.min_slippage, self.max_slippage)
+            }
+            _ => self.base_slippage.clamp(self.min_slippage, self.max_slippage),
+        }
+    }
+}
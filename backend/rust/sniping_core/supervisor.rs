@@ -0,0 +1,91 @@
+use anyhow::Result;
+use chrono::Utc;
+use config::Config;
+use log::{info, error, warn};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use crate::common::{AlertSeverity, ColonyAlert, Message, MessageQueue};
+use crate::sniping_core::SnipingState;
+
+// Runs the sniping core's background loops, restarting one that returns an error with an
+// exponential backoff. A loop that keeps failing is given up on after `max_restarts`; the
+// core is then marked degraded and a critical ColonyAlert naming the component is published.
+#[derive(Clone)]
+pub struct Supervisor {
+    max_restarts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    state: Arc<RwLock<SnipingState>>,
+    message_queue: Option<MessageQueue>,
+}
+
+impl Supervisor {
+    pub fn new(config: &Config, state: Arc<RwLock<SnipingState>>) -> Self {
+        Self {
+            max_restarts: config.get_int("sniping_core.supervisor.max_restarts").unwrap_or(5) as u32,
+            initial_backoff: Duration::from_millis(
+                config.get_int("sniping_core.supervisor.backoff_ms").unwrap_or(1000) as u64
+            ),
+            max_backoff: Duration::from_millis(
+                config.get_int("sniping_core.supervisor.max_backoff_ms").unwrap_or(30_000) as u64
+            ),
+            state,
+            message_queue: None,
+        }
+    }
+
+    pub fn set_message_queue(&mut self, message_queue: MessageQueue) {
+        self.message_queue = Some(message_queue);
+    }
+
+    // `run` starts the loop afresh on every attempt. A loop that returns Ok is done and
+    // isn't restarted.
+    pub fn supervise<F, Fut>(&self, component: &str, mut run: F) -> JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send,
+    {
+        let supervisor = self.clone();
+        let component = component.to_string();
+        tokio::spawn(async move {
+            let mut restarts = 0;
+            let mut backoff = supervisor.initial_backoff;
+            loop {
+                match run().await {
+                    Ok(()) => {
+                        info!("{} stopped", component);
+                        return;
+                    }
+                    Err(e) if restarts >= supervisor.max_restarts => {
+                        error!("{} failed after {} restarts, giving up: {}", component, restarts, e);
+                        supervisor.mark_degraded(&component).await;
+                        return;
+                    }
+                    Err(e) => {
+                        restarts += 1;
+                        warn!("{} failed, restarting in {:?} ({}/{}): {}",
+                              component, backoff, restarts, supervisor.max_restarts, e);
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(supervisor.max_backoff);
+                    }
+                }
+            }
+        })
+    }
+
+    async fn mark_degraded(&self, component: &str) {
+        self.state.write().await.degraded_components.push(component.to_string());
+        if let Some(message_queue) = &self.message_queue {
+            message_queue.publish(Message::ColonyAlert(ColonyAlert {
+                source: "sniping_core".to_string(),
+                token_address: None,
+                severity: AlertSeverity::Critical,
+                message: format!("{} stopped after exhausting its restarts, sniping core is degraded", component),
+                timestamp: Utc::now(),
+            })).await;
+        }
+    }
+}
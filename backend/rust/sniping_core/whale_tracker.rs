@@ -0,0 +1,213 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use config::{Config, ConfigError};
+use futures_util::stream::{select_all, StreamExt};
+use log::{info, error, warn};
+use serde_json::json;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+use std::sync::{Mutex, RwLock};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use crate::config::ConfigVersion;
+
+const RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+// Follows a set of known profitable wallets and remembers which tokens they have been
+// buying. A buy is any transaction mentioning the wallet that leaves it holding more of a
+// mint than before. The wallet list comes from `sniping_core.whale_tracker.wallets` and can
+// be swapped at runtime with `reload`.
+pub struct WhaleTracker {
+    ws_url: String,
+    rpc_client: RpcClient,
+    window: chrono::Duration, // Buys older than this no longer count
+    wallets: RwLock<HashSet<String>>,
+    wallets_changed: watch::Sender<()>,
+    buys: Mutex<HashMap<String, VecDeque<DateTime<Utc>>>>, // Keyed by token
+}
+
+impl WhaleTracker {
+    pub fn new(config: &Config) -> Result<Self> {
+        let (wallets_changed, _) = watch::channel(());
+        // Same endpoints as the rest of the colony, from rpc.toml
+        let rpc_url = config.get_string("helius.mainnet")?;
+        let ws_url = config.get_string("helius.ws_mainnet")
+            .unwrap_or_else(|_| rpc_url.replacen("http", "ws", 1));
+
+        Ok(Self {
+            ws_url,
+            rpc_client: RpcClient::new(rpc_url),
+            window: chrono::Duration::seconds(
+                config.get_int("sniping_core.whale_tracker.window_secs").unwrap_or(600)
+            ),
+            wallets: RwLock::new(Self::read_wallets(config)?),
+            wallets_changed,
+            buys: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn read_wallets(config: &Config) -> Result<HashSet<String>> {
+        let wallets = match config.get::<Vec<String>>("sniping_core.whale_tracker.wallets") {
+            Ok(wallets) => wallets,
+            Err(ConfigError::NotFound(_)) => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        for wallet in &wallets {
+            Pubkey::from_str(wallet)
+                .map_err(|e| anyhow::anyhow!("Invalid whale wallet {}: {}", wallet, e))?;
+        }
+        Ok(wallets.into_iter().collect())
+    }
+
+    // Re-reads the watched wallets; a running tracker resubscribes to the new set. A bad
+    // list is rejected and the current one kept.
+    pub fn reload(&self, config: &Config) -> Result<()> {
+        let wallets = Self::read_wallets(config)?;
+        let changed = {
+            let mut current = self.wallets.write().unwrap();
+            let changed = *current != wallets;
+            *current = wallets;
+            changed
+        };
+        if changed {
+            info!("Whale tracker now watching {} wallets", self.wallets.read().unwrap().len());
+            self.wallets_changed.send_replace(());
+        }
+        Ok(())
+    }
+
+    // Calls `reload` with a freshly loaded config after every config file change
+    pub fn follow_config_changes<F>(self: Arc<Self>, mut changes: watch::Receiver<ConfigVersion>, load: F) -> JoinHandle<()>
+    where
+        F: Fn() -> Result<Config> + Send + 'static,
+    {
+        tokio::spawn(async move {
+            while changes.changed().await.is_ok() {
+                if let Err(e) = load().and_then(|config| self.reload(&config)) {
+                    error!("Whale tracker kept its wallet list, reload failed: {}", e);
+                }
+            }
+        })
+    }
+
+    pub fn watched_wallets(&self) -> Vec<String> {
+        self.wallets.read().unwrap().iter().cloned().collect()
+    }
+
+    // Buys of `token_address` by watched wallets within the window
+    pub fn recent_buys(&self, token_address: &str) -> u32 {
+        let cutoff = Utc::now() - self.window;
+        self.buys.lock().unwrap()
+            .get(token_address)
+            .map_or(0, |buys| buys.iter().filter(|at| **at >= cutoff).count() as u32)
+    }
+
+    // Buys by wallets that aren't watched are ignored
+    pub fn record_buy(&self, wallet: &str, token_address: &str) {
+        if !self.wallets.read().unwrap().contains(wallet) {
+            return;
+        }
+
+        let now = Utc::now();
+        let cutoff = now - self.window;
+        let mut buys = self.buys.lock().unwrap();
+        // Forget tokens nobody has bought within the window so the map stays bounded
+        buys.retain(|_, token_buys| token_buys.back().map_or(false, |at| *at >= cutoff));
+        let token_buys = buys.entry(token_address.to_string()).or_default();
+        while token_buys.front().map_or(false, |at| *at < cutoff) {
+            token_buys.pop_front();
+        }
+        token_buys.push_back(now);
+    }
+
+    // Subscribes to the logs of every watched wallet, reconnecting on errors and
+    // resubscribing whenever the wallet list changes
+    pub fn start(self: Arc<Self>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut wallets_changed = self.wallets_changed.subscribe();
+            loop {
+                wallets_changed.borrow_and_update();
+                if let Err(e) = self.follow_wallets(&mut wallets_changed).await {
+                    warn!("Whale tracker subscription failed, reconnecting: {}", e);
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            }
+        })
+    }
+
+    // Returns Ok once the wallet list changes so the caller resubscribes
+    async fn follow_wallets(&self, wallets_changed: &mut watch::Receiver<()>) -> Result<()> {
+        let wallets = self.watched_wallets();
+        if wallets.is_empty() {
+            let _ = wallets_changed.changed().await;
+            return Ok(());
+        }
+
+        let pubsub = PubsubClient::new(&self.ws_url).await?;
+        let mut streams = Vec::new();
+        for wallet in wallets {
+            let (stream, _unsubscribe) = pubsub.logs_subscribe(
+                RpcTransactionLogsFilter::Mentions(vec![wallet.clone()]),
+                RpcTransactionLogsConfig { commitment: Some(CommitmentConfig::confirmed()) },
+            ).await?;
+            streams.push(stream.map(move |response| (wallet.clone(), response.value)).boxed());
+        }
+        let mut logs = select_all(streams);
+
+        loop {
+            tokio::select! {
+                _ = wallets_changed.changed() => return Ok(()),
+                next = logs.next() => {
+                    let Some((wallet, response)) = next else {
+                        return Err(anyhow::anyhow!("Log subscriptions closed"));
+                    };
+                    if response.err.is_some() {
+                        continue;
+                    }
+                    match self.fetch_token_buys(&wallet, &response.signature).await {
+                        Ok(tokens) => tokens.iter().for_each(|token| self.record_buy(&wallet, token)),
+                        Err(e) => warn!("Whale tracker skipped {}: {}", response.signature, e),
+                    }
+                }
+            }
+        }
+    }
+
+    async fn fetch_token_buys(&self, wallet: &str, signature: &str) -> Result<Vec<String>> {
+        let transaction: serde_json::Value = self.rpc_client.send(
+            RpcRequest::GetTransaction,
+            json!([signature, {
+                "encoding": "json",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        ).await?;
+        Ok(Self::token_buys(wallet, &transaction))
+    }
+
+    // Mints whose balance owned by `wallet` grew in a getTransaction result
+    pub fn token_buys(wallet: &str, transaction: &serde_json::Value) -> Vec<String> {
+        let balances = |key: &str| -> HashMap<String, u128> {
+            transaction["meta"][key].as_array().into_iter().flatten()
+                .filter(|balance| balance["owner"].as_str() == Some(wallet))
+                .filter_map(|balance| Some((
+                    balance["mint"].as_str()?.to_string(),
+                    balance["uiTokenAmount"]["amount"].as_str()?.parse().ok()?,
+                )))
+                .collect()
+        };
+        let before = balances("preTokenBalances");
+
+        balances("postTokenBalances").into_iter()
+            .filter(|(mint, amount)| *amount > before.get(mint).copied().unwrap_or(0))
+            .map(|(mint, _)| mint)
+            .collect()
+    }
+}
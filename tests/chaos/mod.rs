@@ -4,7 +4,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 use rand::Rng;
 use crate::rpc::RpcClientManager;
-use crate::common::MessageQueue;
+use crate::common::{MessageQueue, OverflowPolicy};
 
 pub struct ChaosTest {
     network_delay: Duration,
@@ -41,6 +41,7 @@ impl ChaosTest {
         self.test_network_delays().await?;
 
         // Test RPC failures
+        #[cfg(feature = "fault-injection")]
         self.test_rpc_failures().await?;
 
         // Test message queue reliability
@@ -74,37 +75,41 @@ impl ChaosTest {
         Ok(())
     }
 
+    // Faults are injected into the real manager, so failures go through its error tracking
+    #[cfg(feature = "fault-injection")]
     async fn test_rpc_failures(&self) -> Result<()> {
         println!("Testing RPC failures...");
         
         let mut success_count = 0;
         let total_tests = 100;
 
+        self.rpc_manager.set_fault_injection(self.rpc_failure_rate, self.network_delay);
         for i in 0..total_tests {
-            let mut rng = rand::thread_rng();
-            let should_fail = rng.gen_bool(self.rpc_failure_rate);
-
-            if should_fail {
-                // Simulate RPC failure
-                sleep(Duration::from_millis(100)).await;
-                println!("RPC failure test {}: Simulated failure", i);
-            } else {
-                // Attempt RPC operation
-                match self.rpc_manager.get_client(crate::rpc::RpcProvider::Helius).await {
-                    Ok(_) => {
-                        success_count += 1;
-                        println!("RPC failure test {}: Success", i);
-                    }
-                    Err(e) => {
-                        println!("RPC failure test {}: Actual failure: {}", i, e);
-                    }
+            let start = std::time::Instant::now();
+            match self.rpc_manager.get_client(crate::rpc::RpcProvider::Helius).await {
+                Ok(_) => {
+                    success_count += 1;
+                    println!("RPC failure test {}: Success", i);
+                }
+                Err(e) => {
+                    self.rpc_manager.error_tracker().record(crate::rpc::RpcProvider::Helius, &e);
+                    println!("RPC failure test {}: Failure: {}", i, e);
                 }
             }
+            assert!(start.elapsed() >= self.network_delay);
         }
+        self.rpc_manager.clear_fault_injection();
 
         let success_rate = success_count as f64 / total_tests as f64;
         println!("RPC failure test results: {:.2}% success rate", success_rate * 100.0);
 
+        // Binomial spread over 100 calls stays well inside this
+        let expected = 1.0 - self.rpc_failure_rate;
+        assert!(
+            (success_rate - expected).abs() <= 0.15,
+            "success rate {:.2} too far from expected {:.2}", success_rate, expected
+        );
+
         Ok(())
     }
 
@@ -112,7 +117,9 @@ impl ChaosTest {
         println!("Testing message queue reliability...");
         
         let subscriber_id = "chaos_test_subscriber".to_string();
-        let mut receiver = self.message_queue.subscribe(subscriber_id.clone()).await;
+        let mut receiver = self.message_queue
+            .subscribe(subscriber_id.clone(), 50, OverflowPolicy::Block)
+            .await;
         
         // Send test messages
         for i in 0..50 {
@@ -126,9 +133,9 @@ impl ChaosTest {
             self.message_queue.publish(message).await;
         }
 
-        // Verify message delivery
+        // Verify message delivery; everything published is already buffered
         let mut received_count = 0;
-        while let Some(_) = receiver.recv().await {
+        while let Some(_) = receiver.try_recv() {
             received_count += 1;
         }
 
@@ -1,5 +1,5 @@
 use antbot::{
-    common::{Message, MessageQueue, TradeSignal, RiskUpdate, LiquidityAlert, WalletInfo},
+    common::{Message, MessageQueue, TradeSignal, RiskUpdate, LiquidityAlert, WalletInfo, OverflowPolicy},
     config::ConfigManager,
     rpc::RpcClientManager,
     api::WebSocketServer,
@@ -105,7 +105,9 @@ async fn test_sniping_core(
     message_queue.publish(Message::TradeSignal(trade_signal)).await;
     
     // Verify buy execution
-    let mut receiver = message_queue.subscribe("test_buy_verification".to_string()).await;
+    let mut receiver = message_queue
+        .subscribe("test_buy_verification".to_string(), 100, OverflowPolicy::Block)
+        .await;
     if let Some(Message::TradeSignal(signal)) = receiver.recv().await {
         assert_eq!(signal.token_address, mock_token);
         assert!(signal.confidence >= 0.8);
@@ -155,11 +157,7 @@ async fn test_exit_strategies(
     }
     
     // Test killswitch activation
-    let liquidity_alert = LiquidityAlert {
-        token_address: "TokenA".to_string(),
-        liquidity_drop: 0.8,
-        timestamp: chrono::Utc::now(),
-    };
+    let liquidity_alert = LiquidityAlert::drop("TokenA", 10000.0, 2000.0);
     
     message_queue.publish(Message::LiquidityAlert(liquidity_alert)).await;
     assert!(killswitch.should_activate().await);
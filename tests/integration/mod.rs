@@ -1,16 +1,45 @@
 use antbot::{
-    common::{Message, MessageQueue, TradeSignal, RiskUpdate, LiquidityAlert},
-    config::ConfigManager,
+    common::{
+        Message, MessageQueue, TradeSignal, RiskUpdate, LiquidityAlert,
+        AlertType, AlertSeverity, MessageKind, OverflowPolicy, Metrics,
+        TradeExecutionEvent, TradeEventStatus,
+    },
+    config::{ConfigManager, ConfigVersion, SecretsFilePolicy, check_secrets_permissions},
     rpc::RpcClientManager,
     api::WebSocketServer,
-    logging::Logger,
+    logging::{Logger, LogRotation},
+    ant_colony::{ColonyState, WorkerSnapshot, ProfitTierStatus, TierState, AlertRecord},
+    backend::DashboardWebSocket,
 };
 use anyhow::Result;
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::tungstenite::{Message as WsMessage, protocol::frame::coding::CloseCode};
 use tokio::time::{sleep, Duration};
 use std::path::PathBuf;
 
 mod full_system_workflow;
 
+const DASHBOARD_TOKEN: &str = "test-dashboard-token";
+
+type DashboardClient = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+// Connects and completes the auth handshake
+async fn connect_dashboard(url: &str) -> Result<DashboardClient> {
+    let (mut client, _) = tokio_tungstenite::connect_async(url).await?;
+    client.send(WsMessage::Text(format!(r#"{{"auth":"{}"}}"#, DASHBOARD_TOKEN))).await?;
+    let ack = next_text(&mut client).await?;
+    assert!(ack.contains("\"authenticated\":true"));
+    Ok(client)
+}
+
+async fn next_text(client: &mut DashboardClient) -> Result<String> {
+    let received = tokio::time::timeout(Duration::from_secs(1), client.next())
+        .await?
+        .expect("client stream ended unexpectedly")?;
+    Ok(received.into_text()?)
+}
+
 #[tokio::test]
 async fn test_sniping_ant_colony_integration() -> Result<()> {
     // Initialize components
@@ -33,12 +62,14 @@ async fn test_sniping_ant_colony_integration() -> Result<()> {
         confidence: 0.8,
     };
 
+    // Subscribe to messages; only messages published afterwards are delivered
+    let mut receiver = message_queue
+        .subscribe("test_subscriber".to_string(), 100, OverflowPolicy::Block)
+        .await;
+
     // Publish trade signal
     message_queue.publish(Message::TradeSignal(trade_signal.clone())).await;
 
-    // Subscribe to messages
-    let mut receiver = message_queue.subscribe("test_subscriber".to_string()).await;
-
     // Verify message was received
     if let Some(Message::TradeSignal(received_signal)) = receiver.recv().await {
         assert_eq!(received_signal.token_address, trade_signal.token_address);
@@ -52,17 +83,23 @@ async fn test_sniping_ant_colony_integration() -> Result<()> {
 
 #[tokio::test]
 async fn test_websocket_broadcast() -> Result<()> {
-    let server = WebSocketServer::new();
+    let server = WebSocketServer::new(DASHBOARD_TOKEN);
     let addr = "127.0.0.1:3000".parse().unwrap();
     
     // Start server in background
-    let server_handle = tokio::spawn(async move {
-        server.start(addr).await;
-    });
+    let server_handle = {
+        let server = server.clone();
+        tokio::spawn(async move { server.start(addr).await })
+    };
 
     // Wait for server to start
     sleep(Duration::from_millis(100)).await;
 
+    // Connect two dashboard clients to the same server; both land in the shared registry
+    let mut first = connect_dashboard("ws://127.0.0.1:3000/ws").await?;
+    let mut second = connect_dashboard("ws://127.0.0.1:3000/ws").await?;
+    assert_eq!(server.client_count().await, 2);
+
     // Create test message
     let test_message = Message::RiskUpdate(RiskUpdate {
         position_size: 1000.0,
@@ -74,12 +111,370 @@ async fn test_websocket_broadcast() -> Result<()> {
     // Broadcast message
     server.broadcast_update(test_message).await;
 
+    // Every connected client receives the update
+    for client in [&mut first, &mut second] {
+        let text = next_text(client).await?;
+        assert!(text.contains("\"type\":\"RiskUpdate\""));
+    }
+
     // Cleanup
     server_handle.abort();
 
     Ok(())
 }
 
+#[tokio::test]
+async fn test_closed_websocket_client_is_removed_from_registry() -> Result<()> {
+    let server = WebSocketServer::new(DASHBOARD_TOKEN);
+    let addr = "127.0.0.1:3001".parse().unwrap();
+
+    let server_handle = {
+        let server = server.clone();
+        tokio::spawn(async move { server.start(addr).await })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    let mut client = connect_dashboard("ws://127.0.0.1:3001/ws").await?;
+    assert_eq!(server.client_count().await, 1);
+
+    // The client goes away; a broadcast must not leave it in the registry
+    client.close(None).await?;
+    drop(client);
+    sleep(Duration::from_millis(100)).await;
+
+    server.broadcast_update(Message::RiskUpdate(RiskUpdate {
+        position_size: 1000.0,
+        daily_loss: 50.0,
+        daily_trades: 5,
+        timestamp: chrono::Utc::now(),
+    })).await;
+
+    assert_eq!(server.client_count().await, 0);
+
+    server_handle.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_rate_limit_rejects_request_bursts() -> Result<()> {
+    let server = WebSocketServer::new(DASHBOARD_TOKEN);
+    let addr = "127.0.0.1:3002".parse().unwrap();
+
+    let server_handle = {
+        let server = server.clone();
+        tokio::spawn(async move { server.start(addr).await })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    // Well over the 10 requests/sec quota, all from the same IP
+    let client = reqwest::Client::new();
+    let mut rate_limited = 0;
+    for _ in 0..30 {
+        let response = client.get("http://127.0.0.1:3002/ws").send().await?;
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            rate_limited += 1;
+        }
+    }
+
+    assert!(rate_limited > 0, "expected some requests to be rate limited");
+    assert!(rate_limited < 30, "the first requests fit within the quota");
+
+    server_handle.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_rejects_client_with_bad_auth() -> Result<()> {
+    let server = WebSocketServer::new(DASHBOARD_TOKEN);
+    let addr = "127.0.0.1:3003".parse().unwrap();
+
+    let server_handle = {
+        let server = server.clone();
+        tokio::spawn(async move { server.start(addr).await })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    let (mut client, _) = tokio_tungstenite::connect_async("ws://127.0.0.1:3003/ws").await?;
+    client.send(WsMessage::Text(r#"{"auth":"wrong-token"}"#.to_string())).await?;
+
+    // The server closes with a policy violation and forgets the client
+    let received = tokio::time::timeout(Duration::from_secs(1), client.next())
+        .await?
+        .expect("client stream ended without a close frame")?;
+    match received {
+        WsMessage::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Policy),
+        other => panic!("expected a policy close, got {:?}", other),
+    }
+    assert_eq!(server.authenticated_count().await, 0);
+
+    // Nothing is broadcast to an unauthenticated connection
+    let (mut silent, _) = tokio_tungstenite::connect_async("ws://127.0.0.1:3003/ws").await?;
+    sleep(Duration::from_millis(100)).await;
+    server.broadcast_update(Message::RiskUpdate(RiskUpdate {
+        position_size: 1000.0,
+        daily_loss: 50.0,
+        daily_trades: 5,
+        timestamp: chrono::Utc::now(),
+    })).await;
+    assert!(tokio::time::timeout(Duration::from_millis(200), silent.next()).await.is_err());
+
+    server_handle.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_with_empty_token_accepts_no_client() -> Result<()> {
+    let server = WebSocketServer::new("");
+    let addr = "127.0.0.1:3008".parse().unwrap();
+
+    let server_handle = {
+        let server = server.clone();
+        tokio::spawn(async move { server.start(addr).await })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    // Matching the empty token is not enough to get in
+    let (mut client, _) = tokio_tungstenite::connect_async("ws://127.0.0.1:3008/ws").await?;
+    client.send(WsMessage::Text(r#"{"auth":""}"#.to_string())).await?;
+    let received = tokio::time::timeout(Duration::from_secs(1), client.next())
+        .await?
+        .expect("client stream ended without a close frame")?;
+    match received {
+        WsMessage::Close(Some(frame)) => assert_eq!(frame.code, CloseCode::Policy),
+        other => panic!("expected a policy close, got {:?}", other),
+    }
+    assert_eq!(server.authenticated_count().await, 0);
+
+    server_handle.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_forwards_only_subscribed_topics() -> Result<()> {
+    let server = WebSocketServer::new(DASHBOARD_TOKEN);
+    let addr = "127.0.0.1:3004".parse().unwrap();
+
+    let server_handle = {
+        let server = server.clone();
+        tokio::spawn(async move { server.start(addr).await })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    let mut trades_only = connect_dashboard("ws://127.0.0.1:3004/ws").await?;
+    trades_only.send(WsMessage::Text(r#"{"subscribe":["trade_signal"]}"#.to_string())).await?;
+    assert!(next_text(&mut trades_only).await?.contains("trade_signal"));
+
+    let mut risk_only = connect_dashboard("ws://127.0.0.1:3004/ws").await?;
+    risk_only.send(WsMessage::Text(r#"{"subscribe":["risk_update"]}"#.to_string())).await?;
+    assert!(next_text(&mut risk_only).await?.contains("risk_update"));
+
+    server.broadcast_update(Message::RiskUpdate(RiskUpdate {
+        position_size: 1000.0,
+        daily_loss: 50.0,
+        daily_trades: 5,
+        timestamp: chrono::Utc::now(),
+    })).await;
+
+    assert!(next_text(&mut risk_only).await?.contains("\"type\":\"RiskUpdate\""));
+    assert!(tokio::time::timeout(Duration::from_millis(200), trades_only.next()).await.is_err());
+
+    server_handle.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_websocket_server_returns_after_shutdown_signal() -> Result<()> {
+    let server = WebSocketServer::new(DASHBOARD_TOKEN);
+    let addr = "127.0.0.1:3005".parse().unwrap();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+
+    let server_handle = {
+        let server = server.clone();
+        tokio::spawn(async move {
+            server.start_with_shutdown(addr, async {
+                let _ = shutdown_rx.await;
+            }).await
+        })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    let mut client = connect_dashboard("ws://127.0.0.1:3005/ws").await?;
+
+    shutdown_tx.send(()).expect("server is still waiting for shutdown");
+
+    // The server closes the client's socket and the serve future completes
+    let received = tokio::time::timeout(Duration::from_secs(1), client.next())
+        .await?
+        .expect("client stream ended without a close frame")?;
+    assert!(matches!(received, WsMessage::Close(_)));
+
+    tokio::time::timeout(Duration::from_secs(5), server_handle).await???;
+    assert_eq!(server.client_count().await, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dashboard_payload_reflects_colony_state() -> Result<()> {
+    let now = chrono::Utc::now();
+    let mut state = ColonyState {
+        paper_trading: true,
+        total_profit: 1.5,
+        active_workers: vec![WorkerSnapshot {
+            id: "worker-1".to_string(),
+            is_active: true,
+            balance: 12.0,
+            total_trades: 4,
+            success_rate: 0.75,
+            profit_loss: 1.5,
+            last_active: Some(now),
+        }],
+        profit_tiers: vec![
+            ProfitTierStatus { multiplier: 2.0, percentage: 0.25, status: TierState::Hit, timestamp: Some(now) },
+            ProfitTierStatus { multiplier: 5.0, percentage: 0.25, status: TierState::Pending, timestamp: None },
+        ],
+        ..ColonyState::default()
+    };
+    state.record_fill(2.0, 0.01);
+    state.record_fill(-0.5, 0.01);
+    state.record_alert(AlertRecord {
+        alert_type: "rug_detector".to_string(),
+        severity: AlertSeverity::Critical,
+        details: "Liquidity dropped by 80%".to_string(),
+        timestamp: now,
+    });
+
+    let dashboard = DashboardWebSocket::new(std::sync::Arc::new(tokio::sync::RwLock::new(state)));
+    let payload = dashboard.dashboard_payload().await?;
+
+    assert_eq!(payload["paperTrading"], true);
+    assert_eq!(payload["workers"][0]["id"], "worker-1");
+    assert_eq!(payload["workers"][0]["status"], "active");
+    assert_eq!(payload["workers"][0]["successRate"], 0.75);
+    assert_eq!(payload["metrics"]["totalTrades"], 2);
+    assert_eq!(payload["metrics"]["successfulTrades"], 1);
+    assert_eq!(payload["metrics"]["failedTrades"], 1);
+    assert_eq!(payload["metrics"]["totalGasSpent"], 0.02);
+    assert_eq!(payload["profitTiers"][0]["status"], "hit");
+    assert_eq!(payload["profitTiers"][1]["status"], "pending");
+    assert!(payload["profitTiers"][1]["timestamp"].is_null());
+    assert_eq!(payload["alerts"][0]["type"], "rug_detector");
+    assert_eq!(payload["alerts"][0]["severity"], "Critical");
+    assert_eq!(payload["performanceData"].as_array().unwrap().len(), 2);
+    assert_eq!(payload["performanceData"][1]["profit"], -0.5);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dashboard_broadcast_loop_pushes_frames_until_shutdown() -> Result<()> {
+    use warp::Filter;
+
+    let state = std::sync::Arc::new(tokio::sync::RwLock::new(ColonyState::default()));
+    let dashboard = std::sync::Arc::new(DashboardWebSocket::new(state));
+    let route = {
+        let dashboard = dashboard.clone();
+        warp::path("dashboard").and(warp::ws()).map(move |ws: warp::ws::Ws| {
+            let dashboard = dashboard.clone();
+            ws.on_upgrade(move |socket| async move { dashboard.handle_connection(socket).await })
+        })
+    };
+    let server_handle = tokio::spawn(warp::serve(route).run(([127, 0, 0, 1], 3007)));
+    sleep(Duration::from_millis(100)).await;
+
+    let (mut client, _) = tokio_tungstenite::connect_async("ws://127.0.0.1:3007/dashboard").await?;
+    sleep(Duration::from_millis(50)).await;
+    let loop_handle = dashboard.clone().start_broadcast_loop(Duration::from_millis(20));
+
+    for _ in 0..2 {
+        let frame: serde_json::Value = serde_json::from_str(&next_text(&mut client).await?)?;
+        assert!(frame.get("metrics").is_some());
+    }
+
+    dashboard.shutdown();
+    tokio::time::timeout(Duration::from_secs(1), loop_handle).await??;
+
+    server_handle.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_metrics_endpoint_exports_colony_metrics() -> Result<()> {
+    let metrics = Metrics::new();
+    let message_queue = MessageQueue::new(16);
+    let mut server = WebSocketServer::new(DASHBOARD_TOKEN);
+    server.set_metrics(metrics.clone());
+    server.set_message_queue(message_queue.clone());
+    let addr = "127.0.0.1:3006".parse().unwrap();
+
+    let server_handle = {
+        let server = server.clone();
+        tokio::spawn(async move { server.start(addr).await })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    // RPC usage is counted by the manager the metrics are handed to
+    let config_manager = ConfigManager::new(PathBuf::from("./config")).await?;
+    let mut rpc_manager = RpcClientManager::new(&config_manager.get_rpc_config().await).await?;
+    rpc_manager.set_metrics(metrics.clone());
+    rpc_manager.get_client(antbot::rpc::RpcProvider::Helius).await?;
+    assert_eq!(metrics.rpc_calls("helius"), 1);
+
+    // One message waits in an unread subscription
+    let _subscription = message_queue.subscribe("slow".to_string(), 4, OverflowPolicy::DropOldest).await;
+    message_queue.publish(risk_update(1)).await;
+    metrics.observe_scan(Duration::from_millis(250));
+    metrics.set_total_profit(1.5);
+
+    let body = reqwest::get("http://127.0.0.1:3006/metrics").await?.text().await?;
+    for name in [
+        "antbot_active_trades",
+        "antbot_total_profit_sol 1.5",
+        "antbot_rpc_calls_total{provider=\"helius\"} 1",
+        "antbot_scan_duration_seconds_count 1",
+        "antbot_message_queue_depth 1",
+        "antbot_trade_success_rate",
+    ] {
+        assert!(body.contains(name), "missing {} in:\n{}", name, body);
+    }
+
+    server_handle.abort();
+    Ok(())
+}
+
+#[test]
+fn test_log_rotation_rolls_over_and_prunes_old_files() -> Result<()> {
+    use log4rs::append::Append;
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("ant_colony.log");
+    let rotation = LogRotation::new(200, 2);
+    let appender = rotation.appender(&path)?;
+
+    // Each line is well over 50 bytes, so 20 of them roll the file several times
+    for i in 0..20 {
+        appender.append(&log::Record::builder()
+            .level(log::Level::Info)
+            .target("ant_colony")
+            .args(format_args!("colony tick {} with enough padding to grow the file", i))
+            .build())?;
+    }
+    appender.flush();
+
+    assert!(path.exists());
+    assert!(rotation.get_rotated_filename(&path, 1).exists());
+    assert!(rotation.get_rotated_filename(&path, 2).exists());
+    assert!(!rotation.get_rotated_filename(&path, 3).exists());
+    assert!(std::fs::metadata(rotation.get_rotated_filename(&path, 1))?.len() >= 200);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_rpc_connection_pool() -> Result<()> {
     let config_manager = ConfigManager::new(PathBuf::from("./config")).await?;
@@ -104,21 +499,163 @@ async fn test_rpc_connection_pool() -> Result<()> {
     Ok(())
 }
 
+// Acknowledges the first programSubscribe it receives, then pushes one account notification
+async fn serve_program_notification(listener: tokio::net::TcpListener, pool: String, program: String) -> Result<()> {
+    let (stream, _) = listener.accept().await?;
+    let mut ws = tokio_tungstenite::accept_async(stream).await?;
+    let request: serde_json::Value = loop {
+        if let WsMessage::Text(text) = ws.next().await.expect("client closed before subscribing")? {
+            break serde_json::from_str(&text)?;
+        }
+    };
+    assert_eq!(request["method"], "programSubscribe");
+    assert_eq!(request["params"][0], program.as_str());
+
+    ws.send(WsMessage::Text(serde_json::json!({"jsonrpc": "2.0", "result": 7, "id": request["id"]}).to_string())).await?;
+    ws.send(WsMessage::Text(serde_json::json!({
+        "jsonrpc": "2.0",
+        "method": "programNotification",
+        "params": {
+            "subscription": 7,
+            "result": {
+                "context": {"slot": 42},
+                "value": {
+                    "pubkey": pool,
+                    "account": {
+                        "lamports": 2039280,
+                        "data": ["AQID", "base64"],
+                        "owner": program,
+                        "executable": false,
+                        "rentEpoch": 0,
+                    },
+                },
+            },
+        },
+    }).to_string())).await?;
+
+    // Hold the connection open until the client goes away
+    while ws.next().await.is_some() {}
+    Ok(())
+}
+
 #[tokio::test]
-async fn test_config_hot_reload() -> Result<()> {
+async fn test_program_subscription_delivers_notifications() -> Result<()> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let program = solana_sdk::pubkey::Pubkey::new_unique();
+    let pool = solana_sdk::pubkey::Pubkey::new_unique();
+    let addr = listener.local_addr()?;
+    tokio::spawn(serve_program_notification(listener, pool.to_string(), program.to_string()));
+
     let config_manager = ConfigManager::new(PathBuf::from("./config")).await?;
+    let mut rpc_config = config_manager.get_rpc_config().await;
+    rpc_config.rpc_strategy.monitoring = "helius".to_string();
+    rpc_config.helius.ws_mainnet = Some(format!("ws://{}", addr));
+    let rpc_manager = RpcClientManager::new(&rpc_config).await?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let subscription = rpc_manager.subscribe_program(program, move |notification| {
+        let _ = tx.send(notification);
+    }).await?;
+
+    let notification = tokio::time::timeout(Duration::from_secs(2), rx.recv()).await?
+        .expect("subscription closed without a notification");
+    assert_eq!(notification.slot, 42);
+    assert_eq!(notification.pubkey, pool);
+    assert_eq!(notification.account.owner, program);
+    assert_eq!(notification.account.data, vec![1, 2, 3]);
+
+    subscription.abort();
+    Ok(())
+}
+
+// Acknowledges the first logsSubscribe it receives, then pushes a failed transaction's logs
+// followed by a successful one's
+async fn serve_logs_notifications(listener: tokio::net::TcpListener, program: String, signature: String) -> Result<()> {
+    let (stream, _) = listener.accept().await?;
+    let mut ws = tokio_tungstenite::accept_async(stream).await?;
+    let request: serde_json::Value = loop {
+        if let WsMessage::Text(text) = ws.next().await.expect("client closed before subscribing")? {
+            break serde_json::from_str(&text)?;
+        }
+    };
+    assert_eq!(request["method"], "logsSubscribe");
+    assert_eq!(request["params"][0]["mentions"][0], program.as_str());
+
+    ws.send(WsMessage::Text(serde_json::json!({"jsonrpc": "2.0", "result": 9, "id": request["id"]}).to_string())).await?;
+    let failed = solana_sdk::signature::Signature::new_unique().to_string();
+    for (signature, err) in [(failed, serde_json::json!({"InstructionError": [0, "InvalidAccountData"]})), (signature, serde_json::Value::Null)] {
+        ws.send(WsMessage::Text(serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "logsNotification",
+            "params": {
+                "subscription": 9,
+                "result": {
+                    "context": {"slot": 43},
+                    "value": {
+                        "signature": signature,
+                        "err": err,
+                        "logs": [format!("Program {} invoke [1]", program), "Program log: initialize2: InitializeInstruction2"],
+                    },
+                },
+            },
+        }).to_string())).await?;
+    }
+
+    while ws.next().await.is_some() {}
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_logs_subscription_skips_failed_transactions() -> Result<()> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let program = solana_sdk::pubkey::Pubkey::new_unique();
+    let signature = solana_sdk::signature::Signature::new_unique();
+    let addr = listener.local_addr()?;
+    tokio::spawn(serve_logs_notifications(listener, program.to_string(), signature.to_string()));
+
+    let config_manager = ConfigManager::new(PathBuf::from("./config")).await?;
+    let mut rpc_config = config_manager.get_rpc_config().await;
+    rpc_config.rpc_strategy.monitoring = "helius".to_string();
+    rpc_config.helius.ws_mainnet = Some(format!("ws://{}", addr));
+    let rpc_manager = RpcClientManager::new(&rpc_config).await?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let subscription = rpc_manager.subscribe_logs(program, move |notification| {
+        let _ = tx.send(notification);
+    }).await?;
+
+    let notification = tokio::time::timeout(Duration::from_secs(2), rx.recv()).await?
+        .expect("subscription closed without a notification");
+    assert_eq!(notification.slot, 43);
+    assert_eq!(notification.signature, signature);
+    assert!(notification.logs.iter().any(|line| line.contains("initialize2")));
+
+    subscription.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_config_hot_reload() -> Result<()> {
+    // Edit a copy so the repository's config is left untouched
+    let config_dir = tempfile::tempdir()?;
+    for file in ["settings.toml", "rpc.toml", "api_keys.toml"] {
+        std::fs::copy(PathBuf::from("./config").join(file), config_dir.path().join(file))?;
+    }
+    let config_manager = std::sync::Arc::new(ConfigManager::new(config_dir.path().to_path_buf()).await?);
     let initial_settings = config_manager.get_settings().await;
+    assert_eq!(initial_settings.max_concurrent_trades, 5);
 
     // Start watching for changes
-    let watch_handle = tokio::spawn(async move {
-        config_manager.watch_for_changes().await;
-    });
+    let watch_handle = {
+        let config_manager = config_manager.clone();
+        tokio::spawn(async move { config_manager.watch_for_changes().await })
+    };
 
     // Wait for a moment to ensure watcher is active
     sleep(Duration::from_millis(100)).await;
 
     // Modify settings file
-    let settings_path = PathBuf::from("./config/settings.toml");
+    let settings_path = config_dir.path().join("settings.toml");
     let mut settings = tokio::fs::read_to_string(&settings_path).await?;
     settings = settings.replace(
         "max_concurrent_trades = 5",
@@ -126,15 +663,406 @@ async fn test_config_hot_reload() -> Result<()> {
     );
     tokio::fs::write(&settings_path, settings).await?;
 
-    // Wait for changes to be detected
+    // The watcher must still be alive well after watch_for_changes started
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while config_manager.get_settings().await.max_concurrent_trades != 10 {
+        assert!(tokio::time::Instant::now() < deadline, "settings change was not picked up");
+        sleep(Duration::from_millis(50)).await;
+    }
+
+    // Cleanup
+    watch_handle.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_config_hot_reload_keeps_good_config_after_bad_edit() -> Result<()> {
+    // Work on a copy so the real config directory is never left broken
+    let config_dir = tempfile::tempdir()?;
+    for file in ["settings.toml", "rpc.toml", "api_keys.toml"] {
+        std::fs::copy(PathBuf::from("./config").join(file), config_dir.path().join(file))?;
+    }
+    let settings_path = config_dir.path().join("settings.toml");
+    let original = tokio::fs::read_to_string(&settings_path).await?;
+
+    let config_manager = std::sync::Arc::new(ConfigManager::new(config_dir.path().to_path_buf()).await?);
+    let initial_settings = config_manager.get_settings().await;
+    let watch_handle = {
+        let config_manager = config_manager.clone();
+        tokio::spawn(async move { config_manager.watch_for_changes().await })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    // A broken edit is retried, rejected, and the previous settings stay in place
+    tokio::fs::write(&settings_path, "max_concurrent_trades = [not toml").await?;
     sleep(Duration::from_secs(2)).await;
+    assert_eq!(
+        config_manager.get_settings().await.max_concurrent_trades,
+        initial_settings.max_concurrent_trades
+    );
 
-    // Get updated settings
-    let updated_settings = config_manager.get_settings().await;
-    assert_eq!(updated_settings.max_concurrent_trades, 10);
+    // Fixing the file is picked up by the next reload
+    let fixed = original.replace("max_concurrent_trades = 5", "max_concurrent_trades = 7");
+    tokio::fs::write(&settings_path, fixed).await?;
+    sleep(Duration::from_secs(2)).await;
+    assert_eq!(config_manager.get_settings().await.max_concurrent_trades, 7);
+
+    watch_handle.abort();
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_config_reload_notifies_subscribers() -> Result<()> {
+    let config_dir = tempfile::tempdir()?;
+    for file in ["settings.toml", "rpc.toml", "api_keys.toml"] {
+        std::fs::copy(PathBuf::from("./config").join(file), config_dir.path().join(file))?;
+    }
+    let settings_path = config_dir.path().join("settings.toml");
+
+    let config_manager = std::sync::Arc::new(ConfigManager::new(config_dir.path().to_path_buf()).await?);
+    let mut changes = config_manager.subscribe_changes();
+    assert_eq!(*changes.borrow(), ConfigVersion(0));
+
+    let watch_handle = {
+        let config_manager = config_manager.clone();
+        tokio::spawn(async move { config_manager.watch_for_changes().await })
+    };
+    sleep(Duration::from_millis(100)).await;
+
+    let settings = tokio::fs::read_to_string(&settings_path).await?
+        .replace("max_slippage_percentage = 1.0", "max_slippage_percentage = 2.0");
+    tokio::fs::write(&settings_path, settings).await?;
+
+    tokio::time::timeout(Duration::from_secs(3), changes.changed()).await??;
+    assert_eq!(*changes.borrow(), ConfigVersion(1));
+    assert_eq!(config_manager.current_version(), ConfigVersion(1));
+    assert_eq!(config_manager.get_settings().await.max_slippage_percentage, 2.0);
 
-    // Cleanup
     watch_handle.abort();
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_env_vars_override_config_files() -> Result<()> {
+    let config_dir = tempfile::tempdir()?;
+    for file in ["settings.toml", "rpc.toml", "api_keys.toml"] {
+        std::fs::copy(PathBuf::from("./config").join(file), config_dir.path().join(file))?;
+    }
+
+    std::env::set_var("ANTBOT_RPC__HELIUS__MAINNET", "https://override.example/rpc");
+    std::env::set_var("ANTBOT_SETTINGS__TEMP_DIR", "/tmp/antbot-override");
+    let loaded = ConfigManager::new(config_dir.path().to_path_buf()).await;
+    std::env::remove_var("ANTBOT_RPC__HELIUS__MAINNET");
+    std::env::remove_var("ANTBOT_SETTINGS__TEMP_DIR");
+
+    let config_manager = loaded?;
+    assert_eq!(config_manager.get_rpc_config().await.helius.mainnet, "https://override.example/rpc");
+    assert_eq!(config_manager.get_settings().await.temp_dir, "/tmp/antbot-override");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_profile_overrides_layer_over_base_settings() -> Result<()> {
+    let config_dir = tempfile::tempdir()?;
+    for file in ["settings.toml", "rpc.toml", "api_keys.toml"] {
+        std::fs::copy(PathBuf::from("./config").join(file), config_dir.path().join(file))?;
+    }
+    std::fs::write(
+        config_dir.path().join("settings.prod.toml"),
+        "max_concurrent_trades = 20\ntemp_dir = \"/var/tmp/antbot\"\n",
+    )?;
+
+    let base = ConfigManager::new(config_dir.path().to_path_buf()).await?.get_settings().await;
+    let config_manager = ConfigManager::with_profile(config_dir.path().to_path_buf(), Some("prod".to_string())).await?;
+    assert_eq!(config_manager.profile(), Some("prod"));
+
+    // Overridden keys take the profile value, everything else falls back to the base
+    let prod = config_manager.get_settings().await;
+    assert_eq!(prod.max_concurrent_trades, 20);
+    assert_eq!(prod.temp_dir, "/var/tmp/antbot");
+    assert_eq!(prod.max_slippage_percentage, base.max_slippage_percentage);
+    assert_eq!(prod.data_dir, base.data_dir);
+    assert_eq!(prod.max_daily_trades, base.max_daily_trades);
+
+    // The merged result is validated, and a missing profile is an error rather than the base
+    std::fs::write(config_dir.path().join("settings.staging.toml"), "max_concurrent_trades = 500\n")?;
+    assert!(ConfigManager::with_profile(config_dir.path().to_path_buf(), Some("staging".to_string())).await.is_err());
+    assert!(ConfigManager::with_profile(config_dir.path().to_path_buf(), Some("dev".to_string())).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_missing_api_keys_are_listed_at_startup() -> Result<()> {
+    let config_dir = tempfile::tempdir()?;
+    for file in ["settings.toml", "rpc.toml", "api_keys.toml"] {
+        std::fs::copy(PathBuf::from("./config").join(file), config_dir.path().join(file))?;
+    }
+
+    // Blank one key and drop another entirely
+    let api_keys_path = config_dir.path().join("api_keys.toml");
+    let api_keys = std::fs::read_to_string(&api_keys_path)?
+        .replace("helius_api_key = \"YOUR_HELIUS_API_KEY\"", "helius_api_key = \"\"")
+        .replace("sentry_dsn = \"YOUR_SENTRY_DSN\"", "");
+    std::fs::write(&api_keys_path, api_keys)?;
+
+    let error = match ConfigManager::new(config_dir.path().to_path_buf()).await {
+        Ok(_) => panic!("startup should fail when API keys are missing"),
+        Err(e) => e.to_string(),
+    };
+    assert!(error.contains("network.helius_api_key"), "{}", error);
+    assert!(error.contains("monitoring.sentry_dsn"), "{}", error);
+    assert!(!error.contains("birdeye_api_key"), "{}", error);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_secrets_file_permissions_follow_policy() -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let config_dir = tempfile::tempdir()?;
+    let api_keys_path = config_dir.path().join("api_keys.toml");
+    std::fs::copy(PathBuf::from("./config/api_keys.toml"), &api_keys_path)?;
+
+    // World-readable: warn lets startup continue, refuse stops it
+    std::fs::set_permissions(&api_keys_path, std::fs::Permissions::from_mode(0o644))?;
+    assert!(check_secrets_permissions(&api_keys_path, SecretsFilePolicy::Warn).is_ok());
+    assert!(check_secrets_permissions(&api_keys_path, SecretsFilePolicy::Ignore).is_ok());
+    let error = check_secrets_permissions(&api_keys_path, SecretsFilePolicy::Refuse).unwrap_err();
+    assert!(error.to_string().contains("readable by group or others"), "{}", error);
+
+    // Owner-only passes even under the strictest policy
+    std::fs::set_permissions(&api_keys_path, std::fs::Permissions::from_mode(0o600))?;
+    assert!(check_secrets_permissions(&api_keys_path, SecretsFilePolicy::Refuse).is_ok());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_message_queue_fans_out_to_every_subscriber() -> Result<()> {
+    let message_queue = MessageQueue::new(16);
+    let mut first = message_queue.subscribe("first".to_string(), 16, OverflowPolicy::Block).await;
+    let mut second = message_queue.clone().subscribe("second".to_string(), 16, OverflowPolicy::Block).await;
+
+    for daily_trades in 0..3 {
+        message_queue.publish(Message::RiskUpdate(RiskUpdate {
+            position_size: 1000.0,
+            daily_loss: 0.0,
+            daily_trades,
+            timestamp: chrono::Utc::now(),
+        })).await;
+    }
+
+    for subscriber in [&mut first, &mut second] {
+        for expected in 0..3 {
+            match subscriber.recv().await {
+                Some(Message::RiskUpdate(update)) => assert_eq!(update.daily_trades, expected),
+                other => panic!("Unexpected message: {:?}", other),
+            }
+        }
+    }
+
+    // Unsubscribing ends only that subscription
+    message_queue.unsubscribe("first").await;
+    assert!(first.recv().await.is_none());
+    assert_eq!(message_queue.subscriber_count().await, 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_message_wire_format_round_trips() -> Result<()> {
+    let timestamp = chrono::Utc::now();
+    let messages = vec![
+        ("TradeSignal", Message::TradeSignal(TradeSignal {
+            token_address: "token".to_string(),
+            action: antbot::common::TradeAction::Buy,
+            price: 1.5,
+            amount: 100.0,
+            timestamp,
+            confidence: 0.9,
+        })),
+        ("RiskUpdate", Message::RiskUpdate(RiskUpdate {
+            position_size: 1000.0,
+            daily_loss: 25.0,
+            daily_trades: 7,
+            timestamp,
+        })),
+        ("LiquidityAlert", Message::LiquidityAlert(LiquidityAlert {
+            pool_address: "pool".to_string(),
+            token_address: "token".to_string(),
+            alert_type: AlertType::LiquidityDrop,
+            severity: AlertSeverity::High,
+            current_value: 5000.0,
+            threshold_value: 0.25,
+            previous_liquidity: Some(10000.0),
+            timestamp,
+            message: "Liquidity halved".to_string(),
+        })),
+        ("TradeExecution", Message::TradeExecution(TradeExecutionEvent {
+            signature: Some("5sig".to_string()),
+            realized_pnl: Some(0.42),
+            timestamp,
+            ..TradeExecutionEvent::new("token", antbot::common::TradeAction::Sell, 250.0, TradeEventStatus::Confirmed)
+        })),
+    ];
+
+    for (tag, message) in messages {
+        let json = serde_json::to_value(&message)?;
+        assert_eq!(json["type"], tag);
+        // Variant fields sit alongside the tag rather than under a nested key
+        assert!(json.get(tag).is_none());
+
+        let decoded: Message = serde_json::from_value(json.clone())?;
+        assert_eq!(serde_json::to_value(&decoded)?, json);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_liquidity_drop_constructor_fills_full_alert() -> Result<()> {
+    let alert = LiquidityAlert::drop("token", 10000.0, 2000.0);
+    assert_eq!(alert.token_address, "token");
+    assert!(matches!(alert.alert_type, AlertType::LiquidityDrop));
+    assert_eq!(alert.severity, AlertSeverity::Critical);
+    assert_eq!(alert.current_value, 2000.0);
+    assert_eq!(alert.previous_liquidity, Some(10000.0));
+    assert_eq!(alert.message, "Liquidity dropped by 80%");
+    assert!((alert.liquidity_drop().unwrap() - 0.8).abs() < 1e-9);
+
+    // The alert threshold is the producer's and never read as the baseline
+    let halved = LiquidityAlert {
+        threshold_value: 0.25,
+        ..LiquidityAlert::drop("token", 10000.0, 5000.0)
+    };
+    assert_eq!(halved.liquidity_drop(), Some(0.5));
+    assert_eq!(LiquidityAlert::drop("token", 1.0, 0.7).severity, AlertSeverity::Medium);
+    assert_eq!(LiquidityAlert::drop("token", 1.0, -0.5).liquidity_drop(), Some(1.0));
+
+    // Producers that don't report a baseline don't report a drop
+    let unknown = LiquidityAlert { previous_liquidity: None, ..halved.clone() };
+    assert_eq!(unknown.liquidity_drop(), None);
+    let surge = LiquidityAlert { alert_type: AlertType::LiquiditySurge, ..halved };
+    assert_eq!(surge.liquidity_drop(), None);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_filtered_subscription_only_receives_matching_kinds() -> Result<()> {
+    let message_queue = MessageQueue::new(16);
+    let mut signals_only = message_queue
+        .subscribe_filtered("buy_engine".to_string(), MessageKind::TRADE_SIGNAL, 16, OverflowPolicy::Block)
+        .await;
+
+    message_queue.publish(Message::RiskUpdate(RiskUpdate {
+        position_size: 1000.0,
+        daily_loss: 0.0,
+        daily_trades: 1,
+        timestamp: chrono::Utc::now(),
+    })).await;
+    assert!(signals_only.try_recv().is_none());
+
+    message_queue.publish(Message::TradeSignal(TradeSignal {
+        token_address: "token".to_string(),
+        action: antbot::common::TradeAction::Buy,
+        price: 1.0,
+        amount: 10.0,
+        timestamp: chrono::Utc::now(),
+        confidence: 0.8,
+    })).await;
+    assert!(matches!(signals_only.try_recv(), Some(Message::TradeSignal(_))));
+
+    Ok(())
+}
+
+fn risk_update(daily_trades: u32) -> Message {
+    Message::RiskUpdate(RiskUpdate {
+        position_size: 1000.0,
+        daily_loss: 0.0,
+        daily_trades,
+        timestamp: chrono::Utc::now(),
+    })
+}
+
+fn daily_trades(message: Option<Message>) -> u32 {
+    match message {
+        Some(Message::RiskUpdate(update)) => update.daily_trades,
+        other => panic!("Expected a risk update, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_overflow_policies_on_full_subscriber_channel() -> Result<()> {
+    let message_queue = MessageQueue::new(100);
+    let mut drop_oldest = message_queue.subscribe("oldest".to_string(), 2, OverflowPolicy::DropOldest).await;
+    let mut drop_newest = message_queue.subscribe("newest".to_string(), 2, OverflowPolicy::DropNewest).await;
+
+    for i in 0..3 {
+        message_queue.publish(risk_update(i)).await;
+    }
+
+    // DropOldest keeps the latest two, DropNewest keeps the first two
+    assert_eq!(daily_trades(drop_oldest.try_recv()), 1);
+    assert_eq!(daily_trades(drop_oldest.try_recv()), 2);
+    assert_eq!(daily_trades(drop_newest.try_recv()), 0);
+    assert_eq!(daily_trades(drop_newest.try_recv()), 1);
+    assert_eq!(message_queue.dropped_count("oldest").await, Some(1));
+    assert_eq!(message_queue.dropped_count("newest").await, Some(1));
+    message_queue.unsubscribe("oldest").await;
+    message_queue.unsubscribe("newest").await;
+
+    // Block holds the publisher until the subscriber makes room, losing nothing
+    let mut blocking = message_queue.subscribe("blocking".to_string(), 1, OverflowPolicy::Block).await;
+    message_queue.publish(risk_update(0)).await;
+    let publisher = tokio::spawn({
+        let message_queue = message_queue.clone();
+        async move { message_queue.publish(risk_update(1)).await }
+    });
+    sleep(Duration::from_millis(100)).await;
+    assert!(!publisher.is_finished());
+
+    assert_eq!(daily_trades(blocking.recv().await), 0);
+    tokio::time::timeout(Duration::from_secs(1), publisher).await??;
+    assert_eq!(daily_trades(blocking.recv().await), 1);
+    assert_eq!(message_queue.dropped_count("blocking").await, Some(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dropped_subscription_stops_blocking_publishers() -> Result<()> {
+    let message_queue = MessageQueue::new(100);
+    let blocking = message_queue.subscribe("blocking".to_string(), 1, OverflowPolicy::Block).await;
+    message_queue.publish(risk_update(0)).await;
+
+    // Full, and about to hold the next publish until someone reads it
+    let publisher = tokio::spawn({
+        let message_queue = message_queue.clone();
+        async move { message_queue.publish(risk_update(1)).await }
+    });
+    sleep(Duration::from_millis(100)).await;
+    assert!(!publisher.is_finished());
+
+    // Nobody ever will, so dropping the subscription releases the publisher and unregisters it
+    drop(blocking);
+    tokio::time::timeout(Duration::from_secs(1), publisher).await??;
+    tokio::time::timeout(Duration::from_secs(1), message_queue.publish(risk_update(2))).await?;
+    assert_eq!(message_queue.subscriber_count().await, 0);
+    assert_eq!(message_queue.dropped_count("blocking").await, None);
+
+    // Resubscribing under an id isn't undone when the replaced subscription is dropped
+    let replaced = message_queue.subscribe("reused".to_string(), 4, OverflowPolicy::DropOldest).await;
+    let mut current = message_queue.subscribe("reused".to_string(), 4, OverflowPolicy::DropOldest).await;
+    drop(replaced);
+    message_queue.publish(risk_update(3)).await;
+    assert_eq!(daily_trades(current.try_recv()), 3);
+
+    Ok(())
+}
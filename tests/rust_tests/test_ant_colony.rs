@@ -1,6 +1,30 @@
 use antbot::ant_colony::{queen::Queen, princess::Princess, worker::Worker, sentry::Sentry};
 use antbot::config::Config;
+use antbot::ant_colony::{
+    ColonyState, CapitalManager, ProfitManager, RugDetector, TransactionHandler,
+    RugAlert, RugAlertType, RugAlertSeverity, TokenBlacklist, TradeProfit, BalanceSource, PositionReconciler,
+    TradeJournal, JournalEvent, WalletLock, LockOwner,
+    PendingConfirmations, SESSION_JOURNAL_ID, PoolLocator, PoolInfo, WalletHealthMonitor,
+    StrategyBreakers, TransactionBundle, ColonyHealth, HealthSignals, HealthVerdict, TokenStats,
+    CompromiseGuard, WalletActivitySource, ObservedTransaction, AlertSeverity, MonitorBudget, MonitorPriority,
+    Candle, CandleSource, RiskGovernor, TradingFrozen, VolatilityTracker, GasPriceSource, HandlerGasPriceSource, WalletPool,
+    ProfitDistribution, ColonyScaler, BlockhashCache, BlockhashSource,
+    TierState, ProfitTier, MAX_DASHBOARD_HISTORY,
+};
+use antbot::sniping_core::{TradeExecution, TradeStatus as ExecutionStatus};
+use antbot::logging::ErrorReporter;
+use antbot::common::{TradeError, Message, MessageKind, MessageQueue, OverflowPolicy, TradeAction, ColonyAlert, AlertForwarder};
+use antbot::common::{JupiterClient, SwapExecutor, associated_token_address};
+use antbot::fund_management::VaultManager;
 use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use serde_json::json;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::{Keypair, Signer}, system_instruction, transaction::Transaction};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use wiremock::matchers::{body_partial_json, method, path};
+use base64::Engine;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -18,6 +42,32 @@ async fn test_queen_initialization() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_queen_run_loop_ticks_and_stops() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.queen.reinvestment_threshold", 1000.0)?
+        .set_override("ant_colony.queen.risk_threshold", 0.8)?
+        .set_override("ant_colony.queen.initial_capital", 1000.0)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState { active_trades: 10, ..ColonyState::default() }));
+    let queen = Arc::new(RwLock::new(Queen::new(&config, state.clone()).await?));
+    let monitor = tokio::spawn(Queen::run(queen.clone()));
+
+    // The first pass records its tick and risk without deadlocking on the state
+    tokio::time::timeout(std::time::Duration::from_secs(2), async {
+        while state.read().await.last_loop_tick.is_none() {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    }).await?;
+    assert!((state.read().await.risk_level - (0.1 * 0.4 + 0.8 * 0.6)).abs() < 1e-9);
+
+    // Shutdown gets the queen between passes and the loop ends
+    queen.write().await.shutdown().await?;
+    tokio::time::timeout(std::time::Duration::from_secs(10), monitor).await??;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_queen_capital_management() -> Result<()> {
     let config = Config::load()?;
@@ -119,7 +169,7 @@ async fn test_worker_initialization() -> Result<()> {
 
 #[tokio::test]
 async fn test_worker_profit_management() -> Result<()> {
-    let config = Config::load()?;
+    let config = colony_config_builder()?.build()?;
     let state = Arc::new(RwLock::new(ColonyState::default()));
     
     let worker = Worker::new(&config, state.clone()).await?;
@@ -134,10 +184,39 @@ async fn test_worker_profit_management() -> Result<()> {
     assert_eq!(distribution.queen_share, 55.0);
     assert_eq!(distribution.worker_share, 55.0);
     assert_eq!(worker.get_collected_profits(), 0.0);
+    assert_eq!(state.read().await.total_capital, 55.0);
     
     Ok(())
 }
 
+#[tokio::test]
+async fn test_worker_distribution_edge_cases() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.worker.profit_distribution", 0.25)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState { total_capital: 10.0, ..ColonyState::default() }));
+    let worker = Worker::new(&config, state.clone()).await?;
+
+    // Nothing collected: nothing moves
+    let distribution = worker.distribute_profits().await?;
+    assert_eq!(distribution, ProfitDistribution { queen_share: 0.0, worker_share: 0.0 });
+    assert_eq!(state.read().await.total_capital, 10.0);
+
+    // Zero is accepted, losses and non-finite amounts are not
+    worker.record_profit(0.0).await?;
+    assert!(worker.record_profit(-5.0).await.is_err());
+    assert!(worker.record_profit(f64::NAN).await.is_err());
+    assert_eq!(worker.get_collected_profits(), 0.0);
+
+    // The split follows profit_distribution
+    worker.record_profit(40.0).await?;
+    let distribution = worker.distribute_profits().await?;
+    assert_eq!(distribution, ProfitDistribution { queen_share: 10.0, worker_share: 30.0 });
+    assert_eq!(state.read().await.total_capital, 20.0);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_sentry_initialization() -> Result<()> {
     let config = Config::load()?;
@@ -218,4 +297,2667 @@ async fn test_integration_workflow() -> Result<()> {
     queen.collect_capital(distribution.queen_share).await?;
     
     Ok(())
-} 
\ No newline at end of file
+}
+
+// Self-contained config for the colony components exercised below
+fn colony_config_builder() -> Result<::config::ConfigBuilder<::config::builder::DefaultState>> {
+    Ok(::config::Config::builder()
+        .set_default("ant_colony.rug_detector.price_drop_threshold", 0.5)?
+        .set_default("ant_colony.rug_detector.volume_drop_threshold", 0.7)?
+        .set_default("ant_colony.rug_detector.liquidity_drop_threshold", 0.6)?
+        .set_default("ant_colony.rug_detector.holder_drop_threshold", 0.4)?
+        .set_default("ant_colony.rug_detector.contract_risk_threshold", 0.8)?
+        .set_default("ant_colony.rug_detector.history_window", 24)?
+        .set_default("ant_colony.rug_detector.auto_blacklist", true)?
+        .set_default("ant_colony.princess.max_position_size", 20.0)?
+        .set_default("ant_colony.princess.min_position_size", 5.0)?
+        .set_default("ant_colony.princess.initial_balance", 20.0)?
+        .set_default("ant_colony.princess.max_trades", 5)?
+        .set_default("ant_colony.princess.min_success_rate", 0.3)?
+        .set_default("ant_colony.princess.capital_allocation", 0.2)?
+        .set_default("ant_colony.princess.trade_timeout", 300)?
+        .set_default("ant_colony.capital_manager.worker_ant_budget", 20.0)?
+        .set_default("ant_colony.capital_manager.max_active_workers", 15)?
+        .set_default("ant_colony.capital_manager.min_active_workers", 10)?
+        .set_default("ant_colony.capital_manager.initial_capital", 300.0)?
+        .set_default("ant_colony.profit_manager.min_profit_threshold", 0.1)?
+        .set_default("ant_colony.transaction_handler.jito_rpc_url", "http://127.0.0.1:8899")?
+        .set_default("ant_colony.transaction_handler.helius_rpc_url", "http://127.0.0.1:8899")?
+        .set_default("ant_colony.transaction_handler.jito_check_interval", 30)?
+        .set_default("ant_colony.transaction_handler.max_retries", 3)?
+        .set_default("ant_colony.transaction_handler.retry_delay_ms", 100)?
+        .set_default("ant_colony.transaction_handler.bundle_size", 5)?
+        .set_default("ant_colony.transaction_handler.min_priority_fee", 1000)?
+        .set_default("ant_colony.transaction_handler.max_priority_fee", 10000)?)
+}
+
+async fn build_princess(config: &::config::Config, state: Arc<RwLock<ColonyState>>) -> Result<Princess> {
+    let capital_manager = Arc::new(RwLock::new(CapitalManager::new(config, state.clone()).await?));
+    let profit_manager = Arc::new(RwLock::new(ProfitManager::new(config, state.clone()).await?));
+    let rug_detector = Arc::new(RwLock::new(RugDetector::new(config, state.clone()).await?));
+    let transaction_handler = Arc::new(RwLock::new(TransactionHandler::new(config).await?));
+
+    Princess::new(config, state, capital_manager, profit_manager, rug_detector, transaction_handler).await
+}
+
+#[tokio::test]
+async fn test_honeypot_detection_auto_blacklists_token() -> Result<()> {
+    let config = colony_config_builder()?.build()?;
+    let data_dir = tempfile::tempdir()?;
+    let blacklist_path = data_dir.path().join("blacklist.json");
+    let state = Arc::new(RwLock::new(ColonyState {
+        blacklist: TokenBlacklist::load(blacklist_path.clone())?,
+        ..ColonyState::default()
+    }));
+
+    let mut rug_detector = RugDetector::new(&config, state.clone()).await?;
+    let princess = build_princess(&config, state.clone()).await?;
+    let honeypot = "HoneypotToken".to_string();
+
+    rug_detector.handle_rug_alert(RugAlert {
+        token_address: honeypot.clone(),
+        alert_type: RugAlertType::HoneypotDetected,
+        severity: RugAlertSeverity::High,
+        timestamp: chrono::Utc::now(),
+        details: "Sell simulation reverted".to_string(),
+    }).await?;
+
+    // The token is blacklisted with the reason recorded and persisted
+    {
+        let state = state.read().await;
+        let entry = state.blacklist.get(&honeypot).expect("token should be blacklisted");
+        assert!(entry.reason.contains("HoneypotDetected"));
+    }
+    assert!(TokenBlacklist::load(blacklist_path)?.contains(&honeypot));
+
+    // A subsequent signal for the token is rejected
+    let result = princess.execute_trade(honeypot.clone(), 1.0).await;
+    assert!(result.unwrap_err().to_string().contains("blacklisted"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_auto_blacklist_can_be_disabled() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.rug_detector.auto_blacklist", false)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut rug_detector = RugDetector::new(&config, state.clone()).await?;
+
+    rug_detector.handle_rug_alert(RugAlert {
+        token_address: "HoneypotToken".to_string(),
+        alert_type: RugAlertType::HoneypotDetected,
+        severity: RugAlertSeverity::High,
+        timestamp: chrono::Utc::now(),
+        details: "Sell simulation reverted".to_string(),
+    }).await?;
+
+    assert!(!state.read().await.blacklist.contains("HoneypotToken"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_blacklist_and_whitelist_survive_restart() -> Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    let path = data_dir.path().join("blacklist.json");
+
+    let blacklist = TokenBlacklist::load(path.clone())?;
+    assert!(blacklist.add("ScamToken", "Blacklisted manually")?);
+    assert!(!blacklist.add("ScamToken", "Blacklisted manually")?);
+    assert!(blacklist.whitelist("GoodToken")?);
+
+    // Clones share the lists, so the sniping core sees the colony's entries
+    let shared = blacklist.clone();
+    assert!(!shared.is_allowed("ScamToken"));
+    assert!(shared.is_allowed("OtherToken"));
+
+    // Reloading from disk stands in for a restart
+    let reloaded = TokenBlacklist::load(path)?;
+    assert!(reloaded.contains("ScamToken"));
+    assert_eq!(reloaded.get("ScamToken").unwrap().reason, "Blacklisted manually");
+    assert!(reloaded.is_whitelisted("GoodToken"));
+
+    reloaded.set_whitelist_only(true);
+    assert!(reloaded.is_allowed("GoodToken"));
+    assert!(!reloaded.is_allowed("OtherToken"));
+    assert!(!reloaded.is_allowed("ScamToken"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_profit_cap_forces_full_exit_below_top_tier() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.profit_manager.max_unrealized_profit", 5.0)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+
+    profit_manager.add_trade(TradeProfit {
+        trade_id: "capped".to_string(),
+        token_address: "TokenA".to_string(),
+        entry_price: 1.0,
+        entry_time: chrono::Utc::now(),
+        current_price: 1.0,
+        position_size: 100.0,
+        gas_fees: 0.0,
+        realized_profits: 0.0,
+        unrealized_profits: 0.0,
+        profit_tiers_hit: Vec::new(),
+        pool_address: "PoolA".to_string(),
+    }).await?;
+
+    // 1.1x is below every tier, but 10 SOL unrealized is over the 5 SOL cap
+    profit_manager.update_trade_price("capped", 1.1).await?;
+    profit_manager.check_profit_tiers().await?;
+
+    let trade = profit_manager.get_trade_profits("capped").await.unwrap();
+    assert_eq!(trade.position_size, 0.0);
+    assert_eq!(trade.unrealized_profits, 0.0);
+    assert!(trade.realized_profits > 5.0);
+    assert!(trade.profit_tiers_hit.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_ladder_sold_to_nothing_confirms_exit() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST")).and(path("/trades"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let dir = tempfile::tempdir()?;
+    let config = colony_config_builder()?
+        .set_override("trade_webhook.enabled", true)?
+        .set_override("trade_webhook.url", format!("{}/trades", server.uri()))?
+        .set_override("trade_webhook.dead_letter_path", dir.path().join("dead.jsonl").to_string_lossy().to_string())?
+        .set_override("ant_colony.profit_manager.default_volatility", 0.0)?
+        .add_source(::config::File::from_str(r#"
+            [[ant_colony.profit_manager.tiers]]
+            multiplier = 1.2
+            percentage = 1.0
+        "#, ::config::FileFormat::Toml))
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+    profit_manager.add_trade(open_position("laddered")).await?;
+    profit_manager.update_trade_price("laddered", 1.3).await?;
+
+    // The only rung sells the whole position, so no full exit runs and the ladder confirms it
+    profit_manager.check_profit_tiers().await?;
+    assert_eq!(profit_manager.get_trade_profits("laddered").await.unwrap().position_size, 0.0);
+
+    let mut bodies = Vec::new();
+    for _ in 0..50 {
+        bodies = server.received_requests().await.unwrap();
+        if !bodies.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert_eq!(bodies.len(), 1);
+    let body: serde_json::Value = serde_json::from_slice(&bodies[0].body)?;
+    assert_eq!(body["outcome"], "exited");
+    assert_eq!(body["trade_id"], "laddered");
+
+    // A closed trade is skipped on later checks rather than confirmed again
+    profit_manager.check_profit_tiers().await?;
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_profit_tiers_load_from_config_array() -> Result<()> {
+    let config = colony_config_builder()?
+        .add_source(::config::File::from_str(r#"
+            [[ant_colony.profit_manager.tiers]]
+            multiplier = 1.3
+            percentage = 0.5
+            gas_buffer = 1.1
+            volatility_adjustment = 0.05
+
+            [[ant_colony.profit_manager.tiers]]
+            multiplier = 2.0
+            percentage = 0.3
+
+            [[ant_colony.profit_manager.tiers]]
+            multiplier = 4.0
+            percentage = 0.2
+            gas_buffer = 1.5
+            volatility_adjustment = 0.1
+        "#, ::config::FileFormat::Toml))
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let profit_manager = ProfitManager::new(&config, state.clone()).await?;
+
+    // Omitted fields take the same defaults as the flat tier keys
+    assert_eq!(profit_manager.profit_tiers(), &[
+        ProfitTier { multiplier: 1.3, percentage: 0.5, gas_buffer: 1.1, volatility_adjustment: 0.05 },
+        ProfitTier { multiplier: 2.0, percentage: 0.3, gas_buffer: 1.0, volatility_adjustment: 0.0 },
+        ProfitTier { multiplier: 4.0, percentage: 0.2, gas_buffer: 1.5, volatility_adjustment: 0.1 },
+    ]);
+
+    // Without any tier config the built-in four-tier ladder applies
+    let defaults = ProfitManager::new(&colony_config_builder()?.build()?, state.clone()).await?;
+    assert_eq!(defaults.profit_tiers().len(), 4);
+    assert_eq!(defaults.profit_tiers()[0].multiplier, 1.2);
+
+    // Ladders that don't climb, or sell more than the position, are rejected
+    let unordered = colony_config_builder()?
+        .add_source(::config::File::from_str(r#"
+            [[ant_colony.profit_manager.tiers]]
+            multiplier = 2.0
+            percentage = 0.3
+
+            [[ant_colony.profit_manager.tiers]]
+            multiplier = 1.5
+            percentage = 0.3
+        "#, ::config::FileFormat::Toml))
+        .build()?;
+    assert!(ProfitManager::new(&unordered, state.clone()).await.is_err());
+    assert!(ProfitTier::validate_ladder(&[
+        ProfitTier { multiplier: 1.5, percentage: 0.6, gas_buffer: 1.0, volatility_adjustment: 0.0 },
+        ProfitTier { multiplier: 2.0, percentage: 0.6, gas_buffer: 1.0, volatility_adjustment: 0.0 },
+    ]).is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_exit_ladder_dry_run_matches_tier_math() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.profit_tiers.tier_1_multiplier", 1.5)?
+        .set_override("ant_colony.profit_tiers.tier_1_percentage", 0.5)?
+        .set_override("ant_colony.profit_tiers.tier_1_gas_buffer", 1.0)?
+        .set_override("ant_colony.profit_tiers.tier_1_volatility_adjustment", 0.0)?
+        .set_override("ant_colony.profit_tiers.tier_2_multiplier", 3.0)?
+        .set_override("ant_colony.profit_tiers.tier_2_percentage", 0.5)?
+        .set_override("ant_colony.profit_tiers.tier_2_gas_buffer", 2.0)?
+        .set_override("ant_colony.profit_tiers.tier_2_volatility_adjustment", 0.0)?
+        .set_override("ant_colony.profit_manager.gas.safety_buffer", 1.0)?
+        .set_override("ant_colony.profit_manager.default_volatility", 0.0)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+    // Priced so a sell costs exactly 0.01 SOL including the base fee
+    profit_manager.set_gas_price_source(Arc::new(FixedGasPrice(49_975_000.0)));
+    profit_manager.update_gas_price_history().await?;
+
+    profit_manager.add_trade(TradeProfit {
+        trade_id: "winner".to_string(),
+        token_address: "TokenA".to_string(),
+        entry_price: 1.0,
+        entry_time: chrono::Utc::now(),
+        current_price: 1.0,
+        position_size: 100.0,
+        gas_fees: 0.0,
+        realized_profits: 0.0,
+        unrealized_profits: 0.0,
+        profit_tiers_hit: Vec::new(),
+        pool_address: "PoolA".to_string(),
+    }).await?;
+
+    let scenarios = profit_manager.simulate_exit_ladder("TokenA", &[1.2, 2.0, 4.0]).await?;
+    assert_eq!(scenarios.len(), 3);
+
+    // Below the first tier nothing sells
+    assert!(scenarios[0].sells.is_empty());
+    assert_eq!(scenarios[0].remaining_position, 100.0);
+    assert!((scenarios[0].unrealized_profit - 20.0).abs() < 1e-9);
+
+    // At 2x only tier one fires: half of 100 tokens, 1 SOL gain each, less 0.01 gas
+    let at_2x = &scenarios[1];
+    assert_eq!(at_2x.sells.len(), 1);
+    assert_eq!(at_2x.sells[0].tier_multiplier, Some(1.5));
+    assert!((at_2x.sells[0].sell_amount - 50.0).abs() < 1e-9);
+    assert!((at_2x.realized_profit - 49.99).abs() < 1e-9);
+    assert!((at_2x.remaining_position - 50.0).abs() < 1e-9);
+    assert!((at_2x.unrealized_profit - 50.0).abs() < 1e-9);
+
+    // At 4x tier two sells half of what tier one left, carrying tier one's gas as a cost
+    let at_4x = &scenarios[2];
+    assert_eq!(at_4x.sells.len(), 2);
+    assert!((at_4x.sells[0].net_profit - 149.99).abs() < 1e-9);
+    assert!((at_4x.sells[1].sell_amount - 25.0).abs() < 1e-9);
+    assert!((at_4x.sells[1].estimated_gas - 0.02).abs() < 1e-9);
+    assert!((at_4x.sells[1].net_profit - 74.97).abs() < 1e-9);
+    assert!((at_4x.realized_profit - 224.96).abs() < 1e-9);
+    assert!((at_4x.remaining_position - 25.0).abs() < 1e-9);
+
+    // The dry run leaves the real position untouched
+    let trade = profit_manager.get_trade_profits("winner").await.unwrap();
+    assert_eq!(trade.position_size, 100.0);
+    assert_eq!(trade.realized_profits, 0.0);
+    assert!(trade.profit_tiers_hit.is_empty());
+    assert!(profit_manager.simulate_exit_ladder("TokenB", &[2.0]).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_monitor_budget_caps_tokens_across_sentries() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_default("ant_colony.sentry.check_interval", 60)?
+        .set_default("ant_colony.sentry.max_monitors", 10)?
+        .set_default("ant_colony.sentry.risk_thresholds.liquidity_drop", 0.3)?
+        .set_default("ant_colony.sentry.risk_thresholds.price_drop", 0.3)?
+        .set_default("ant_colony.sentry.risk_thresholds.contract_risk", 0.7)?
+        .set_default("ant_colony.sentry.risk_thresholds.sentiment_threshold", 0.3)?
+        .set_override("ant_colony.monitor_budget.enabled", true)?
+        .set_override("ant_colony.monitor_budget.max_monitors", 4)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState {
+        monitor_budget: Arc::new(MonitorBudget::new(&config)),
+        ..ColonyState::default()
+    }));
+    let sentries = vec![
+        Sentry::new(&config, state.clone()).await?,
+        Sentry::new(&config, state.clone()).await?,
+        Sentry::new(&config, state.clone()).await?,
+    ];
+
+    // Each sentry alone is under its own limit of 10, together they ask for 9 tokens
+    for (i, sentry) in sentries.iter().enumerate() {
+        sentry.monitor_token(&format!("Candidate{}", i), MonitorPriority::Candidate { risk: 0.2 + i as f64 * 0.1 }).await?;
+        sentry.monitor_token(&format!("Risky{}", i), MonitorPriority::Candidate { risk: 0.9 }).await?;
+    }
+    sentries[0].monitor_token("HeldA", MonitorPriority::Held).await?;
+    sentries[1].monitor_token("HeldB", MonitorPriority::Held).await?;
+    sentries[2].monitor_token("HeldC", MonitorPriority::Held).await?;
+
+    let mut monitored = Vec::new();
+    for sentry in &sentries {
+        sentry.sync_monitors().await;
+        monitored.extend(sentry.active_monitors().await);
+    }
+    monitored.sort();
+
+    // Held positions all kept their slots; the one left went to the riskiest candidate
+    assert_eq!(monitored.len(), 4);
+    assert_eq!(state.read().await.monitor_budget.len(), 4);
+    for held in ["HeldA", "HeldB", "HeldC"] {
+        assert!(monitored.contains(&held.to_string()), "{} should be monitored", held);
+    }
+    let candidates: Vec<&String> = monitored.iter().filter(|t| !t.starts_with("Held")).collect();
+    assert_eq!(candidates.len(), 1);
+    assert!(candidates[0].starts_with("Risky"));
+
+    // A further candidate can't displace held positions, nor the equally risky one
+    sentries[0].monitor_token("Late", MonitorPriority::Candidate { risk: 0.9 }).await?;
+    assert!(!sentries[0].active_monitors().await.contains(&"Late".to_string()));
+    assert_eq!(state.read().await.monitor_budget.len(), 4);
+
+    Ok(())
+}
+
+// Replays a fixed run of candles for any token
+struct CannedCandles(Vec<f64>);
+
+#[async_trait]
+impl CandleSource for CannedCandles {
+    async fn recent_candles(&self, _token_address: &str, _pool_address: &str, limit: usize) -> Result<Vec<Candle>> {
+        let start = chrono::Utc::now() - chrono::Duration::minutes(self.0.len() as i64);
+        Ok(self.0.iter().take(limit).enumerate().map(|(i, close)| Candle {
+            timestamp: start + chrono::Duration::minutes(i as i64),
+            open: *close,
+            high: *close,
+            low: *close,
+            close: *close,
+        }).collect())
+    }
+}
+
+// A separate run of candles per token
+struct TokenCandles(HashMap<String, CannedCandles>);
+
+#[async_trait]
+impl CandleSource for TokenCandles {
+    async fn recent_candles(&self, token_address: &str, pool_address: &str, limit: usize) -> Result<Vec<Candle>> {
+        match self.0.get(token_address) {
+            Some(candles) => candles.recent_candles(token_address, pool_address, limit).await,
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_position_open_backfills_volatility_history() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.profit_manager.backfill.window", 10)?
+        .set_override("ant_colony.profit_manager.backfill.volatility_scale", 0.2)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+    profit_manager.set_candle_source(Arc::new(CannedCandles(vec![1.0, 1.1, 0.99, 1.2, 1.08, 1.3])));
+    assert!(profit_manager.volatility_tracker().volatility("TokenA").is_none());
+
+    profit_manager.add_trade(TradeProfit {
+        trade_id: "fresh".to_string(),
+        token_address: "TokenA".to_string(),
+        entry_price: 1.3,
+        entry_time: chrono::Utc::now(),
+        current_price: 1.3,
+        position_size: 10.0,
+        gas_fees: 0.0,
+        realized_profits: 0.0,
+        unrealized_profits: 0.0,
+        profit_tiers_hit: Vec::new(),
+        pool_address: "PoolA".to_string(),
+    }).await?;
+
+    // Seeded before a single live tick, and read on the very first evaluation
+    let tracker = profit_manager.volatility_tracker();
+    assert_eq!(tracker.history_len("TokenA"), 6);
+    let volatility = tracker.volatility("TokenA").expect("backfilled history yields a reading");
+    assert!(volatility > 0.0 && volatility < 1.0);
+    assert!((volatility - 0.1).abs() > 1e-6, "should not be the neutral default");
+    assert!((tracker.trend("TokenA").unwrap() - 0.3).abs() < 1e-9);
+
+    // Live ticks extend the seeded history
+    profit_manager.update_trade_price("fresh", 1.25).await?;
+    assert_eq!(profit_manager.volatility_tracker().history_len("TokenA"), 7);
+
+    Ok(())
+}
+
+#[test]
+fn test_volatility_is_normalized_return_stdev() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.profit_manager.backfill.min_samples", 3)?
+        .set_override("ant_colony.profit_manager.backfill.volatility_scale", 0.2)?
+        .build()?;
+    let mut tracker = VolatilityTracker::new(&config);
+
+    // Too little history gives no reading rather than a made-up one
+    tracker.record("TokenA", 1.0);
+    tracker.record("TokenA", 1.1);
+    assert!(tracker.volatility("TokenA").is_none());
+
+    // Returns of +10%, -10%, +10%: stdev 0.0943, over a scale of 0.2
+    tracker.record("TokenA", 0.99);
+    tracker.record("TokenA", 1.089);
+    let volatility = tracker.volatility("TokenA").unwrap();
+    assert!((volatility - 0.0942809 / 0.2).abs() < 1e-6);
+
+    // A flat price is not volatile, and wild swings cap at 1
+    for _ in 0..3 {
+        tracker.record("Flat", 2.0);
+    }
+    assert_eq!(tracker.volatility("Flat"), Some(0.0));
+    for price in [1.0, 3.0, 0.5, 4.0] {
+        tracker.record("Wild", price);
+    }
+    assert_eq!(tracker.volatility("Wild"), Some(1.0));
+
+    Ok(())
+}
+
+struct FixedGasPrice(f64);
+
+#[async_trait]
+impl GasPriceSource for FixedGasPrice {
+    async fn priority_fee(&self) -> Result<f64> {
+        Ok(self.0)
+    }
+}
+
+// Samples a new fee on every call
+struct ScriptedGasPrices(std::sync::Mutex<Vec<f64>>);
+
+#[async_trait]
+impl GasPriceSource for ScriptedGasPrices {
+    async fn priority_fee(&self) -> Result<f64> {
+        self.0.lock().unwrap().pop().ok_or_else(|| anyhow::anyhow!("no more fees"))
+    }
+}
+
+// Each fetch returns a new blockhash, valid for 150 blocks from a height that advances by 5
+#[derive(Default)]
+struct AdvancingBlockhashes(std::sync::atomic::AtomicU64);
+
+#[async_trait]
+impl BlockhashSource for AdvancingBlockhashes {
+    async fn latest_blockhash(&self) -> Result<(Hash, u64)> {
+        let fetches = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        Ok((Hash::new_unique(), fetches * 5 + 150))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_blockhash_cache_refreshes_on_interval() -> Result<()> {
+    let config = ::config::Config::builder()
+        .set_override("ant_colony.transaction_handler.blockhash_cache.refresh_interval_ms", 2000)?
+        .build()?;
+    let source = Arc::new(AdvancingBlockhashes::default());
+    let cache = Arc::new(BlockhashCache::new(&config, source.clone()));
+    assert!(cache.cached().is_none());
+    assert!(cache.is_expired(0));
+
+    let refresher = cache.clone().start();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let first = cache.cached().expect("refreshed on start");
+    assert_eq!(first.last_valid_block_height, 155);
+    assert_eq!(cache.blockhash().await?, first.blockhash);
+
+    // Nothing new until the interval elapses
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    assert_eq!(cache.cached(), Some(first));
+
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    let second = cache.cached().unwrap();
+    assert_ne!(second.blockhash, first.blockhash);
+    assert_eq!(second.last_valid_block_height, 160);
+    assert_eq!(cache.blockhash().await?, second.blockhash);
+    assert_eq!(source.0.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    assert!(!cache.is_expired(160));
+    assert!(cache.is_expired(161));
+    // Older blockhashes are still known, with their own validity window
+    assert_eq!(cache.lookup(&first.blockhash), Some(first));
+    assert!(first.is_expired(156));
+    assert!(cache.lookup(&Hash::new_unique()).is_none());
+
+    refresher.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_optimal_gas_price_tracks_rolling_fee_history() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.profit_manager.gas_price_window", 4)?
+        .set_override("ant_colony.profit_manager.gas.percentile", 0.75)?
+        .set_override("ant_colony.profit_manager.gas.safety_buffer", 1.5)?
+        .set_override("ant_colony.profit_manager.gas.compute_units", 200_000)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+
+    // With nothing sampled only the base fee is budgeted
+    assert_eq!(profit_manager.get_optimal_gas_price().await?, 0.0);
+    assert!((profit_manager.estimate_gas_cost().await? - 0.000005).abs() < 1e-12);
+
+    // Popped from the back: 100_000 is sampled first and falls out of the 4-sample window
+    profit_manager.set_gas_price_source(Arc::new(ScriptedGasPrices(std::sync::Mutex::new(
+        vec![1_000.0, 4_000.0, 3_000.0, 2_000.0, 100_000.0]
+    ))));
+    for _ in 0..5 {
+        profit_manager.update_gas_price_history().await?;
+    }
+    // 75th percentile of [1000, 2000, 3000, 4000] is 3000, plus the 50% buffer
+    assert_eq!(profit_manager.get_optimal_gas_price().await?, 4_500.0);
+    // 5000 lamports base plus 4500 µlamports × 200k CU = 900 lamports
+    assert!((profit_manager.estimate_gas_cost().await? - 0.0000059).abs() < 1e-12);
+
+    // A failed sample keeps the window as it was
+    profit_manager.update_gas_price_history().await?;
+    assert_eq!(profit_manager.get_optimal_gas_price().await?, 4_500.0);
+
+    Ok(())
+}
+
+// On-chain balances a test can change mid-run, per wallet
+#[derive(Default)]
+struct MockBalanceSource {
+    balances: std::sync::Mutex<HashMap<(Pubkey, String), f64>>,
+}
+
+impl MockBalanceSource {
+    fn set_balance(&self, wallet: &Pubkey, token_address: &str, balance: f64) {
+        self.balances.lock().unwrap().insert((*wallet, token_address.to_string()), balance);
+    }
+}
+
+#[async_trait]
+impl BalanceSource for MockBalanceSource {
+    async fn token_balance(&self, wallet: &Pubkey, token_address: &str) -> Result<f64> {
+        Ok(self.balances.lock().unwrap().get(&(*wallet, token_address.to_string())).copied().unwrap_or(0.0))
+    }
+}
+
+fn reconciled_trade(trade_id: &str, token_address: &str, position_size: f64) -> TradeProfit {
+    TradeProfit {
+        trade_id: trade_id.to_string(),
+        token_address: token_address.to_string(),
+        entry_price: 1.0,
+        entry_time: chrono::Utc::now(),
+        current_price: 1.2,
+        position_size,
+        gas_fees: 0.0,
+        realized_profits: 0.0,
+        unrealized_profits: 0.2 * position_size,
+        profit_tiers_hit: Vec::new(),
+        pool_address: "PoolA".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_runtime_reconciliation_detects_position_sold_outside_bot() -> Result<()> {
+    let config = colony_config_builder()?.build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let profit_manager = Arc::new(RwLock::new(ProfitManager::new(&config, state).await?));
+
+    let wallet = Pubkey::new_unique();
+    let balances = Arc::new(MockBalanceSource::default());
+    balances.set_balance(&wallet, "TokenA", 100.0);
+    let reconciler = PositionReconciler::new(&config, balances.clone(), Some(wallet));
+    reconciler.register(profit_manager.clone(), Vec::new());
+
+    profit_manager.write().await.add_trade(reconciled_trade("drifting", "TokenA", 100.0)).await?;
+
+    // In sync: nothing to correct
+    assert!(reconciler.reconcile().await?.is_empty());
+
+    // The tokens leave the wallet without the bot selling them
+    balances.set_balance(&wallet, "TokenA", 0.0);
+    let drifts = reconciler.reconcile().await?;
+
+    assert_eq!(drifts.len(), 1);
+    assert_eq!(drifts[0].token_address, "TokenA");
+    assert_eq!(drifts[0].tracked_size, 100.0);
+    assert_eq!(drifts[0].on_chain_size, 0.0);
+
+    let trade = profit_manager.read().await.get_trade_profits("drifting").await.unwrap();
+    assert_eq!(trade.position_size, 0.0);
+    assert_eq!(trade.unrealized_profits, 0.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reconciliation_totals_shared_mints_and_pool_wallets() -> Result<()> {
+    let config = colony_config_builder()?.build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let trading_wallet = Pubkey::new_unique();
+    let pool_wallets = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let balances = Arc::new(MockBalanceSource::default());
+    let reconciler = PositionReconciler::new(&config, balances.clone(), Some(trading_wallet));
+
+    // Two princesses on the trading wallet share a mint; a third buys through its pool
+    let first = Arc::new(RwLock::new(ProfitManager::new(&config, state.clone()).await?));
+    let second = Arc::new(RwLock::new(ProfitManager::new(&config, state.clone()).await?));
+    let pooled = Arc::new(RwLock::new(ProfitManager::new(&config, state.clone()).await?));
+    reconciler.register(first.clone(), Vec::new());
+    reconciler.register(second.clone(), Vec::new());
+    reconciler.register(pooled.clone(), pool_wallets.clone());
+    first.write().await.add_trade(reconciled_trade("first", "Shared", 60.0)).await?;
+    second.write().await.add_trade(reconciled_trade("second", "Shared", 40.0)).await?;
+    pooled.write().await.add_trade(reconciled_trade("pooled", "Pooled", 50.0)).await?;
+    balances.set_balance(&trading_wallet, "Shared", 100.0);
+    balances.set_balance(&pool_wallets[0], "Pooled", 20.0);
+    balances.set_balance(&pool_wallets[1], "Pooled", 30.0);
+
+    // Neither the shared mint nor the pool buy reads as drift
+    assert!(reconciler.reconcile().await?.is_empty());
+
+    // Half the shared mint is sold elsewhere: both princesses give up half their share
+    balances.set_balance(&trading_wallet, "Shared", 50.0);
+    let drifts = reconciler.reconcile().await?;
+    assert_eq!(drifts.len(), 1);
+    assert_eq!(drifts[0].tracked_size, 100.0);
+    assert_eq!(first.read().await.get_trade_profits("first").await.unwrap().position_size, 30.0);
+    assert_eq!(second.read().await.get_trade_profits("second").await.unwrap().position_size, 20.0);
+    assert_eq!(pooled.read().await.get_trade_profits("pooled").await.unwrap().position_size, 50.0);
+
+    // A despawned princess's positions drop out of the totals
+    reconciler.unregister(&second);
+    balances.set_balance(&trading_wallet, "Shared", 30.0);
+    assert!(reconciler.reconcile().await?.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_trade_replay_reproduces_decision_timeline() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let journal_path = dir.path().join("trade_journal.jsonl");
+    let config = colony_config_builder()?
+        .set_override("ant_colony.journal.enabled", true)?
+        .set_override("ant_colony.journal.path", journal_path.to_string_lossy().to_string())?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+
+    for (trade_id, token_address) in [("replayed", "TokenA"), ("other", "TokenB")] {
+        profit_manager.add_trade(TradeProfit {
+            trade_id: trade_id.to_string(),
+            token_address: token_address.to_string(),
+            entry_price: 1.0,
+            entry_time: chrono::Utc::now(),
+            current_price: 1.0,
+            position_size: 100.0,
+            gas_fees: 0.0,
+            realized_profits: 0.0,
+            unrealized_profits: 0.0,
+            profit_tiers_hit: Vec::new(),
+            pool_address: "PoolA".to_string(),
+        }).await?;
+    }
+
+    // 1.3x clears the first tier only, which should trigger a single partial sell
+    profit_manager.update_trade_price("replayed", 1.3).await?;
+    profit_manager.check_profit_tiers().await?;
+    profit_manager.update_trade_price("replayed", 1.1).await?;
+
+    let entries = TradeJournal::new(journal_path).replay("replayed")?;
+    let timeline: Vec<&JournalEvent> = entries.iter().map(|e| &e.event).collect();
+
+    assert_eq!(timeline.len(), 5);
+    assert!(matches!(timeline[0], JournalEvent::Opened { token_address, .. } if token_address == "TokenA"));
+    assert!(matches!(timeline[1], JournalEvent::PriceUpdate { price, .. } if *price == 1.3));
+    assert!(matches!(timeline[2], JournalEvent::Decision { action, .. } if action == "take_profit"));
+    assert!(matches!(timeline[3], JournalEvent::TransactionSent { .. }));
+    assert!(matches!(timeline[4], JournalEvent::PriceUpdate { price, .. } if *price == 1.1));
+    assert!(entries.iter().all(|e| e.trade_id == "replayed"));
+
+    let rendered = TradeJournal::format_timeline("replayed", &entries);
+    assert!(rendered.contains("Decided to take_profit"));
+
+    Ok(())
+}
+
+#[test]
+fn test_wallet_lock_acquired_when_free() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("wallets.lock");
+
+    let lock = WalletLock::acquire(path.clone(), chrono::Duration::seconds(60))?;
+    assert!(path.exists());
+
+    // Releasing the lock lets the next instance in
+    drop(lock);
+    assert!(!path.exists());
+    WalletLock::acquire(path, chrono::Duration::seconds(60))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_wallet_lock_refused_while_held() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("wallets.lock");
+
+    let _held = WalletLock::acquire(path.clone(), chrono::Duration::seconds(60))?;
+    assert!(WalletLock::acquire(path, chrono::Duration::seconds(60)).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_stale_wallet_lock_is_taken_over() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("wallets.lock");
+
+    // A crashed instance left its lock behind without heartbeating for ten minutes
+    let last_seen = chrono::Utc::now() - chrono::Duration::minutes(10);
+    let crashed = LockOwner {
+        instance_id: "crashed-instance".to_string(),
+        pid: 1,
+        acquired_at: last_seen,
+        heartbeat_at: last_seen,
+    };
+    std::fs::write(&path, serde_json::to_string(&crashed)?)?;
+
+    let lock = WalletLock::acquire(path, chrono::Duration::seconds(60))?;
+    assert_ne!(lock.owner().instance_id, "crashed-instance");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pending_confirmations_defer_submissions_until_drained() -> Result<()> {
+    let pending = Arc::new(PendingConfirmations::new(2));
+
+    let first = pending.reserve().await;
+    let second = pending.reserve().await;
+    assert_eq!(pending.pending_count(), 2);
+    assert!(pending.try_reserve().is_none());
+
+    // A third submission waits while both are unconfirmed
+    let waiting = tokio::spawn({
+        let pending = pending.clone();
+        async move { pending.reserve().await }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(!waiting.is_finished());
+
+    // One confirmation frees exactly one slot
+    drop(first);
+    let third = tokio::time::timeout(std::time::Duration::from_secs(1), waiting).await??;
+    assert_eq!(pending.pending_count(), 2);
+
+    drop(second);
+    drop(third);
+    assert_eq!(pending.pending_count(), 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_position_ceiling_rejects_entries_despite_ample_capital() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.princess.max_open_positions", 2)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState {
+        total_capital: 1_000_000.0,
+        ..ColonyState::default()
+    }));
+    let mut princess = build_princess(&config, state.clone()).await?;
+    princess.init().await?;
+
+    princess.execute_trade("TokenA".to_string(), 1.0).await?;
+    princess.execute_trade("TokenB".to_string(), 1.0).await?;
+    assert_eq!(princess.open_position_count().await, 2);
+
+    // Plenty of capital remains, but the count cap still blocks a third entry
+    let result = princess.execute_trade("TokenC".to_string(), 1.0).await;
+    assert!(result.unwrap_err().to_string().contains("position ceiling"));
+    assert_eq!(princess.open_position_count().await, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_entries_cannot_exceed_position_ceiling() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.princess.max_open_positions", 1)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState {
+        total_capital: 1_000_000.0,
+        ..ColonyState::default()
+    }));
+    let mut princess = build_princess(&config, state.clone()).await?;
+    princess.init().await?;
+
+    let (first, second) = tokio::join!(
+        princess.execute_trade("TokenA".to_string(), 1.0),
+        princess.execute_trade("TokenB".to_string(), 1.0),
+    );
+    assert_eq!([first.is_ok(), second.is_ok()].iter().filter(|ok| **ok).count(), 1);
+    assert_eq!(princess.open_position_count().await, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_profit_acceleration_sells_more_and_sooner_under_high_volatility() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.profit_manager.profit_acceleration.enabled", true)?
+        .set_override("ant_colony.profit_manager.profit_acceleration.volatility_threshold", 0.3)?
+        .set_override("ant_colony.profit_manager.profit_acceleration.sensitivity", 2.0)?
+        .set_override("ant_colony.profit_manager.profit_acceleration.max_acceleration", 2.0)?
+        .set_override("ant_colony.profit_manager.backfill.volatility_scale", 1.0)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+    // Backfilled history puts the calm token near 0.06 volatility and the volatile one near 0.67
+    profit_manager.set_candle_source(Arc::new(TokenCandles(HashMap::from([
+        ("CalmToken".to_string(), CannedCandles(vec![1.0, 1.0, 1.0, 1.0])),
+        ("VolatileToken".to_string(), CannedCandles(vec![1.0, 0.5, 1.0, 0.5, 1.0])),
+    ]))));
+
+    for (trade_id, token_address) in [("calm", "CalmToken"), ("volatile", "VolatileToken")] {
+        profit_manager.add_trade(TradeProfit {
+            trade_id: trade_id.to_string(),
+            token_address: token_address.to_string(),
+            entry_price: 1.0,
+            entry_time: chrono::Utc::now(),
+            current_price: 1.0,
+            position_size: 100.0,
+            gas_fees: 0.0,
+            realized_profits: 0.0,
+            unrealized_profits: 0.0,
+            profit_tiers_hit: Vec::new(),
+            pool_address: "PoolA".to_string(),
+        }).await?;
+    }
+
+    // 1.15x is short of the first 1.2x tier at baseline
+    profit_manager.update_trade_price("calm", 1.15).await?;
+    profit_manager.update_trade_price("volatile", 1.15).await?;
+    profit_manager.check_profit_tiers().await?;
+
+    let calm = profit_manager.get_trade_profits("calm").await.unwrap();
+    let volatile = profit_manager.get_trade_profits("volatile").await.unwrap();
+    assert_eq!(calm.position_size, 100.0);
+    assert!(volatile.position_size < 100.0, "accelerated tier should trigger earlier");
+
+    // Once both reach the first tier, the volatile trade sells a larger fraction
+    profit_manager.update_trade_price("calm", 1.25).await?;
+    profit_manager.check_profit_tiers().await?;
+    let calm = profit_manager.get_trade_profits("calm").await.unwrap();
+    let calm_sold = 100.0 - calm.position_size;
+    let volatile_sold = 100.0 - volatile.position_size;
+    assert!(calm_sold > 0.0);
+    assert!(volatile_sold > calm_sold);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_session_report_matches_simulated_session() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.profit_manager.max_unrealized_profit", 5.0)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state.clone()).await?;
+    let mut rug_detector = RugDetector::new(&config, state.clone()).await?;
+
+    for trade_id in ["capped", "laddered"] {
+        profit_manager.add_trade(TradeProfit {
+            trade_id: trade_id.to_string(),
+            token_address: format!("{}Token", trade_id),
+            entry_price: 1.0,
+            entry_time: chrono::Utc::now(),
+            current_price: 1.0,
+            position_size: if trade_id == "capped" { 100.0 } else { 10.0 },
+            gas_fees: 0.0,
+            realized_profits: 0.0,
+            unrealized_profits: 0.0,
+            profit_tiers_hit: Vec::new(),
+            pool_address: "PoolA".to_string(),
+        }).await?;
+    }
+
+    // One trade is force-exited above the cap, the other takes a partial profit and stays open
+    profit_manager.update_trade_price("capped", 1.1).await?;
+    profit_manager.update_trade_price("laddered", 1.3).await?;
+    profit_manager.check_profit_tiers().await?;
+
+    rug_detector.handle_rug_alert(RugAlert {
+        token_address: "SuspiciousToken".to_string(),
+        alert_type: RugAlertType::HoneypotDetected,
+        severity: RugAlertSeverity::Medium,
+        timestamp: chrono::Utc::now(),
+        details: "Sell simulation reverted".to_string(),
+    }).await?;
+
+    let capped = profit_manager.get_trade_profits("capped").await.unwrap();
+    let laddered = profit_manager.get_trade_profits("laddered").await.unwrap();
+    assert!(laddered.realized_profits > 0.0);
+
+    let report = state.read().await.session.report();
+    assert_eq!(report.trades_taken, 2);
+    assert_eq!(report.closed_trades, 1);
+    assert_eq!(report.win_rate, 1.0);
+    assert!((report.net_pnl - (capped.realized_profits + laddered.realized_profits)).abs() < 1e-9);
+    assert!((report.fees_spent - (capped.gas_fees + laddered.gas_fees)).abs() < 1e-9);
+    assert_eq!(report.alerts_raised, 1);
+    assert!(report.to_string().contains("Trades taken:  2"));
+
+    // The summary round-trips through the journal under the session id
+    let data_dir = tempfile::tempdir()?;
+    let journal = TradeJournal::new(data_dir.path().join("journal.jsonl"));
+    journal.record(SESSION_JOURNAL_ID, JournalEvent::SessionSummary { report: report.clone() });
+    let entries = journal.replay(SESSION_JOURNAL_ID)?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].event, JournalEvent::SessionSummary { report });
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_princess_rejects_invalid_trade_amounts() -> Result<()> {
+    let config = colony_config_builder()?.build()?;
+    let state = Arc::new(RwLock::new(ColonyState {
+        total_capital: 1000.0,
+        ..ColonyState::default()
+    }));
+    let mut princess = build_princess(&config, state).await?;
+    princess.init().await?;
+
+    for amount in [0.0, -1.0, f64::NAN] {
+        let err = princess.execute_trade("TokenA".to_string(), amount).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<TradeError>(), Some(TradeError::InvalidAmount(_))),
+                "amount {} should be rejected, got {}", amount, err);
+    }
+    assert_eq!(princess.open_position_count().await, 0);
+
+    Ok(())
+}
+
+// Pools a test can rearrange to simulate liquidity moving between them
+#[derive(Default)]
+struct MockPoolLocator {
+    pools: std::sync::Mutex<HashMap<String, Vec<PoolInfo>>>,
+}
+
+impl MockPoolLocator {
+    fn set_pools(&self, token_address: &str, pools: &[(&str, &str, f64)]) {
+        let pools = pools.iter()
+            .map(|(pool_address, dex, liquidity)| PoolInfo {
+                pool_address: pool_address.to_string(),
+                dex: dex.to_string(),
+                liquidity: *liquidity,
+            })
+            .collect();
+        self.pools.lock().unwrap().insert(token_address.to_string(), pools);
+    }
+}
+
+#[async_trait]
+impl PoolLocator for MockPoolLocator {
+    async fn pools_for(&self, token_address: &str) -> Result<Vec<PoolInfo>> {
+        Ok(self.pools.lock().unwrap().get(token_address).cloned().unwrap_or_default())
+    }
+}
+
+#[tokio::test]
+async fn test_pool_migration_repoints_position_to_new_pool() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.profit_manager.pool_migration.enabled", true)?
+        .set_override("ant_colony.profit_manager.pool_migration.min_liquidity_share", 0.8)?
+        .build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+
+    let pools = Arc::new(MockPoolLocator::default());
+    pools.set_pools("TokenA", &[("CurvePool", "pumpfun", 30_000.0)]);
+    profit_manager.set_pool_locator(pools.clone());
+
+    profit_manager.add_trade(TradeProfit {
+        trade_id: "graduating".to_string(),
+        token_address: "TokenA".to_string(),
+        entry_price: 1.0,
+        entry_time: chrono::Utc::now(),
+        current_price: 1.3,
+        position_size: 100.0,
+        gas_fees: 0.0,
+        realized_profits: 0.0,
+        unrealized_profits: 30.0,
+        profit_tiers_hit: vec![1.2],
+        pool_address: "CurvePool".to_string(),
+    }).await?;
+
+    // Still on the bonding curve: nothing to do
+    assert!(profit_manager.check_pool_migrations().await?.is_empty());
+
+    // Graduation drains the curve into a Raydium pool
+    pools.set_pools("TokenA", &[("CurvePool", "pumpfun", 500.0), ("RaydiumPool", "raydium", 85_000.0)]);
+    let migrations = profit_manager.check_pool_migrations().await?;
+
+    assert_eq!(migrations.len(), 1);
+    assert_eq!(migrations[0].from_pool, "CurvePool");
+    assert_eq!(migrations[0].to_pool, "RaydiumPool");
+    assert_eq!(migrations[0].to_dex, "raydium");
+
+    let trade = profit_manager.get_trade_profits("graduating").await.unwrap();
+    assert_eq!(trade.pool_address, "RaydiumPool");
+    assert_eq!(trade.position_size, 100.0);
+    assert_eq!(trade.entry_price, 1.0);
+    assert_eq!(trade.profit_tiers_hit, vec![1.2]);
+
+    // Already on the new pool, so the next check is quiet
+    assert!(profit_manager.check_pool_migrations().await?.is_empty());
+
+    Ok(())
+}
+
+// Remembers which pool each backfill asked for
+#[derive(Default)]
+struct PoolRecordingCandles {
+    pools: std::sync::Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl CandleSource for PoolRecordingCandles {
+    async fn recent_candles(&self, _token_address: &str, pool_address: &str, _limit: usize) -> Result<Vec<Candle>> {
+        self.pools.lock().unwrap().push(pool_address.to_string());
+        Ok(Vec::new())
+    }
+}
+
+#[tokio::test]
+async fn test_opened_trade_gets_its_deepest_pool() -> Result<()> {
+    let config = colony_config_builder()?.build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state).await?;
+
+    let pools = Arc::new(MockPoolLocator::default());
+    pools.set_pools("TokenA", &[("CurvePool", "pumpfun", 500.0), ("RaydiumPool", "raydium", 85_000.0)]);
+    profit_manager.set_pool_locator(pools);
+    let candles = Arc::new(PoolRecordingCandles::default());
+    profit_manager.set_candle_source(candles.clone());
+
+    profit_manager.add_trade(TradeProfit { pool_address: String::new(), ..open_position("unpooled") }).await?;
+
+    // The backfill reads candles from the pool the trade was opened on
+    assert_eq!(profit_manager.get_trade_profits("unpooled").await.unwrap().pool_address, "RaydiumPool");
+    assert_eq!(*candles.pools.lock().unwrap(), vec!["RaydiumPool".to_string()]);
+
+    // A pool the caller already knows is kept
+    profit_manager.add_trade(TradeProfit { token_address: "TokenB".to_string(), ..open_position("pooled") }).await?;
+    assert_eq!(profit_manager.get_trade_profits("pooled").await.unwrap().pool_address, "PoolA");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wallet_health_pauses_only_the_failing_wallet() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.wallet_health.enabled", true)?
+        .set_override("ant_colony.wallet_health.window_secs", 300)?
+        .set_override("ant_colony.wallet_health.min_samples", 4)?
+        .set_override("ant_colony.wallet_health.max_failure_rate", 0.5)?
+        .set_override("ant_colony.wallet_health.pause_secs", 600)?
+        .build()?;
+    let monitor = WalletHealthMonitor::new(&config);
+
+    // Too few outcomes to judge yet, even though every one failed
+    for _ in 0..3 {
+        assert!(!monitor.record("FailingWallet", false));
+    }
+    assert!(monitor.check("FailingWallet").is_ok());
+
+    // The fourth failure crosses the threshold and pauses the wallet
+    assert!(monitor.record("FailingWallet", false));
+    let paused = monitor.check("FailingWallet").unwrap_err();
+    assert_eq!(paused.wallet, "FailingWallet");
+    assert_eq!(paused.failures, 4);
+    assert_eq!(paused.samples, 4);
+
+    // A mostly healthy wallet keeps submitting
+    for succeeded in [true, true, false, true, true] {
+        assert!(!monitor.record("HealthyWallet", succeeded));
+    }
+    assert!(monitor.check("HealthyWallet").is_ok());
+    assert!(!monitor.is_paused("HealthyWallet"));
+    assert!(monitor.is_paused("FailingWallet"));
+    assert_eq!(monitor.failure_rate("HealthyWallet"), 0.2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_strategy_breaker_disables_only_the_losing_strategy() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.strategy_breaker.enabled", true)?
+        .set_override("ant_colony.strategy_breaker.window_secs", 3600)?
+        .set_override("ant_colony.strategy_breaker.min_window_pnl", -2.0)?
+        .set_override("ant_colony.strategy_breaker.cooldown_secs", 0)?
+        .build()?;
+    let breakers = StrategyBreakers::new(&config);
+
+    // Losses accumulate across the window until they pass the floor
+    assert!(!breakers.record_pnl("momentum", -1.0));
+    assert!(!breakers.record_pnl("momentum", 0.5));
+    assert!(breakers.check("momentum").is_ok());
+    assert!(breakers.record_pnl("momentum", -1.8));
+
+    let disabled = breakers.check("momentum").unwrap_err();
+    assert_eq!(disabled.strategy, "momentum");
+    assert!((disabled.window_pnl - -2.3).abs() < 1e-9);
+    assert!(disabled.until.is_none());
+
+    // A strategy with mixed but positive results keeps trading
+    for pnl in [-1.5, 2.0, -0.5, 1.0] {
+        assert!(!breakers.record_pnl("sniper", pnl));
+    }
+    assert!(breakers.check("sniper").is_ok());
+    assert!(breakers.is_disabled("momentum"));
+
+    // Without a cooldown only a manual reset brings it back
+    breakers.reset("momentum");
+    assert!(breakers.check("momentum").is_ok());
+    assert_eq!(breakers.window_pnl("momentum"), 0.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_daily_loss_limit_freezes_trading() -> Result<()> {
+    let config = colony_config_builder()?
+        .set_override("ant_colony.risk_governor.enabled", true)?
+        .set_override("ant_colony.risk_governor.max_daily_loss_sol", 100.0)?
+        .set_override("general.max_daily_trades", 50)?
+        .build()?;
+    let governor = Arc::new(RiskGovernor::new(&config));
+    let message_queue = MessageQueue::new(16);
+    let mut updates = message_queue.subscribe("risk".to_string(), 16, OverflowPolicy::Block).await;
+    governor.set_message_queue(message_queue);
+
+    // Losses below the limit, with a win in between, keep trading open
+    governor.record_realized(-60.0).await;
+    governor.record_realized(10.0).await;
+    assert!(governor.check().is_ok());
+
+    governor.record_realized(-50.0).await;
+    let frozen = governor.check().unwrap_err();
+    assert!((frozen.daily_loss - 100.0).abs() < 1e-9);
+    assert_eq!(frozen.max_daily_loss, 100.0);
+
+    let mut last_loss = None;
+    while let Some(message) = updates.try_recv() {
+        if let Message::RiskUpdate(update) = message {
+            last_loss = Some(update.daily_loss);
+        }
+    }
+    assert_eq!(last_loss, Some(100.0));
+
+    // The princess and the sentries see the same freeze through the colony state
+    let state = Arc::new(RwLock::new(ColonyState {
+        total_capital: 1_000_000.0,
+        risk_governor: governor.clone(),
+        ..ColonyState::default()
+    }));
+    let princess = build_princess(&config, state.clone()).await?;
+    let err = princess.execute_trade("token_frozen".to_string(), 10.0).await.unwrap_err();
+    assert!(err.downcast_ref::<TradingFrozen>().is_some());
+
+    let sentry = Sentry::new(&config, state.clone()).await?;
+    assert!(sentry.should_freeze_trading().await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_jito_bundle_is_submitted_with_tip_and_polled_until_confirmed() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/bundles"))
+        .and(body_partial_json(json!({"method": "sendBundle"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": "bundle-123"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let payer = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    let signature = transaction.signatures[0];
+
+    // Not landed on the first poll, confirmed on the second
+    Mock::given(method("POST"))
+        .and(path("/api/v1/bundles"))
+        .and(body_partial_json(json!({"method": "getBundleStatuses", "params": [["bundle-123"]]})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {"context": {"slot": 1}, "value": []}})))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/bundles"))
+        .and(body_partial_json(json!({"method": "getBundleStatuses"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+            "context": {"slot": 2},
+            "value": [{
+                "bundle_id": "bundle-123",
+                "transactions": [signature.to_string()],
+                "slot": 2,
+                "confirmation_status": "confirmed",
+                "err": {"Ok": null}
+            }]
+        }})))
+        .mount(&server)
+        .await;
+
+    mount_signature_statuses(&server, &["confirmed"]).await;
+    mount_transaction_meta(&server, 5_000, 1_400).await;
+    let tip_blockhash = Hash::new_unique();
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getLatestBlockhash"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+            "context": {"slot": 1},
+            "value": {"blockhash": tip_blockhash.to_string(), "lastValidBlockHeight": 100},
+        }})))
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.jito_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.jito.status_poll_interval_ms", 10)?
+        .set_override("ant_colony.transaction_handler.jito.status_timeout_ms", 2000)?
+        .set_override("ant_colony.transaction_handler.jito.tip_lamports", 7_500)?
+        .build()?;
+    let mut transaction_handler = TransactionHandler::new(&config).await?;
+    let tip_payer = Keypair::new();
+    let tip_wallet = tip_payer.pubkey().to_string();
+    transaction_handler.set_jito_tip_payer(tip_payer);
+    let guard_config = ::config::Config::builder()
+        .set_override("ant_colony.compromise_guard.enabled", true)?
+        .build()?;
+    let compromise_guard = Arc::new(CompromiseGuard::new(&guard_config));
+    transaction_handler.set_compromise_guard(compromise_guard.clone());
+
+    let result = transaction_handler.execute_bundle(TransactionBundle {
+        transactions: vec![transaction],
+        priority_fee: 5_000,
+        timestamp: chrono::Utc::now(),
+    }).await?;
+
+    assert!(result.success);
+    assert_eq!(result.signature, signature);
+    // Network fee from the confirmed meta plus the configured tip, not the per-CU priority fee
+    assert_eq!(result.gas_price, 12_500);
+    assert_eq!(result.gas_used, 1_400);
+
+    // The bundle went out with the tip transaction appended
+    let requests = server.received_requests().await.unwrap();
+    let send_bundle: serde_json::Value = requests.iter()
+        .map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).unwrap())
+        .find(|body| body["method"] == "sendBundle")
+        .unwrap();
+    assert_eq!(send_bundle["params"][0].as_array().unwrap().len(), 2);
+    assert_eq!(send_bundle["params"][1]["encoding"], "base64");
+
+    // Neither the swap nor the tip look foreign to the wallet scans, though neither is journaled
+    let tip_bytes = base64::engine::general_purpose::STANDARD
+        .decode(send_bundle["params"][0][1].as_str().unwrap())?;
+    let tip: Transaction = bincode::deserialize(&tip_bytes)?;
+    assert_eq!(tip.message.instructions[0].data,
+               system_instruction::transfer(&Pubkey::new_unique(), &Pubkey::new_unique(), 7_500).data);
+    // On a fresh blockhash rather than the swap's, which may be a durable nonce
+    assert_eq!(tip.message.recent_blockhash, tip_blockhash);
+    let history = ScriptedWalletActivity {
+        transactions: vec![observed(&signature.to_string(), true), observed(&tip.signatures[0].to_string(), true)],
+    };
+    let known = std::collections::HashSet::new();
+    assert!(compromise_guard.scan(&payer.pubkey().to_string(), &history, &known).await?.is_none());
+    assert!(compromise_guard.scan(&tip_wallet, &history, &known).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_simulation_aborts_send() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(json!({"method": "simulateTransaction"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+            "context": {"slot": 1},
+            "value": {
+                "err": {"InstructionError": [0, {"Custom": 6001}]},
+                "logs": [
+                    "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+                    "Program log: Error: Slippage tolerance exceeded",
+                ],
+                "accounts": null,
+                "unitsConsumed": 41_000,
+                "returnData": null
+            }
+        }})))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.jito_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.preflight.enabled", true)?
+        .build()?;
+    let transaction_handler = TransactionHandler::new(&config).await?;
+
+    let payer = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+
+    let simulation = transaction_handler.simulate(&transaction).await?;
+    assert!(!simulation.succeeded());
+    assert_eq!(simulation.units_consumed, Some(41_000));
+    assert_eq!(simulation.logs.len(), 2);
+
+    let error = transaction_handler.execute_transaction(transaction).await.unwrap_err().to_string();
+    assert!(error.contains("Slippage tolerance exceeded"), "{}", error);
+
+    // Nothing but the two simulations reached the node
+    let methods: Vec<String> = server.received_requests().await.unwrap().iter()
+        .map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).unwrap()["method"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(methods, vec!["simulateTransaction", "simulateTransaction"]);
+
+    Ok(())
+}
+
+// A fresh position in TokenA, 100 tokens bought at 1.0
+fn open_position(trade_id: &str) -> TradeProfit {
+    TradeProfit {
+        trade_id: trade_id.to_string(),
+        token_address: "TokenA".to_string(),
+        entry_price: 1.0,
+        entry_time: chrono::Utc::now(),
+        current_price: 1.0,
+        position_size: 100.0,
+        gas_fees: 0.0,
+        realized_profits: 0.0,
+        unrealized_profits: 0.0,
+        profit_tiers_hit: Vec::new(),
+        pool_address: "PoolA".to_string(),
+    }
+}
+
+// A swap executor for `signer` whose Jupiter routes and blockhashes come from `server` and
+// whose sends go through a TransactionHandler built from `config`
+async fn mock_swap_executor(server: &MockServer, config: ::config::ConfigBuilder<::config::builder::DefaultState>) -> Result<Arc<SwapExecutor>> {
+    let config = config
+        .set_override("jupiter.quote_url", format!("{}/quote", server.uri()))?
+        .set_override("jupiter.swap_url", format!("{}/swap", server.uri()))?
+        .set_override("ant_colony.transaction_handler.jito_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .build()?;
+    let signer = Arc::new(Keypair::new());
+    let transaction_handler = Arc::new(RwLock::new(TransactionHandler::new(&config).await?));
+    Ok(Arc::new(SwapExecutor::new(JupiterClient::new(&config)?, signer, transaction_handler)))
+}
+
+// The node reports every mint with 6 decimals
+async fn mount_token_decimals(server: &MockServer) {
+    Mock::given(method("POST")).and(body_partial_json(json!({ "method": "getTokenSupply" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": { "slot": 1 },
+                "value": { "amount": "1000000000000", "decimals": 6, "uiAmount": 1000000.0, "uiAmountString": "1000000" },
+            },
+        })))
+        .mount(server)
+        .await;
+}
+
+// Jupiter routes 40 tokens (6 decimals) into 100 SOL, and the node hands out a blockhash
+async fn mount_sell_route(server: &MockServer, signer: &Pubkey) -> Result<()> {
+    mount_token_decimals(server).await;
+    let swap = Transaction::new_with_payer(
+        &[system_instruction::transfer(signer, &Pubkey::new_unique(), 1)],
+        Some(signer),
+    );
+    Mock::given(method("GET")).and(path("/quote"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "inputMint": "TokenA",
+            "inAmount": "40000000",
+            "outputMint": "So11111111111111111111111111111111111111112",
+            "outAmount": "100000000000",
+            "priceImpactPct": "0.01",
+            "routePlan": [{ "swapInfo": { "label": "Raydium" }, "percent": 100 }],
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("POST")).and(path("/swap"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "swapTransaction": base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&swap)?),
+        })))
+        .mount(server)
+        .await;
+    Mock::given(method("POST")).and(body_partial_json(json!({ "method": "getLatestBlockhash" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": { "slot": 1 },
+                "value": { "blockhash": Hash::new_unique().to_string(), "lastValidBlockHeight": 100 },
+            },
+        })))
+        .mount(server)
+        .await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_partial_sell_goes_through_swap_executor() -> Result<()> {
+    // Sizes are converted at the mint's decimals, so the token has to be a real mint address
+    let mint = Pubkey::new_unique().to_string();
+
+    // No route, so the sell can't be built and the tier stays open
+    let server = MockServer::start().await;
+    mount_token_decimals(&server).await;
+    Mock::given(method("GET")).and(path("/quote"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    let failing = colony_config_builder()?.set_override("ant_colony.profit_manager.default_volatility", 0.0)?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&failing.clone().build()?, state.clone()).await?;
+    profit_manager.set_swap_executor(mock_swap_executor(&server, failing).await?);
+    profit_manager.add_trade(TradeProfit { token_address: mint.clone(), ..open_position("failed") }).await?;
+    profit_manager.update_trade_price("failed", 1.3).await?;
+
+    profit_manager.check_profit_tiers().await?;
+    let trade = profit_manager.get_trade_profits("failed").await.unwrap();
+    assert!(trade.profit_tiers_hit.is_empty());
+    assert_eq!(trade.realized_profits, 0.0);
+    assert_eq!(trade.position_size, 100.0);
+    assert_eq!(state.read().await.total_profit, 0.0);
+
+    // A paper fill succeeds: the 1.2x tier sells 40% and the gain is booked everywhere
+    let server = MockServer::start().await;
+    let filling = colony_config_builder()?
+        .set_override("general.paper_trading", true)?
+        .set_override("ant_colony.profit_manager.default_volatility", 0.0)?;
+    let swap_executor = mock_swap_executor(&server, filling.clone()).await?;
+    mount_sell_route(&server, &swap_executor.pubkey()).await?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let message_queue = MessageQueue::new(16);
+    let mut signals = message_queue.subscribe("signals".to_string(), 16, OverflowPolicy::Block).await;
+    let mut profit_manager = ProfitManager::new(&filling.build()?, state.clone()).await?;
+    profit_manager.set_swap_executor(swap_executor);
+    profit_manager.set_message_queue(message_queue);
+    profit_manager.add_trade(TradeProfit { token_address: mint.clone(), ..open_position("filled") }).await?;
+    profit_manager.update_trade_price("filled", 1.3).await?;
+
+    profit_manager.check_profit_tiers().await?;
+    let trade = profit_manager.get_trade_profits("filled").await.unwrap();
+    assert_eq!(trade.profit_tiers_hit, vec![1.2]);
+    assert!((trade.position_size - 60.0).abs() < 1e-9);
+    assert!((trade.realized_profits - 12.0).abs() < 1e-3);
+    assert_eq!(state.read().await.total_profit, trade.realized_profits);
+    // 40 tokens went out as 40 * 10^6 raw units
+    let quotes = server.received_requests().await.unwrap().into_iter()
+        .filter(|request| request.url.path() == "/quote")
+        .collect::<Vec<_>>();
+    assert!(quotes[0].url.query_pairs().any(|(key, value)| key == "amount" && value == "40000000"));
+
+    match signals.try_recv() {
+        Some(Message::TradeSignal(signal)) => {
+            assert_eq!(signal.token_address, mint);
+            assert!(matches!(signal.action, TradeAction::Sell));
+            assert!((signal.amount - 40.0).abs() < 1e-9);
+        }
+        other => panic!("Expected a sell signal, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_exit_floor_follows_current_price() -> Result<()> {
+    let mint = Pubkey::new_unique().to_string();
+    let server = MockServer::start().await;
+    let config = colony_config_builder()?
+        .set_override("general.paper_trading", true)?
+        .set_override("ant_colony.profit_manager.max_exit_slippage", 0.15)?;
+    let swap_executor = mock_swap_executor(&server, config.clone()).await?;
+    // The route pays 100 SOL for the whole 100-token position: 1 SOL per token
+    mount_sell_route(&server, &swap_executor.pubkey()).await?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config.build()?, state).await?;
+    profit_manager.set_swap_executor(swap_executor);
+    let losing = TradeProfit { token_address: mint.clone(), entry_price: 2.0, ..open_position("losing") };
+    profit_manager.add_trade(losing).await?;
+
+    // 1 SOL is more than 15% below a 1.5 quote, so the route is refused
+    profit_manager.update_trade_price("losing", 1.5).await?;
+    assert!(profit_manager.sell_token_fraction(&mint, 1.0, "test").await.is_err());
+    assert_eq!(profit_manager.get_trade_profits("losing").await.unwrap().position_size, 100.0);
+
+    // Within 15% of a 1.05 quote the position exits, even though it is below the 2.0 entry
+    profit_manager.update_trade_price("losing", 1.05).await?;
+    assert!(profit_manager.sell_token_fraction(&mint, 1.0, "test").await?);
+    assert_eq!(profit_manager.get_trade_profits("losing").await.unwrap().position_size, 0.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_failed_capped_exit_does_not_stop_the_pass() -> Result<()> {
+    let server = MockServer::start().await;
+    let journal_dir = tempfile::tempdir()?;
+    let journal_path = journal_dir.path().join("trade_journal.jsonl");
+    let config = colony_config_builder()?
+        .set_override("general.paper_trading", true)?
+        .set_override("ant_colony.profit_manager.max_exit_slippage", 0.15)?
+        .set_override("ant_colony.profit_manager.max_unrealized_profit", 5.0)?
+        .set_override("ant_colony.journal.enabled", true)?
+        .set_override("ant_colony.journal.path", journal_path.to_string_lossy().to_string())?;
+    let swap_executor = mock_swap_executor(&server, config.clone()).await?;
+    // The route pays 1 SOL per token for either position
+    mount_sell_route(&server, &swap_executor.pubkey()).await?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config.build()?, state).await?;
+    profit_manager.set_swap_executor(swap_executor);
+    for trade_id in ["refused", "exited"] {
+        let trade = TradeProfit {
+            token_address: Pubkey::new_unique().to_string(),
+            entry_price: 0.5,
+            ..open_position(trade_id)
+        };
+        profit_manager.add_trade(trade).await?;
+    }
+
+    // Both are above the cap, but 1 SOL is more than 15% below the first one's 2.0 quote
+    profit_manager.update_trade_price("refused", 2.0).await?;
+    profit_manager.update_trade_price("exited", 1.05).await?;
+    profit_manager.check_profit_tiers().await?;
+
+    // The refused exit is journaled and its position waits for the next pass, untouched by the ladder
+    let refused = profit_manager.get_trade_profits("refused").await.unwrap();
+    assert_eq!(refused.position_size, 100.0);
+    assert!(refused.profit_tiers_hit.is_empty());
+    let entries = TradeJournal::new(journal_path).replay("refused")?;
+    assert!(entries.iter().any(|entry| matches!(entry.event, JournalEvent::TransactionFailed { .. })));
+    assert_eq!(profit_manager.get_trade_profits("exited").await.unwrap().position_size, 0.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_configured_swap_executor_creates_missing_token_account_on_buy() -> Result<()> {
+    let server = MockServer::start().await;
+    let dir = tempfile::tempdir()?;
+    let keypair = Keypair::new();
+    let keypair_path = dir.path().join("trading.json");
+    solana_sdk::signature::write_keypair_file(&keypair, &keypair_path)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    mount_sell_route(&server, &keypair.pubkey()).await?;
+    // The wallet has never held the mint
+    Mock::given(method("POST")).and(body_partial_json(json!({ "method": "getAccountInfo" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "context": { "slot": 1 }, "value": null },
+        })))
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("jupiter.quote_url", format!("{}/quote", server.uri()))?
+        .set_override("jupiter.swap_url", format!("{}/swap", server.uri()))?
+        .set_override("jupiter.keypair_path", keypair_path.to_string_lossy().to_string())?
+        .set_override("ant_colony.transaction_handler.jito_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .build()?;
+    let transaction_handler = Arc::new(RwLock::new(TransactionHandler::new(&config).await?));
+    let swap_executor = SwapExecutor::from_config(&config, transaction_handler)?.unwrap();
+
+    let mint = Pubkey::new_unique();
+    let buy = swap_executor.build_buy(&mint.to_string(), 0.5).await?;
+
+    // The buy carries the idempotent create for the wallet's token account
+    let token_account = associated_token_address(&keypair.pubkey(), &mint);
+    let message = &buy.message;
+    assert!(message.instructions.iter().any(|instruction| {
+        instruction.data == vec![1]
+            && message.account_keys[instruction.accounts[1] as usize] == token_account
+    }));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_swap_executor_signs_on_cached_blockhash() -> Result<()> {
+    let server = MockServer::start().await;
+    let config = colony_config_builder()?
+        .set_override("jupiter.quote_url", format!("{}/quote", server.uri()))?
+        .set_override("jupiter.swap_url", format!("{}/swap", server.uri()))?
+        .set_override("ant_colony.transaction_handler.jito_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .build()?;
+    let signer = Arc::new(Keypair::new());
+    mount_sell_route(&server, &signer.pubkey()).await?;
+    let cache = Arc::new(BlockhashCache::new(&config, Arc::new(AdvancingBlockhashes::default())));
+    let cached = cache.refresh().await?;
+
+    let transaction_handler = Arc::new(RwLock::new(TransactionHandler::new(&config).await?));
+    let mut swap_executor = SwapExecutor::new(JupiterClient::new(&config)?, signer, transaction_handler);
+    swap_executor.set_blockhash_cache(cache);
+    let sell = swap_executor.build_sell(&Pubkey::new_unique().to_string(), 40.0, 1.0).await?;
+
+    assert_eq!(sell.message.recent_blockhash, cached.blockhash);
+    // Signing didn't go back to the node for a blockhash
+    let requests = server.received_requests().await.unwrap();
+    assert!(!requests.iter().any(|request| String::from_utf8_lossy(&request.body).contains("getLatestBlockhash")));
+    Ok(())
+}
+
+fn filled_buy(token_address: &str, status: ExecutionStatus) -> TradeExecution {
+    TradeExecution {
+        token_address: token_address.to_string(),
+        amount: 10.0,
+        price: 1.0,
+        timestamp: chrono::Utc::now(),
+        status,
+        transaction_hash: Some("sig".to_string()),
+        error: None,
+        total_costs: 0.005,
+        min_sell_price: 1.0,
+        liquidity_class: Default::default(),
+        trigger: Default::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_portfolio_summarizes_and_exports_closed_trades() -> Result<()> {
+    let portfolio = Portfolio::default();
+
+    // A winner and a loser, each sold down to nothing
+    let mut winner = open_position("winner");
+    portfolio.update_position(&winner);
+    winner.current_price = 1.5;
+    winner.position_size = 0.0;
+    winner.realized_profits = 30.0;
+    winner.gas_fees = 0.02;
+    portfolio.close_position(&winner)?;
+
+    let mut loser = TradeProfit { token_address: "TokenB".to_string(), entry_price: 2.0, position_size: 50.0, ..open_position("loser") };
+    portfolio.update_position(&loser);
+    loser.current_price = 1.8;
+    loser.position_size = 0.0;
+    loser.realized_profits = -10.0;
+    loser.gas_fees = 0.01;
+    portfolio.close_position(&loser)?;
+
+    // Closing again is refused rather than booked as a second trade
+    assert!(portfolio.close_position(&loser).is_err());
+
+    // A sniped buy still open, and one that never filled
+    portfolio.record_execution("sniped", &filled_buy("TokenC", ExecutionStatus::Completed));
+    portfolio.record_execution("unfilled", &filled_buy("TokenD", ExecutionStatus::Failed));
+    let mut sniped = open_position("sniped");
+    sniped.token_address = "TokenC".to_string();
+    sniped.position_size = 10.0;
+    sniped.current_price = 1.2;
+    sniped.unrealized_profits = 2.0;
+    sniped.gas_fees = 0.005;
+    portfolio.update_position(&sniped);
+
+    let summary = portfolio.summary();
+    assert_eq!(summary.open_positions, 1);
+    assert_eq!(summary.closed_trades, 2);
+    assert!((summary.total_realized - 20.0).abs() < 1e-9);
+    assert!((summary.total_unrealized - 2.0).abs() < 1e-9);
+    assert_eq!(summary.win_rate, 0.5);
+    assert!((summary.fees_paid - 0.035).abs() < 1e-9);
+
+    let data_dir = tempfile::tempdir()?;
+    let path = data_dir.path().join("reports").join("portfolio.csv");
+    portfolio.export_csv(&path)?;
+    let csv = std::fs::read_to_string(&path)?;
+    let rows: Vec<Vec<&str>> = csv.lines().map(|line| line.split(',').collect()).collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0], vec!["trade_id", "token_address", "entry_time", "exit_time", "entry_price",
+                             "exit_price", "amount", "realized_pnl", "fees"]);
+    assert_eq!((rows[1][0], rows[1][1], rows[1][5], rows[1][6], rows[1][7]), ("winner", "TokenA", "1.5", "100", "30"));
+    assert_eq!((rows[2][0], rows[2][1], rows[2][6], rows[2][7]), ("loser", "TokenB", "50", "-10"));
+
+    // The profit manager feeds the colony's portfolio as positions open and reprice
+    let config = colony_config_builder()?.build()?;
+    let state = Arc::new(RwLock::new(ColonyState::default()));
+    let mut profit_manager = ProfitManager::new(&config, state.clone()).await?;
+    profit_manager.add_trade(open_position("live")).await?;
+    profit_manager.update_trade_price("live", 1.1).await?;
+    let summary: PortfolioSummary = state.read().await.portfolio.summary();
+    assert_eq!(summary.open_positions, 1);
+    assert!((summary.total_unrealized - 10.0).abs() < 1e-9);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_paper_trading_fills_without_sending() -> Result<()> {
+    // Any request at all reaching the node would show up here
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": null})))
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.jito_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("general.paper_trading", true)?
+        .build()?;
+    let transaction_handler = TransactionHandler::new(&config).await?;
+    assert!(transaction_handler.is_paper_trading());
+
+    let payer = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    let result = transaction_handler.execute_transaction(transaction).await?;
+
+    assert!(result.success);
+    assert!(result.error.is_none());
+    let paper_trades = transaction_handler.paper_trades();
+    assert_eq!(paper_trades.len(), 1);
+    assert_eq!(paper_trades[0].signature, result.signature);
+    assert_eq!(paper_trades[0].bundle.transactions.len(), 1);
+    assert!(server.received_requests().await.unwrap().is_empty());
+
+    Ok(())
+}
+
+// Answers getSignatureStatuses with each confirmation status in turn, the last one repeating
+async fn mount_signature_statuses(server: &MockServer, statuses: &[&str]) {
+    for (i, status) in statuses.iter().enumerate() {
+        let last = i == statuses.len() - 1;
+        let mock = Mock::given(method("POST"))
+            .and(path("/"))
+            .and(body_partial_json(json!({"method": "getSignatureStatuses"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+                "context": {"slot": 10 + i},
+                "value": [{"slot": 10 + i, "confirmations": 0, "err": null, "status": {"Ok": null}, "confirmationStatus": status}]
+            }})))
+            .with_priority(1 + i as u8);
+        if last {
+            mock.mount(server).await;
+        } else {
+            mock.up_to_n_times(1).mount(server).await;
+        }
+    }
+}
+
+async fn mount_transaction_meta(server: &MockServer, fee: u64, compute_units: u64) {
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(json!({"method": "getTransaction"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+            "slot": 11,
+            "blockTime": null,
+            "transaction": ["", "base64"],
+            "meta": {
+                "err": null,
+                "status": {"Ok": null},
+                "fee": fee,
+                "preBalances": [],
+                "postBalances": [],
+                "computeUnitsConsumed": compute_units
+            }
+        }})))
+        .mount(server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(json!({"method": "getVersion"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {"solana-core": "1.16.0"}})))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_helius_execution_reports_success_only_after_confirmation() -> Result<()> {
+    let server = MockServer::start().await;
+    let payer = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    let signature = transaction.signatures[0];
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(json!({"method": "sendTransaction"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": signature.to_string()})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    // Only processed on the first poll, confirmed on the second
+    mount_signature_statuses(&server, &["processed", "confirmed"]).await;
+    mount_transaction_meta(&server, 5_000, 1_400).await;
+
+    // Jito stays out of the way so the bundle goes through Helius
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.confirmation.commitment", "confirmed")?
+        .set_override("ant_colony.transaction_handler.confirmation.poll_interval_ms", 10)?
+        .set_override("ant_colony.transaction_handler.confirmation.timeout_ms", 2000)?
+        .build()?;
+    let transaction_handler = TransactionHandler::new(&config).await?;
+
+    let result = transaction_handler.execute_bundle(TransactionBundle {
+        transactions: vec![transaction],
+        priority_fee: 5_000,
+        timestamp: chrono::Utc::now(),
+    }).await?;
+
+    assert!(result.success);
+    assert_eq!(result.signature, signature);
+    assert_eq!(result.gas_used, 1_400);
+    assert_eq!(result.gas_price, 5_000);
+
+    // Success was reported only after the status moved past processed
+    let requests = server.received_requests().await.unwrap();
+    let status_polls = requests.iter()
+        .map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).unwrap())
+        .filter(|body| body["method"] == "getSignatureStatuses")
+        .count();
+    assert_eq!(status_polls, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_helius_send_submits_the_signed_transaction() -> Result<()> {
+    let server = MockServer::start().await;
+    let payer = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    let signature = transaction.signatures[0];
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&transaction)?);
+
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(json!({
+            "method": "sendTransaction",
+            "params": [encoded, {"skipPreflight": true, "encoding": "base64"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": signature.to_string()})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    mount_signature_statuses(&server, &["confirmed"]).await;
+    mount_transaction_meta(&server, 5_000, 1_400).await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius.skip_preflight", true)?
+        .set_override("ant_colony.transaction_handler.confirmation.poll_interval_ms", 10)?
+        .build()?;
+    let transaction_handler = TransactionHandler::new(&config).await?;
+
+    let result = transaction_handler.execute_bundle(TransactionBundle {
+        transactions: vec![transaction],
+        priority_fee: 5_000,
+        timestamp: chrono::Utc::now(),
+    }).await?;
+
+    assert!(result.success);
+    assert_eq!(result.signature, signature);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_helius_send_errors_are_retried_then_surfaced() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(body_partial_json(json!({"method": "sendTransaction"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0", "id": 1,
+            "error": {"code": -32002, "message": "Transaction simulation failed: Blockhash not found"}
+        })))
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.retry_delay_ms", 10)?
+        .build()?;
+    let transaction_handler = TransactionHandler::new(&config).await?;
+
+    let payer = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    let result = transaction_handler.execute_bundle(TransactionBundle {
+        transactions: vec![transaction],
+        priority_fee: 5_000,
+        timestamp: chrono::Utc::now(),
+    }).await;
+
+    // No success is invented: every retry hit the RPC and the failure comes back
+    assert!(result.is_err());
+    let sends = server.received_requests().await.unwrap().iter()
+        .map(|request| serde_json::from_slice::<serde_json::Value>(&request.body).unwrap())
+        .filter(|body| body["method"] == "sendTransaction")
+        .count();
+    assert_eq!(sends, 3);
+
+    Ok(())
+}
+
+// A processed-only transaction never counts as executed
+#[tokio::test]
+async fn test_confirm_signature_times_out_below_commitment() -> Result<()> {
+    let server = MockServer::start().await;
+    mount_signature_statuses(&server, &["processed"]).await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.confirmation.poll_interval_ms", 10)?
+        .build()?;
+    let transaction_handler = TransactionHandler::new(&config).await?;
+
+    let confirmed = transaction_handler
+        .confirm_signature(&solana_sdk::signature::Signature::default(), std::time::Duration::from_millis(100))
+        .await?;
+    assert!(!confirmed);
+
+    Ok(())
+}
+
+#[test]
+fn test_priority_fee_from_recent_fees() {
+    // Ten recent fees for the same accounts, one outlier at the top
+    let fees = [500, 1200, 1500, 1800, 2000, 2500, 3000, 4000, 6000, 50000];
+
+    // 90th percentile of ten samples is the ninth lowest fee
+    assert_eq!(TransactionHandler::priority_fee_from_recent(&fees, 0.9, 1000, 10000), 6000);
+    // The outlier is capped at the configured maximum
+    assert_eq!(TransactionHandler::priority_fee_from_recent(&fees, 1.0, 1000, 10000), 10000);
+    // A quiet network never bids below the minimum
+    assert_eq!(TransactionHandler::priority_fee_from_recent(&fees, 0.0, 1000, 10000), 1000);
+    assert_eq!(TransactionHandler::priority_fee_from_recent(&[0, 0, 0], 0.9, 1000, 10000), 1000);
+    assert_eq!(TransactionHandler::priority_fee_from_recent(&[], 0.9, 1000, 10000), 1000);
+}
+
+#[tokio::test]
+async fn test_compute_budget_bids_priority_fee_per_compute_unit() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getRecentPrioritizationFees"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": [
+            {"slot": 1, "prioritizationFee": 2_000},
+            {"slot": 2, "prioritizationFee": 4_000},
+        ]})))
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.priority_fee_percentile", 1.0)?
+        .set_override("ant_colony.transaction_handler.compute_unit_limit", 300_000)?
+        .build()?;
+    let transaction_handler = TransactionHandler::new(&config).await?;
+
+    // A swap that already asks for its own price, which is replaced rather than doubled up
+    let payer = Keypair::new();
+    let recipient = Pubkey::new_unique();
+    let swap = Transaction::new_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_price(1),
+            system_instruction::transfer(&payer.pubkey(), &recipient, 1_000),
+        ],
+        Some(&payer.pubkey()),
+    );
+    let priced = transaction_handler.with_compute_budget(&swap).await?;
+
+    let message = &priced.message;
+    assert_eq!(message.instructions.len(), 3);
+    assert_eq!(message.instructions[0].data, ComputeBudgetInstruction::set_compute_unit_limit(300_000).data);
+    assert_eq!(message.instructions[1].data, ComputeBudgetInstruction::set_compute_unit_price(4_000).data);
+    assert_eq!(message.account_keys[message.instructions[2].accounts[1] as usize], recipient);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sell_gas_budget_reads_the_handler_bid_with_its_floor() -> Result<()> {
+    // A quiet network: nothing paid a priority fee in the recent slots
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getRecentPrioritizationFees"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": [
+            {"slot": 1, "prioritizationFee": 0},
+            {"slot": 2, "prioritizationFee": 0},
+        ]})))
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.min_priority_fee", 1000)?
+        .build()?;
+    let transaction_handler = Arc::new(RwLock::new(TransactionHandler::new(&config).await?));
+    let gas_price_source = HandlerGasPriceSource::new(transaction_handler);
+
+    // The sell is budgeted at the handler's floor rather than at zero
+    assert_eq!(gas_price_source.priority_fee().await?, 1000.0);
+
+    Ok(())
+}
+
+// Healthy baseline that tracks every signal, so each case below trips exactly one input
+fn healthy_signals() -> HealthSignals {
+    HealthSignals {
+        rpc_providers: Some((3, 3)),
+        last_loop_tick: Some(chrono::Utc::now()),
+        error_rate: 0.0,
+        drawdown: 0.0,
+        trading_halted: false,
+        paused_wallets: 0,
+        disabled_strategies: 0,
+        budget_remaining: Some(0.8),
+    }
+}
+
+#[test]
+fn test_colony_health_verdict_per_signal() {
+    let health = ColonyHealth::default();
+
+    let summary = health.evaluate(&healthy_signals());
+    assert_eq!(summary.verdict, HealthVerdict::Healthy);
+    assert!(summary.reasons.is_empty());
+
+    let cases: Vec<(&str, HealthVerdict, HealthSignals)> = vec![
+        ("rpc", HealthVerdict::Degraded, HealthSignals { rpc_providers: Some((2, 3)), ..healthy_signals() }),
+        ("rpc", HealthVerdict::Critical, HealthSignals { rpc_providers: Some((0, 3)), ..healthy_signals() }),
+        ("loop", HealthVerdict::Degraded,
+         HealthSignals { last_loop_tick: Some(chrono::Utc::now() - chrono::Duration::seconds(60)), ..healthy_signals() }),
+        ("loop", HealthVerdict::Critical,
+         HealthSignals { last_loop_tick: Some(chrono::Utc::now() - chrono::Duration::seconds(600)), ..healthy_signals() }),
+        ("errors", HealthVerdict::Degraded, HealthSignals { error_rate: 0.3, ..healthy_signals() }),
+        ("errors", HealthVerdict::Critical, HealthSignals { error_rate: 0.6, ..healthy_signals() }),
+        ("drawdown", HealthVerdict::Degraded, HealthSignals { drawdown: 0.15, ..healthy_signals() }),
+        ("drawdown", HealthVerdict::Critical, HealthSignals { drawdown: 0.3, ..healthy_signals() }),
+        ("trading", HealthVerdict::Critical, HealthSignals { trading_halted: true, ..healthy_signals() }),
+        ("wallets", HealthVerdict::Degraded, HealthSignals { paused_wallets: 1, ..healthy_signals() }),
+        ("strategies", HealthVerdict::Degraded, HealthSignals { disabled_strategies: 2, ..healthy_signals() }),
+        ("budget", HealthVerdict::Degraded, HealthSignals { budget_remaining: Some(0.2), ..healthy_signals() }),
+        ("budget", HealthVerdict::Critical, HealthSignals { budget_remaining: Some(0.01), ..healthy_signals() }),
+    ];
+
+    for (signal, verdict, signals) in cases {
+        let summary = health.evaluate(&signals);
+        assert_eq!(summary.verdict, verdict, "{} should be {:?}", signal, verdict);
+        assert_eq!(summary.reasons.len(), 1, "{} should be the only reason", signal);
+        assert_eq!(summary.reasons[0].signal, signal);
+        assert_eq!(summary.reasons[0].verdict, verdict);
+    }
+}
+
+#[test]
+fn test_colony_health_takes_worst_verdict_and_keeps_all_reasons() {
+    let summary = ColonyHealth::default().evaluate(&HealthSignals {
+        paused_wallets: 1,
+        drawdown: 0.3,
+        ..healthy_signals()
+    });
+
+    assert_eq!(summary.verdict, HealthVerdict::Critical);
+    let signals: Vec<&str> = summary.reasons.iter().map(|r| r.signal.as_str()).collect();
+    assert_eq!(signals, vec!["drawdown", "wallets"]);
+}
+
+#[test]
+fn test_health_signals_read_the_colony_capital_budget() {
+    let state = ColonyState::default();
+    // Nothing has reported yet and there are no RPC pools to judge
+    assert_eq!(state.health_signals().budget_remaining, None);
+    assert_eq!(state.health_signals().rpc_providers, None);
+
+    state.capital_budget.report("princess-a", 10.0, 100.0);
+    state.capital_budget.report("princess-b", 40.0, 100.0);
+    assert_eq!(state.health_signals().budget_remaining, Some(0.25));
+
+    // A despawned princess's manager drops out of the budget
+    state.capital_budget.remove("princess-a");
+    assert_eq!(state.health_signals().budget_remaining, Some(0.4));
+}
+
+#[tokio::test]
+async fn test_persisted_token_stats_size_the_next_entry() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let stats_path = dir.path().join("token_stats.json");
+    let config = colony_config_builder()?
+        .set_override("ant_colony.token_stats.enabled", true)?
+        .set_override("ant_colony.token_stats.min_samples", 3)?
+        .set_override("ant_colony.token_stats.kelly_fraction", 1.0)?
+        .build()?;
+
+    // Two thirds of launches in this class won 2 SOL, the rest lost 1 SOL: Kelly is 0.5
+    {
+        let mut stats = TokenStats::load(stats_path.clone(), &config)?;
+        for pnl in [2.0, 2.0, -1.0, 2.0, -1.0, 2.0] {
+            stats.record("pump_launch", pnl);
+        }
+        // A class that only ever lost
+        for _ in 0..3 {
+            stats.record("rugged_launch", -1.0);
+        }
+        stats.snapshot().expect("enabled stats have a snapshot").save().await?;
+    }
+
+    // After a restart the stats come back from disk
+    let stats = TokenStats::load(stats_path, &config)?;
+    let performance = stats.get("pump_launch").expect("stats should persist");
+    assert!((performance.win_rate() - 2.0 / 3.0).abs() < 1e-9);
+    assert!((performance.average_pnl() - 1.0).abs() < 1e-9);
+
+    // A fifth of the colony's 100 SOL is allocated to the princess
+    let state = Arc::new(RwLock::new(ColonyState {
+        token_stats: stats,
+        total_capital: 100.0,
+        ..ColonyState::default()
+    }));
+    let mut princess = build_princess(&config, state).await?;
+    princess.init().await?;
+
+    // Half of its 20 SOL for the proven class; nothing for the losing one, whose trades are
+    // skipped
+    assert!((princess.size_position("pump_launch", 15.0).await - 10.0).abs() < 1e-9);
+    assert_eq!(princess.size_position("rugged_launch", 15.0).await, 0.0);
+    assert!(princess.execute_trade("rugged_launch".to_string(), 15.0).await.is_err());
+    // No history yet: the requested size stands
+    assert_eq!(princess.size_position("unseen_launch", 15.0).await, 15.0);
+
+    Ok(())
+}
+
+// Replays a fixed on-chain history for every wallet
+struct ScriptedWalletActivity {
+    transactions: Vec<ObservedTransaction>,
+}
+
+#[async_trait]
+impl WalletActivitySource for ScriptedWalletActivity {
+    async fn recent_transactions(&self, _wallet: &str, limit: usize) -> Result<Vec<ObservedTransaction>> {
+        Ok(self.transactions.iter().take(limit).cloned().collect())
+    }
+}
+
+fn observed(signature: &str, outgoing: bool) -> ObservedTransaction {
+    ObservedTransaction { signature: signature.to_string(), outgoing, block_time: None }
+}
+
+#[tokio::test]
+async fn test_unexplained_outgoing_transaction_halts_wallet() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let journal = TradeJournal::new(dir.path().join("trade_journal.jsonl"));
+    journal.record("trade-1", JournalEvent::TransactionSent { signature: "bot-sig".to_string(), amount: 1.0 });
+    let config = colony_config_builder()?
+        .set_override("ant_colony.compromise_guard.enabled", true)?
+        .build()?;
+    let guard = CompromiseGuard::new(&config);
+    let known = journal.sent_signatures()?;
+
+    // The bot's own sends and incoming transfers are accounted for
+    let explained = ScriptedWalletActivity {
+        transactions: vec![observed("bot-sig", true), observed("airdrop-sig", false)],
+    };
+    assert!(guard.scan("PrincessWallet", &explained, &known).await?.is_none());
+    assert!(guard.check("PrincessWallet").is_ok());
+
+    // Someone else signed from the wallet
+    let drained = ScriptedWalletActivity {
+        transactions: vec![observed("bot-sig", true), observed("drain-sig", true)],
+    };
+    let alert = guard.scan("PrincessWallet", &drained, &known).await?.expect("wallet should be halted");
+    assert_eq!(alert.signature, "drain-sig");
+    assert!(matches!(alert.severity, AlertSeverity::Critical));
+
+    // The key stays out of use until an operator clears it
+    assert!(guard.check("PrincessWallet").unwrap_err().to_string().contains("compromised"));
+    assert!(guard.check("OtherWallet").is_ok());
+    guard.clear("PrincessWallet");
+    assert!(guard.check("PrincessWallet").is_ok());
+
+    // Through a princess the halt also counts as a session alert
+    let state = Arc::new(RwLock::new(ColonyState {
+        compromise_guard: Arc::new(CompromiseGuard::new(&config)),
+        ..ColonyState::default()
+    }));
+    let princess = build_princess(&config, state.clone()).await?;
+    assert!(princess.audit_wallet(&drained, &journal).await?.is_some());
+    assert_eq!(state.read().await.session.report().alerts_raised, 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_critical_rug_alert_sends_one_telegram_message() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/botTOKEN/sendMessage"))
+        .and(body_partial_json(json!({"chat_id": "42"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"ok": true})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.rug_detector.auto_blacklist", false)?
+        .set_override("notifications.telegram.enabled", true)?
+        .set_override("notifications.telegram.api_url", server.uri())?
+        .set_override("notifications.telegram.bot_token", "TOKEN")?
+        .set_override("notifications.telegram.chat_id", "42")?
+        .build()?;
+    let mut forwarder = AlertForwarder::new(&config)?;
+    assert!(forwarder.is_enabled());
+
+    // The rug detector publishes its alerts to the queue the forwarder listens on
+    let queue = MessageQueue::new(16);
+    let mut alerts = queue.subscribe_filtered("test".to_string(), MessageKind::COLONY_ALERT, 16, OverflowPolicy::DropOldest).await;
+    let mut rug_detector = RugDetector::new(&config, Arc::new(RwLock::new(ColonyState::default()))).await?;
+    rug_detector.set_message_queue(queue.clone());
+    let rug = RugAlert {
+        token_address: "RugToken".to_string(),
+        alert_type: RugAlertType::LiquidityDrop,
+        severity: RugAlertSeverity::Critical,
+        timestamp: chrono::Utc::now(),
+        details: "90% of liquidity pulled".to_string(),
+    };
+    rug_detector.handle_rug_alert(rug.clone()).await?;
+    rug_detector.handle_rug_alert(rug).await?;
+
+    let mut published = Vec::new();
+    while let Some(Message::ColonyAlert(alert)) = alerts.try_recv() {
+        published.push(alert);
+    }
+    assert_eq!(published.len(), 2);
+    assert_eq!(published[0].token_address.as_deref(), Some("RugToken"));
+
+    // The repeat is coalesced, and anything below High is never forwarded
+    assert!(forwarder.forward(&published[0]).await);
+    assert!(!forwarder.forward(&published[1]).await);
+    let medium = ColonyAlert { severity: antbot::common::AlertSeverity::Medium, message: "Volume dropped".to_string(), ..published[0].clone() };
+    assert!(!forwarder.forward(&medium).await);
+
+    server.verify().await;
+    Ok(())
+}
+
+// Records what would have been sent to Sentry
+#[derive(Default)]
+struct StubErrorTracker {
+    captured: std::sync::Mutex<Vec<(String, String, Option<String>)>>,
+}
+
+impl ErrorReporter for StubErrorTracker {
+    fn capture_error_with_context(&self, error: &anyhow::Error, component_id: &str, token_address: Option<&str>) {
+        self.captured.lock().unwrap().push((
+            error.to_string(),
+            component_id.to_string(),
+            token_address.map(str::to_string),
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_failed_bundle_is_captured_by_error_tracker() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.jito_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.max_retries", 1)?
+        .set_override("ant_colony.transaction_handler.retry_delay_ms", 0)?
+        .build()?;
+    let tracker = Arc::new(StubErrorTracker::default());
+    let mut transaction_handler = TransactionHandler::new(&config).await?;
+    transaction_handler.set_error_tracker(tracker.clone());
+
+    let payer = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000)],
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    );
+    let result = transaction_handler.execute_bundle(TransactionBundle {
+        transactions: vec![transaction],
+        priority_fee: 1_000,
+        timestamp: chrono::Utc::now(),
+    }).await;
+    assert!(result.is_err());
+
+    let captured = tracker.captured.lock().unwrap().clone();
+    assert_eq!(captured.len(), 1);
+    assert!(captured[0].0.contains("Max retries"));
+    assert_eq!(captured[0].1, transaction_handler.id());
+    assert_eq!(captured[0].2, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_wallet_pool_rotates_and_wraps() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("princess_0.pool");
+    let pool = WalletPool::load_or_create(&path, "test-key", 3, 2)?;
+    let wallets = pool.addresses();
+    assert_eq!(wallets.len(), 3);
+
+    // Two trades per wallet, then on to the next, wrapping back to the first
+    let mut signed = Vec::new();
+    for _ in 0..8 {
+        signed.push(pool.current().pubkey());
+        pool.record_trade();
+    }
+    assert_eq!(signed, vec![
+        wallets[0], wallets[0], wallets[1], wallets[1],
+        wallets[2], wallets[2], wallets[0], wallets[0],
+    ]);
+
+    // The same keypairs come back from disk, and only with the right key
+    assert!(!std::fs::read_to_string(&path)?.contains(&pool.current().to_base58_string()));
+    assert_eq!(WalletPool::load_or_create(&path, "test-key", 3, 2)?.addresses(), wallets);
+    assert!(WalletPool::load(&path, "wrong-key", 2).is_err());
+
+    // The shipped placeholder passphrase is refused rather than used to seal real keys
+    let placeholder = ::config::Config::builder()
+        .set_override("ant_colony.wallet_pool.enabled", true)?
+        .set_override("ant_colony.wallet_pool.dir", dir.path().to_string_lossy().to_string())?
+        .set_override("security.encryption_key", "YOUR_ENCRYPTION_KEY")?
+        .build()?;
+    assert!(WalletPool::from_config(&placeholder, 1).is_err());
+
+    // A wallet's token account is created until a trade has landed with it
+    let mint = Pubkey::new_unique();
+    let (token_account, create) = pool.token_account_setup(&wallets[0], &mint);
+    assert!(create.is_some());
+    pool.mark_token_account(&wallets[0], &mint);
+    assert_eq!(pool.token_account_setup(&wallets[0], &mint), (token_account, None));
+    assert!(pool.token_account_setup(&wallets[1], &mint).1.is_some());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_presigned_transaction_is_built_on_durable_nonce() -> Result<()> {
+    use solana_sdk::nonce::state::{Data, DurableNonce, State, Versions};
+
+    let authority = Keypair::new();
+    let nonce_account = Pubkey::new_unique();
+    let stored = Data::new(authority.pubkey(), DurableNonce::from_blockhash(&Hash::new_unique()), 5_000);
+    let account_data = bincode::serialize(&Versions::new(State::Initialized(stored.clone())))?;
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getAccountInfo"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0", "id": 1,
+            "result": { "context": { "slot": 1 }, "value": {
+                "data": [base64::engine::general_purpose::STANDARD.encode(&account_data), "base64"],
+                "executable": false, "lamports": 1_447_680,
+                "owner": "11111111111111111111111111111111", "rentEpoch": 0, "space": account_data.len(),
+            }},
+        })))
+        .mount(&server)
+        .await;
+
+    let config = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.jito_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("ant_colony.transaction_handler.nonce.enabled", true)?
+        .set_override("ant_colony.transaction_handler.nonce.account", nonce_account.to_string())?
+        .build()?;
+    let transaction_handler = TransactionHandler::new(&config).await?;
+    assert_eq!(transaction_handler.nonce_account(), Some(nonce_account));
+
+    let nonce = transaction_handler.fetch_nonce(&nonce_account).await?;
+    assert_eq!(nonce, stored.blockhash());
+
+    let recipient = Pubkey::new_unique();
+    let buy = Transaction::new_with_payer(
+        &[system_instruction::transfer(&authority.pubkey(), &recipient, 1_000)],
+        Some(&authority.pubkey()),
+    );
+    let presigned = transaction_handler.presign(buy, &[&authority]).await?;
+
+    // Advancing the nonce comes first, then the compute budget, and the nonce replaces the blockhash
+    let message = &presigned.message;
+    assert_eq!(message.recent_blockhash, nonce);
+    assert_eq!(message.instructions.len(), 4);
+    let advance = &message.instructions[0];
+    assert_eq!(message.account_keys[advance.program_id_index as usize], solana_sdk::system_program::id());
+    assert_eq!(message.account_keys[advance.accounts[0] as usize], nonce_account);
+    assert_eq!(advance.data, bincode::serialize(&solana_sdk::system_instruction::SystemInstruction::AdvanceNonceAccount)?);
+    for budget in &message.instructions[1..3] {
+        assert_eq!(message.account_keys[budget.program_id_index as usize], solana_sdk::compute_budget::id());
+    }
+    assert_eq!(message.account_keys[message.instructions[3].accounts[1] as usize], recipient);
+    assert!(presigned.verify().is_ok());
+
+    Ok(())
+}
+
+fn scaler_config() -> Result<::config::Config> {
+    Ok(::config::Config::builder()
+        .set_default("ant_colony.performance_monitor.success_rate_threshold_low", 0.3)?
+        .set_default("ant_colony.performance_monitor.success_rate_threshold_high", 0.6)?
+        .set_default("ant_colony.performance_monitor.min_workers", 2)?
+        .set_default("ant_colony.performance_monitor.max_workers", 8)?
+        .set_default("ant_colony.performance_monitor.scaling.scale_up_factor", 1.2)?
+        .set_default("ant_colony.performance_monitor.scaling.scale_down_factor", 0.7)?
+        .build()?)
+}
+
+#[tokio::test]
+async fn test_scaling_predicates_at_threshold_boundaries() -> Result<()> {
+    let mut scaler = ColonyScaler::new(&scaler_config()?);
+
+    // Nothing recorded yet: hold
+    assert!(!scaler.should_scale_up());
+    assert!(!scaler.should_scale_down());
+
+    // Exactly on a threshold holds as well
+    scaler.record_success_rate(0.6)?;
+    assert!(!scaler.should_scale_up());
+    scaler.record_success_rate(0.3)?;
+    assert!(!scaler.should_scale_down());
+
+    scaler.record_success_rate(0.61)?;
+    assert!(scaler.should_scale_up());
+    assert!(!scaler.should_scale_down());
+    scaler.record_success_rate(0.29)?;
+    assert!(scaler.should_scale_down());
+    assert!(!scaler.should_scale_up());
+
+    assert!(scaler.record_success_rate(1.5).is_err());
+    assert_eq!(scaler.last_success_rate(), Some(0.29));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_scaling_target_stays_within_bounds() -> Result<()> {
+    let scaler = ColonyScaler::new(&scaler_config()?);
+
+    assert_eq!(scaler.target(5, true), 6);
+    assert_eq!(scaler.target(6, false), 4);
+    assert_eq!(scaler.target(8, true), 8);
+    assert_eq!(scaler.target(2, false), 2);
+    assert_eq!(scaler.target(0, true), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vault_deposit_goes_through_the_colony_handler() -> Result<()> {
+    let server = MockServer::start().await;
+    let blockhash = Hash::new_unique();
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getLatestBlockhash"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+            "context": {"slot": 1},
+            "value": {"blockhash": blockhash.to_string(), "lastValidBlockHeight": 100},
+        }})))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir()?;
+    let keypair_path = dir.path().join("trading.json");
+    solana_sdk::signature::write_keypair_file(&Keypair::new(), &keypair_path)
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let vault = Pubkey::new_unique();
+    let builder = colony_config_builder()?
+        .set_override("ant_colony.transaction_handler.helius_rpc_url", server.uri())?
+        .set_override("general.paper_trading", true)?
+        .set_override("jupiter.keypair_path", keypair_path.to_str().unwrap())?;
+    let handler = Arc::new(RwLock::new(TransactionHandler::new(&builder.clone().build()?).await?));
+
+    // Without a vault address the deposit is only recorded
+    let unset = builder.clone().set_override("fund_management.vault_address", "")?.build()?;
+    assert!(VaultManager::from_config(&unset, handler.clone())?.deposit_to_vault(1.0).await?.is_none());
+    assert!(handler.read().await.paper_trades().is_empty());
+
+    // With one it is sent through the shared handler on its blockhash
+    let config = builder.set_override("fund_management.vault_address", vault.to_string())?.build()?;
+    let vault_manager = VaultManager::from_config(&config, handler.clone())?;
+    let signature = vault_manager.deposit_to_vault(0.5).await?.expect("transfer was sent");
+    let paper_trades = handler.read().await.paper_trades();
+    assert_eq!(paper_trades.len(), 1);
+    assert_eq!(paper_trades[0].signature, signature);
+    let transaction = &paper_trades[0].bundle.transactions[0];
+    assert_eq!(transaction.message.recent_blockhash, blockhash);
+    assert!(transaction.message.account_keys.contains(&vault));
+    assert_eq!(vault_manager.vault_balance(), 0.5);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_princesses_and_ladder_populate_the_dashboard_state() -> Result<()> {
+    let config = colony_config_builder()?.build()?;
+    let state = Arc::new(RwLock::new(ColonyState {
+        total_capital: 1000.0,
+        ..ColonyState::default()
+    }));
+    let mut princess = build_princess(&config, state.clone()).await?;
+
+    // Every rung of the ladder is listed before any position reaches it
+    let tiers = state.read().await.profit_tiers.clone();
+    assert!(!tiers.is_empty());
+    assert!(tiers.iter().all(|tier| tier.status == TierState::Pending && tier.timestamp.is_none()));
+
+    princess.init().await?;
+    {
+        let state = state.read().await;
+        assert_eq!(state.active_workers.len(), 1);
+        assert_eq!(state.active_workers[0].id, princess.get_id());
+        assert!(state.active_workers[0].is_active);
+        assert_eq!(state.active_workers[0].total_trades, 0);
+    }
+
+    princess.update_trade_status("TokenA", true, 0.5).await?;
+    princess.update_trade_status("TokenB", false, -0.25).await?;
+    let state = state.read().await;
+    assert_eq!(state.active_workers.len(), 1);
+    let worker = &state.active_workers[0];
+    assert_eq!(worker.total_trades, 2);
+    assert_eq!(worker.success_rate, 0.5);
+    assert_eq!(worker.profit_loss, 0.25);
+    assert!(worker.last_active.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_dashboard_history_drops_oldest_entries() {
+    let mut state = ColonyState::default();
+    for n in 0..MAX_DASHBOARD_HISTORY + 5 {
+        state.record_fill(n as f64, 0.0);
+    }
+    assert_eq!(state.performance_history.len(), MAX_DASHBOARD_HISTORY);
+    assert_eq!(state.performance_history.front().unwrap().profit, 5.0);
+
+    state.track_profit_tiers(&[ProfitTier { multiplier: 2.0, percentage: 0.5, gas_buffer: 1.0, volatility_adjustment: 0.0 }]);
+    state.record_tier_hit(2.0);
+    assert_eq!(state.profit_tiers[0].status, TierState::Hit);
+    assert!(state.profit_tiers[0].timestamp.is_some());
+}
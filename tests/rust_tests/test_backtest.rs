@@ -0,0 +1,65 @@
+use antbot::backtest::{Backtester, EntryRule, parse_csv};
+use antbot::sniping_core::{ExitManager, SnipingState};
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+// Thin pool at launch, a pump to 2x, then the dump
+const PUMP_AND_DUMP: &str = "\
+timestamp,price,volume,liquidity
+# launch
+1700000000,1.00,1000,2000
+1700000060,1.00,5000,20000
+1700000120,1.50,40000,35000
+1700000180,2.00,90000,50000
+1700000240,1.20,120000,30000
+1700000300,0.80,150000,15000
+1700000360,0.50,160000,8000
+";
+
+#[tokio::test]
+async fn test_backtest_pump_and_dump_triggers_stop_loss() -> Result<()> {
+    let records = parse_csv(PUMP_AND_DUMP)?;
+    assert_eq!(records.len(), 7);
+
+    let config = ::config::Config::builder().build()?;
+    let exit_manager = ExitManager::new(&config, Arc::new(RwLock::new(SnipingState::default()))).await?;
+    let mut backtester = Backtester::new(exit_manager, EntryRule {
+        amount: 100.0,
+        stop_loss_pct: 20.0,
+        min_liquidity: 10_000.0,
+        ..EntryRule::default()
+    });
+
+    let trade = backtester.run("PumpMint", &records).await?.expect("entry rule was met");
+
+    // Entered once liquidity was there, rode the pump and was stopped out on the way down
+    assert_eq!(trade.entry_price, 1.0);
+    assert_eq!(trade.entry_time, records[1].timestamp);
+    assert_eq!(trade.fills.len(), 1);
+    assert_eq!(trade.fills[0].reason, "StopLoss");
+    assert_eq!(trade.fills[0].price, 0.8);
+    assert_eq!(trade.fills[0].amount, 100.0);
+    assert_eq!(trade.exit_time, Some(records[5].timestamp));
+    assert!((trade.pnl + 20.0).abs() < 1e-9);
+    assert!((trade.max_drawdown - 0.6).abs() < 1e-9);
+
+    let summary = backtester.summary();
+    assert_eq!(summary.trades.len(), 1);
+    assert_eq!(summary.win_rate, 0.0);
+    assert!((summary.total_return + 0.2).abs() < 1e-9);
+    assert!((summary.max_drawdown - 0.6).abs() < 1e-9);
+
+    Ok(())
+}
+
+#[test]
+fn test_price_history_csv_rejects_bad_rows() {
+    assert!(parse_csv("timestamp,price,volume\n1700000000,1.0,10\n").is_err());
+    assert!(parse_csv("timestamp,price,volume,liquidity\n1700000000,abc,10,10\n").is_err());
+
+    // Columns may come in any order and timestamps may be RFC 3339
+    let records = parse_csv("price,liquidity,timestamp,volume\n2.5,100,2023-11-14T22:13:20Z,7\n").unwrap();
+    assert_eq!(records[0].price, 2.5);
+    assert_eq!(records[0].timestamp.timestamp(), 1_700_000_000);
+}
@@ -0,0 +1,82 @@
+use antbot::fund_management::{FundManager, VaultManager, VaultTransfer};
+use anyhow::Result;
+use async_trait::async_trait;
+use solana_sdk::signature::Signature;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct StubVaultTransfer {
+    fail: bool,
+    transfers: Mutex<Vec<u64>>,
+}
+
+#[async_trait]
+impl VaultTransfer for StubVaultTransfer {
+    async fn transfer(&self, lamports: u64) -> Result<Signature> {
+        if self.fail {
+            return Err(anyhow::anyhow!("transfer rejected"));
+        }
+        self.transfers.lock().unwrap().push(lamports);
+        Ok(Signature::new_unique())
+    }
+}
+
+#[tokio::test]
+async fn test_fund_manager_splits_profits() -> Result<()> {
+    let fund_manager = FundManager::new();
+    let (reinvestment, vault) = fund_manager.distribute_profits(200.0).await?;
+    assert_eq!(reinvestment, 160.0);
+    assert_eq!(vault, 40.0);
+
+    let fund_manager = FundManager::with_rates(0.7, 0.3)?;
+    let (reinvestment, vault) = fund_manager.distribute_profits(10.0).await?;
+    assert!((reinvestment + vault - 10.0).abs() < 1e-9);
+    assert!((vault - 3.0).abs() < 1e-9);
+
+    assert_eq!(fund_manager.distribute_profits(0.0).await?, (0.0, 0.0));
+    assert!(fund_manager.distribute_profits(-1.0).await.is_err());
+    assert!(FundManager::with_rates(0.8, 0.3).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fund_manager_reads_rates_from_config() -> Result<()> {
+    let config = ::config::Config::builder()
+        .set_default("fund_management.reinvestment_rate", 0.6)?
+        .set_default("fund_management.reserve_rate", 0.4)?
+        .build()?;
+    let fund_manager = FundManager::from_config(&config)?;
+    assert_eq!(fund_manager.reinvestment_rate(), 0.6);
+    assert_eq!(fund_manager.reserve_rate(), 0.4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_vault_deposit_moves_reserve_and_tracks_balance() -> Result<()> {
+    let transfer = Arc::new(StubVaultTransfer::default());
+    let mut vault_manager = VaultManager::new();
+    vault_manager.set_transfer(transfer.clone());
+
+    assert!(vault_manager.deposit_to_vault(1.5).await?.is_some());
+    vault_manager.deposit_to_vault(0.25).await?;
+    assert_eq!(*transfer.transfers.lock().unwrap(), vec![1_500_000_000, 250_000_000]);
+    assert_eq!(vault_manager.vault_balance(), 1.75);
+
+    // A failed transfer leaves the balance alone
+    let mut failing = VaultManager::new();
+    failing.set_transfer(Arc::new(StubVaultTransfer { fail: true, ..Default::default() }));
+    assert!(failing.deposit_to_vault(1.0).await.is_err());
+    assert_eq!(failing.vault_balance(), 0.0);
+
+    assert!(vault_manager.deposit_to_vault(0.0).await.is_err());
+    Ok(())
+}
+
+#[test]
+fn test_split_always_adds_up_to_the_profit() {
+    let fund_manager = FundManager::with_rates(0.7, 0.3).unwrap();
+    for profit in [0.1, 1.0 / 3.0, 12.345, 1e6] {
+        let (reinvestment, vault) = fund_manager.split(profit);
+        assert_eq!(reinvestment + vault, profit);
+    }
+}
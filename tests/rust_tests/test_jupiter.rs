@@ -0,0 +1,161 @@
+use antbot::common::{JupiterClient, AtaResolver, associated_token_address};
+use anyhow::Result;
+use base64::Engine;
+use serde_json::json;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::{Keypair, Signer}, system_instruction, transaction::Transaction};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use wiremock::matchers::{body_partial_json, method, path, query_param};
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const TOKEN_MINT: &str = "TokenMint1111111111111111111111111111111111";
+
+fn jupiter_config(server: &MockServer) -> Result<::config::Config> {
+    Ok(::config::Config::builder()
+        .set_default("jupiter.quote_url", format!("{}/quote", server.uri()))?
+        .set_default("jupiter.swap_url", format!("{}/swap", server.uri()))?
+        .set_default("jupiter.slippage_bps", 250)?
+        .build()?)
+}
+
+fn canned_quote() -> serde_json::Value {
+    json!({
+        "inputMint": SOL_MINT,
+        "inAmount": "500000000",
+        "outputMint": TOKEN_MINT,
+        "outAmount": "1234567",
+        "otherAmountThreshold": "1203703",
+        "swapMode": "ExactIn",
+        "slippageBps": 250,
+        "priceImpactPct": "0.012",
+        "routePlan": [{ "swapInfo": { "label": "Raydium" }, "percent": 100 }],
+    })
+}
+
+#[tokio::test]
+async fn test_jupiter_builds_swap_from_quote() -> Result<()> {
+    let server = MockServer::start().await;
+    let user = Keypair::new();
+
+    // What Jupiter would hand back: an unsigned legacy transaction paid for by the user
+    let swap = Transaction::new_with_payer(
+        &[system_instruction::transfer(&user.pubkey(), &Pubkey::new_unique(), 1)],
+        Some(&user.pubkey()),
+    );
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&swap)?);
+
+    Mock::given(method("GET"))
+        .and(path("/quote"))
+        .and(query_param("inputMint", SOL_MINT))
+        .and(query_param("outputMint", TOKEN_MINT))
+        .and(query_param("amount", "500000000"))
+        .and(query_param("slippageBps", "250"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(canned_quote()))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/swap"))
+        .and(body_partial_json(json!({
+            "userPublicKey": user.pubkey().to_string(),
+            "quoteResponse": { "outAmount": "1234567" },
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "swapTransaction": encoded,
+            "lastValidBlockHeight": 279_000_000u64,
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let jupiter = JupiterClient::new(&jupiter_config(&server)?)?;
+    let (quote, transaction) = jupiter.build_swap(jupiter.base_mint(), TOKEN_MINT, 500_000_000, &user.pubkey()).await?;
+
+    assert_eq!(quote.in_amount, 500_000_000);
+    assert_eq!(quote.out_amount, 1_234_567);
+    assert!((quote.price_impact - 0.012).abs() < 1e-9);
+    assert_eq!(transaction, swap);
+    assert_eq!(transaction.message.account_keys[0], user.pubkey());
+    assert_eq!(transaction.message.recent_blockhash, Hash::default());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_jupiter_errors_when_no_route() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/quote"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": "Could not find any route",
+            "errorCode": "COULD_NOT_FIND_ANY_ROUTE",
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/swap"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let jupiter = JupiterClient::new(&jupiter_config(&server)?)?;
+    let error = jupiter.build_swap(SOL_MINT, TOKEN_MINT, 1_000, &Pubkey::new_unique()).await
+        .expect_err("a quote without a route must not produce a swap");
+    assert!(error.to_string().contains("No Jupiter route"), "unexpected error: {}", error);
+
+    Ok(())
+}
+
+// Answers getAccountInfo for every address with `value`
+async fn mount_account_info(server: &MockServer, value: serde_json::Value) {
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getAccountInfo"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0", "id": 1,
+            "result": { "context": { "slot": 1 }, "value": value },
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_missing_token_account_is_created_before_buy() -> Result<()> {
+    let user = Keypair::new();
+    let mint = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let buy = Transaction::new_with_payer(
+        &[system_instruction::transfer(&user.pubkey(), &recipient, 1)],
+        Some(&user.pubkey()),
+    );
+
+    // The RPC has never seen the account, so creating it goes first
+    let server = MockServer::start().await;
+    mount_account_info(&server, serde_json::Value::Null).await;
+    let mut transaction = buy.clone();
+    let address = AtaResolver::new(server.uri()).ensure_ata(&user.pubkey(), &mint, &mut transaction).await?;
+
+    assert_eq!(address, associated_token_address(&user.pubkey(), &mint));
+    let message = &transaction.message;
+    assert_eq!(message.instructions.len(), 2);
+    assert_eq!(message.account_keys[0], user.pubkey());
+    let create = &message.instructions[0];
+    assert_eq!(message.account_keys[create.program_id_index as usize].to_string(),
+               "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+    assert_eq!(message.account_keys[create.accounts[1] as usize], address);
+    assert_eq!(message.account_keys[create.accounts[3] as usize], mint);
+    // The buy itself is kept, after the create
+    let transfer = &message.instructions[1];
+    assert_eq!(message.account_keys[transfer.accounts[1] as usize], recipient);
+
+    // An existing account leaves the buy untouched
+    let server = MockServer::start().await;
+    mount_account_info(&server, json!({
+        "data": ["", "base64"], "executable": false, "lamports": 2_039_280,
+        "owner": "TokenkegQfeZyiNwAJbNbGCPFHBQ6DFvaSoahXZ7WHRW", "rentEpoch": 0, "space": 165,
+    })).await;
+    let mut transaction = buy.clone();
+    AtaResolver::new(server.uri()).ensure_ata(&user.pubkey(), &mint, &mut transaction).await?;
+    assert_eq!(transaction, buy);
+
+    Ok(())
+}
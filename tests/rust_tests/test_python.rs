@@ -0,0 +1,34 @@
+use antbot::python::PythonContext;
+use anyhow::Result;
+use pyo3::prelude::*;
+
+#[test]
+fn test_python_context_reads_sys_version() -> Result<()> {
+    let ctx = PythonContext::new()?;
+
+    let sys = ctx.import_module("sys")?;
+    let version: String = Python::with_gil(|py| sys.getattr(py, "version")?.extract(py))?;
+    assert!(version.starts_with('3'), "unexpected interpreter version {}", version);
+
+    // The same interpreter answers through call_function, each call taking the GIL itself
+    let short_version: String = Python::with_gil(|py| {
+        ctx.call_function("platform", "python_version", &[])?.extract(py).map_err(anyhow::Error::from)
+    })?;
+    assert!(version.starts_with(&short_version));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_async_python_call_round_trips_dict_as_json() -> Result<()> {
+    let ctx = PythonContext::new()?;
+
+    // json.loads hands back a dict, which comes out the other side serialized again
+    let payload = serde_json::json!({"token": "TokenA", "sentiment": 0.75, "sources": ["x", "telegram"]});
+    let result = ctx.call_function_async("json", "loads", vec![payload.to_string().into()]).await?;
+
+    let round_tripped: serde_json::Value = serde_json::from_str(&result)?;
+    assert_eq!(round_tripped, payload);
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use antbot::rpc::{RpcProvider, RpcErrorKind, ErrorPenalties, ProviderErrorTracker, RpcClientWrapper, PoolSettings, PooledClient, HealthCheck, check_health};
+use anyhow::anyhow;
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient as AsyncRpcClient;
+use solana_client::rpc_client::RpcClient;
+use std::time::Duration;
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::{Keypair, Signer}, system_instruction, transaction::Transaction};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use wiremock::matchers::{body_partial_json, method};
+
+#[test]
+fn test_provider_errors_are_classified_and_counted() {
+    let tracker = ProviderErrorTracker::new(ErrorPenalties::default());
+
+    let cases = [
+        (anyhow!("HTTP status client error (429 Too Many Requests)"), RpcErrorKind::RateLimited),
+        (anyhow!("HTTP status client error (401 Unauthorized)"), RpcErrorKind::Unauthorized),
+        (anyhow!("operation timed out"), RpcErrorKind::Timeout),
+        (anyhow!("RPC response error -32005: Node is behind by 150 slots"), RpcErrorKind::NodeBehind),
+        (anyhow!("invalid transaction: blockhash not found"), RpcErrorKind::Other),
+    ];
+
+    for (error, expected) in &cases {
+        assert_eq!(tracker.record(RpcProvider::Helius, error), *expected);
+    }
+
+    for (_, kind) in &cases {
+        assert_eq!(tracker.count(RpcProvider::Helius, *kind), 1);
+        assert_eq!(tracker.count(RpcProvider::Triton, *kind), 0);
+    }
+    assert_eq!(tracker.counts().len(), cases.len());
+}
+
+#[test]
+fn test_auth_and_rate_limit_errors_deprioritize_provider() {
+    let tracker = ProviderErrorTracker::new(ErrorPenalties::default());
+    let order = [RpcProvider::Helius, RpcProvider::Triton, RpcProvider::Jito];
+    assert_eq!(tracker.rank(&order), order.to_vec());
+
+    // An auth failure outweighs a couple of timeouts elsewhere
+    tracker.record(RpcProvider::Helius, &anyhow!("403 Forbidden"));
+    tracker.record(RpcProvider::Triton, &anyhow!("request timed out"));
+    tracker.record(RpcProvider::Triton, &anyhow!("request timed out"));
+    assert_eq!(
+        tracker.rank(&order),
+        vec![RpcProvider::Jito, RpcProvider::Triton, RpcProvider::Helius]
+    );
+
+    // Rate limiting on Jito drops it behind the timing-out Triton too
+    tracker.record(RpcProvider::Jito, &anyhow!("429 Too Many Requests"));
+    assert_eq!(
+        tracker.rank(&order),
+        vec![RpcProvider::Triton, RpcProvider::Jito, RpcProvider::Helius]
+    );
+}
+
+#[test]
+fn test_error_penalties_expire_after_window() {
+    let tracker = ProviderErrorTracker::new(ErrorPenalties {
+        window_secs: 0,
+        ..ErrorPenalties::default()
+    });
+
+    tracker.record(RpcProvider::Helius, &anyhow!("401 Unauthorized"));
+    std::thread::sleep(std::time::Duration::from_millis(10));
+
+    // Lifetime counters remain for metrics, but routing forgives old errors
+    assert_eq!(tracker.count(RpcProvider::Helius, RpcErrorKind::Unauthorized), 1);
+    assert_eq!(tracker.penalty(RpcProvider::Helius), 0.0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transaction_cost_adds_priority_fee_and_jito_tip() -> anyhow::Result<()> {
+    let server = MockServer::start().await;
+    // Two signatures at 5000 lamports each
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getFeeForMessage"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "result": {"context": {"slot": 1}, "value": 10_000},
+            "id": 1,
+        })))
+        .mount(&server)
+        .await;
+
+    let payer = Keypair::new();
+    let cosigner = Keypair::new();
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1),
+            system_instruction::transfer(&cosigner.pubkey(), &Pubkey::new_unique(), 1),
+        ],
+        Some(&payer.pubkey()),
+        &[&payer, &cosigner],
+        Hash::new_unique(),
+    );
+
+    // 10,000 micro-lamports over 200,000 compute units is a 2,000 lamport priority fee
+    let config = ::config::Config::builder()
+        .set_override("ant_colony.transaction_handler.min_priority_fee", 10_000)?
+        .set_override("ant_colony.transaction_handler.compute_unit_limit", 200_000)?
+        .set_override("ant_colony.transaction_handler.jito.tip_lamports", 1_000)?
+        .build()?;
+    let jito = RpcClientWrapper::new(RpcClient::new(server.uri()), RpcProvider::Jito, &config);
+    assert_eq!(jito.estimate_transaction_cost(&transaction).await?, 13_000);
+
+    // Other providers never pay the tip
+    let helius = RpcClientWrapper::new(RpcClient::new(server.uri()), RpcProvider::Helius, &config);
+    assert_eq!(helius.estimate_transaction_cost(&transaction).await?, 12_000);
+
+    // The setters still override what was loaded
+    let mut overridden = RpcClientWrapper::new(RpcClient::new(server.uri()), RpcProvider::Jito, &config);
+    overridden.set_priority_fee(0);
+    overridden.set_jito_tip(0);
+    assert_eq!(overridden.estimate_transaction_cost(&transaction).await?, 10_000);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unhealthy_connection_fails_recycle_check() -> anyhow::Result<()> {
+    let healthy = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getHealth"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0", "result": "ok", "id": 1,
+        })))
+        .expect(1)
+        .mount(&healthy)
+        .await;
+
+    let behind = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getHealth"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "jsonrpc": "2.0",
+            "error": {"code": -32005, "message": "Node is behind by 150 slots", "data": {"numSlotsBehind": 150}},
+            "id": 1,
+        })))
+        .expect(2)
+        .mount(&behind)
+        .await;
+
+    // The pool reuses a connection only when this passes, and replaces it otherwise
+    let timeout = Duration::from_secs(2);
+    assert!(check_health(&AsyncRpcClient::new(healthy.uri()), timeout).await.is_ok());
+    let error = check_health(&AsyncRpcClient::new(behind.uri()), timeout).await.unwrap_err();
+    assert!(error.to_string().contains("failed health check"));
+
+    // An unreachable node fails too
+    assert!(check_health(&AsyncRpcClient::new("http://127.0.0.1:1".to_string()), timeout).await.is_err());
+
+    // Recently used connections are reused without a getHealth round trip
+    let mut pooled = PooledClient::new(RpcClient::new(behind.uri()));
+    let settings = PoolSettings { health_check_idle_secs: 3600, ..PoolSettings::default() };
+    assert!(HealthCheck::new(&behind.uri(), &settings).recycle(&mut pooled).await.is_ok());
+    let settings = PoolSettings { health_check_idle_secs: 0, ..PoolSettings::default() };
+    assert!(HealthCheck::new(&behind.uri(), &settings).recycle(&mut pooled).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_slow_node_fails_health_check_within_timeout() -> anyhow::Result<()> {
+    let slow = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getHealth"})))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_json(json!({"jsonrpc": "2.0", "result": "ok", "id": 1}))
+            .set_delay(Duration::from_secs(10)))
+        .mount(&slow)
+        .await;
+
+    let started = std::time::Instant::now();
+    let error = check_health(&AsyncRpcClient::new(slow.uri()), Duration::from_millis(200)).await.unwrap_err();
+    assert!(error.to_string().contains("no answer"));
+    assert!(started.elapsed() < Duration::from_secs(2));
+    Ok(())
+}
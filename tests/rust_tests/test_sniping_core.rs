@@ -1,5 +1,31 @@
-use antbot::sniping_core::{radar::Radar, buy_engine::BuyEngine, exit_strategies::ExitManager};
+use antbot::sniping_core::{Radar, BuyEngine, ExitManager};
+use antbot::sniping_core::{ActiveTrade, ExitType, TakeProfitLevel};
 use antbot::config::Config;
+use antbot::sniping_core::{SnipingState, CoinScanner, AdaptiveSlippage, LiquidityClass, TradeStatus, LiquiditySource};
+use antbot::sniping_core::{LaunchObserver, PoolMonitor, PoolSnapshot, ObservationOutcome, TokenOpportunity};
+use antbot::sniping_core::{ExitLiquidityCheck, ExitQuoter, ExitQuote};
+use antbot::sniping_core::{QuoteFreshness, EntryQuoter, EntryQuote};
+use antbot::sniping_core::{PriceFeed, PriceProvider, PriceSource};
+use antbot::sniping_core::{PoolLiquidity, PoolDecoder, PoolReserves, BondingCurveDecoder};
+use antbot::sniping_core::{SentimentKillswitch, TriggerCondition};
+use antbot::sniping_core::{DcaStrategy, DcaPlan, DcaStop};
+use antbot::sniping_core::{PositionSizer, SizingStrategy, kelly_fraction};
+use antbot::sniping_core::WhaleTracker;
+use antbot::sniping_core::CopyTrader;
+use antbot::sniping_core::Supervisor;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use antbot::ant_colony::TradeStats;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use async_trait::async_trait;
+use serde_json::json;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use wiremock::matchers::{body_partial_json, method, path, query_param};
+use base64::Engine;
+use antbot::common::{TradeError, MarketData, MarketDataProvider};
+use antbot::common::{Message, MessageKind, MessageQueue, OverflowPolicy, LiquidityAlert, AlertType, AlertSeverity};
+use antbot::common::{TradeAction, TradeEventStatus};
+use antbot::ant_colony::TokenBlacklist;
 use anyhow::Result;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -54,22 +80,47 @@ async fn test_buy_engine_execution() -> Result<()> {
     Ok(())
 }
 
+// Deep enough in USD to pass the entry checks, but only 50 SOL on the side a buy pays into
+struct ShallowPool;
+
+#[async_trait]
+impl LiquiditySource for ShallowPool {
+    async fn liquidity(&self, _token_address: &str) -> Result<f64> {
+        Ok(20_000.0)
+    }
+
+    async fn reserve_in(&self, _token_address: &str) -> Result<f64> {
+        Ok(50.0)
+    }
+}
+
 #[tokio::test]
 async fn test_buy_engine_slippage_protection() -> Result<()> {
-    let config = Config::load()?;
-    let state = Arc::new(RwLock::new(SnipingState::default()));
-    
-    let buy_engine = BuyEngine::new(&config, state.clone()).await?;
-    let test_trade = Trade {
-        token_address: "0x1234...5678".to_string(),
-        amount: 1.0,
-        max_slippage: 0.1, // Very low slippage tolerance
-        gas_price: 50,
-    };
-    
-    let result = buy_engine.execute_trade(&test_trade).await?;
-    assert!(!result.success); // Should fail due to high slippage
-    
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.01)? // Very low slippage tolerance
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .build()?;
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.set_liquidity_source(Arc::new(ShallowPool));
+    buy_engine.init().await?;
+
+    // 0.95 SOL after the volatility haircut into 50 SOL moves the price ~1.9%
+    let result = buy_engine.execute_trade("0x1234...5678", 1.0).await;
+    let error = result.expect_err("Should fail due to high slippage").to_string();
+    assert!(error.contains("Price impact"), "unexpected error: {}", error);
+    assert!(buy_engine.get_active_trades().await.is_empty());
+    // The failed buy is gone rather than left for the run loop to retry
+    assert!(buy_engine.get_pending_trades().await.is_empty());
+    assert!(buy_engine.process_pending_trades().await?.is_empty());
+
+    // The same buy through a pool deep enough stays within tolerance
+    let mut deep_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    deep_engine.set_liquidity_source(Arc::new(DeepPools));
+    deep_engine.init().await?;
+    deep_engine.execute_trade("0x1234...5678", 1.0).await?;
+
     Ok(())
 }
 
@@ -86,60 +137,95 @@ async fn test_exit_strategy_initialization() -> Result<()> {
     Ok(())
 }
 
-#[tokio::test]
-async fn test_exit_strategy_stop_loss() -> Result<()> {
+// Market data whose price per token is set by the test; unknown tokens fail like an outage
+#[derive(Default)]
+struct FakeMarketData {
+    prices: std::sync::Mutex<std::collections::HashMap<String, f64>>,
+}
+
+impl FakeMarketData {
+    fn set_price(&self, token_address: &str, price: f64) {
+        self.prices.lock().unwrap().insert(token_address.to_string(), price);
+    }
+
+    fn clear(&self, token_address: &str) {
+        self.prices.lock().unwrap().remove(token_address);
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for FakeMarketData {
+    async fn market_data(&self, token_address: &str) -> Result<MarketData> {
+        let price = self.prices.lock().unwrap().get(token_address).copied()
+            .ok_or_else(|| anyhow::anyhow!("no market data for {}", token_address))?;
+        Ok(MarketData { price, volume_24h: 25_000.0 })
+    }
+}
+
+async fn exit_manager_with_fake_market() -> Result<(ExitManager, Arc<FakeMarketData>)> {
     let config = Config::load()?;
     let state = Arc::new(RwLock::new(SnipingState::default()));
-    
-    let exit_manager = ExitManager::new(&config, state.clone()).await?;
+    let market = Arc::new(FakeMarketData::default());
+    let mut exit_manager = ExitManager::new(&config, state).await?;
+    exit_manager.set_market_data(market.clone());
+    Ok((exit_manager, market))
+}
+
+#[tokio::test]
+async fn test_exit_strategy_stop_loss() -> Result<()> {
+    let (exit_manager, market) = exit_manager_with_fake_market().await?;
     let test_trade = ActiveTrade {
         token_address: "0x1234...5678".to_string(),
         entry_price: 100.0,
         amount: 1.0,
         stop_loss: 90.0, // 10% stop loss
         take_profit: 120.0, // 20% take profit
+        ..ActiveTrade::default()
     };
     
     exit_manager.add_trade(test_trade).await?;
+
+    market.set_price("0x1234...5678", 95.0);
+    assert!(exit_manager.check_exit_conditions().await?.is_empty());
     
     // Simulate price drop below stop loss
-    let result = exit_manager.check_exit_conditions(85.0).await?;
-    assert!(result.should_exit);
-    assert_eq!(result.exit_type, ExitType::StopLoss);
+    market.set_price("0x1234...5678", 85.0);
+    let exits = exit_manager.check_exit_conditions().await?;
+    assert_eq!(exits.len(), 1);
+    assert_eq!(exits[0].exit_type, ExitType::StopLoss);
+    assert_eq!(exits[0].price, 85.0);
+    assert!(exit_manager.get_active_trades().is_empty());
     
     Ok(())
 }
 
 #[tokio::test]
 async fn test_exit_strategy_take_profit() -> Result<()> {
-    let config = Config::load()?;
-    let state = Arc::new(RwLock::new(SnipingState::default()));
-    
-    let exit_manager = ExitManager::new(&config, state.clone()).await?;
+    let (exit_manager, market) = exit_manager_with_fake_market().await?;
     let test_trade = ActiveTrade {
         token_address: "0x1234...5678".to_string(),
         entry_price: 100.0,
         amount: 1.0,
         stop_loss: 90.0,
         take_profit: 120.0,
+        ..ActiveTrade::default()
     };
     
     exit_manager.add_trade(test_trade).await?;
     
     // Simulate price rise above take profit
-    let result = exit_manager.check_exit_conditions(125.0).await?;
-    assert!(result.should_exit);
-    assert_eq!(result.exit_type, ExitType::TakeProfit);
+    market.set_price("0x1234...5678", 125.0);
+    let exits = exit_manager.check_exit_conditions().await?;
+    assert_eq!(exits.len(), 1);
+    assert_eq!(exits[0].exit_type, ExitType::TakeProfit);
+    assert_eq!(exits[0].volume_24h, 25_000.0);
     
     Ok(())
 }
 
 #[tokio::test]
 async fn test_exit_strategy_trailing_stop() -> Result<()> {
-    let config = Config::load()?;
-    let state = Arc::new(RwLock::new(SnipingState::default()));
-    
-    let exit_manager = ExitManager::new(&config, state.clone()).await?;
+    let (exit_manager, market) = exit_manager_with_fake_market().await?;
     let test_trade = ActiveTrade {
         token_address: "0x1234...5678".to_string(),
         entry_price: 100.0,
@@ -147,24 +233,134 @@ async fn test_exit_strategy_trailing_stop() -> Result<()> {
         stop_loss: 90.0,
         take_profit: 120.0,
         trailing_stop: 5.0, // 5% trailing stop
+        ..ActiveTrade::default()
     };
     
     exit_manager.add_trade(test_trade).await?;
     
     // Simulate price movement with trailing stop
-    let result = exit_manager.check_exit_conditions(110.0).await?;
-    assert!(!result.should_exit);
+    market.set_price("0x1234...5678", 110.0);
+    assert!(exit_manager.check_exit_conditions().await?.is_empty());
     
-    let result = exit_manager.check_exit_conditions(115.0).await?;
-    assert!(!result.should_exit);
+    market.set_price("0x1234...5678", 115.0);
+    assert!(exit_manager.check_exit_conditions().await?.is_empty());
+
+    // The stop followed the high to 115 * 0.95 = 109.25
+    let trade = &exit_manager.get_active_trades()[0];
+    assert_eq!(trade.highest_price, 115.0);
+    assert!((trade.current_stop_loss - 109.25).abs() < 1e-9);
     
-    let result = exit_manager.check_exit_conditions(109.0).await?;
-    assert!(result.should_exit);
-    assert_eq!(result.exit_type, ExitType::TrailingStop);
+    market.set_price("0x1234...5678", 109.0);
+    let exits = exit_manager.check_exit_conditions().await?;
+    assert_eq!(exits.len(), 1);
+    assert_eq!(exits[0].exit_type, ExitType::TrailingStop);
     
     Ok(())
 }
 
+#[tokio::test]
+async fn test_exit_strategy_skips_tokens_without_market_data() -> Result<()> {
+    let (exit_manager, market) = exit_manager_with_fake_market().await?;
+    for token_address in ["healthy", "dark"] {
+        exit_manager.add_trade(ActiveTrade {
+            token_address: token_address.to_string(),
+            entry_price: 100.0,
+            amount: 1.0,
+            stop_loss: 90.0,
+            take_profit: 120.0,
+            ..ActiveTrade::default()
+        }).await?;
+    }
+
+    // A failed lookup is not a zero price: the dark token holds while the other one exits
+    market.set_price("healthy", 80.0);
+    let exits = exit_manager.check_exit_conditions().await?;
+    assert_eq!(exits.len(), 1);
+    assert_eq!(exits[0].token_address.as_deref(), Some("healthy"));
+    let remaining = exit_manager.get_active_trades();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].token_address, "dark");
+    assert_eq!(remaining[0].highest_price, 100.0);
+
+    // Once data comes back the trade is evaluated again
+    market.clear("healthy");
+    market.set_price("dark", 130.0);
+    let exits = exit_manager.check_exit_conditions().await?;
+    assert_eq!(exits.len(), 1);
+    assert_eq!(exits[0].exit_type, ExitType::TakeProfit);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_exit_strategy_scales_out_across_take_profit_levels() -> Result<()> {
+    let (exit_manager, market) = exit_manager_with_fake_market().await?;
+    exit_manager.add_trade(ActiveTrade {
+        token_address: "0x1234...5678".to_string(),
+        entry_price: 100.0,
+        amount: 10.0,
+        stop_loss: 90.0,
+        take_profit_levels: vec![
+            TakeProfitLevel { target_price: 160.0, fraction: 0.4 },
+            TakeProfitLevel { target_price: 120.0, fraction: 0.3 },
+            TakeProfitLevel { target_price: 140.0, fraction: 0.3 },
+        ],
+        ..ActiveTrade::default()
+    }).await?;
+
+    let mut sold = 0.0;
+    for (price, cumulative_fraction) in [(125.0, 0.3), (130.0, 0.3), (145.0, 0.6), (165.0, 1.0)] {
+        market.set_price("0x1234...5678", price);
+        for exit in exit_manager.check_exit_conditions().await? {
+            assert_eq!(exit.exit_type, ExitType::TakeProfit);
+            sold += exit.amount;
+        }
+        assert!((sold / 10.0 - cumulative_fraction).abs() < 1e-9, "sold {} at {}", sold, price);
+
+        // The trade stays tracked with what is left until the last level closes it
+        let trades = exit_manager.get_active_trades();
+        if cumulative_fraction < 1.0 {
+            assert!((trades[0].remaining_amount - (10.0 - sold)).abs() < 1e-9);
+        } else {
+            assert!(trades.is_empty());
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_last_take_profit_level_sells_only_its_fraction() -> Result<()> {
+    let (exit_manager, market) = exit_manager_with_fake_market().await?;
+    exit_manager.add_trade(ActiveTrade {
+        token_address: "0x1234...5678".to_string(),
+        entry_price: 100.0,
+        amount: 10.0,
+        stop_loss: 90.0,
+        take_profit_levels: vec![
+            TakeProfitLevel { target_price: 120.0, fraction: 0.5 },
+            TakeProfitLevel { target_price: 140.0, fraction: 0.25 },
+        ],
+        ..ActiveTrade::default()
+    }).await?;
+
+    market.set_price("0x1234...5678", 145.0);
+    let exits = exit_manager.check_exit_conditions().await?;
+    assert_eq!(exits.len(), 1);
+    assert!((exits[0].amount - 7.5).abs() < 1e-9);
+    assert!((exits[0].remaining_amount - 2.5).abs() < 1e-9);
+
+    // The quarter the ladder never sold stays open until a stop takes it
+    assert!(exit_manager.check_exit_conditions().await?.is_empty());
+    market.set_price("0x1234...5678", 85.0);
+    let exits = exit_manager.check_exit_conditions().await?;
+    assert_eq!(exits[0].exit_type, ExitType::StopLoss);
+    assert!((exits[0].amount - 2.5).abs() < 1e-9);
+    assert!(exit_manager.get_active_trades().is_empty());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_integration_workflow() -> Result<()> {
     let config = Config::load()?;
@@ -173,7 +369,9 @@ async fn test_integration_workflow() -> Result<()> {
     // Initialize components
     let radar = Radar::new(&config, state.clone()).await?;
     let buy_engine = BuyEngine::new(&config, state.clone()).await?;
-    let exit_manager = ExitManager::new(&config, state.clone()).await?;
+    let market = Arc::new(FakeMarketData::default());
+    let mut exit_manager = ExitManager::new(&config, state.clone()).await?;
+    exit_manager.set_market_data(market.clone());
     
     // Test complete workflow
     let test_pair = "0x1234...5678".to_string();
@@ -196,21 +394,1645 @@ async fn test_integration_workflow() -> Result<()> {
     
     // Add to exit manager
     let active_trade = ActiveTrade {
-        token_address: trade.token_address,
+        token_address: trade.token_address.clone(),
         entry_price: 100.0,
         amount: trade.amount,
         stop_loss: 90.0,
         take_profit: 120.0,
+        ..ActiveTrade::default()
     };
     
     exit_manager.add_trade(active_trade).await?;
     
     // Test exit conditions
-    let exit_result = exit_manager.check_exit_conditions(95.0).await?;
-    assert!(!exit_result.should_exit);
+    market.set_price(&trade.token_address, 95.0);
+    assert!(exit_manager.check_exit_conditions().await?.is_empty());
     
-    let exit_result = exit_manager.check_exit_conditions(85.0).await?;
-    assert!(exit_result.should_exit);
+    market.set_price(&trade.token_address, 85.0);
+    assert!(exit_manager.check_exit_conditions().await?[0].should_exit);
     
     Ok(())
-} 
\ No newline at end of file
+}
+
+// Self-contained config for the sniping components exercised below
+fn sniping_config_builder() -> Result<::config::ConfigBuilder<::config::builder::DefaultState>> {
+    Ok(::config::Config::builder()
+        .set_default("sniping_core.coin_scanner.scan_interval", 1)?
+        .set_default("sniping_core.coin_scanner.batch_size", 100)?
+        .set_default("sniping_core.coin_scanner.max_concurrent_scans", 4)?
+        .set_default("sniping_core.coin_scanner.min_liquidity", 10000.0)?
+        .set_default("sniping_core.coin_scanner.min_holders", 50)?
+        .set_default("sniping_core.coin_scanner.min_market_cap", 50000.0)?
+        .set_default("sniping_core.coin_scanner.dex_screener_api_key", "test-key")?
+        .set_default("sniping_core.coin_scanner.pump_fun_api_key", "test-key")?
+        .set_default("sniping_core.coin_scanner.birdeye_api_key", "test-key")?
+        .set_default("sniping_core.coin_scanner.honeypot.enabled", false)?
+        .set_default("sniping_core.coin_scanner.honeypot.rpc_url", "http://127.0.0.1:8899")?
+        .set_default("sniping_core.coin_scanner.honeypot.swap_api_url", "http://127.0.0.1:8080")?
+        .set_default("sniping_core.coin_scanner.honeypot.simulation_wallet", "11111111111111111111111111111111")?
+        .set_default("sniping_core.coin_scanner.honeypot.simulation_amount_lamports", 10_000_000)?
+        .set_default("sniping_core.coin_scanner.honeypot.max_transfer_tax", 0.1)?)
+}
+
+fn mock_coin(token_address: &str) -> serde_json::Value {
+    json!({
+        "token_address": token_address,
+        "pair_address": format!("{}-SOL", token_address),
+        "liquidity": 50000.0,
+        "volume_24h": 20000.0,
+        "price": 0.001,
+        "holders": 200,
+        "market_cap": 100000.0,
+        "created_at": chrono::Utc::now(),
+        "social_volume": 500.0,
+        "contract_audit_status": "Verified",
+        "risk_score": 0.2,
+        "priority_score": 0.0,
+    })
+}
+
+fn active_sniping_state() -> Arc<RwLock<SnipingState>> {
+    Arc::new(RwLock::new(SnipingState {
+        is_active: true,
+        ..SnipingState::default()
+    }))
+}
+
+#[tokio::test]
+async fn test_coin_scanner_queries_sources_concurrently() -> Result<()> {
+    let server = MockServer::start().await;
+    let delay = std::time::Duration::from_millis(500);
+
+    Mock::given(method("GET")).and(path("/pump-fun"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_json(json!([mock_coin("PumpToken")]))
+            .set_delay(delay))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET")).and(path("/dex-screener"))
+        .respond_with(ResponseTemplate::new(200)
+            .set_body_json(json!([mock_coin("DexToken")]))
+            .set_delay(delay))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?
+        .build()?;
+    let mut scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+
+    let start = std::time::Instant::now();
+    scanner.scan_coins().await?;
+
+    // Querying the sources one after another would take at least twice the delay
+    assert!(start.elapsed() < delay * 2);
+
+    let tokens: Vec<String> = scanner.get_monitored_coins().await
+        .into_iter()
+        .map(|c| c.token_address)
+        .collect();
+    assert!(tokens.contains(&"PumpToken".to_string()));
+    assert!(tokens.contains(&"DexToken".to_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coin_scanner_survives_a_failing_source() -> Result<()> {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET")).and(path("/pump-fun"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET")).and(path("/dex-screener"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([mock_coin("DexToken")])))
+        .mount(&server)
+        .await;
+
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?
+        .build()?;
+    let mut scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+
+    scanner.scan_coins().await?;
+
+    let coins = scanner.get_monitored_coins().await;
+    assert_eq!(coins.len(), 1);
+    assert_eq!(coins[0].token_address, "DexToken");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coin_scanner_rejects_blacklisted_tokens() -> Result<()> {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET")).and(path("/dex-screener"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([mock_coin("DexToken"), mock_coin("ScamToken")])))
+        .mount(&server)
+        .await;
+
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?
+        .build()?;
+    let blacklist = TokenBlacklist::default();
+    blacklist.add("ScamToken", "Honeypot")?;
+    let mut scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+    scanner.set_blacklist(blacklist);
+
+    scanner.scan_coins().await?;
+
+    let coins = scanner.get_monitored_coins().await;
+    assert_eq!(coins.len(), 1);
+    assert_eq!(coins[0].token_address, "DexToken");
+
+    Ok(())
+}
+
+// Quotes, swap instructions and RPC for one simulated round trip from a wallet holding 1 SOL:
+// 0.01 SOL buys tokens quoted to sell back for 0.009 SOL
+async fn mount_round_trip(server: &MockServer, wallet: &Pubkey, mint: &Pubkey, simulation: serde_json::Value) {
+    let token_account = antbot::common::associated_token_address(wallet, mint);
+    let rpc = |method_name: &str, result: serde_json::Value| {
+        Mock::given(method("POST"))
+            .and(path("/"))
+            .and(body_partial_json(json!({"method": method_name})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": result})))
+    };
+
+    Mock::given(method("GET")).and(path("/quote")).and(query_param("outputMint", mint.to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"outAmount": "1000000"})))
+        .mount(server)
+        .await;
+    Mock::given(method("GET")).and(path("/quote")).and(query_param("inputMint", mint.to_string()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"outAmount": "9000000"})))
+        .mount(server)
+        .await;
+    Mock::given(method("POST")).and(path("/swap-instructions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"swapInstruction": {
+            "programId": Pubkey::new_unique().to_string(), "accounts": [], "data": "",
+        }})))
+        .mount(server)
+        .await;
+    rpc("getLatestBlockhash", json!({"context": {"slot": 1}, "value": {
+        "blockhash": solana_sdk::hash::Hash::new_unique().to_string(), "lastValidBlockHeight": 100,
+    }})).mount(server).await;
+    rpc("getFeeForMessage", json!({"context": {"slot": 1}, "value": 5_000})).mount(server).await;
+    for (account, balance) in [(*wallet, 1_000_000_000u64), (token_account, 0)] {
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "getBalance", "params": [account.to_string()]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+                "context": {"slot": 1}, "value": balance,
+            }})))
+            .mount(server)
+            .await;
+    }
+    rpc("simulateTransaction", json!({"context": {"slot": 1}, "value": simulation})).mount(server).await;
+}
+
+fn honeypot_config_builder(server: &MockServer, wallet: &Pubkey) -> Result<::config::ConfigBuilder<::config::builder::DefaultState>> {
+    Ok(sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.honeypot.enabled", true)?
+        .set_override("sniping_core.coin_scanner.honeypot.rpc_url", server.uri())?
+        .set_override("sniping_core.coin_scanner.honeypot.swap_api_url", server.uri())?
+        .set_override("sniping_core.coin_scanner.honeypot.simulation_wallet", wallet.to_string())?)
+}
+
+#[tokio::test]
+async fn test_honeypot_tax_excludes_network_fee_and_token_account_rent() -> Result<()> {
+    let server = MockServer::start().await;
+    let wallet = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let simulated_account = |lamports: u64| json!({
+        "lamports": lamports, "data": ["", "base64"], "owner": Pubkey::default().to_string(),
+        "executable": false, "rentEpoch": 0,
+    });
+    // The sell returns 0.0081 SOL; the wallet also paid the fee and the new token account's rent
+    mount_round_trip(&server, &wallet, &mint, json!({
+        "err": null, "logs": [], "unitsConsumed": 0, "returnData": null,
+        "accounts": [
+            simulated_account(1_000_000_000 - 10_000_000 - 5_000 - 2_039_280 + 8_100_000),
+            simulated_account(2_039_280),
+        ],
+    })).await;
+
+    let config = honeypot_config_builder(&server, &wallet)?.build()?;
+    let scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+
+    let result = scanner.simulate_buy_sell(&mint.to_string()).await?;
+    assert!(result.can_sell);
+    assert!((result.transfer_tax - 0.1).abs() < 1e-9);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coin_scanner_blacklists_honeypots() -> Result<()> {
+    let server = MockServer::start().await;
+    let wallet = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    mount_round_trip(&server, &wallet, &mint, json!({
+        "err": {"InstructionError": [1, {"Custom": 6001}]},
+        "logs": [], "accounts": null, "unitsConsumed": 0, "returnData": null,
+    })).await;
+    Mock::given(method("GET")).and(path("/dex-screener"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([mock_coin(&mint.to_string())])))
+        .mount(&server)
+        .await;
+
+    let config = honeypot_config_builder(&server, &wallet)?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?
+        .build()?;
+    let blacklist = TokenBlacklist::default();
+    let mut scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+    scanner.set_blacklist(blacklist.clone());
+
+    scanner.scan_coins().await?;
+
+    assert!(scanner.get_monitored_coins().await.is_empty());
+    assert!(!blacklist.is_allowed(&mint.to_string()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_honeypot_only_for_missing_sell_route_or_failing_sell_leg() -> Result<()> {
+    let wallet = Pubkey::new_unique();
+    let failed_simulation = |err: serde_json::Value| json!({
+        "err": err, "logs": [], "accounts": null, "unitsConsumed": 0, "returnData": null,
+    });
+    let no_route = ResponseTemplate::new(400)
+        .set_body_json(json!({"error": "Could not find any route", "errorCode": "COULD_NOT_FIND_ANY_ROUTE"}));
+
+    // (sell quote override, simulation, honeypot?); anything else is an error the scanner retries later
+    let cases: Vec<(&str, Option<ResponseTemplate>, serde_json::Value, bool)> = vec![
+        ("no sell route", Some(no_route), failed_simulation(json!(null)), true),
+        ("rate limited sell quote", Some(ResponseTemplate::new(429)), failed_simulation(json!(null)), false),
+        ("unavailable sell quote", Some(ResponseTemplate::new(503)), failed_simulation(json!(null)), false),
+        ("failing sell leg", None, failed_simulation(json!({"InstructionError": [1, {"Custom": 6001}]})), true),
+        ("failing buy leg", None, failed_simulation(json!({"InstructionError": [0, {"Custom": 6001}]})), false),
+        ("unfunded fee", None, failed_simulation(json!("InsufficientFundsForFee")), false),
+    ];
+
+    for (case, sell_quote, simulation, honeypot) in cases {
+        let server = MockServer::start().await;
+        let mint = Pubkey::new_unique();
+        if let Some(response) = sell_quote {
+            Mock::given(method("GET")).and(path("/quote")).and(query_param("inputMint", mint.to_string()))
+                .respond_with(response)
+                .with_priority(1)
+                .mount(&server)
+                .await;
+        }
+        mount_round_trip(&server, &wallet, &mint, simulation).await;
+        let config = honeypot_config_builder(&server, &wallet)?.build()?;
+        let scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+
+        let outcome = scanner.simulate_buy_sell(&mint.to_string()).await;
+        if honeypot {
+            assert!(!outcome?.can_sell, "{} should be a honeypot", case);
+        } else {
+            assert!(outcome.is_err(), "{} should be retried rather than judged", case);
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_honeypot_check_requires_a_funded_simulation_wallet() -> Result<()> {
+    // The System Program holds no SOL, so every simulated buy would fail
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.honeypot.enabled", true)?
+        .build()?;
+    assert!(CoinScanner::new(&config, active_sniping_state()).await.is_err());
+
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.honeypot.enabled", true)?
+        .set_override("sniping_core.coin_scanner.honeypot.simulation_wallet", "")?
+        .build()?;
+    assert!(CoinScanner::new(&config, active_sniping_state()).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coin_scanner_dedupes_tokens_across_sources() -> Result<()> {
+    let server = MockServer::start().await;
+    let listed_at = chrono::Utc::now();
+
+    let mut stale = mock_coin("SharedToken");
+    stale["created_at"] = json!(listed_at - chrono::Duration::minutes(2));
+    stale["liquidity"] = json!(20000.0);
+
+    Mock::given(method("GET")).and(path("/pump-fun"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([stale])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET")).and(path("/dex-screener"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET")).and(path("/birdeye"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "data": {
+                "items": [{
+                    "address": "SharedToken",
+                    "liquidity": 75000.0,
+                    "v24hUSD": 30000.0,
+                    "price": 0.002,
+                    "holder": 300,
+                    "mc": 150000.0,
+                    // Birdeye omits the offset; the time is UTC
+                    "liquidityAddedAt": listed_at.naive_utc().format("%Y-%m-%dT%H:%M:%S").to_string(),
+                }]
+            }
+        })))
+        .mount(&server)
+        .await;
+
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?
+        .build()?;
+    let mut scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+
+    scanner.scan_coins().await?;
+
+    // The Birdeye record is fresher, so it replaces the pump.fun one
+    let coins = scanner.get_monitored_coins().await;
+    assert_eq!(coins.len(), 1);
+    assert_eq!(coins[0].token_address, "SharedToken");
+    assert_eq!(coins[0].liquidity, 75000.0);
+
+    Ok(())
+}
+
+async fn rank_with_weights(server: &MockServer, weights: [(&str, f64); 5]) -> Result<Vec<String>> {
+    let mut builder = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?;
+    for (signal, weight) in weights {
+        builder = builder.set_override(format!("sniping_core.coin_scanner.weights.{}", signal), weight)?;
+    }
+    let mut scanner = CoinScanner::new(&builder.build()?, active_sniping_state()).await?;
+
+    scanner.scan_coins().await?;
+    Ok(scanner.get_prioritized_coins().await
+        .into_iter()
+        .map(|c| c.token_address)
+        .collect())
+}
+
+#[tokio::test]
+async fn test_priority_weights_change_ranking() -> Result<()> {
+    let server = MockServer::start().await;
+
+    let mut volume_coin = mock_coin("VolumeToken");
+    volume_coin["volume_24h"] = json!(20000.0);
+    volume_coin["social_volume"] = json!(0.0);
+    let mut social_coin = mock_coin("SocialToken");
+    social_coin["volume_24h"] = json!(0.0);
+    social_coin["social_volume"] = json!(1000.0);
+
+    Mock::given(method("GET")).and(path("/pump-fun"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([volume_coin, social_coin])))
+        .mount(&server)
+        .await;
+
+    let volume_heavy = rank_with_weights(&server, [
+        ("liquidity", 0.3), ("volume", 0.3), ("holders", 0.2), ("social", 0.05), ("risk", 0.15),
+    ]).await?;
+    assert_eq!(volume_heavy, vec!["VolumeToken", "SocialToken"]);
+
+    let social_driven = rank_with_weights(&server, [
+        ("liquidity", 0.2), ("volume", 0.05), ("holders", 0.15), ("social", 0.45), ("risk", 0.15),
+    ]).await?;
+    assert_eq!(social_driven, vec!["SocialToken", "VolumeToken"]);
+
+    Ok(())
+}
+
+const WHALE_WALLET: &str = "SysvarRent111111111111111111111111111111111";
+
+#[tokio::test]
+async fn test_whale_buys_raise_priority_score() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET")).and(path("/pump-fun"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([mock_coin("QuietToken"), mock_coin("WhaleToken")])))
+        .mount(&server)
+        .await;
+
+    let mut builder = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?
+        .set_override("sniping_core.coin_scanner.whale_buys_for_full_score", 2)?
+        .set_override("sniping_core.whale_tracker.wallets", vec![WHALE_WALLET])?
+        .set_override("helius.mainnet", server.uri())?;
+    for (signal, weight) in [("liquidity", 0.3), ("volume", 0.2), ("holders", 0.15), ("social", 0.1), ("risk", 0.15), ("whales", 0.1)] {
+        builder = builder.set_override(format!("sniping_core.coin_scanner.weights.{}", signal), weight)?;
+    }
+    let config = builder.build()?;
+    let tracker = Arc::new(WhaleTracker::new(&config)?);
+    let mut scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+    scanner.set_whale_tracker(tracker.clone());
+
+    let score = |coins: &[antbot::sniping_core::CoinMetrics], token: &str| {
+        coins.iter().find(|c| c.token_address == token).map(|c| c.priority_score).unwrap()
+    };
+    scanner.scan_coins().await?;
+    let before = scanner.get_prioritized_coins().await;
+    assert_eq!(score(&before, "WhaleToken"), score(&before, "QuietToken"));
+
+    // A getTransaction result where the watched wallet's balance of the mint grew
+    let transaction = json!({"meta": {
+        "preTokenBalances": [],
+        "postTokenBalances": [{"owner": WHALE_WALLET, "mint": "WhaleToken", "uiTokenAmount": {"amount": "5000"}}],
+    }});
+    for token in WhaleTracker::token_buys(WHALE_WALLET, &transaction) {
+        tracker.record_buy(WHALE_WALLET, &token);
+    }
+    tracker.record_buy("UnwatchedWallet", "QuietToken");
+    assert_eq!(tracker.recent_buys("WhaleToken"), 1);
+    assert_eq!(tracker.recent_buys("QuietToken"), 0);
+
+    scanner.scan_coins().await?;
+    let after = scanner.get_prioritized_coins().await;
+    assert!((score(&after, "WhaleToken") - score(&after, "QuietToken") - 0.05).abs() < 1e-9);
+    assert_eq!(after[0].token_address, "WhaleToken");
+
+    // Dropping the wallet from config stops further buys from counting
+    tracker.reload(&sniping_config_builder()?.set_override("sniping_core.whale_tracker.wallets", Vec::<String>::new())?.build()?)?;
+    tracker.record_buy(WHALE_WALLET, "WhaleToken");
+    assert_eq!(tracker.recent_buys("WhaleToken"), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_priority_weights_must_sum_to_one() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.weights.liquidity", 0.3)?
+        .set_override("sniping_core.coin_scanner.weights.volume", 0.3)?
+        .set_override("sniping_core.coin_scanner.weights.holders", 0.3)?
+        .set_override("sniping_core.coin_scanner.weights.social", 0.3)?
+        .set_override("sniping_core.coin_scanner.weights.risk", 0.3)?
+        .build()?;
+
+    assert!(CoinScanner::new(&config, active_sniping_state()).await.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_adaptive_slippage_tracks_fill_quality() -> Result<()> {
+    let config = ::config::Config::builder()
+        .set_default("sniping_core.buy_engine.adaptive_slippage.enabled", true)?
+        .set_default("sniping_core.buy_engine.adaptive_slippage.min_slippage", 0.01)?
+        .set_default("sniping_core.buy_engine.adaptive_slippage.max_slippage", 0.15)?
+        .set_default("sniping_core.buy_engine.adaptive_slippage.headroom", 1.5)?
+        .set_default("sniping_core.buy_engine.adaptive_slippage.window", 5)?
+        .build()?;
+    let mut slippage = AdaptiveSlippage::new(&config, 0.05)?;
+    let class = LiquidityClass::Thin;
+    assert_eq!(slippage.effective_slippage(class), 0.05);
+
+    // Fills landing 8% above the quote need more room than the 5% base
+    for _ in 0..5 {
+        slippage.record_fill(class, 1.0, 1.08);
+    }
+    let widened = slippage.effective_slippage(class);
+    assert!(widened > 0.05);
+    assert!(widened <= 0.15);
+
+    // Other classes keep their own history
+    assert_eq!(slippage.effective_slippage(LiquidityClass::Deep), 0.05);
+
+    // Clean fills push the poor ones out of the window and tighten toward the floor
+    for _ in 0..5 {
+        slippage.record_fill(class, 1.0, 1.002);
+    }
+    let tightened = slippage.effective_slippage(class);
+    assert!(tightened < widened);
+    assert!(tightened < 0.05);
+    assert!(tightened >= 0.01);
+
+    Ok(())
+}
+
+// Replays a scripted sequence of (price, liquidity) readings, repeating the last one
+struct ScriptedPool {
+    readings: Vec<(f64, f64)>,
+    polls: std::sync::atomic::AtomicUsize,
+}
+
+impl ScriptedPool {
+    fn new(readings: Vec<(f64, f64)>) -> Self {
+        Self { readings, polls: std::sync::atomic::AtomicUsize::new(0) }
+    }
+}
+
+#[async_trait]
+impl PoolMonitor for ScriptedPool {
+    async fn snapshot(&self, _opportunity: &TokenOpportunity) -> Result<PoolSnapshot> {
+        let poll = self.polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let (price, liquidity) = self.readings[poll.min(self.readings.len() - 1)];
+        Ok(PoolSnapshot { price, liquidity, sellable: true, timestamp: chrono::Utc::now() })
+    }
+}
+
+fn new_launch(token_address: &str) -> TokenOpportunity {
+    TokenOpportunity {
+        token_address: token_address.to_string(),
+        pair_address: format!("{}-SOL", token_address),
+        liquidity: 50000.0,
+        holders: 10,
+        market_cap: 60000.0,
+        price: 0.001,
+        volume_24h: 0.0,
+        created_at: chrono::Utc::now(),
+        risk_score: 0.3,
+    }
+}
+
+#[tokio::test]
+async fn test_launch_observer_skips_dump_and_enters_healthy_pool() -> Result<()> {
+    let config = ::config::Config::builder()
+        .set_default("sniping_core.launch_observer.enabled", true)?
+        .set_default("sniping_core.launch_observer.window_ms", 300)?
+        .set_default("sniping_core.launch_observer.poll_interval_ms", 50)?
+        .set_default("sniping_core.launch_observer.max_price_drop", 0.2)?
+        .set_default("sniping_core.launch_observer.max_liquidity_drop", 0.3)?
+        .build()?;
+    let observer = LaunchObserver::new(&config)?;
+
+    // Pumps, then dumps 50% from the peak two polls into the window
+    let dumping = ScriptedPool::new(vec![(0.001, 50000.0), (0.002, 50000.0), (0.001, 48000.0)]);
+    let outcome = observer.observe(&new_launch("DumpToken"), &dumping).await?;
+    assert!(matches!(outcome, ObservationOutcome::Skip(_)));
+
+    // Drifts around without red flags for the whole window
+    let healthy = ScriptedPool::new(vec![(0.001, 50000.0), (0.00105, 51000.0), (0.00098, 50500.0)]);
+    let start = std::time::Instant::now();
+    let outcome = observer.observe(&new_launch("HealthyToken"), &healthy).await?;
+    assert_eq!(outcome, ObservationOutcome::Enter);
+    assert!(start.elapsed() >= std::time::Duration::from_millis(300));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coin_scanner_skips_malformed_items_and_keeps_valid_ones() -> Result<()> {
+    let server = MockServer::start().await;
+
+    let mut missing_fields = mock_coin("BrokenToken");
+    missing_fields.as_object_mut().unwrap().remove("liquidity");
+    let mut wrong_shape = mock_coin("WrongShapeToken");
+    wrong_shape["holders"] = json!("lots");
+
+    Mock::given(method("GET")).and(path("/pump-fun"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            mock_coin("GoodToken"),
+            missing_fields,
+            wrong_shape,
+            "not a coin",
+            mock_coin("OtherGoodToken"),
+        ])))
+        .mount(&server)
+        .await;
+
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?
+        .build()?;
+    let mut scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+
+    scanner.scan_coins().await?;
+
+    let mut tokens: Vec<String> = scanner.get_monitored_coins().await
+        .into_iter()
+        .map(|c| c.token_address)
+        .collect();
+    tokens.sort();
+    assert_eq!(tokens, vec!["GoodToken".to_string(), "OtherGoodToken".to_string()]);
+    assert_eq!(scanner.skipped_item_count(), 3);
+
+    Ok(())
+}
+
+// Sells return a fixed fraction of the spot value, whatever the entry side looks like
+struct FixedExitQuoter {
+    recovered_fraction: f64,
+}
+
+#[async_trait]
+impl ExitQuoter for FixedExitQuoter {
+    async fn quote_sell(&self, _token_address: &str, token_amount: f64) -> Result<ExitQuote> {
+        let spot_out = token_amount * 0.001;
+        Ok(ExitQuote { expected_out: spot_out * self.recovered_fraction, spot_out })
+    }
+}
+
+#[tokio::test]
+async fn test_exit_liquidity_check_rejects_costly_full_exit() -> Result<()> {
+    let config = ::config::Config::builder()
+        .set_default("sniping_core.buy_engine.exit_check.enabled", true)?
+        .set_default("sniping_core.buy_engine.exit_check.max_exit_slippage", 0.15)?
+        .build()?;
+    let check = ExitLiquidityCheck::new(&config)?;
+
+    // 50k of entry-side liquidity comfortably covers a 1 SOL buy in both cases
+    let launch = new_launch("ExitToken");
+    assert!(launch.liquidity / 1.0 >= 3.0);
+
+    // Selling the full position back would lose 40%
+    let costly = FixedExitQuoter { recovered_fraction: 0.6 };
+    let outcome = check.check(&launch, 1.0, &costly).await?;
+    assert!(matches!(outcome, ObservationOutcome::Skip(reason) if reason.contains("exit")));
+
+    // A 5% exit cost is within the ceiling
+    let acceptable = FixedExitQuoter { recovered_fraction: 0.95 };
+    let outcome = check.check(&launch, 1.0, &acceptable).await?;
+    assert_eq!(outcome, ObservationOutcome::Enter);
+
+    Ok(())
+}
+
+// Each quote is a little worse than the last, as during a fast pump
+struct SequencedQuoter {
+    quotes: std::sync::atomic::AtomicU32,
+    impact_step: f64,
+}
+
+#[async_trait]
+impl EntryQuoter for SequencedQuoter {
+    async fn quote_buy(&self, _token_address: &str, _amount: f64) -> Result<EntryQuote> {
+        let n = self.quotes.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        Ok(EntryQuote {
+            price: 0.001 * n as f64,
+            price_impact: self.impact_step * n as f64,
+            quoted_at: std::time::Instant::now(),
+        })
+    }
+}
+
+fn quote_freshness(max_age_ms: i64) -> Result<QuoteFreshness> {
+    let config = ::config::Config::builder()
+        .set_default("sniping_core.buy_engine.quote_freshness.enabled", true)?
+        .set_default("sniping_core.buy_engine.quote_freshness.max_age_ms", max_age_ms)?
+        .set_default("sniping_core.buy_engine.quote_freshness.max_requotes", 2)?
+        .build()?;
+    QuoteFreshness::new(&config)
+}
+
+#[tokio::test]
+async fn test_stale_quote_is_refreshed_before_submission() -> Result<()> {
+    let freshness = quote_freshness(50)?;
+    let quoter = SequencedQuoter { quotes: Default::default(), impact_step: 0.01 };
+    let builds = std::sync::atomic::AtomicU32::new(0);
+
+    // The first build is slow enough for its quote to age past the limit
+    let quoted = freshness.build_with_fresh_quote("QuoteToken", 1.0, 0.05, &quoter, |quote| {
+        let build = builds.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let price = quote.price;
+        async move {
+            if build == 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            }
+            Ok(price)
+        }
+    }).await?;
+
+    // What gets submitted was rebuilt from the second quote
+    assert_eq!(quoted.requotes, 1);
+    assert_eq!(quoter.quotes.load(std::sync::atomic::Ordering::SeqCst), 2);
+    assert_eq!(builds.load(std::sync::atomic::Ordering::SeqCst), 2);
+    assert_eq!(quoted.built, 0.002);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_requote_outside_slippage_aborts_buy() -> Result<()> {
+    let freshness = quote_freshness(50)?;
+    // The first quote fits a 5% budget, the re-quote doesn't
+    let quoter = SequencedQuoter { quotes: Default::default(), impact_step: 0.04 };
+
+    let result = freshness.build_with_fresh_quote("QuoteToken", 1.0, 0.05, &quoter, |_| async {
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        Ok(())
+    }).await;
+
+    assert!(result.unwrap_err().to_string().contains("exceeds max slippage"));
+    assert_eq!(quoter.quotes.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_engine_rejects_invalid_trade_amounts() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.05)?
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .build()?;
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.init().await?;
+
+    for amount in [0.0, -1.0, f64::NAN] {
+        let err = buy_engine.execute_trade("TokenA", amount).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<TradeError>(), Some(TradeError::InvalidAmount(_))),
+                "amount {} should be rejected, got {}", amount, err);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_buy_engine_cancels_pending_buys_past_expiry() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.05)?
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 0.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .set_default("sniping_core.buy_engine.pending_expiry.enabled", true)?
+        .set_default("sniping_core.buy_engine.pending_expiry.max_age_ms", 100)?
+        .build()?;
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.init().await?;
+
+    buy_engine.queue_buy("StaleToken", 0.5).await?;
+
+    // Still fresh: the sweep leaves it queued
+    assert!(buy_engine.sweep_stale_pending().await?.is_empty());
+    assert_eq!(buy_engine.get_pending_trades().await.len(), 1);
+
+    tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+    buy_engine.queue_buy("FreshToken", 0.5).await?;
+
+    let cancelled = buy_engine.sweep_stale_pending().await?;
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].token_address, "StaleToken");
+    assert!(matches!(cancelled[0].status, TradeStatus::Cancelled));
+
+    // Only the fresh buy is left to execute and nothing was filled
+    let pending: Vec<String> = buy_engine.get_pending_trades().await.into_iter().map(|t| t.token_address).collect();
+    assert_eq!(pending, vec!["FreshToken"]);
+    assert!(buy_engine.get_active_trades().await.is_empty());
+
+    Ok(())
+}
+
+struct DeepPools;
+
+#[async_trait]
+impl LiquiditySource for DeepPools {
+    async fn liquidity(&self, _token_address: &str) -> Result<f64> {
+        Ok(50_000.0)
+    }
+
+    // 250 SOL a side at $100
+    async fn reserve_in(&self, _token_address: &str) -> Result<f64> {
+        Ok(250.0)
+    }
+}
+
+#[tokio::test]
+async fn test_concurrent_buys_for_different_tokens_both_become_active() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.05)?
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .build()?;
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.set_liquidity_source(Arc::new(DeepPools));
+    buy_engine.init().await?;
+
+    let (first, second) = tokio::join!(
+        buy_engine.execute_trade("TokenA", 0.5),
+        buy_engine.execute_trade("TokenB", 0.5),
+    );
+    first?;
+    second?;
+
+    let mut active: Vec<String> = buy_engine.get_active_trades().await.into_iter().map(|t| t.token_address).collect();
+    active.sort();
+    assert_eq!(active, vec!["TokenA", "TokenB"]);
+    assert!(buy_engine.get_pending_trades().await.is_empty());
+
+    // A second buy for a token already held is refused rather than duplicated
+    assert!(buy_engine.execute_trade("TokenA", 0.5).await.is_err());
+    assert_eq!(buy_engine.get_active_trades().await.len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_dry_run_buy_publishes_submitted_then_simulated_events() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.05)?
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .build()?;
+    let message_queue = MessageQueue::new(16);
+    let mut events = message_queue
+        .subscribe_filtered("executions".to_string(), MessageKind::TRADE_EXECUTION, 16, OverflowPolicy::DropOldest)
+        .await;
+
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.set_liquidity_source(Arc::new(DeepPools));
+    buy_engine.set_message_queue(message_queue.clone());
+    buy_engine.init().await?;
+    let trade = buy_engine.execute_trade("TokenA", 0.5).await?;
+
+    let mut statuses = Vec::new();
+    while let Some(Message::TradeExecution(event)) = events.try_recv() {
+        assert_eq!(event.token_address, "TokenA");
+        assert!(matches!(event.action, TradeAction::Buy));
+        assert_eq!(event.amount, trade.amount);
+        // Without a swap executor nothing is sent, so there is no signature to report
+        assert_eq!(event.signature, None);
+        statuses.push(event.status);
+    }
+    assert_eq!(statuses, vec![TradeEventStatus::Submitted, TradeEventStatus::Simulated]);
+    assert_eq!(trade.transaction_hash, None);
+
+    Ok(())
+}
+
+// A price the test can move between engine passes
+struct MovingPriceProvider(std::sync::Mutex<f64>);
+
+#[async_trait]
+impl PriceProvider for MovingPriceProvider {
+    fn source(&self) -> PriceSource {
+        PriceSource::Jupiter
+    }
+
+    async fn price(&self, _token_address: &str) -> Result<f64> {
+        Ok(*self.0.lock().unwrap())
+    }
+}
+
+#[tokio::test]
+async fn test_limit_buy_waits_for_price_to_cross_trigger() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.05)?
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .build()?;
+    let price = Arc::new(MovingPriceProvider(std::sync::Mutex::new(1.2)));
+    let mut feed = PriceFeed::new(&config)?;
+    feed.register(price.clone());
+
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.set_liquidity_source(Arc::new(DeepPools));
+    buy_engine.set_price_feed(Arc::new(feed));
+    buy_engine.init().await?;
+    buy_engine.queue_limit_buy("TokenA", 0.5, TriggerCondition::LimitBelow(1.0)).await?;
+
+    // Above the limit the order just waits
+    assert!(buy_engine.process_pending_trades().await?.is_empty());
+    assert_eq!(buy_engine.get_pending_trades().await.len(), 1);
+    assert!(buy_engine.get_active_trades().await.is_empty());
+
+    // Once the price drops through it, the buy fills and moves to active
+    *price.0.lock().unwrap() = 0.9;
+    let filled = buy_engine.process_pending_trades().await?;
+    assert_eq!(filled.len(), 1);
+    assert_eq!(filled[0].trigger, TriggerCondition::LimitBelow(1.0));
+    assert!(buy_engine.get_pending_trades().await.is_empty());
+    assert_eq!(buy_engine.get_active_trades().await.len(), 1);
+
+    assert!(TriggerCondition::StopAbove(2.0).is_met(2.5));
+    assert!(!TriggerCondition::StopAbove(2.0).is_met(1.5));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_queued_buys_pass_entry_checks_and_execute_once() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.05)?
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .build()?;
+    let blacklist = TokenBlacklist::default();
+    blacklist.add("ScamToken", "Honeypot")?;
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.set_liquidity_source(Arc::new(DeepPools));
+    buy_engine.set_blacklist(blacklist);
+    buy_engine.init().await?;
+
+    // A second order for a token already queued is refused
+    buy_engine.queue_buy("TokenA", 0.5).await?;
+    assert!(buy_engine.queue_buy("TokenA", 0.5).await.is_err());
+
+    // A queued buy for a blacklisted token is dropped instead of executed
+    buy_engine.queue_buy("ScamToken", 0.5).await?;
+    let filled = buy_engine.process_pending_trades().await?;
+    assert_eq!(filled.len(), 1);
+    assert_eq!(filled[0].token_address, "TokenA");
+    assert!(buy_engine.get_pending_trades().await.is_empty());
+    assert_eq!(buy_engine.get_active_trades().await.len(), 1);
+
+    // Nothing is left to run again on the next pass
+    assert!(buy_engine.process_pending_trades().await?.is_empty());
+    assert_eq!(buy_engine.get_active_trades().await.len(), 1);
+
+    // A price lookup failure leaves a limit order waiting rather than failing the pass
+    let mut unpriced = BuyEngine::new(&config, active_sniping_state()).await?;
+    unpriced.set_liquidity_source(Arc::new(DeepPools));
+    unpriced.set_price_feed(Arc::new(PriceFeed::new(&config)?));
+    unpriced.init().await?;
+    unpriced.queue_limit_buy("TokenB", 0.5, TriggerCondition::LimitBelow(1.0)).await?;
+    assert!(unpriced.process_pending_trades().await?.is_empty());
+    assert_eq!(unpriced.get_pending_trades().await.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_filled_buy_is_tracked_by_exit_manager() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.05)?
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .set_override("sniping_core.exit_strategy.stop_loss_pct", 10.0)?
+        .set_override("sniping_core.exit_strategy.take_profit_pct", 50.0)?
+        .build()?;
+    let mut feed = PriceFeed::new(&config)?;
+    feed.register(Arc::new(MovingPriceProvider(std::sync::Mutex::new(0.5))));
+    let state = active_sniping_state();
+    let exit_manager = Arc::new(ExitManager::new(&config, state.clone()).await?);
+
+    let mut buy_engine = BuyEngine::new(&config, state).await?;
+    buy_engine.set_liquidity_source(Arc::new(DeepPools));
+    buy_engine.set_price_feed(Arc::new(feed));
+    buy_engine.set_exit_manager(exit_manager.clone());
+    buy_engine.init().await?;
+    let trade = buy_engine.execute_trade("TokenA", 0.5).await?;
+
+    let tracked = exit_manager.get_active_trades();
+    assert_eq!(tracked.len(), 1);
+    assert_eq!(tracked[0].token_address, "TokenA");
+    assert_eq!(tracked[0].entry_price, 0.5);
+    assert!((tracked[0].amount - trade.amount / 0.5).abs() < 1e-9);
+    assert!((tracked[0].stop_loss - 0.45).abs() < 1e-9);
+    assert!((tracked[0].take_profit - 0.75).abs() < 1e-9);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unfilled_limit_buy_expires_after_ttl() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .set_default("sniping_core.buy_engine.limit_orders.ttl_ms", 0)?
+        .build()?;
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.set_liquidity_source(Arc::new(DeepPools));
+    buy_engine.queue_limit_buy("TokenA", 0.5, TriggerCondition::LimitBelow(1.0)).await?;
+
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    buy_engine.sweep_stale_pending().await?;
+    assert!(buy_engine.get_pending_trades().await.is_empty());
+
+    Ok(())
+}
+
+// A running engine that fills DCA slices against a deep pool
+async fn dca_buy_engine() -> Result<(::config::Config, Arc<BuyEngine>)> {
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.max_slippage", 0.05)?
+        .set_default("sniping_core.buy_engine.gas_multiplier", 1.2)?
+        .set_default("sniping_core.buy_engine.min_liquidity", 10000.0)?
+        .set_default("sniping_core.buy_engine.max_position_size", 1.0)?
+        .build()?;
+    let mut buy_engine = BuyEngine::new(&config, active_sniping_state()).await?;
+    buy_engine.set_liquidity_source(Arc::new(DeepPools));
+    buy_engine.init().await?;
+    Ok((config, Arc::new(buy_engine)))
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_dca_buys_equal_slices_at_configured_cadence() -> Result<()> {
+    let (config, buy_engine) = dca_buy_engine().await?;
+    let message_queue = MessageQueue::new(16);
+    let mut events = message_queue
+        .subscribe_filtered("executions".to_string(), MessageKind::TRADE_EXECUTION, 16, OverflowPolicy::DropOldest)
+        .await;
+
+    let interval = std::time::Duration::from_secs(60);
+    let mut dca = DcaStrategy::new(&config, DcaPlan {
+        token_address: "TokenA".to_string(),
+        total_budget: 1.0,
+        slices: 4,
+        interval,
+        price_ceiling: None,
+    }, buy_engine.clone())?;
+    dca.set_message_queue(message_queue.clone());
+    let dca = Arc::new(dca);
+    let run = dca.clone().start();
+
+    // One slice on start, then one per interval; the clock only moves when the test sleeps
+    let half = interval / 2;
+    for expected in 1..=4 {
+        tokio::time::sleep(if expected == 1 { half } else { interval }).await;
+        assert_eq!(dca.progress().filled_slices, expected);
+    }
+
+    let progress = run.await??;
+    assert_eq!(progress.filled_slices, 4);
+    assert!(progress.remaining_budget.abs() < 1e-9);
+    assert_eq!(progress.stopped, Some(DcaStop::Completed));
+
+    // Every slice bought exactly its share, skipping the sizer, into one position
+    let active = buy_engine.get_active_trades().await;
+    assert_eq!(active.len(), 1);
+    assert!((active[0].amount - 1.0).abs() < 1e-9);
+    assert!(buy_engine.get_pending_trades().await.is_empty());
+
+    let mut filled = 0;
+    while let Some(Message::TradeExecution(event)) = events.try_recv() {
+        assert_eq!(event.status, TradeEventStatus::Simulated);
+        assert_eq!(event.amount, 0.25);
+        filled += 1;
+    }
+    assert_eq!(filled, 4);
+
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_dca_stops_on_cancel_and_rug_alert() -> Result<()> {
+    let (config, buy_engine) = dca_buy_engine().await?;
+    let message_queue = MessageQueue::new(16);
+    let interval = std::time::Duration::from_secs(60);
+    let plan = |token: &str| DcaPlan {
+        token_address: token.to_string(),
+        total_budget: 1.0,
+        slices: 4,
+        interval,
+        price_ceiling: None,
+    };
+
+    let cancelled = Arc::new(DcaStrategy::new(&config, plan("TokenA"), buy_engine.clone())?);
+    let run = cancelled.clone().start();
+    tokio::time::sleep(interval + interval / 2).await;
+    cancelled.cancel();
+    let progress = run.await??;
+    assert_eq!(progress.stopped, Some(DcaStop::Cancelled));
+    assert_eq!(progress.filled_slices, 2);
+    assert_eq!(progress.remaining_budget, 0.5);
+
+    let mut rugged = DcaStrategy::new(&config, plan("TokenB"), buy_engine.clone())?;
+    rugged.set_message_queue(message_queue.clone());
+    let rugged = Arc::new(rugged);
+    let run = rugged.clone().start();
+    tokio::time::sleep(interval / 2).await;
+    message_queue.publish(Message::LiquidityAlert(LiquidityAlert::drop("TokenB", 1.0, 0.1))).await;
+    let progress = run.await??;
+    assert_eq!(progress.stopped, Some(DcaStop::RugAlert));
+    assert_eq!(progress.filled_slices, 1);
+
+    // Two runs on one token each get their own alert subscription
+    let first = Arc::new({
+        let mut dca = DcaStrategy::new(&config, plan("TokenC"), buy_engine.clone())?;
+        dca.set_message_queue(message_queue.clone());
+        dca
+    });
+    let second = Arc::new({
+        let mut dca = DcaStrategy::new(&config, plan("TokenC"), buy_engine.clone())?;
+        dca.set_message_queue(message_queue.clone());
+        dca
+    });
+    let (first_run, second_run) = (first.clone().start(), second.clone().start());
+    tokio::time::sleep(interval / 2).await;
+    message_queue.publish(Message::LiquidityAlert(LiquidityAlert::drop("TokenC", 1.0, 0.1))).await;
+    assert_eq!(first_run.await??.stopped, Some(DcaStop::RugAlert));
+    assert_eq!(second_run.await??.stopped, Some(DcaStop::RugAlert));
+
+    Ok(())
+}
+
+#[test]
+fn test_kelly_fraction_for_known_odds() -> Result<()> {
+    // Even odds with a 60% win rate stake 20%; 2:1 odds at a coin flip stake 25%
+    assert!((kelly_fraction(0.6, 1.0, 1.0) - 0.2).abs() < 1e-9);
+    assert!((kelly_fraction(0.5, 2.0, 1.0) - 0.25).abs() < 1e-9);
+    // No edge stakes nothing rather than going negative; no losses on record stake the win rate
+    assert_eq!(kelly_fraction(0.4, 1.0, 1.0), 0.0);
+    assert_eq!(kelly_fraction(0.9, 1.0, 0.0), 0.9);
+
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.buy_engine.sizing_strategy", "Kelly")?
+        .set_default("sniping_core.buy_engine.kelly.multiplier", 0.5)?
+        .set_default("sniping_core.buy_engine.kelly.min_trades", 20)?
+        .build()?;
+    let sizer = PositionSizer::new(&config, 0.5)?;
+    assert_eq!(sizer.strategy(), SizingStrategy::Kelly);
+
+    let history = TradeStats { trades: 30, win_rate: 0.6, avg_win: 1.0, avg_loss: 1.0 };
+    // Half Kelly of 2 SOL available is 0.2 SOL whatever was requested, and a large bankroll
+    // is held to the max position
+    assert!((sizer.size(0.1, 0.0, Some(history), 2.0) - 0.2).abs() < 1e-9);
+    assert_eq!(sizer.size(0.1, 0.0, Some(history), 20.0), 0.5);
+    // Too little history falls back to volatility scaling
+    let thin = TradeStats { trades: 5, ..history };
+    assert_eq!(sizer.size(1.0, 0.4, Some(thin), 2.0), 0.8);
+
+    Ok(())
+}
+
+// A pump.fun buy of `tokens` paying at most `max_sol_lamports`, laid out as the program expects
+fn pump_fun_buy(trader: Pubkey, mint: Pubkey, tokens: u64, max_sol_lamports: u64) -> Instruction {
+    let mut data = vec![102, 6, 61, 18, 1, 218, 235, 234];
+    data.extend_from_slice(&tokens.to_le_bytes());
+    data.extend_from_slice(&max_sol_lamports.to_le_bytes());
+    let mut accounts: Vec<AccountMeta> = (0..12).map(|_| AccountMeta::new_readonly(Pubkey::new_unique(), false)).collect();
+    accounts[2] = AccountMeta::new_readonly(mint, false);
+    accounts[6] = AccountMeta::new(trader, true);
+
+    Instruction {
+        program_id: Pubkey::from_str("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P").unwrap(),
+        accounts,
+        data,
+    }
+}
+
+#[tokio::test]
+async fn test_copy_trader_queues_proportional_buy_for_decoded_swap() -> Result<()> {
+    let target = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.copy_trader.target_wallet", target.to_string())?
+        .set_default("sniping_core.copy_trader.scale_factor", 0.1)?
+        .set_default("sniping_core.copy_trader.default_token_cap", 0.3)?
+        .build()?;
+    let buy_engine = Arc::new(BuyEngine::new(&config, active_sniping_state()).await?);
+    let copy_trader = CopyTrader::new(&config, buy_engine.clone())?;
+
+    // The target buys 2 SOL worth, so a tenth of that is queued
+    let swap = copy_trader.decode(&pump_fun_buy(target, mint, 1_000_000, 2_000_000_000))?.unwrap();
+    assert_eq!(swap.token_mint, mint);
+    assert!(matches!(swap.action, TradeAction::Buy));
+    assert_eq!(swap.sol_amount, 2.0);
+
+    let mirrored = copy_trader.mirror(&swap).await?.unwrap();
+    assert!((mirrored.amount - 0.2).abs() < 1e-9);
+    let pending = buy_engine.get_pending_trades().await;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].token_address, mint.to_string());
+    assert!((pending[0].amount - 0.2).abs() < 1e-9);
+
+    // A repeat buy while the first is still queued is skipped rather than stacked
+    assert!(copy_trader.mirror(&swap).await?.is_none());
+    assert!((copy_trader.committed(&mint.to_string()) - 0.2).abs() < 1e-9);
+
+    // Other wallets' swaps are not copied
+    assert!(copy_trader.decode(&pump_fun_buy(Pubkey::new_unique(), mint, 1_000_000, 2_000_000_000))?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_copy_trader_releases_commitment_of_expired_buy() -> Result<()> {
+    let target = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.copy_trader.target_wallet", target.to_string())?
+        .set_default("sniping_core.copy_trader.scale_factor", 0.1)?
+        .set_default("sniping_core.copy_trader.default_token_cap", 0.3)?
+        .set_default("sniping_core.buy_engine.pending_expiry.max_age_ms", 0)?
+        .set_default("sniping_core.buy_engine.pending_expiry.recheck_entry", false)?
+        .build()?;
+    let buy_engine = Arc::new(BuyEngine::new(&config, active_sniping_state()).await?);
+    let copy_trader = CopyTrader::new(&config, buy_engine.clone())?;
+    let swap = copy_trader.decode(&pump_fun_buy(target, mint, 1_000_000, 2_000_000_000))?.unwrap();
+
+    copy_trader.mirror(&swap).await?.unwrap();
+    assert!((copy_trader.committed(&mint.to_string()) - 0.2).abs() < 1e-9);
+
+    // The queued buy expires without filling, so the cap is free for the next copy
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    assert_eq!(buy_engine.sweep_stale_pending().await?.len(), 1);
+    let mirrored = copy_trader.mirror(&swap).await?.unwrap();
+    assert!((mirrored.amount - 0.2).abs() < 1e-9);
+    assert!((copy_trader.committed(&mint.to_string()) - 0.2).abs() < 1e-9);
+
+    Ok(())
+}
+
+fn signatures_response(signature: &str) -> serde_json::Value {
+    json!({"jsonrpc": "2.0", "id": 1, "result": [{
+        "signature": signature, "slot": 1, "err": null, "memo": null,
+        "blockTime": null, "confirmationStatus": "finalized"
+    }]})
+}
+
+#[tokio::test]
+async fn test_copy_trader_sizes_routed_buy_from_balance_change() -> Result<()> {
+    let server = MockServer::start().await;
+    let target = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+
+    // An aggregator's instruction that calls pump.fun, which only shows up as an inner instruction
+    let buy = pump_fun_buy(target, mint, 1_000_000, 2_000_000_000);
+    let mut router_accounts = buy.accounts.clone();
+    router_accounts.push(AccountMeta::new_readonly(buy.program_id, false));
+    let router = Instruction { program_id: Pubkey::new_unique(), accounts: router_accounts, data: vec![0] };
+    let transaction = solana_sdk::transaction::Transaction::new_with_payer(&[router], Some(&target));
+    let keys = &transaction.message.account_keys;
+    let index = |key: &Pubkey| keys.iter().position(|k| k == key).unwrap();
+    let inner_accounts: Vec<usize> = buy.accounts.iter().map(|meta| index(&meta.pubkey)).collect();
+
+    // The target paid 1.5 SOL plus the fee, though the instruction allowed up to 2
+    let fee = 5_000u64;
+    let mut pre_balances = vec![0u64; keys.len()];
+    let mut post_balances = vec![0u64; keys.len()];
+    pre_balances[0] = 10_000_000_000;
+    post_balances[0] = 10_000_000_000 - 1_500_000_000 - fee;
+
+    let (first, second) = (solana_sdk::signature::Signature::new_unique(), solana_sdk::signature::Signature::new_unique());
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getSignaturesForAddress"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(signatures_response(&first.to_string())))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getSignaturesForAddress"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(signatures_response(&second.to_string())))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({"method": "getTransaction"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+            "slot": 2,
+            "blockTime": null,
+            "transaction": [base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&transaction)?), "base64"],
+            "meta": {
+                "err": null,
+                "status": {"Ok": null},
+                "fee": fee,
+                "preBalances": pre_balances,
+                "postBalances": post_balances,
+                "innerInstructions": [{"index": 0, "instructions": [{
+                    "programIdIndex": index(&buy.program_id),
+                    "accounts": inner_accounts,
+                    "data": solana_sdk::bs58::encode(&buy.data).into_string(),
+                    "stackHeight": 2
+                }]}]
+            }
+        }})))
+        .mount(&server)
+        .await;
+
+    let config = sniping_config_builder()?
+        .set_default("sniping_core.copy_trader.target_wallet", target.to_string())?
+        .set_default("sniping_core.copy_trader.scale_factor", 0.1)?
+        .set_default("sniping_core.copy_trader.default_token_cap", 1.0)?
+        .set_default("sniping_core.copy_trader.rpc_url", server.uri())?
+        .build()?;
+    let buy_engine = Arc::new(BuyEngine::new(&config, active_sniping_state()).await?);
+    let copy_trader = CopyTrader::new(&config, buy_engine.clone())?;
+
+    // The first poll only marks where to start; the second mirrors the routed buy
+    assert!(copy_trader.poll_target().await?.is_empty());
+    let mirrored = copy_trader.poll_target().await?;
+    assert_eq!(mirrored.len(), 1);
+    assert_eq!(mirrored[0].token_address, mint.to_string());
+    assert!((mirrored[0].amount - 0.15).abs() < 1e-9);
+
+    Ok(())
+}
+
+// Stands in for a DEX layout: whatever the account bytes, the pool holds these reserves
+struct FixedReservesDecoder(PoolReserves);
+
+impl PoolDecoder for FixedReservesDecoder {
+    fn pool_address(&self, token_mint: &Pubkey) -> Result<Pubkey> {
+        Ok(*token_mint)
+    }
+
+    fn decode(&self, _data: &[u8]) -> Result<PoolReserves> {
+        Ok(self.0)
+    }
+}
+
+#[test]
+fn test_pool_reserves_map_to_usd_liquidity() -> Result<()> {
+    let decoder = FixedReservesDecoder(PoolReserves { sol: 40.0, token: 600_000_000.0 });
+
+    // 40 SOL at $150 on one side is matched by equal value in tokens on the other
+    let liquidity = PoolLiquidity::liquidity_from_account(&decoder, &[], 150.0)?;
+    assert!((liquidity - 12_000.0).abs() < 1e-6);
+
+    // The bonding curve layout reads real reserves after the discriminator and virtual fields
+    let config = sniping_config_builder()?.build()?;
+    let curve = BondingCurveDecoder::new(&config)?;
+    let mut data = vec![0u8; 49];
+    data[24..32].copy_from_slice(&793_100_000_000_000u64.to_le_bytes());
+    data[32..40].copy_from_slice(&25_000_000_000u64.to_le_bytes());
+    assert_eq!(curve.decode(&data)?, PoolReserves { sol: 25.0, token: 793_100_000.0 });
+    assert!(curve.decode(&data[..30]).is_err());
+
+    Ok(())
+}
+
+// Quotes every token at the same fixed price so the answering source is identifiable
+struct FixedPriceProvider {
+    source: PriceSource,
+    price: f64,
+}
+
+#[async_trait]
+impl PriceProvider for FixedPriceProvider {
+    fn source(&self) -> PriceSource {
+        self.source
+    }
+
+    async fn price(&self, _token_address: &str) -> Result<f64> {
+        Ok(self.price)
+    }
+}
+
+#[tokio::test]
+async fn test_price_feed_uses_override_source_for_pinned_tokens() -> Result<()> {
+    let config = ::config::Config::builder()
+        .add_source(::config::File::from_str(r#"
+            [sniping_core.price_feed]
+            default_chain = ["jupiter", "pump_fun"]
+
+            [[sniping_core.price_feed.overrides]]
+            mint = "PumpOnlyMint"
+            source = "pump_fun"
+        "#, ::config::FileFormat::Toml))
+        .build()?;
+    let mut feed = PriceFeed::new(&config)?;
+    feed.register(Arc::new(FixedPriceProvider { source: PriceSource::Jupiter, price: 1.0 }));
+    feed.register(Arc::new(FixedPriceProvider { source: PriceSource::PumpFun, price: 2.0 }));
+
+    assert_eq!(feed.price("PumpOnlyMint").await?, (2.0, PriceSource::PumpFun));
+    assert_eq!(feed.price("OtherMint").await?, (1.0, PriceSource::Jupiter));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_coin_scanner_batch_size_shrinks_under_rate_limits_and_recovers() -> Result<()> {
+    let server = MockServer::start().await;
+
+    // The first two scans are rate limited, later ones succeed
+    Mock::given(method("GET")).and(path("/pump-fun"))
+        .respond_with(ResponseTemplate::new(429))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET")).and(path("/pump-fun"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([mock_coin("PumpToken")])))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", format!("{}/pump-fun", server.uri()))?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", format!("{}/dex-screener", server.uri()))?
+        .set_override("sniping_core.coin_scanner.birdeye_url", format!("{}/birdeye", server.uri()))?
+        .set_override("sniping_core.coin_scanner.adaptive_batch.enabled", true)?
+        .set_override("sniping_core.coin_scanner.adaptive_batch.min_batch_size", 10)?
+        .set_override("sniping_core.coin_scanner.adaptive_batch.max_batch_size", 100)?
+        .set_override("sniping_core.coin_scanner.adaptive_batch.shrink_factor", 0.5)?
+        .set_override("sniping_core.coin_scanner.adaptive_batch.grow_step", 20)?
+        .build()?;
+    let mut scanner = CoinScanner::new(&config, active_sniping_state()).await?;
+    assert_eq!(scanner.batch_size(), 100);
+
+    scanner.scan_coins().await?;
+    assert_eq!(scanner.batch_size(), 50);
+    scanner.scan_coins().await?;
+    assert_eq!(scanner.batch_size(), 25);
+
+    // Healthy again: grows back step by step, capped at the maximum
+    scanner.scan_coins().await?;
+    assert_eq!(scanner.batch_size(), 45);
+    for _ in 0..5 {
+        scanner.scan_coins().await?;
+    }
+    assert_eq!(scanner.batch_size(), 100);
+
+    Ok(())
+}
+
+fn liquidity_drop_alert(token_address: &str, before: f64, after: f64) -> Message {
+    Message::LiquidityAlert(LiquidityAlert {
+        pool_address: "pool".to_string(),
+        token_address: token_address.to_string(),
+        alert_type: AlertType::LiquidityDrop,
+        severity: AlertSeverity::High,
+        current_value: after,
+        threshold_value: 0.5,
+        previous_liquidity: Some(before),
+        timestamp: chrono::Utc::now(),
+        message: "Liquidity dropped".to_string(),
+    })
+}
+
+#[tokio::test]
+async fn test_killswitch_activates_on_deep_liquidity_drop() -> Result<()> {
+    let config = ::config::Config::builder()
+        .set_default("sniping_core.killswitch.liquidity_drop_threshold", 0.5)?
+        .set_default("sniping_core.killswitch.activation_signals", 2)?
+        .build()?;
+    let message_queue = MessageQueue::new(16);
+    let mut alerts = message_queue
+        .subscribe_filtered("alerts".to_string(), MessageKind::COLONY_ALERT, 16, OverflowPolicy::DropOldest)
+        .await;
+    let killswitch = SentimentKillswitch::new(&config, message_queue.clone()).await;
+
+    // A 30% drop is below the threshold
+    message_queue.publish(liquidity_drop_alert("TokenA", 10_000.0, 7_000.0)).await;
+    assert!(!killswitch.should_activate().await);
+    assert!(alerts.try_recv().is_none());
+
+    // One token rugging, however often it is reported, is a single signal
+    message_queue.publish(liquidity_drop_alert("TokenA", 10_000.0, 2_000.0)).await;
+    message_queue.publish(liquidity_drop_alert("TokenA", 10_000.0, 1_000.0)).await;
+    assert!(!killswitch.should_activate().await);
+    assert_eq!(killswitch.negative_signals(), 1);
+
+    // A second token going the same way trips it and announces the freeze once
+    message_queue.publish(liquidity_drop_alert("TokenB", 10_000.0, 2_000.0)).await;
+    assert!(killswitch.should_activate().await);
+    match alerts.try_recv() {
+        Some(Message::ColonyAlert(alert)) => {
+            assert_eq!(alert.source, "sentiment_killswitch");
+            assert_eq!(alert.severity, AlertSeverity::Critical);
+        }
+        other => panic!("expected a freeze alert, got {:?}", other),
+    }
+    message_queue.publish(liquidity_drop_alert("TokenC", 10_000.0, 1_000.0)).await;
+    assert!(killswitch.should_activate().await);
+    assert!(alerts.try_recv().is_none());
+
+    killswitch.reset();
+    assert!(!killswitch.should_activate().await);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_killswitch_clears_once_signals_age_out() -> Result<()> {
+    let config = ::config::Config::builder()
+        .set_default("sniping_core.killswitch.activation_signals", 2)?
+        .set_default("sniping_core.killswitch.signal_window_ms", 200)?
+        .set_default("sniping_core.killswitch.poll_interval_ms", 10)?
+        .build()?;
+    let message_queue = MessageQueue::new(16);
+    let killswitch = Arc::new(SentimentKillswitch::new(&config, message_queue.clone()).await);
+
+    // The background drain picks alerts up without any buy asking
+    let run = tokio::spawn({
+        let killswitch = killswitch.clone();
+        async move { killswitch.run().await }
+    });
+    message_queue.publish(liquidity_drop_alert("TokenA", 10_000.0, 1_000.0)).await;
+    message_queue.publish(liquidity_drop_alert("TokenB", 10_000.0, 1_000.0)).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(killswitch.is_active());
+
+    // Nothing new within the window lifts the halt without a manual reset
+    tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+    assert!(!killswitch.is_active());
+    assert_eq!(killswitch.negative_signals(), 0);
+
+    run.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_killswitch_counts_negative_sentiment() -> Result<()> {
+    let config = ::config::Config::builder()
+        .set_default("sniping_core.killswitch.sentiment_threshold", -0.5)?
+        .set_default("sniping_core.killswitch.activation_signals", 2)?
+        .build()?;
+    let killswitch = SentimentKillswitch::new(&config, MessageQueue::new(16)).await;
+
+    killswitch.record_sentiment(-0.2).await;
+    killswitch.record_sentiment(-0.7).await;
+    assert_eq!(killswitch.negative_signals(), 1);
+    assert!(!killswitch.should_activate().await);
+
+    killswitch.record_sentiment(-0.9).await;
+    assert!(killswitch.should_activate().await);
+    Ok(())
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_supervisor_restarts_failing_component() -> Result<()> {
+    let config = sniping_config_builder()?
+        .set_override("sniping_core.supervisor.max_restarts", 3)?
+        .set_override("sniping_core.supervisor.backoff_ms", 1000)?
+        .build()?;
+    let state = Arc::new(RwLock::new(SnipingState::default()));
+    let message_queue = MessageQueue::new(16);
+    let mut alerts = message_queue
+        .subscribe_filtered("alerts".to_string(), MessageKind::COLONY_ALERT, 16, OverflowPolicy::DropOldest)
+        .await;
+    let mut supervisor = Supervisor::new(&config, state.clone());
+    supervisor.set_message_queue(message_queue);
+
+    // Errors on the first two runs, then keeps running
+    let attempts = Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let counter = attempts.clone();
+    let flaky = supervisor.supervise("Flaky", move || {
+        let attempt = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        async move {
+            if attempt <= 2 {
+                return Err(anyhow::anyhow!("attempt {} failed", attempt));
+            }
+            std::future::pending::<()>().await;
+            Ok(())
+        }
+    });
+
+    // Backoff doubles: restarts after 1s and then 2s more
+    tokio::time::sleep(std::time::Duration::from_millis(3100)).await;
+    assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+    assert!(!flaky.is_finished());
+    assert!(state.read().await.degraded_components.is_empty());
+    assert!(alerts.try_recv().is_none());
+
+    // One that never recovers is given up on once its restarts run out
+    let broken = supervisor.supervise("Broken", || async { Err::<(), _>(anyhow::anyhow!("always fails")) });
+    broken.await?;
+    assert_eq!(state.read().await.degraded_components, vec!["Broken".to_string()]);
+    match alerts.try_recv() {
+        Some(Message::ColonyAlert(alert)) => {
+            assert_eq!(alert.source, "sniping_core");
+            assert_eq!(alert.severity, AlertSeverity::Critical);
+            assert!(alert.message.contains("Broken"));
+        }
+        _ => panic!("expected a ColonyAlert reporting the degraded component"),
+    }
+
+    flaky.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_shutdown_stops_global_sniping_core() -> Result<()> {
+    // The shipped settings, with every endpoint the core's loops reach pointed at a local server
+    let server = MockServer::start().await;
+    let config = sniping_config_builder()?
+        .add_source(::config::File::with_name("config/settings"))
+        .set_override("jupiter.quote_url", format!("{}/quote", server.uri()))?
+        .set_override("jupiter.swap_url", format!("{}/swap", server.uri()))?
+        .set_override("sniping_core.copy_trader.rpc_url", server.uri())?
+        .set_override("sniping_core.coin_scanner.pump_fun_url", server.uri())?
+        .set_override("sniping_core.coin_scanner.dex_screener_url", server.uri())?
+        .set_override("sniping_core.coin_scanner.birdeye_url", server.uri())?
+        .set_override("sniping_core.coin_scanner.honeypot.rpc_url", server.uri())?
+        .set_override("sniping_core.coin_scanner.honeypot.swap_api_url", server.uri())?
+        .set_override("helius.mainnet", server.uri())?
+        .set_override("helius.ws_mainnet", server.uri().replace("http", "ws"))?
+        .set_override("sniping_core.exit_strategy.market_data.base_url", server.uri())?
+        .build()?;
+    antbot::sniping_core::init(&config, antbot::sniping_core::ColonyServices::default()).await?;
+    let core = antbot::sniping_core::instance().expect("init registers the global core");
+    assert!(core.read().await.is_active().await);
+
+    // Initializing again leaves the running core as it is
+    antbot::sniping_core::init(&config, antbot::sniping_core::ColonyServices::default()).await?;
+    assert!(Arc::ptr_eq(&core, &antbot::sniping_core::instance().unwrap()));
+    assert!(core.read().await.is_active().await);
+
+    antbot::sniping_core::shutdown().await?;
+    assert!(!core.read().await.is_active().await);
+
+    Ok(())
+}
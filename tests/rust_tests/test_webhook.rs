@@ -0,0 +1,108 @@
+use antbot::common::{TradeWebhook, TradeConfirmation, TradeOutcome, Delivery, DeadLetter};
+use anyhow::Result;
+use serde_json::json;
+use std::path::Path;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use wiremock::matchers::{method, path};
+
+fn webhook_config(url: &str, dead_letter_path: &Path) -> Result<::config::Config> {
+    Ok(::config::Config::builder()
+        .set_default("trade_webhook.enabled", true)?
+        .set_default("trade_webhook.url", url)?
+        .set_default("trade_webhook.max_attempts", 3)?
+        .set_default("trade_webhook.retry_delay_ms", 10)?
+        .set_default("trade_webhook.dead_letter_path", dead_letter_path.to_string_lossy().to_string())?
+        .build()?)
+}
+
+fn filled_confirmation() -> Result<TradeConfirmation> {
+    TradeConfirmation::new(TradeOutcome::Filled, "trade-1", "TokenA", &json!({
+        "token_address": "TokenA",
+        "amount": 0.5,
+        "price": 0.0002,
+        "status": "Completed",
+    }))
+}
+
+#[tokio::test]
+async fn test_webhook_delivers_confirmation() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/trades"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir()?;
+    let dead_letters = dir.path().join("dead_letters.jsonl");
+    let webhook = TradeWebhook::new(&webhook_config(&format!("{}/trades", server.uri()), &dead_letters)?)?;
+
+    assert_eq!(webhook.deliver(&filled_confirmation()?).await, Delivery::Delivered { attempts: 1 });
+
+    let requests = server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body)?;
+    assert_eq!(body["outcome"], "filled");
+    assert_eq!(body["trade_id"], "trade-1");
+    assert_eq!(body["trade"]["amount"], 0.5);
+    assert!(!dead_letters.exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_webhook_retries_until_delivery_succeeds() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/trades"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/trades"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir()?;
+    let dead_letters = dir.path().join("dead_letters.jsonl");
+    let webhook = TradeWebhook::new(&webhook_config(&format!("{}/trades", server.uri()), &dead_letters)?)?;
+
+    assert_eq!(webhook.deliver(&filled_confirmation()?).await, Delivery::Delivered { attempts: 3 });
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    assert!(!dead_letters.exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_webhook_dead_letters_permanently_failing_delivery() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/trades"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let dir = tempfile::tempdir()?;
+    let dead_letters = dir.path().join("dead_letters.jsonl");
+    let webhook = TradeWebhook::new(&webhook_config(&format!("{}/trades", server.uri()), &dead_letters)?)?;
+
+    let delivery = webhook.deliver(&filled_confirmation()?).await;
+    assert!(matches!(delivery, Delivery::DeadLettered { attempts: 3, .. }), "{:?}", delivery);
+
+    let contents = std::fs::read_to_string(&dead_letters)?;
+    let entries: Vec<DeadLetter> = contents.lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].attempts, 3);
+    assert_eq!(entries[0].confirmation.trade_id, "trade-1");
+    assert_eq!(entries[0].confirmation.outcome, TradeOutcome::Filled);
+    assert!(entries[0].error.contains("500"));
+
+    Ok(())
+}